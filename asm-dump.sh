@@ -0,0 +1,21 @@
+#!/bin/bash
+set -e
+# Prints the disassembly of the submission binary interleaved with the source lines.
+# Pass --all to show every function, or --function <regex> to select functions by name.
+# Other arguments are passed to cargo.
+dump_args=()
+cargo_args=()
+while [[ $# -gt 0 ]]; do
+  if [[ "$1" == "--all" ]]; then
+    dump_args+=("$1")
+  elif [[ "$1" == "--function" ]]; then
+    dump_args+=("$1" "$2")
+    shift
+  else
+    cargo_args+=("$1")
+  fi
+  shift
+done
+>&2 echo "Building project for target x86_64-unknown-linux-gnu, build mode Release (with symbols and line tables)"
+cargo +nightly build --target x86_64-unknown-linux-gnu --bin basm-submit --features=submit --release --config profile.release.strip=false --config 'profile.release.debug="line-tables-only"' "${cargo_args[@]}"
+python3 scripts/asm-dump.py target/x86_64-unknown-linux-gnu/release/basm-submit "${dump_args[@]}"
@@ -5,6 +5,8 @@ extern crate syn;
 
 mod export;
 mod import;
+mod problem;
+mod table;
 mod types;
 mod utils;
 
@@ -23,3 +25,31 @@ pub fn basm_import(item: TokenStream) -> TokenStream {
     let item = parse_macro_input!(item);
     import::import_impl(item).into()
 }
+
+/// Embeds a table of integers read from a file (whitespace- or comma-separated, relative to
+/// the crate root) in a compact form, which is decoded into a static buffer on first access.
+/// The table file is not part of the solution source, so it does not count toward the code length.
+/// ```ignore
+/// include_table! {
+///     static PRIMES: u32 = "tables/primes.txt";
+/// }
+/// ```
+#[proc_macro]
+pub fn include_table(item: TokenStream) -> TokenStream {
+    table::include_table_impl(item.into()).into()
+}
+
+/// Declares constants whose values are read from the problem configuration file
+/// (`basm/problem.toml`, or the file named by the `BASM_PROBLEM` environment variable) at build time,
+/// so that a shared solution template can be parameterized per problem.
+/// A constant missing from the file takes the default value, if any.
+/// ```ignore
+/// problem_consts! {
+///     const MOD: u64;
+///     const N_MAX: usize = 200_000;
+/// }
+/// ```
+#[proc_macro]
+pub fn problem_consts(item: TokenStream) -> TokenStream {
+    problem::problem_consts_impl(item.into()).into()
+}
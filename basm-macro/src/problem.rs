@@ -0,0 +1,148 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Expr, Ident, Result, Token, Type, Visibility,
+};
+
+/// `$vis const $name: $ty (= $default)?;`
+struct ConstDecl {
+    vis: Visibility,
+    name: Ident,
+    ty: Type,
+    default: Option<Expr>,
+}
+
+impl Parse for ConstDecl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let vis: Visibility = input.parse()?;
+        let _const: Token![const] = input.parse()?;
+        let name: Ident = input.parse()?;
+        let _colon: Token![:] = input.parse()?;
+        let ty: Type = input.parse()?;
+        let default = if input.peek(Token![=]) {
+            let _eq: Token![=] = input.parse()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let _semi: Token![;] = input.parse()?;
+        Ok(Self {
+            vis,
+            name,
+            ty,
+            default,
+        })
+    }
+}
+
+struct ProblemInput {
+    decls: Vec<ConstDecl>,
+}
+
+impl Parse for ProblemInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut decls = vec![];
+        while !input.is_empty() {
+            decls.push(input.parse()?);
+        }
+        Ok(Self { decls })
+    }
+}
+
+/// Strips a `#` comment from a line of TOML, unless it is inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_str = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_str => escaped = true,
+            '"' => in_str = !in_str,
+            '#' if !in_str => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Parses the top-level `key = value` pairs of a TOML file. Only the values that are
+/// also valid Rust literals are supported (integers, floats, booleans and basic strings).
+fn parse_problem_toml(text: &str) -> std::result::Result<Vec<(String, String)>, String> {
+    let mut out = vec![];
+    for (lineno, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            return Err(format!(
+                "line {}: tables are not supported; please put the values at the top level",
+                lineno + 1
+            ));
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected `key = value`", lineno + 1));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let value = value.strip_prefix('+').unwrap_or(value);
+        if key.is_empty() || value.is_empty() {
+            return Err(format!("line {}: expected `key = value`", lineno + 1));
+        }
+        out.push((key.to_owned(), value.to_owned()));
+    }
+    Ok(out)
+}
+
+pub fn problem_consts_impl(item: TokenStream) -> TokenStream {
+    let input: ProblemInput = syn::parse2(item).unwrap();
+
+    // The build script of basm passes the path in BASM_PROBLEM_TOML (see basm/build.rs);
+    // otherwise, the file is relative to the root of the crate being compiled
+    let path = match std::env::var("BASM_PROBLEM_TOML") {
+        Ok(x) => std::path::PathBuf::from(x),
+        Err(_) => {
+            let manifest_dir =
+                std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_owned());
+            std::path::Path::new(&manifest_dir).join("problem.toml")
+        }
+    };
+    let (values, rebuild) = match std::fs::read_to_string(&path) {
+        Ok(text) => {
+            let values = match parse_problem_toml(&text) {
+                Ok(x) => x,
+                Err(e) => panic!("Failed to parse {}: {}", path.display(), e),
+            };
+            let path_str = path.to_string_lossy().into_owned();
+            // Rebuild when the configuration changes
+            (
+                values,
+                quote! { const _: &[u8] = include_bytes!(#path_str); },
+            )
+        }
+        Err(_) => (vec![], quote! {}),
+    };
+
+    let mut out = rebuild;
+    for decl in &input.decls {
+        let (vis, name, ty) = (&decl.vis, &decl.name, &decl.ty);
+        let key = name.to_string();
+        let value: Expr = match values.iter().find(|(k, _)| *k == key) {
+            Some((_, v)) => match syn::parse_str(v) {
+                Ok(x) => x,
+                Err(_) => panic!("Unsupported value {v} for {key} in {}", path.display()),
+            },
+            None => match &decl.default {
+                Some(x) => x.clone(),
+                None => panic!(
+                    "{key} is not defined in {} and has no default value",
+                    path.display()
+                ),
+            },
+        };
+        out.extend(quote! {
+            #vis const #name: #ty = #value;
+        });
+    }
+    out
+}
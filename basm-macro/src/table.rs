@@ -0,0 +1,131 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Ident, LitStr, Result, Token, Type, Visibility,
+};
+
+/// `$vis static $name: $ty = $path;`
+struct TableInput {
+    vis: Visibility,
+    name: Ident,
+    ty: Type,
+    path: LitStr,
+}
+
+impl Parse for TableInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let vis: Visibility = input.parse()?;
+        let _static: Token![static] = input.parse()?;
+        let name: Ident = input.parse()?;
+        let _colon: Token![:] = input.parse()?;
+        let ty: Type = input.parse()?;
+        let _eq: Token![=] = input.parse()?;
+        let path: LitStr = input.parse()?;
+        if !input.is_empty() {
+            let _semi: Token![;] = input.parse()?;
+        }
+        Ok(Self {
+            vis,
+            name,
+            ty,
+            path,
+        })
+    }
+}
+
+/// Returns the range of the integer type named `ty`. A proc macro cannot see the target, so
+/// `usize` and `isize` get their 64-bit ranges here and are checked again in the expansion.
+fn type_range(ty: &str) -> Option<(i128, i128)> {
+    Some(match ty {
+        "u8" => (u8::MIN as i128, u8::MAX as i128),
+        "u16" => (u16::MIN as i128, u16::MAX as i128),
+        "u32" => (u32::MIN as i128, u32::MAX as i128),
+        "u64" => (u64::MIN as i128, u64::MAX as i128),
+        "usize" => (u64::MIN as i128, u64::MAX as i128),
+        "i8" => (i8::MIN as i128, i8::MAX as i128),
+        "i16" => (i16::MIN as i128, i16::MAX as i128),
+        "i32" => (i32::MIN as i128, i32::MAX as i128),
+        "i64" => (i64::MIN as i128, i64::MAX as i128),
+        "isize" => (i64::MIN as i128, i64::MAX as i128),
+        _ => return None,
+    })
+}
+
+/// Encodes the values as the zigzag-encoded differences between consecutive values
+/// (modulo 2^64) in LEB128. Precomputed tables are often monotone or smooth, so most
+/// entries fit in a byte or two, and the result compresses well with LZMA.
+pub fn encode_table(values: &[i128]) -> Vec<u8> {
+    let mut out = vec![];
+    let mut prev = 0u64;
+    for &v in values {
+        let cur = v as u64;
+        let delta = cur.wrapping_sub(prev) as i64;
+        let mut z = ((delta << 1) ^ (delta >> 63)) as u64;
+        loop {
+            if z < 0x80 {
+                out.push(z as u8);
+                break;
+            }
+            out.push((z as u8 & 0x7f) | 0x80);
+            z >>= 7;
+        }
+        prev = cur;
+    }
+    out
+}
+
+pub fn include_table_impl(item: TokenStream) -> TokenStream {
+    let input: TableInput = syn::parse2(item).unwrap();
+    let (vis, name, ty) = (&input.vis, &input.name, &input.ty);
+    let ty_name = quote!(#ty).to_string();
+    let (min, max) = match type_range(&ty_name) {
+        Some(x) => x,
+        None => panic!("include_table! supports primitive integer types only (found {ty_name})"),
+    };
+
+    // The path is relative to the root of the crate being compiled (e.g., `basm/`)
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_owned());
+    let path = std::path::Path::new(&manifest_dir).join(input.path.value());
+    let text = match std::fs::read_to_string(&path) {
+        Ok(x) => x,
+        Err(e) => panic!("Failed to read the table {}: {}", path.display(), e),
+    };
+    let values: Vec<i128> = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|x| !x.is_empty())
+        .map(|x| match x.parse::<i128>() {
+            Ok(v) if (min..=max).contains(&v) => v,
+            _ => panic!(
+                "Invalid {ty_name} value {x} in the table {}",
+                path.display()
+            ),
+        })
+        .collect();
+    let n = values.len();
+    let encoded = Literal::byte_string(&encode_table(&values));
+    let path_str = path.to_string_lossy().into_owned();
+    let width_check = if ty_name == "usize" || ty_name == "isize" {
+        let lo = Literal::i128_suffixed(values.iter().copied().min().unwrap_or(0));
+        let hi = Literal::i128_suffixed(values.iter().copied().max().unwrap_or(0));
+        let msg = format!(
+            "{} has a {ty_name} value out of range for this target",
+            input.path.value()
+        );
+        quote! {
+            const _: () = assert!(#lo >= #ty::MIN as i128 && #hi <= #ty::MAX as i128, #msg);
+        }
+    } else {
+        quote!()
+    };
+    quote! {
+        // Rebuild when the table changes
+        const _: &[u8] = include_bytes!(#path_str);
+        #width_check
+        #vis static #name: basm::utils::Table<#ty, #n> = {
+            // A separate zero-initialized static, so that the buffer is placed in .bss
+            static BUFFER: basm::utils::TableBuffer<#ty, #n> = basm::utils::TableBuffer::new();
+            basm::utils::Table::new(#encoded, &BUFFER)
+        };
+    }
+}
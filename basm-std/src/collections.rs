@@ -9,3 +9,93 @@ pub use fenwick::*;
 
 pub mod segment_tree;
 pub use segment_tree::*;
+
+pub mod lazy_seg_tree;
+pub use lazy_seg_tree::*;
+
+pub mod persistent_seg_tree;
+pub use persistent_seg_tree::*;
+
+pub mod seg_tree_beats;
+pub use seg_tree_beats::*;
+
+pub mod array_vec;
+pub use array_vec::*;
+
+pub mod array_string;
+pub use array_string::*;
+
+pub mod slab;
+pub use slab::*;
+
+pub mod sparse_table;
+pub use sparse_table::*;
+
+pub mod disjoint_sparse_table;
+pub use disjoint_sparse_table::*;
+
+pub mod treap;
+pub use treap::{TreapMap, TreapSet};
+
+pub mod implicit_treap;
+pub use implicit_treap::*;
+
+pub mod splay;
+pub use splay::*;
+
+pub mod link_cut_tree;
+pub use link_cut_tree::*;
+
+pub mod ordered_multiset;
+pub use ordered_multiset::*;
+
+pub mod sliding_window;
+pub use sliding_window::*;
+
+pub mod bitset;
+pub use bitset::*;
+
+pub mod fast_map;
+pub use fast_map::*;
+
+pub mod indexed_heap;
+pub use indexed_heap::*;
+
+pub mod pairing_heap;
+pub use pairing_heap::*;
+
+pub mod wavelet_matrix;
+pub use wavelet_matrix::*;
+
+pub mod merge_sort_tree;
+pub use merge_sort_tree::*;
+
+pub mod li_chao_tree;
+pub use li_chao_tree::*;
+
+pub mod convex_hull_trick;
+pub use convex_hull_trick::*;
+
+pub mod interval_set;
+pub use interval_set::*;
+
+pub mod kd_tree;
+pub use kd_tree::*;
+
+pub mod fast_int_set;
+pub use fast_int_set::*;
+
+pub mod sparse_seg_tree;
+pub use sparse_seg_tree::*;
+
+pub mod undo_queue;
+pub use undo_queue::*;
+
+pub mod binary_trie;
+pub use binary_trie::*;
+
+pub mod deque;
+pub use deque::*;
+
+pub mod pooled_btree;
+pub use pooled_btree::*;
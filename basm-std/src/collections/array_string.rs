@@ -0,0 +1,185 @@
+use core::ops::{Deref, DerefMut};
+
+/// A UTF-8 string with a fixed capacity of `N` bytes that lives entirely on the stack.
+///
+/// It never allocates, and implements [`core::fmt::Write`], so `write!` can format into it.
+/// Pushing beyond the capacity panics; use [`ArrayString::try_push_str`] to handle it instead.
+#[derive(Clone, Copy)]
+pub struct ArrayString<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> ArrayString<N> {
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub fn as_str(&self) -> &str {
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { core::str::from_utf8_unchecked_mut(&mut self.buf[..self.len]) }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Appends `s`, or returns `Err(())` without modifying the string if it does not fit.
+    #[allow(clippy::result_unit_err)]
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), ()> {
+        let end = self.len + s.len();
+        if end > N {
+            return Err(());
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+
+    /// Appends `s`. A runtime error will occur if it does not fit.
+    pub fn push_str(&mut self, s: &str) {
+        self.try_push_str(s).expect("ArrayString is full");
+    }
+
+    /// Appends `c`. A runtime error will occur if it does not fit.
+    pub fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.as_str().chars().next_back()?;
+        self.len -= c.len_utf8();
+        Some(c)
+    }
+
+    /// Shortens the string to `len` bytes. Does nothing if `len >= self.len()`.
+    /// A runtime error will occur if `len` is not on a char boundary.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            assert!(self.as_str().is_char_boundary(len));
+            self.len = len;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for ArrayString<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for ArrayString<N> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> DerefMut for ArrayString<N> {
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> core::fmt::Write for ArrayString<N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.try_push_str(s).map_err(|_| core::fmt::Error)
+    }
+}
+
+impl<const N: usize> core::fmt::Display for ArrayString<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for ArrayString<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<const N: usize> PartialEq for ArrayString<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for ArrayString<N> {}
+
+impl<const N: usize> PartialEq<str> for ArrayString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ArrayString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn push_and_pop_chars() {
+        let mut s = ArrayString::<8>::new();
+        s.push_str("ab");
+        s.push('가');
+        assert_eq!("ab가", s.as_str());
+        assert_eq!(5, s.len());
+        assert_eq!(Some('가'), s.pop());
+        assert_eq!(Some('b'), s.pop());
+        assert_eq!("a", s.as_str());
+    }
+
+    #[test]
+    fn overflow_is_rejected_without_modification() {
+        let mut s = ArrayString::<4>::new();
+        s.push_str("abc");
+        assert_eq!(Err(()), s.try_push_str("de"));
+        assert_eq!("abc", s.as_str());
+        assert!(write!(s, "{}", 12).is_err());
+        s.clear();
+        write!(s, "{}-{}", 1, 23).unwrap();
+        assert_eq!("1-23", s.as_str());
+        assert!(s.is_full());
+    }
+
+    #[test]
+    #[should_panic]
+    fn truncate_inside_char_panics() {
+        let mut s = ArrayString::<8>::new();
+        s.push_str("가나");
+        s.truncate(4);
+    }
+}
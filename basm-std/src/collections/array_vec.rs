@@ -0,0 +1,271 @@
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+
+/// A vector with a fixed capacity `N` that lives entirely on the stack (or wherever it is placed).
+///
+/// It never allocates, so it is suitable for hot paths and small temporary buffers.
+/// Pushing to a full `ArrayVec` panics; use [`ArrayVec::try_push`] to handle it instead.
+pub struct ArrayVec<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            data: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Returns the number of elements that can be pushed before the vector is full.
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    /// Appends `x`. A runtime error will occur if the vector is full.
+    pub fn push(&mut self, x: T) {
+        assert!(self.len < N, "ArrayVec is full");
+        unsafe { self.push_unchecked(x) };
+    }
+
+    /// Appends `x`, or returns it back if the vector is full.
+    pub fn try_push(&mut self, x: T) -> Result<(), T> {
+        if self.len < N {
+            unsafe { self.push_unchecked(x) };
+            Ok(())
+        } else {
+            Err(x)
+        }
+    }
+
+    /// Appends `x` without checking the capacity.
+    ///
+    /// # Safety
+    ///
+    /// The vector must not be full.
+    pub unsafe fn push_unchecked(&mut self, x: T) {
+        debug_assert!(self.len < N);
+        self.data.get_unchecked_mut(self.len).write(x);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { self.data[self.len].assume_init_read() })
+    }
+
+    /// Inserts `x` at position `index`, shifting the following elements to the right.
+    /// A runtime error will occur if the vector is full or `index > self.len()`.
+    pub fn insert(&mut self, index: usize, x: T) {
+        assert!(index <= self.len && self.len < N);
+        unsafe {
+            let p = self.data.as_mut_ptr().add(index);
+            core::ptr::copy(p, p.add(1), self.len - index);
+            (*p).write(x);
+        }
+        self.len += 1;
+    }
+
+    /// Removes and returns the element at position `index`, shifting the following elements to the left.
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len);
+        self.len -= 1;
+        unsafe {
+            let p = self.data.as_mut_ptr().add(index);
+            let x = (*p).assume_init_read();
+            core::ptr::copy(p.add(1), p, self.len - index);
+            x
+        }
+    }
+
+    /// Removes and returns the element at position `index`, replacing it with the last element.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        self.swap(index, len - 1);
+        self.pop().unwrap()
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Does nothing if `len >= self.len()`.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            self.len -= 1;
+            unsafe { self.data[self.len].assume_init_drop() };
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.data.as_ptr() as *const T, self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut T, self.len) }
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayVec<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: core::fmt::Debug, const N: usize> core::fmt::Debug for ArrayVec<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for ArrayVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayVec<T, N> {}
+
+impl<T, const N: usize> Extend<T> for ArrayVec<T, N> {
+    /// A runtime error will occur if the vector overflows.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for ArrayVec<T, N> {
+    /// A runtime error will occur if the iterator yields more than `N` elements.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn push_pop_and_capacity() {
+        let mut v = ArrayVec::<u32, 3>::new();
+        assert!(v.is_empty());
+        v.push(1);
+        v.push(2);
+        assert_eq!(Ok(()), v.try_push(3));
+        assert!(v.is_full());
+        assert_eq!(Err(4), v.try_push(4));
+        assert_eq!(&[1, 2, 3], v.as_slice());
+        assert_eq!(Some(3), v.pop());
+        assert_eq!(1, v.remaining_capacity());
+        assert_eq!(3, v.iter().sum::<u32>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn push_to_full_panics() {
+        let mut v = ArrayVec::<u8, 1>::new();
+        v.push(1);
+        v.push(2);
+    }
+
+    #[test]
+    fn insert_and_remove_shift_elements() {
+        let mut v: ArrayVec<u32, 8> = [1, 2, 4].into_iter().collect();
+        v.insert(2, 3);
+        v.insert(0, 0);
+        v.insert(5, 5);
+        assert_eq!(&[0, 1, 2, 3, 4, 5], &v[..]);
+        assert_eq!(0, v.remove(0));
+        assert_eq!(2, v.swap_remove(1));
+        v.sort();
+        assert_eq!(&[1, 3, 4, 5], &v[..]);
+        assert_eq!(v.clone(), v);
+    }
+
+    #[test]
+    fn drops_elements_exactly_once() {
+        let rc = Rc::new(());
+        {
+            let mut v = ArrayVec::<Rc<()>, 4>::new();
+            v.extend((0..4).map(|_| rc.clone()));
+            assert_eq!(5, Rc::strong_count(&rc));
+            v.truncate(2);
+            assert_eq!(3, Rc::strong_count(&rc));
+            let x = v.remove(0);
+            assert_eq!(3, Rc::strong_count(&rc));
+            drop(x);
+        }
+        assert_eq!(1, Rc::strong_count(&rc));
+        let v: Vec<_> = ArrayVec::<u8, 4>::from_iter([3, 1, 2])
+            .iter()
+            .copied()
+            .collect();
+        assert_eq!(vec![3, 1, 2], v);
+    }
+}
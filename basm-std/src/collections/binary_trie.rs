@@ -0,0 +1,219 @@
+use alloc::vec::Vec;
+
+/// A multiset of `bits`-bit integers stored as a binary trie, for XOR queries.
+///
+/// Nodes live in one pool and link to their children with `u32` indices. Removing elements
+/// only decrements counts, so the pool never shrinks. [`BinaryTrie::xor_all`] XORs every element
+/// with a mask in O(1) by remembering the mask and applying it to later queries.
+#[derive(Clone)]
+pub struct BinaryTrie {
+    child: Vec<[u32; 2]>,
+    count: Vec<u32>,
+    bits: u32,
+    mask: u64,
+}
+
+impl BinaryTrie {
+    /// Creates an empty trie of `bits`-bit integers, where `bits` is at most 64.
+    pub fn new(bits: u32) -> Self {
+        assert!(bits <= 64);
+        Self {
+            child: alloc::vec![[0; 2]],
+            count: alloc::vec![0],
+            bits,
+            mask: 0,
+        }
+    }
+
+    /// Returns the number of elements, counting multiplicity.
+    pub fn len(&self) -> usize {
+        self.count[0] as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count[0] == 0
+    }
+
+    /// Returns the number of nodes in the pool.
+    pub fn node_count(&self) -> usize {
+        self.child.len()
+    }
+
+    fn bit(&self, x: u64, i: u32) -> usize {
+        (x >> i & 1) as usize
+    }
+
+    /// Inserts one copy of `x`.
+    pub fn insert(&mut self, x: u64) {
+        let x = x ^ self.mask;
+        let mut k = 0;
+        self.count[0] += 1;
+        for i in (0..self.bits).rev() {
+            let b = self.bit(x, i);
+            if self.child[k][b] == 0 {
+                self.child[k][b] = self.child.len() as u32;
+                self.child.push([0; 2]);
+                self.count.push(0);
+            }
+            k = self.child[k][b] as usize;
+            self.count[k] += 1;
+        }
+    }
+
+    /// Returns the number of copies of `x`.
+    pub fn count(&self, x: u64) -> usize {
+        let x = x ^ self.mask;
+        let mut k = 0;
+        for i in (0..self.bits).rev() {
+            k = self.child[k][self.bit(x, i)] as usize;
+            if k == 0 {
+                return 0;
+            }
+        }
+        self.count[k] as usize
+    }
+
+    /// Removes one copy of `x`. Returns `false` if `x` is not present.
+    pub fn remove(&mut self, x: u64) -> bool {
+        if self.count(x) == 0 {
+            return false;
+        }
+        let x = x ^ self.mask;
+        let mut k = 0;
+        self.count[0] -= 1;
+        for i in (0..self.bits).rev() {
+            k = self.child[k][self.bit(x, i)] as usize;
+            self.count[k] -= 1;
+        }
+        true
+    }
+
+    /// XORs every element with `m`.
+    pub fn xor_all(&mut self, m: u64) {
+        self.mask ^= m;
+    }
+
+    /// Returns the element `y` minimizing `x ^ y`, or `None` if the trie is empty.
+    pub fn min_xor(&self, x: u64) -> Option<u64> {
+        if self.is_empty() {
+            return None;
+        }
+        let x = x ^ self.mask;
+        let (mut k, mut y) = (0, 0);
+        for i in (0..self.bits).rev() {
+            let mut b = self.bit(x, i);
+            let c = self.child[k][b] as usize;
+            if c == 0 || self.count[c] == 0 {
+                b ^= 1;
+            }
+            y |= (b as u64) << i;
+            k = self.child[k][b] as usize;
+        }
+        Some(y ^ self.mask)
+    }
+
+    /// Returns the element `y` maximizing `x ^ y`, or `None` if the trie is empty.
+    pub fn max_xor(&self, x: u64) -> Option<u64> {
+        let full = if self.bits == 64 {
+            !0
+        } else {
+            (1 << self.bits) - 1
+        };
+        self.min_xor(x ^ full)
+    }
+
+    /// Returns the `k`-th smallest element (0-indexed), or `None` if there are at most `k` elements.
+    pub fn kth(&self, mut k: usize) -> Option<u64> {
+        if k >= self.len() {
+            return None;
+        }
+        let (mut node, mut y) = (0, 0);
+        for i in (0..self.bits).rev() {
+            let b = self.bit(self.mask, i);
+            let c = self.child[node][b] as usize;
+            let cnt = if c == 0 { 0 } else { self.count[c] as usize };
+            let b = if k < cnt {
+                b
+            } else {
+                k -= cnt;
+                b ^ 1
+            };
+            y |= (b as u64) << i;
+            node = self.child[node][b] as usize;
+        }
+        Some(y ^ self.mask)
+    }
+
+    /// Returns the number of elements less than `x`.
+    pub fn count_less(&self, x: u64) -> usize {
+        let (mut node, mut less) = (0, 0);
+        for i in (0..self.bits).rev() {
+            let (m, b) = (self.bit(self.mask, i), self.bit(x, i));
+            if b == 1 {
+                let c = self.child[node][m] as usize;
+                if c != 0 {
+                    less += self.count[c] as usize;
+                }
+            }
+            node = self.child[node][m ^ b] as usize;
+            if node == 0 {
+                break;
+            }
+        }
+        less
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+
+    #[test]
+    fn matches_naive() {
+        let mut trie = BinaryTrie::new(10);
+        let mut naive: Vec<u64> = Vec::new();
+        let mut rng = TestRng::new(7);
+        for _ in 0..2000 {
+            let x = rng.below(1024);
+            match rng.below(8) {
+                0..=2 => {
+                    trie.insert(x);
+                    naive.push(x);
+                }
+                3 | 4 => {
+                    let pos = naive.iter().position(|&y| y == x);
+                    assert_eq!(pos.is_some(), trie.remove(x));
+                    if let Some(p) = pos {
+                        naive.swap_remove(p);
+                    }
+                }
+                5 => {
+                    trie.xor_all(x);
+                    naive.iter_mut().for_each(|y| *y ^= x);
+                }
+                _ => {
+                    assert_eq!(
+                        naive.iter().map(|y| x ^ y).max(),
+                        trie.max_xor(x).map(|y| x ^ y)
+                    );
+                    assert_eq!(
+                        naive.iter().map(|y| x ^ y).min(),
+                        trie.min_xor(x).map(|y| x ^ y)
+                    );
+                    assert_eq!(naive.iter().filter(|&&y| y < x).count(), trie.count_less(x));
+                    assert_eq!(naive.iter().filter(|&&y| y == x).count(), trie.count(x));
+                }
+            }
+            let mut sorted = naive.clone();
+            sorted.sort();
+            let k = rng.index(sorted.len() + 1);
+            assert_eq!(sorted.get(k).copied(), trie.kth(k));
+            assert_eq!(naive.len(), trie.len());
+        }
+        let mut wide = BinaryTrie::new(64);
+        wide.insert(u64::MAX);
+        wide.insert(5);
+        assert_eq!(Some(5), wide.max_xor(u64::MAX));
+    }
+}
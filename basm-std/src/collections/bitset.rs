@@ -0,0 +1,324 @@
+use super::segment_tree::bounds;
+use alloc::vec::Vec;
+use core::ops::{BitAndAssign, BitOrAssign, BitXorAssign, RangeBounds, ShlAssign, ShrAssign};
+
+mod simd {
+    #[cfg_attr(
+        any(target_arch = "x86_64", target_arch = "x86"),
+        target_feature(enable = "avx2")
+    )]
+    pub unsafe fn and(a: &mut [u64], b: &[u64]) {
+        a.iter_mut().zip(b).for_each(|(x, y)| *x &= y);
+    }
+    #[cfg_attr(
+        any(target_arch = "x86_64", target_arch = "x86"),
+        target_feature(enable = "avx2")
+    )]
+    pub unsafe fn or(a: &mut [u64], b: &[u64]) {
+        a.iter_mut().zip(b).for_each(|(x, y)| *x |= y);
+    }
+    #[cfg_attr(
+        any(target_arch = "x86_64", target_arch = "x86"),
+        target_feature(enable = "avx2")
+    )]
+    pub unsafe fn xor(a: &mut [u64], b: &[u64]) {
+        a.iter_mut().zip(b).for_each(|(x, y)| *x ^= y);
+    }
+    #[cfg_attr(
+        any(target_arch = "x86_64", target_arch = "x86"),
+        target_feature(enable = "avx2,popcnt")
+    )]
+    pub unsafe fn popcount(a: &[u64]) -> usize {
+        a.iter().map(|x| x.count_ones() as usize).sum()
+    }
+}
+
+/// A fixed-length set of bits stored in 64-bit words.
+///
+/// The bitwise operators (`&=`, `|=`, `^=`, `<<=`, `>>=`) work a word at a time and are compiled
+/// with AVX2 enabled on x86, as is [`BitSet::count_ones`]. Shifting left moves bit `i` to bit `i + k`,
+/// and bits shifted past the end are dropped.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    /// Creates a set of `len` bits, all zero.
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: alloc::vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the underlying words. The bits past `len()` are always zero.
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    fn trim(&mut self) {
+        if self.len & 63 != 0 {
+            *self.words.last_mut().unwrap() &= (1 << (self.len % 64)) - 1;
+        }
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len);
+        self.words[i / 64] >> (i % 64) & 1 != 0
+    }
+
+    pub fn set(&mut self, i: usize) {
+        assert!(i < self.len);
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    pub fn reset(&mut self, i: usize) {
+        assert!(i < self.len);
+        self.words[i / 64] &= !(1 << (i % 64));
+    }
+
+    pub fn flip(&mut self, i: usize) {
+        assert!(i < self.len);
+        self.words[i / 64] ^= 1 << (i % 64);
+    }
+
+    /// Sets every bit to zero.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Returns the number of ones.
+    pub fn count_ones(&self) -> usize {
+        unsafe { simd::popcount(&self.words) }
+    }
+
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&w| w != 0)
+    }
+
+    /// Calls `f` with each word overlapping `range` and the mask of the bits of the word in `range`.
+    fn for_range<B: RangeBounds<usize>>(&mut self, range: B, mut f: impl FnMut(&mut u64, u64)) {
+        let (l, r) = bounds(range, self.len);
+        if l == r {
+            return;
+        }
+        let (lw, rw) = (l / 64, (r - 1) / 64);
+        for w in lw..=rw {
+            let mut mask = !0u64;
+            if w == lw {
+                mask &= !0 << (l % 64);
+            }
+            if w == rw {
+                mask &= !0 >> (63 - (r - 1) % 64);
+            }
+            f(&mut self.words[w], mask);
+        }
+    }
+
+    /// Sets the bits in `range` to one.
+    pub fn set_range<B: RangeBounds<usize>>(&mut self, range: B) {
+        self.for_range(range, |w, m| *w |= m);
+    }
+
+    /// Sets the bits in `range` to zero.
+    pub fn reset_range<B: RangeBounds<usize>>(&mut self, range: B) {
+        self.for_range(range, |w, m| *w &= !m);
+    }
+
+    /// Flips the bits in `range`.
+    pub fn flip_range<B: RangeBounds<usize>>(&mut self, range: B) {
+        self.for_range(range, |w, m| *w ^= m);
+    }
+
+    /// Returns the number of ones in `range`.
+    pub fn count_ones_range<B: RangeBounds<usize>>(&self, range: B) -> usize {
+        let (l, r) = bounds(range, self.len);
+        if l == r {
+            return 0;
+        }
+        let (lw, rw) = (l / 64, (r - 1) / 64);
+        let lmask = !0u64 << (l % 64);
+        let rmask = !0u64 >> (63 - (r - 1) % 64);
+        if lw == rw {
+            return (self.words[lw] & lmask & rmask).count_ones() as usize;
+        }
+        (self.words[lw] & lmask).count_ones() as usize
+            + unsafe { simd::popcount(&self.words[lw + 1..rw]) }
+            + (self.words[rw] & rmask).count_ones() as usize
+    }
+
+    /// Returns the smallest `j >= i` such that bit `j` is one.
+    pub fn next_one(&self, i: usize) -> Option<usize> {
+        if i >= self.len {
+            return None;
+        }
+        let mut w = i / 64;
+        let mut word = self.words[w] & (!0 << (i % 64));
+        loop {
+            if word != 0 {
+                return Some(w * 64 + word.trailing_zeros() as usize);
+            }
+            w += 1;
+            if w == self.words.len() {
+                return None;
+            }
+            word = self.words[w];
+        }
+    }
+
+    /// Returns the smallest `i` such that bit `i` is one.
+    pub fn first_one(&self) -> Option<usize> {
+        self.next_one(0)
+    }
+
+    /// Returns an iterator over the positions of the ones in increasing order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, &word)| {
+            let mut word = word;
+            core::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let b = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(w * 64 + b)
+            })
+        })
+    }
+}
+
+impl BitAndAssign<&BitSet> for BitSet {
+    fn bitand_assign(&mut self, rhs: &BitSet) {
+        assert_eq!(self.len, rhs.len);
+        unsafe { simd::and(&mut self.words, &rhs.words) }
+    }
+}
+
+impl BitOrAssign<&BitSet> for BitSet {
+    fn bitor_assign(&mut self, rhs: &BitSet) {
+        assert_eq!(self.len, rhs.len);
+        unsafe { simd::or(&mut self.words, &rhs.words) }
+    }
+}
+
+impl BitXorAssign<&BitSet> for BitSet {
+    fn bitxor_assign(&mut self, rhs: &BitSet) {
+        assert_eq!(self.len, rhs.len);
+        unsafe { simd::xor(&mut self.words, &rhs.words) }
+    }
+}
+
+impl ShlAssign<usize> for BitSet {
+    fn shl_assign(&mut self, k: usize) {
+        let n = self.words.len();
+        let (q, r) = (k / 64, k % 64);
+        if q >= n {
+            self.clear();
+            return;
+        }
+        for i in (q..n).rev() {
+            let mut w = self.words[i - q] << r;
+            if r != 0 && i > q {
+                w |= self.words[i - q - 1] >> (64 - r);
+            }
+            self.words[i] = w;
+        }
+        self.words[..q].fill(0);
+        self.trim();
+    }
+}
+
+impl ShrAssign<usize> for BitSet {
+    fn shr_assign(&mut self, k: usize) {
+        let n = self.words.len();
+        let (q, r) = (k / 64, k % 64);
+        if q >= n {
+            self.clear();
+            return;
+        }
+        for i in 0..n - q {
+            let mut w = self.words[i + q] >> r;
+            if r != 0 && i + q + 1 < n {
+                w |= self.words[i + q + 1] << (64 - r);
+            }
+            self.words[i] = w;
+        }
+        self.words[n - q..].fill(0);
+    }
+}
+
+impl core::fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.ones()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn naive(b: &BitSet) -> Vec<bool> {
+        (0..b.len()).map(|i| b.get(i)).collect()
+    }
+
+    #[test]
+    fn shifts_and_ranges_match_naive() {
+        for len in [1, 63, 64, 65, 200] {
+            let mut b = BitSet::new(len);
+            for i in (0..len).filter(|i| i % 3 == 0 || i % 7 == 2) {
+                b.set(i);
+            }
+            let v = naive(&b);
+            for k in [0, 1, 5, 63, 64, 65, 130, 300] {
+                let mut s = b.clone();
+                s <<= k;
+                let expected: Vec<bool> = (0..len).map(|i| i >= k && v[i - k]).collect();
+                assert_eq!(expected, naive(&s));
+                assert_eq!(expected.iter().filter(|&&x| x).count(), s.count_ones());
+                let mut s = b.clone();
+                s >>= k;
+                let expected: Vec<bool> = (0..len).map(|i| i + k < len && v[i + k]).collect();
+                assert_eq!(expected, naive(&s));
+            }
+            for l in 0..len.min(70) {
+                for r in (l..=len).step_by(7) {
+                    let ones = v[l..r].iter().filter(|&&x| x).count();
+                    assert_eq!(ones, b.count_ones_range(l..r));
+                    let mut s = b.clone();
+                    s.flip_range(l..r);
+                    let expected: Vec<bool> = (0..len).map(|i| v[i] ^ (l <= i && i < r)).collect();
+                    assert_eq!(expected, naive(&s));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bitwise_ops_and_iteration() {
+        let mut a = BitSet::new(130);
+        let mut b = BitSet::new(130);
+        a.set_range(10..100);
+        b.set_range(90..130);
+        let mut c = a.clone();
+        c &= &b;
+        assert_eq!((90..100).collect::<Vec<_>>(), c.ones().collect::<Vec<_>>());
+        c |= &b;
+        assert_eq!(40, c.count_ones());
+        c ^= &a;
+        assert_eq!(Some(10), c.first_one());
+        assert_eq!(Some(100), c.next_one(90));
+        c.reset_range(..);
+        assert!(!c.any());
+        assert_eq!(None, c.first_one());
+    }
+}
@@ -0,0 +1,165 @@
+use alloc::vec::Vec;
+
+/// A convex hull trick container for lines `y = a * x + b` added in monotone order of slopes,
+/// answering the minimum or the maximum at a point.
+///
+/// For [`ConvexHullTrick::new_min`] the slopes must be non-increasing, and for
+/// [`ConvexHullTrick::new_max`] non-decreasing. Adding a line takes amortized O(1) time.
+/// [`query`](ConvexHullTrick::query) takes O(log n) for arbitrary `x`, and
+/// [`query_monotone`](ConvexHullTrick::query_monotone) amortized O(1) for non-decreasing `x`.
+///
+/// The hull is maintained with `i128` cross products, so any `i64` coefficients are safe
+/// there; the values `a * x + b` themselves must fit in an `i64`. For slopes in arbitrary order,
+/// use [`LiChaoTree`](super::LiChaoTree).
+pub struct ConvexHullTrick {
+    lines: Vec<(i64, i64)>,
+    ptr: usize,
+    max: bool,
+}
+
+impl ConvexHullTrick {
+    pub fn new_min() -> Self {
+        Self {
+            lines: Vec::new(),
+            ptr: 0,
+            max: false,
+        }
+    }
+
+    pub fn new_max() -> Self {
+        Self {
+            lines: Vec::new(),
+            ptr: 0,
+            max: true,
+        }
+    }
+
+    /// Returns the number of lines on the hull.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Returns `true` if `m` is not below the lower envelope of `l` and `r`, where the slopes
+    /// are non-increasing in the order `l`, `m`, `r`.
+    fn useless(l: (i64, i64), m: (i64, i64), r: (i64, i64)) -> bool {
+        let (a1, b1) = (l.0 as i128, l.1 as i128);
+        let (a2, b2) = (m.0 as i128, m.1 as i128);
+        let (a3, b3) = (r.0 as i128, r.1 as i128);
+        (b3 - b1) * (a1 - a2) <= (b2 - b1) * (a1 - a3)
+    }
+
+    /// Adds the line `y = a * x + b`. A runtime error will occur if the slope order is violated.
+    pub fn add_line(&mut self, a: i64, b: i64) {
+        // Maxima are kept as minima of the negated lines.
+        let line = if self.max { (-a, -b) } else { (a, b) };
+        if let Some(&last) = self.lines.last() {
+            assert!(line.0 <= last.0, "ConvexHullTrick: slopes are not monotone");
+            if line.0 == last.0 {
+                if line.1 >= last.1 {
+                    return;
+                }
+                self.lines.pop();
+            }
+        }
+        while self.lines.len() >= 2
+            && Self::useless(
+                self.lines[self.lines.len() - 2],
+                self.lines[self.lines.len() - 1],
+                line,
+            )
+        {
+            self.lines.pop();
+        }
+        self.lines.push(line);
+        self.ptr = self.ptr.min(self.lines.len() - 1);
+    }
+
+    fn eval(&self, i: usize, x: i64) -> i64 {
+        let (a, b) = self.lines[i];
+        a * x + b
+    }
+
+    fn result(&self, y: i64) -> i64 {
+        if self.max {
+            -y
+        } else {
+            y
+        }
+    }
+
+    /// Returns the minimum (or the maximum) of the lines at `x`, or `None` if there is no line.
+    pub fn query(&self, x: i64) -> Option<i64> {
+        if self.lines.is_empty() {
+            return None;
+        }
+        let (mut lo, mut hi) = (0, self.lines.len() - 1);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if self.eval(mid, x) >= self.eval(mid + 1, x) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        Some(self.result(self.eval(lo, x)))
+    }
+
+    /// Same as [`ConvexHullTrick::query`], but `x` must not decrease between calls.
+    pub fn query_monotone(&mut self, x: i64) -> Option<i64> {
+        if self.lines.is_empty() {
+            return None;
+        }
+        while self.ptr + 1 < self.lines.len()
+            && self.eval(self.ptr, x) >= self.eval(self.ptr + 1, x)
+        {
+            self.ptr += 1;
+        }
+        Some(self.result(self.eval(self.ptr, x)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matches_naive() {
+        for max in [false, true] {
+            let mut lines: Vec<(i64, i64)> = (0..60)
+                .map(|i| ((i * 37 % 41) - 20, (i * i * 13 % 97) - 48))
+                .collect();
+            lines.sort_by_key(|&(a, _)| if max { a } else { -a });
+            let mut cht = if max {
+                ConvexHullTrick::new_max()
+            } else {
+                ConvexHullTrick::new_min()
+            };
+            for (k, &(a, b)) in lines.iter().enumerate() {
+                cht.add_line(a, b);
+                let ys = |x: i64| lines[..=k].iter().map(move |&(a, b)| a * x + b);
+                for x in -30..30 {
+                    let expected = if max { ys(x).max() } else { ys(x).min() };
+                    assert_eq!(expected, cht.query(x));
+                }
+            }
+            for x in -30..30 {
+                assert_eq!(cht.query(x), cht.query_monotone(x));
+            }
+        }
+    }
+
+    #[test]
+    fn large_coefficients_do_not_overflow_the_hull() {
+        let mut cht = ConvexHullTrick::new_min();
+        cht.add_line(1_000_000_000, -4_000_000_000_000_000_000);
+        cht.add_line(0, 0);
+        cht.add_line(-1_000_000_000, -4_000_000_000_000_000_000);
+        assert_eq!(2, cht.len());
+        assert_eq!(Some(-4_000_000_000_000_000_000), cht.query(0));
+        assert_eq!(None, ConvexHullTrick::new_max().query(0));
+    }
+}
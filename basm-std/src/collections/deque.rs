@@ -0,0 +1,303 @@
+use alloc::boxed::Box;
+use core::mem::MaybeUninit;
+use core::ops::{Index, IndexMut};
+
+macro_rules! deque_methods {
+    () => {
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        /// Returns the number of elements the deque can hold without growing.
+        pub fn capacity(&self) -> usize {
+            self.data.len()
+        }
+
+        fn slot(&self, i: usize) -> usize {
+            let j = self.head + i;
+            if j >= self.data.len() {
+                j - self.data.len()
+            } else {
+                j
+            }
+        }
+
+        pub fn push_back(&mut self, x: T) {
+            self.reserve_one();
+            let j = self.slot(self.len);
+            self.data[j].write(x);
+            self.len += 1;
+        }
+
+        pub fn push_front(&mut self, x: T) {
+            self.reserve_one();
+            self.head = self.slot(self.data.len() - 1);
+            self.data[self.head].write(x);
+            self.len += 1;
+        }
+
+        pub fn pop_back(&mut self) -> Option<T> {
+            if self.len == 0 {
+                return None;
+            }
+            self.len -= 1;
+            let j = self.slot(self.len);
+            Some(unsafe { self.data[j].assume_init_read() })
+        }
+
+        pub fn pop_front(&mut self) -> Option<T> {
+            if self.len == 0 {
+                return None;
+            }
+            let x = unsafe { self.data[self.head].assume_init_read() };
+            self.head = self.slot(1);
+            self.len -= 1;
+            Some(x)
+        }
+
+        pub fn get(&self, i: usize) -> Option<&T> {
+            if i < self.len {
+                Some(unsafe { self.data[self.slot(i)].assume_init_ref() })
+            } else {
+                None
+            }
+        }
+
+        pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+            if i < self.len {
+                let j = self.slot(i);
+                Some(unsafe { self.data[j].assume_init_mut() })
+            } else {
+                None
+            }
+        }
+
+        pub fn front(&self) -> Option<&T> {
+            self.get(0)
+        }
+
+        pub fn back(&self) -> Option<&T> {
+            self.len.checked_sub(1).and_then(|i| self.get(i))
+        }
+
+        pub fn clear(&mut self) {
+            while self.pop_back().is_some() {}
+            self.head = 0;
+        }
+
+        /// Returns an iterator over the elements from front to back.
+        pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+            (0..self.len).map(move |i| unsafe { self.data[self.slot(i)].assume_init_ref() })
+        }
+    };
+}
+
+/// A double-ended queue of capacity `N` backed by an inline ring buffer.
+///
+/// It never allocates, which makes it a cheap BFS queue when the number of vertices is bounded.
+/// Pushing to a full `FixedDeque` panics.
+pub struct FixedDeque<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> FixedDeque<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            data: [const { MaybeUninit::uninit() }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    fn reserve_one(&mut self) {
+        assert!(self.len < N, "FixedDeque is full");
+    }
+
+    deque_methods!();
+}
+
+impl<T, const N: usize> Default for FixedDeque<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for FixedDeque<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// A growable double-ended queue backed by a heap-allocated ring buffer.
+///
+/// Unlike `alloc::collections::VecDeque`, its capacity is always a power of two, and the hot
+/// `push_back`/`pop_front` pair compiles to a few instructions without a slow path per call.
+pub struct FastDeque<T> {
+    data: Box<[MaybeUninit<T>]>,
+    head: usize,
+    len: usize,
+}
+
+impl<T> FastDeque<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty deque that can hold at least `capacity` elements without growing.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Self::buffer(capacity.max(4).next_power_of_two()),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn buffer(cap: usize) -> Box<[MaybeUninit<T>]> {
+        (0..cap).map(|_| MaybeUninit::uninit()).collect()
+    }
+
+    #[inline]
+    fn reserve_one(&mut self) {
+        if self.len == self.data.len() {
+            self.grow();
+        }
+    }
+
+    #[cold]
+    fn grow(&mut self) {
+        let mut data = Self::buffer(self.data.len() * 2);
+        for (i, x) in data.iter_mut().take(self.len).enumerate() {
+            *x = MaybeUninit::new(unsafe { self.data[self.slot(i)].assume_init_read() });
+        }
+        self.data = data;
+        self.head = 0;
+    }
+
+    deque_methods!();
+}
+
+impl<T> Default for FastDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for FastDeque<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> FromIterator<T> for FastDeque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = Self::new();
+        for x in iter {
+            deque.push_back(x);
+        }
+        deque
+    }
+}
+
+impl<T, const N: usize> Index<usize> for FixedDeque<T, N> {
+    type Output = T;
+    fn index(&self, i: usize) -> &T {
+        self.get(i).expect("index out of bounds")
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for FixedDeque<T, N> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        self.get_mut(i).expect("index out of bounds")
+    }
+}
+
+impl<T> Index<usize> for FastDeque<T> {
+    type Output = T;
+    fn index(&self, i: usize) -> &T {
+        self.get(i).expect("index out of bounds")
+    }
+}
+
+impl<T> IndexMut<usize> for FastDeque<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        self.get_mut(i).expect("index out of bounds")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::collections::VecDeque;
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn matches_vec_deque() {
+        let mut fixed = FixedDeque::<u32, 5>::new();
+        let mut fast = FastDeque::new();
+        let mut naive = VecDeque::new();
+        let mut rng = TestRng::new(9);
+        for i in 0..1000 {
+            match rng.below(4) {
+                0 if naive.len() < 5 => {
+                    fixed.push_back(i);
+                    fast.push_back(i);
+                    naive.push_back(i);
+                }
+                1 if naive.len() < 5 => {
+                    fixed.push_front(i);
+                    fast.push_front(i);
+                    naive.push_front(i);
+                }
+                2 => {
+                    let x = naive.pop_back();
+                    assert_eq!(x, fixed.pop_back());
+                    assert_eq!(x, fast.pop_back());
+                }
+                _ => {
+                    let x = naive.pop_front();
+                    assert_eq!(x, fixed.pop_front());
+                    assert_eq!(x, fast.pop_front());
+                }
+            }
+            assert!(naive.iter().eq(fixed.iter()) && naive.iter().eq(fast.iter()));
+            assert_eq!(naive.back(), fast.back());
+        }
+        let mut big: FastDeque<usize> = (0..100).collect();
+        big.push_front(1000);
+        big[1] = 7;
+        assert_eq!((101, 1000, 7, 99), (big.len(), big[0], big[1], big[100]));
+        assert!(big.iter().rev().take(3).copied().eq([99, 98, 97]));
+    }
+
+    #[test]
+    fn drops_elements_exactly_once() {
+        let rc = Rc::new(());
+        {
+            let mut fixed = FixedDeque::<Rc<()>, 4>::new();
+            let mut fast = FastDeque::with_capacity(1);
+            for _ in 0..3 {
+                fixed.push_front(rc.clone());
+                fast.push_front(rc.clone());
+            }
+            for _ in 0..10 {
+                fast.push_back(rc.clone());
+            }
+            drop(fixed.pop_back());
+            let _: Vec<_> = (0..2).map(|_| fast.pop_front()).collect();
+            assert_eq!(1 + 2 + 11, Rc::strong_count(&rc));
+        }
+        assert_eq!(1, Rc::strong_count(&rc));
+    }
+}
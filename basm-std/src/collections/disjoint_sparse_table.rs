@@ -0,0 +1,92 @@
+use super::segment_tree::bounds;
+use alloc::vec::Vec;
+
+/// A disjoint sparse table answering range queries over an immutable array in O(1),
+/// after O(n log n) preprocessing.
+///
+/// Unlike [`SparseTable`](super::SparseTable), `op` only needs to be associative,
+/// so sums and products modulo a prime can be queried as well.
+pub struct DisjointSparseTable<T, Op> {
+    a: Vec<T>,
+    table: Vec<Vec<T>>,
+    op: Op,
+}
+
+impl<T: Clone, Op: Fn(&T, &T) -> T> DisjointSparseTable<T, Op> {
+    /// Example: `DisjointSparseTable::new(&a, |x, y| x * y % P)`
+    pub fn new(a: &[T], op: Op) -> Self {
+        let n = a.len();
+        let levels = if n < 2 {
+            0
+        } else {
+            (n - 1).ilog2() as usize + 1
+        };
+        let mut table = Vec::with_capacity(levels);
+        for h in 0..levels {
+            let half = 1 << h;
+            let mut row = a.to_vec();
+            for mid in (half..n).step_by(2 * half) {
+                for i in (mid - half..mid - 1).rev() {
+                    row[i] = op(&a[i], &row[i + 1]);
+                }
+                for i in mid + 1..(mid + half).min(n) {
+                    row[i] = op(&row[i - 1], &a[i]);
+                }
+            }
+            table.push(row);
+        }
+        Self {
+            a: a.to_vec(),
+            table,
+            op,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.a.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.a.is_empty()
+    }
+
+    /// Returns the result of `op` over the elements in `range`, from left to right.
+    /// A runtime error will occur if `range` is empty.
+    pub fn query<B>(&self, range: B) -> T
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = bounds(range, self.len());
+        assert!(l < r, "DisjointSparseTable: empty range");
+        let r = r - 1;
+        if l == r {
+            return self.a[l].clone();
+        }
+        let row = &self.table[(l ^ r).ilog2() as usize];
+        (self.op)(&row[l], &row[r])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn queries_match_naive() {
+        const P: u64 = 998_244_353;
+        for n in [1, 2, 3, 8, 13] {
+            let a: Vec<u64> = (0..n).map(|i| (i * 7919 + 13) % P).collect();
+            let prod = DisjointSparseTable::new(&a, |x, y| x * y % P);
+            let s: Vec<String> = a.iter().map(|x| alloc::format!("{},", x)).collect();
+            let concat = DisjointSparseTable::new(&s, |x, y| x.clone() + y);
+            for l in 0..a.len() {
+                for r in l + 1..=a.len() {
+                    let p = a[l..r].iter().fold(1, |p, x| p * x % P);
+                    assert_eq!(p, prod.query(l..r));
+                    assert_eq!(s[l..r].concat(), concat.query(l..r));
+                }
+            }
+        }
+    }
+}
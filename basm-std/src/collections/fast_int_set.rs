@@ -0,0 +1,186 @@
+use alloc::vec::Vec;
+
+/// A set of integers in `0..n`, stored as a tree of 64-bit words where each bit of a word
+/// records whether the word below it is nonzero.
+///
+/// With `n` up to 2^24 the tree has four levels, so `insert`, `remove`, `next` and `prev`
+/// touch at most a handful of words each and are much faster than a `BTreeSet`.
+#[derive(Clone)]
+pub struct FastIntSet {
+    levels: Vec<Vec<u64>>,
+    n: usize,
+    count: usize,
+}
+
+impl FastIntSet {
+    /// Creates an empty set over the universe `0..n`.
+    pub fn new(n: usize) -> Self {
+        let mut levels = Vec::new();
+        let mut words = n.div_ceil(64).max(1);
+        loop {
+            levels.push(alloc::vec![0u64; words]);
+            if words == 1 {
+                break;
+            }
+            words = words.div_ceil(64);
+        }
+        Self {
+            levels,
+            n,
+            count: 0,
+        }
+    }
+
+    /// Returns the size of the universe.
+    pub fn universe(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn contains(&self, x: usize) -> bool {
+        x < self.n && self.levels[0][x >> 6] >> (x & 63) & 1 != 0
+    }
+
+    /// Adds `x` to the set. Returns `true` if it was not present.
+    pub fn insert(&mut self, x: usize) -> bool {
+        assert!(x < self.n, "value out of bounds");
+        if self.contains(x) {
+            return false;
+        }
+        let mut x = x;
+        for level in self.levels.iter_mut() {
+            let word = &mut level[x >> 6];
+            let was_empty = *word == 0;
+            *word |= 1 << (x & 63);
+            if !was_empty {
+                break;
+            }
+            x >>= 6;
+        }
+        self.count += 1;
+        true
+    }
+
+    /// Removes `x` from the set. Returns `true` if it was present.
+    pub fn remove(&mut self, x: usize) -> bool {
+        if !self.contains(x) {
+            return false;
+        }
+        let mut x = x;
+        for level in self.levels.iter_mut() {
+            let word = &mut level[x >> 6];
+            *word &= !(1 << (x & 63));
+            if *word != 0 {
+                break;
+            }
+            x >>= 6;
+        }
+        self.count -= 1;
+        true
+    }
+
+    /// Returns the smallest element that is at least `x`.
+    pub fn next(&self, x: usize) -> Option<usize> {
+        let mut cur = x;
+        for (i, level) in self.levels.iter().enumerate() {
+            let w = cur >> 6;
+            if w >= level.len() {
+                return None;
+            }
+            let mask = level[w] & (!0 << (cur & 63));
+            if mask != 0 {
+                cur = w << 6 | mask.trailing_zeros() as usize;
+                for level in self.levels[..i].iter().rev() {
+                    cur = cur << 6 | level[cur].trailing_zeros() as usize;
+                }
+                return Some(cur);
+            }
+            cur = w + 1;
+        }
+        None
+    }
+
+    /// Returns the largest element that is at most `x`.
+    pub fn prev(&self, x: usize) -> Option<usize> {
+        if self.n == 0 {
+            return None;
+        }
+        let mut cur = x.min(self.n - 1);
+        for (i, level) in self.levels.iter().enumerate() {
+            let w = cur >> 6;
+            let mask = level[w] & (!0 >> (63 - (cur & 63)));
+            if mask != 0 {
+                cur = w << 6 | (63 - mask.leading_zeros() as usize);
+                for level in self.levels[..i].iter().rev() {
+                    cur = cur << 6 | (63 - level[cur].leading_zeros() as usize);
+                }
+                return Some(cur);
+            }
+            if w == 0 {
+                return None;
+            }
+            cur = w - 1;
+        }
+        None
+    }
+
+    /// Returns the smallest element.
+    pub fn min(&self) -> Option<usize> {
+        self.next(0)
+    }
+
+    /// Returns the largest element.
+    pub fn max(&self) -> Option<usize> {
+        self.prev(usize::MAX)
+    }
+
+    /// Returns an iterator over the elements in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut cur = self.next(0);
+        core::iter::from_fn(move || {
+            let x = cur?;
+            cur = self.next(x + 1);
+            Some(x)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::collections::BTreeSet;
+
+    #[test]
+    fn matches_btree_set() {
+        let n = 300_000;
+        let mut set = FastIntSet::new(n);
+        let mut naive = BTreeSet::new();
+        let mut rng = TestRng::new(1);
+        for i in 0..20000 {
+            let x = rng.index(if i % 2 == 0 { n } else { 5000 });
+            match rng.below(4) {
+                0 | 1 => assert_eq!(naive.insert(x), set.insert(x)),
+                2 => assert_eq!(naive.remove(&x), set.remove(x)),
+                _ => {
+                    assert_eq!(naive.range(x..).next().copied(), set.next(x));
+                    assert_eq!(naive.range(..=x).next_back().copied(), set.prev(x));
+                }
+            }
+            assert_eq!(naive.len(), set.len());
+        }
+        assert_eq!(naive.first().copied(), set.min());
+        assert_eq!(naive.last().copied(), set.max());
+        assert!(naive.iter().copied().eq(set.iter()));
+        assert_eq!(None, FastIntSet::new(0).max());
+    }
+}
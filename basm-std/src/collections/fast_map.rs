@@ -0,0 +1,438 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash, Hasher};
+
+const MUL: u64 = 0x9e37_79b9_7f4a_7c15;
+
+fn mix(x: u64) -> u64 {
+    let m = x as u128 * MUL as u128;
+    (m as u64) ^ ((m >> 64) as u64)
+}
+
+/// A fast hasher for integer-like keys, processing eight bytes per multiplication.
+///
+/// On its own it is not resistant to crafted inputs; its strength comes from the random seed
+/// chosen by [`FastBuildHasher::new`].
+#[derive(Clone, Copy)]
+pub struct FastHasher {
+    state: u64,
+}
+
+impl Hasher for FastHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for c in &mut chunks {
+            self.write_u64(u64::from_le_bytes(c.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut buf = [0u8; 8];
+            buf[..rest.len()].copy_from_slice(rest);
+            self.write_u64(u64::from_le_bytes(buf) ^ (rest.len() as u64) << 59);
+        }
+    }
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i as u64);
+    }
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i as u64);
+    }
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i as u64);
+    }
+    fn write_u64(&mut self, i: u64) {
+        self.state = mix(self.state ^ i);
+    }
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+    fn finish(&self) -> u64 {
+        mix(self.state ^ MUL)
+    }
+}
+
+/// Creates [`FastHasher`]s with a seed drawn at runtime (from the timestamp counter and the stack address),
+/// so that anti-hash tests prepared in advance cannot target the table layout.
+#[derive(Clone, Copy)]
+pub struct FastBuildHasher {
+    seed: u64,
+}
+
+impl Default for FastBuildHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FastBuildHasher {
+    pub fn new() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        let t = unsafe { core::arch::x86_64::_rdtsc() };
+        #[cfg(target_arch = "x86")]
+        let t = unsafe { core::arch::x86::_rdtsc() };
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+        let t = 0u64;
+        let local = 0u8;
+        let addr = &local as *const u8 as u64;
+        Self::with_seed(mix(t) ^ addr.rotate_left(32))
+    }
+
+    /// Creates a deterministic instance, e.g., for reproducing a run.
+    pub const fn with_seed(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl BuildHasher for FastBuildHasher {
+    type Hasher = FastHasher;
+    fn build_hasher(&self) -> FastHasher {
+        FastHasher { state: self.seed }
+    }
+}
+
+/// A hash map with open addressing and Robin Hood linear probing, for `no_std` solutions.
+///
+/// `meta[i]` is 0 for an empty slot, and otherwise 1 plus the distance of the entry from its home slot.
+/// It is a `u32`, so it cannot overflow however long a probe sequence becomes.
+/// Since Robin Hood probing keeps these distances small, a lookup stops as soon as it meets an entry
+/// closer to its home than the probe, and removal shifts the following entries back instead of leaving
+/// tombstones. The table is kept at most 7/8 full.
+pub struct FastMap<K: Hash + Eq, V> {
+    meta: Vec<u32>,
+    slots: Vec<Option<(K, V)>>,
+    len: usize,
+    hasher: FastBuildHasher,
+}
+
+impl<K: Hash + Eq, V> Default for FastMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V> FastMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a map that can hold `capacity` entries without rehashing.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, FastBuildHasher::new())
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: FastBuildHasher) -> Self {
+        let buckets = (capacity + capacity / 7 + 1).next_power_of_two().max(8);
+        let mut slots = Vec::with_capacity(buckets);
+        slots.resize_with(buckets, || None);
+        Self {
+            meta: alloc::vec![0; buckets],
+            slots,
+            len: 0,
+            hasher,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of entries the map can hold without rehashing.
+    pub fn capacity(&self) -> usize {
+        self.slots.len() / 8 * 7
+    }
+
+    pub fn clear(&mut self) {
+        self.meta.fill(0);
+        self.slots.iter_mut().for_each(|s| *s = None);
+        self.len = 0;
+    }
+
+    fn mask(&self) -> usize {
+        self.slots.len() - 1
+    }
+
+    fn home<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        (self.hasher.hash_one(key) as usize) & self.mask()
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut i = self.home(key);
+        let mut dist = 1;
+        loop {
+            let m = self.meta[i];
+            if m < dist {
+                return None;
+            }
+            if m == dist {
+                let (k, _) = self.slots[i].as_ref().unwrap();
+                if k.borrow() == key {
+                    return Some(i);
+                }
+            }
+            i = (i + 1) & self.mask();
+            dist += 1;
+        }
+    }
+
+    fn grow(&mut self) {
+        let buckets = self.slots.len() * 2;
+        let old = core::mem::take(&mut self.slots);
+        self.meta = alloc::vec![0; buckets];
+        self.slots.resize_with(buckets, || None);
+        self.len = 0;
+        for (k, v) in old.into_iter().flatten() {
+            self.insert(k, v);
+        }
+    }
+
+    /// Inserts `val` with `key`, returning the previous value of `key` if any.
+    pub fn insert(&mut self, key: K, val: V) -> Option<V> {
+        if let Some(i) = self.find(&key) {
+            let (_, v) = self.slots[i].as_mut().unwrap();
+            return Some(core::mem::replace(v, val));
+        }
+        self.insert_new(key, val);
+        None
+    }
+
+    /// Inserts an entry whose key is not present, and returns its slot.
+    fn insert_new(&mut self, key: K, val: V) -> usize {
+        if self.len + 1 > self.capacity() {
+            self.grow();
+        }
+        let mut i = self.home(&key);
+        let mut dist = 1;
+        let mut carry = (key, val);
+        let mut placed = None;
+        loop {
+            let m = self.meta[i];
+            if m == 0 {
+                self.meta[i] = dist;
+                self.slots[i] = Some(carry);
+                self.len += 1;
+                return placed.unwrap_or(i);
+            }
+            if m < dist {
+                // Robin Hood: the entry closer to its home gives way.
+                self.meta[i] = dist;
+                carry = self.slots[i].replace(carry).unwrap();
+                dist = m;
+                placed.get_or_insert(i);
+            }
+            i = (i + 1) & self.mask();
+            dist += 1;
+        }
+    }
+
+    /// Returns the value of `key`, inserting `f()` first if it is not present.
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: K, f: F) -> &mut V {
+        let i = match self.find(&key) {
+            Some(i) => i,
+            None => self.insert_new(key, f()),
+        };
+        &mut self.slots[i].as_mut().unwrap().1
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).map(|i| &self.slots[i].as_ref().unwrap().1)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key)
+            .map(|i| &mut self.slots[i].as_mut().unwrap().1)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Removes `key`, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut i = self.find(key)?;
+        let (_, val) = self.slots[i].take().unwrap();
+        self.meta[i] = 0;
+        self.len -= 1;
+        // Backward shift: pull the following displaced entries one slot closer to their homes.
+        loop {
+            let j = (i + 1) & self.mask();
+            if self.meta[j] <= 1 {
+                break;
+            }
+            self.meta[i] = self.meta[j] - 1;
+            self.meta[j] = 0;
+            self.slots[i] = self.slots[j].take();
+            i = j;
+        }
+        Some(val)
+    }
+
+    /// Returns an iterator over the entries in an unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.slots.iter().flatten().map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over the entries in an unspecified order, with mutable values.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.slots.iter_mut().flatten().map(|(k, v)| (&*k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for FastMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (k, v) in iter {
+            map.insert(k, v);
+        }
+        map
+    }
+}
+
+/// A hash set based on [`FastMap`].
+pub struct FastSet<K: Hash + Eq> {
+    map: FastMap<K, ()>,
+}
+
+impl<K: Hash + Eq> Default for FastSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq> FastSet<K> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates a set that can hold `capacity` keys without rehashing.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: FastMap::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Returns `true` if `key` was not present.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Returns `true` if `key` was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(key).is_some()
+    }
+
+    /// Returns an iterator over the keys in an unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        self.map.keys()
+    }
+}
+
+impl<K: Hash + Eq> FromIterator<K> for FastSet<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for k in iter {
+            set.insert(k);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::collections::BTreeMap;
+    use alloc::string::String;
+
+    #[test]
+    fn matches_btree_map() {
+        let mut map = FastMap::with_capacity_and_hasher(0, FastBuildHasher::with_seed(1));
+        let mut btree = BTreeMap::new();
+        let mut rng = TestRng::new(1);
+        for step in 0..20000u64 {
+            // Multiples of a large power of two collide under weak hashes.
+            let key = rng.below(3000) << 20;
+            match rng.below(4) {
+                0 | 1 => assert_eq!(btree.insert(key, step), map.insert(key, step)),
+                2 => assert_eq!(btree.remove(&key), map.remove(&key)),
+                _ => assert_eq!(btree.get(&key), map.get(&key)),
+            }
+            assert_eq!(btree.len(), map.len());
+        }
+        let mut entries: alloc::vec::Vec<_> = map.iter().map(|(&k, &v)| (k, v)).collect();
+        entries.sort();
+        assert!(btree.into_iter().eq(entries));
+    }
+
+    #[test]
+    fn string_keys_and_counting() {
+        let words = ["a", "bb", "a", "ccc", "bb", "a"];
+        let mut count: FastMap<String, u32> = FastMap::new();
+        for w in words {
+            *count.get_or_insert_with(w.into(), || 0) += 1;
+        }
+        assert_eq!(Some(&3), count.get("a"));
+        assert_eq!(Some(&2), count.get("bb"));
+        assert_eq!(None, count.get("d"));
+        let set: FastSet<&str> = words.into_iter().collect();
+        assert_eq!(3, set.len());
+        assert!(set.contains("ccc"));
+        let map = FastMap::<u32, u32>::with_capacity(1000);
+        assert!(map.capacity() >= 1000);
+    }
+}
@@ -1,4 +1,5 @@
-use alloc::{vec, vec::Vec};
+use crate::platform::allocator::api::{self, Allocator, Global};
+use alloc::vec::Vec;
 
 pub trait FenwickOp {
     type T: Clone;
@@ -8,17 +9,36 @@ pub trait FenwickOp {
     fn apply(v: &mut Self::T, u: &Self::U);
 }
 
-pub struct FenwickTree<Op: FenwickOp> {
-    v: Vec<Op::T>,
+/// A Fenwick tree over `Op`. The nodes are stored with the allocator `A`
+/// (see [`api`](crate::platform::allocator::api)).
+pub struct FenwickTree<Op: FenwickOp, A: Allocator = Global> {
+    v: api::Vec<Op::T, A>,
 }
 
 impl<Op: FenwickOp> FenwickTree<Op> {
     pub fn new(n: usize) -> Self {
+        Self::new_in(n, Global)
+    }
+}
+
+impl<Op: FenwickOp, A: Allocator> FenwickTree<Op, A> {
+    /// Same as [`FenwickTree::new`], but the nodes are allocated with `alloc` (e.g., `&arena`).
+    pub fn new_in(n: usize, alloc: A) -> Self {
         Self {
-            v: vec![Op::e(); n],
+            v: api::vec_from_elem_in(Op::e(), n, alloc),
         }
     }
 
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.v.len()
+    }
+
+    /// Returns true if and only if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.v.is_empty()
+    }
+
     pub fn update<U>(&mut self, mut i: usize, u: U)
     where
         U: core::borrow::Borrow<Op::U>,
@@ -42,7 +62,8 @@ impl<Op: FenwickOp> FenwickTree<Op> {
 
 impl<Op: FenwickOp> FromIterator<Op::T> for FenwickTree<Op> {
     fn from_iter<T: IntoIterator<Item = Op::T>>(iter: T) -> Self {
-        let mut v: Vec<Op::T> = iter.into_iter().collect();
+        let v: Vec<Op::T> = iter.into_iter().collect();
+        let mut v = api::vec_from_vec(v);
         for i in 0..v.len() {
             let k = i | (i + 1);
             if k < v.len() {
@@ -52,3 +73,270 @@ impl<Op: FenwickOp> FromIterator<Op::T> for FenwickTree<Op> {
         Self { v }
     }
 }
+
+/// The [`FenwickOp`] of sums over `T` (integers or floats), where updates add to an element.
+pub struct FenwickSum<T>(core::marker::PhantomData<T>);
+
+impl<T> FenwickOp for FenwickSum<T>
+where
+    T: Copy + Default + core::ops::Add<Output = T> + core::ops::AddAssign,
+{
+    type T = T;
+    type U = T;
+    fn e() -> T {
+        T::default()
+    }
+    fn combine(l: &T, r: &T) -> T {
+        *l + *r
+    }
+    fn apply(v: &mut T, u: &T) {
+        *v += *u;
+    }
+}
+
+/// A Fenwick tree of sums, supporting point updates, range sums and k-th element descent
+/// in O(log n) without defining a [`FenwickOp`].
+pub type Fenwick<T> = FenwickTree<FenwickSum<T>>;
+
+impl<T, A: Allocator> FenwickTree<FenwickSum<T>, A>
+where
+    T: Copy + Default + PartialOrd + core::ops::Add<Output = T> + core::ops::AddAssign,
+    T: core::ops::SubAssign,
+{
+    /// Adds `x` to the `i`-th element.
+    pub fn add(&mut self, i: usize, x: T) {
+        self.update(i, x);
+    }
+
+    /// Returns the sum of the first `count` elements.
+    pub fn prefix_sum(&self, count: usize) -> T {
+        self.query(count)
+    }
+
+    /// Returns the sum of the elements in `range`.
+    pub fn range_sum<B>(&self, range: B) -> T
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        use core::ops::Bound::*;
+        let l = match range.start_bound() {
+            Included(&x) => x,
+            Excluded(&x) => x + 1,
+            Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Included(&x) => x + 1,
+            Excluded(&x) => x,
+            Unbounded => self.v.len(),
+        };
+        let mut result = self.query(r);
+        result -= self.query(l);
+        result
+    }
+
+    /// Returns the index of the `k`-th (0-based) unit when the `i`-th element is regarded as
+    /// the count of `i`s in a multiset, that is, the smallest `i` such that `prefix_sum(i + 1) > k`.
+    /// Returns `len()` if the sum of all elements is at most `k`.
+    ///
+    /// All elements must be nonnegative. Takes O(log n) time.
+    pub fn kth(&self, mut k: T) -> usize {
+        let n = self.v.len();
+        let mut pos = 0;
+        let mut step = if n == 0 { 0 } else { 1 << n.ilog2() };
+        while step > 0 {
+            if pos + step <= n && self.v[pos + step - 1] <= k {
+                k -= self.v[pos + step - 1];
+                pos += step;
+            }
+            step >>= 1;
+        }
+        pos
+    }
+}
+
+/// A two-dimensional Fenwick tree of sums over a `h` by `w` grid, supporting point updates and
+/// rectangle sums in O(log h log w). The cells are stored in a single `Vec`.
+pub struct Fenwick2D<T> {
+    v: Vec<T>,
+    h: usize,
+    w: usize,
+}
+
+impl<T> Fenwick2D<T>
+where
+    T: Copy + Default + core::ops::AddAssign + core::ops::SubAssign,
+{
+    /// Creates a `h` by `w` grid of zeros.
+    pub fn new(h: usize, w: usize) -> Self {
+        Self {
+            v: alloc::vec![T::default(); h * w],
+            h,
+            w,
+        }
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.h
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> usize {
+        self.w
+    }
+
+    fn update(&mut self, r: usize, c: usize, x: T, neg: bool) {
+        let mut i = r;
+        while i < self.h {
+            let row = &mut self.v[i * self.w..(i + 1) * self.w];
+            let mut j = c;
+            while j < self.w {
+                if neg {
+                    row[j] -= x;
+                } else {
+                    row[j] += x;
+                }
+                j |= j + 1;
+            }
+            i |= i + 1;
+        }
+    }
+
+    /// Adds `x` to the cell `(r, c)`.
+    pub fn add(&mut self, r: usize, c: usize, x: T) {
+        self.update(r, c, x, false);
+    }
+
+    /// Returns the sum of the cells `(i, j)` with `i < rows` and `j < cols`.
+    pub fn prefix_sum(&self, rows: usize, cols: usize) -> T {
+        let mut result = T::default();
+        let mut i = rows;
+        while i > 0 {
+            let row = &self.v[(i - 1) * self.w..i * self.w];
+            let mut j = cols;
+            while j > 0 {
+                result += row[j - 1];
+                j &= j - 1;
+            }
+            i &= i - 1;
+        }
+        result
+    }
+
+    /// Returns the sum of the cells in `rows` × `cols`.
+    pub fn rect_sum(&self, rows: core::ops::Range<usize>, cols: core::ops::Range<usize>) -> T {
+        let mut result = self.prefix_sum(rows.end, cols.end);
+        result -= self.prefix_sum(rows.start, cols.end);
+        result -= self.prefix_sum(rows.end, cols.start);
+        result += self.prefix_sum(rows.start, cols.start);
+        result
+    }
+}
+
+/// The range-update variant of [`Fenwick2D`]: adds a value to every cell of a rectangle and
+/// reads a single cell, in O(log h log w) each. It keeps a [`Fenwick2D`] of the differences.
+pub struct RangeAddFenwick2D<T> {
+    diff: Fenwick2D<T>,
+}
+
+impl<T> RangeAddFenwick2D<T>
+where
+    T: Copy + Default + core::ops::AddAssign + core::ops::SubAssign,
+{
+    /// Creates a `h` by `w` grid of zeros.
+    pub fn new(h: usize, w: usize) -> Self {
+        Self {
+            diff: Fenwick2D::new(h + 1, w + 1),
+        }
+    }
+
+    /// Adds `x` to every cell in `rows` × `cols`.
+    pub fn add(&mut self, rows: core::ops::Range<usize>, cols: core::ops::Range<usize>, x: T) {
+        self.diff.update(rows.start, cols.start, x, false);
+        self.diff.update(rows.start, cols.end, x, true);
+        self.diff.update(rows.end, cols.start, x, true);
+        self.diff.update(rows.end, cols.end, x, false);
+    }
+
+    /// Returns the value of the cell `(r, c)`.
+    pub fn get(&self, r: usize, c: usize) -> T {
+        self.diff.prefix_sum(r + 1, c + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+
+    #[test]
+    fn sums_match_naive() {
+        let mut a = [3i64, -1, 4, 1, -5, 9, 2, -6, 5, 3];
+        let mut fw: Fenwick<i64> = a.iter().copied().collect();
+        fw.add(4, 10);
+        a[4] += 10;
+        for l in 0..=a.len() {
+            assert_eq!(a[..l].iter().sum::<i64>(), fw.prefix_sum(l));
+            for r in l..=a.len() {
+                assert_eq!(a[l..r].iter().sum::<i64>(), fw.range_sum(l..r));
+            }
+        }
+        assert_eq!(a.iter().sum::<i64>(), fw.range_sum(..));
+        assert_eq!(a[2] + a[3], fw.range_sum(2..=3));
+    }
+
+    #[test]
+    fn kth_finds_order_statistics() {
+        let counts = [0u32, 2, 0, 1, 3, 0, 0];
+        let mut fw = Fenwick::new(counts.len());
+        for (i, &c) in counts.iter().enumerate() {
+            fw.add(i, c);
+        }
+        assert_eq!(counts.len(), fw.len());
+        let expected = [1, 1, 3, 4, 4, 4, 7, 7];
+        for (k, &e) in expected.iter().enumerate() {
+            assert_eq!(e, fw.kth(k as u32));
+        }
+        let empty = Fenwick::<u32>::new(0);
+        assert!(empty.is_empty());
+        assert_eq!(0, empty.kth(0));
+    }
+
+    #[test]
+    fn rect_sums_match_naive() {
+        let (h, w) = (7, 9);
+        let mut grid = [[0i64; 9]; 7];
+        let mut fw = Fenwick2D::new(h, w);
+        let mut ra = RangeAddFenwick2D::new(h, w);
+        let mut rng = TestRng::new(1);
+        for _ in 0..100 {
+            let (r, c) = (rng.index(h), rng.index(w));
+            let x = rng.below(10) as i64 - 5;
+            grid[r][c] += x;
+            fw.add(r, c, x);
+            ra.add(r..h, 0..c + 1, x);
+        }
+        for r0 in 0..=h {
+            for r1 in r0..=h {
+                for c0 in 0..=w {
+                    for c1 in c0..=w {
+                        let naive: i64 = grid[r0..r1]
+                            .iter()
+                            .map(|row| row[c0..c1].iter().sum::<i64>())
+                            .sum();
+                        assert_eq!(naive, fw.rect_sum(r0..r1, c0..c1));
+                    }
+                }
+            }
+        }
+        for r in 0..h {
+            for c in 0..w {
+                let naive: i64 = grid[..=r]
+                    .iter()
+                    .map(|row| row[c..].iter().sum::<i64>())
+                    .sum();
+                assert_eq!(naive, ra.get(r, c));
+            }
+        }
+    }
+}
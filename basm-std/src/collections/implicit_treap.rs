@@ -0,0 +1,301 @@
+use super::segment_tree::bounds;
+use super::treap::xorshift32;
+use super::Slab;
+use alloc::vec::Vec;
+use core::ops::RangeBounds;
+
+const NIL: u32 = Slab::<()>::NIL;
+
+struct Node {
+    val: i64,
+    sum: i64,
+    add: i64,
+    rev: bool,
+    pri: u32,
+    size: u32,
+    l: u32,
+    r: u32,
+}
+
+/// A sequence of `i64` based on an implicit-key treap, supporting insertion and removal at any position,
+/// range reverse, range add, range sum and moving a subarray elsewhere, each in expected O(log n).
+///
+/// The nodes live in a [`Slab`] and are linked with `u32` handles.
+pub struct ImplicitTreap {
+    nodes: Slab<Node>,
+    root: u32,
+    seed: u32,
+}
+
+impl Default for ImplicitTreap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImplicitTreap {
+    pub const fn new() -> Self {
+        Self {
+            nodes: Slab::new(),
+            root: NIL,
+            seed: 2463534242,
+        }
+    }
+
+    pub fn from_slice(a: &[i64]) -> Self {
+        let mut treap = Self::new();
+        for &x in a {
+            let t = treap.new_node(x);
+            treap.root = treap.merge(treap.root, t);
+        }
+        treap
+    }
+
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == NIL
+    }
+
+    fn new_node(&mut self, val: i64) -> u32 {
+        let pri = xorshift32(&mut self.seed);
+        self.nodes.insert(Node {
+            val,
+            sum: val,
+            add: 0,
+            rev: false,
+            pri,
+            size: 1,
+            l: NIL,
+            r: NIL,
+        })
+    }
+
+    fn size(&self, t: u32) -> usize {
+        if t == NIL {
+            0
+        } else {
+            self.nodes[t].size as usize
+        }
+    }
+
+    fn sum_of(&self, t: u32) -> i64 {
+        if t == NIL {
+            0
+        } else {
+            self.nodes[t].sum
+        }
+    }
+
+    fn pull(&mut self, t: u32) {
+        let (l, r) = (self.nodes[t].l, self.nodes[t].r);
+        let size = 1 + self.size(l) + self.size(r);
+        let sum = self.nodes[t].val + self.sum_of(l) + self.sum_of(r);
+        let node = &mut self.nodes[t];
+        node.size = size as u32;
+        node.sum = sum;
+    }
+
+    fn apply(&mut self, t: u32, add: i64, rev: bool) {
+        if t == NIL {
+            return;
+        }
+        let node = &mut self.nodes[t];
+        node.val += add;
+        node.sum += add * node.size as i64;
+        node.add += add;
+        node.rev ^= rev;
+    }
+
+    fn push(&mut self, t: u32) {
+        let node = &mut self.nodes[t];
+        let (add, rev) = (node.add, node.rev);
+        if add == 0 && !rev {
+            return;
+        }
+        node.add = 0;
+        node.rev = false;
+        if rev {
+            core::mem::swap(&mut node.l, &mut node.r);
+        }
+        let (l, r) = (node.l, node.r);
+        self.apply(l, add, rev);
+        self.apply(r, add, rev);
+    }
+
+    /// Splits `t` into its first `i` elements and the rest.
+    fn split(&mut self, t: u32, i: usize) -> (u32, u32) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        self.push(t);
+        let ls = self.size(self.nodes[t].l);
+        if i <= ls {
+            let (a, b) = self.split(self.nodes[t].l, i);
+            self.nodes[t].l = b;
+            self.pull(t);
+            (a, t)
+        } else {
+            let (a, b) = self.split(self.nodes[t].r, i - ls - 1);
+            self.nodes[t].r = a;
+            self.pull(t);
+            (t, b)
+        }
+    }
+
+    fn merge(&mut self, a: u32, b: u32) -> u32 {
+        if a == NIL {
+            return b;
+        }
+        if b == NIL {
+            return a;
+        }
+        if self.nodes[a].pri > self.nodes[b].pri {
+            self.push(a);
+            let r = self.merge(self.nodes[a].r, b);
+            self.nodes[a].r = r;
+            self.pull(a);
+            a
+        } else {
+            self.push(b);
+            let l = self.merge(a, self.nodes[b].l);
+            self.nodes[b].l = l;
+            self.pull(b);
+            b
+        }
+    }
+
+    /// Calls `f` with the root of the subtree holding the elements in `range`.
+    fn with_range<B, R>(&mut self, range: B, f: impl FnOnce(&mut Self, u32) -> R) -> R
+    where
+        B: RangeBounds<usize>,
+    {
+        let (l, r) = bounds(range, self.len());
+        let (a, b) = self.split(self.root, l);
+        let (m, c) = self.split(b, r - l);
+        let result = f(self, m);
+        let b = self.merge(m, c);
+        self.root = self.merge(a, b);
+        result
+    }
+
+    /// Inserts `x` so that it becomes the `i`-th element.
+    pub fn insert(&mut self, i: usize, x: i64) {
+        assert!(i <= self.len());
+        let t = self.new_node(x);
+        let (a, b) = self.split(self.root, i);
+        let a = self.merge(a, t);
+        self.root = self.merge(a, b);
+    }
+
+    pub fn push_back(&mut self, x: i64) {
+        self.insert(self.len(), x);
+    }
+
+    /// Removes and returns the `i`-th element.
+    pub fn remove(&mut self, i: usize) -> i64 {
+        assert!(i < self.len());
+        let (a, b) = self.split(self.root, i);
+        let (m, c) = self.split(b, 1);
+        self.root = self.merge(a, c);
+        self.nodes.remove(m).val
+    }
+
+    pub fn get(&mut self, i: usize) -> i64 {
+        assert!(i < self.len());
+        self.with_range(i..=i, |s, t| s.nodes[t].val)
+    }
+
+    /// Reverses the elements in `range`.
+    pub fn reverse<B: RangeBounds<usize>>(&mut self, range: B) {
+        self.with_range(range, |s, t| s.apply(t, 0, true))
+    }
+
+    /// Adds `x` to the elements in `range`.
+    pub fn add<B: RangeBounds<usize>>(&mut self, range: B, x: i64) {
+        self.with_range(range, |s, t| s.apply(t, x, false))
+    }
+
+    /// Returns the sum of the elements in `range`.
+    pub fn sum<B: RangeBounds<usize>>(&mut self, range: B) -> i64 {
+        self.with_range(range, |s, t| s.sum_of(t))
+    }
+
+    /// Cuts out the elements in `range` and pastes them so that they start at position `to`
+    /// of the resulting sequence.
+    pub fn move_range<B: RangeBounds<usize>>(&mut self, range: B, to: usize) {
+        let (l, r) = bounds(range, self.len());
+        assert!(to + (r - l) <= self.len());
+        let (a, b) = self.split(self.root, l);
+        let (m, c) = self.split(b, r - l);
+        let rest = self.merge(a, c);
+        let (a, c) = self.split(rest, to);
+        let a = self.merge(a, m);
+        self.root = self.merge(a, c);
+    }
+
+    /// Returns the elements in order.
+    pub fn to_vec(&mut self) -> Vec<i64> {
+        let mut out = Vec::with_capacity(self.len());
+        let mut stack = Vec::new();
+        let mut t = self.root;
+        loop {
+            while t != NIL {
+                self.push(t);
+                stack.push(t);
+                t = self.nodes[t].l;
+            }
+            let Some(u) = stack.pop() else {
+                break;
+            };
+            out.push(self.nodes[u].val);
+            t = self.nodes[u].r;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matches_naive() {
+        let mut a: Vec<i64> = (0..10).collect();
+        let mut treap = ImplicitTreap::from_slice(&a);
+        let mut seed = 5u32;
+        for _ in 0..2000 {
+            let n = a.len();
+            let r = xorshift32(&mut seed) as usize;
+            let l = r % (n + 1);
+            let h = l + (r >> 8) % (n + 1 - l);
+            match (r >> 16) % 7 {
+                0 => {
+                    treap.insert(l, r as i64 % 100);
+                    a.insert(l, r as i64 % 100);
+                }
+                1 if n > 0 => assert_eq!(a.remove(l % n), treap.remove(l % n)),
+                2 => {
+                    treap.reverse(l..h);
+                    a[l..h].reverse();
+                }
+                3 => {
+                    treap.add(l..h, 3);
+                    a[l..h].iter_mut().for_each(|x| *x += 3);
+                }
+                4 => {
+                    let to = (r >> 24) % (n - (h - l) + 1);
+                    treap.move_range(l..h, to);
+                    let m: Vec<i64> = a.drain(l..h).collect();
+                    a.splice(to..to, m);
+                }
+                5 if n > 0 => assert_eq!(a[l % n], treap.get(l % n)),
+                _ => assert_eq!(a[l..h].iter().sum::<i64>(), treap.sum(l..h)),
+            }
+            assert_eq!(a.len(), treap.len());
+        }
+        assert_eq!(a, treap.to_vec());
+    }
+}
@@ -0,0 +1,206 @@
+use alloc::vec::Vec;
+
+const NIL: u32 = u32::MAX;
+
+/// A binary min-heap over the ids `0..n`, each holding at most one key, with O(log n)
+/// [`push`](IndexedHeap::push), [`pop`](IndexedHeap::pop) and [`decrease_key`](IndexedHeap::decrease_key).
+///
+/// Unlike `BinaryHeap` with lazy deletion, the heap never holds more than `n` entries,
+/// which keeps Dijkstra and Prim at O(m log n) with O(n) memory.
+pub struct IndexedHeap<T: Ord> {
+    heap: Vec<u32>,
+    pos: Vec<u32>,
+    keys: Vec<Option<T>>,
+}
+
+impl<T: Ord> IndexedHeap<T> {
+    /// Creates an empty heap for the ids `0..n`.
+    pub fn new(n: usize) -> Self {
+        let mut keys = Vec::with_capacity(n);
+        keys.resize_with(n, || None);
+        Self {
+            heap: Vec::new(),
+            pos: alloc::vec![NIL; n],
+            keys,
+        }
+    }
+
+    /// Returns the number of ids in the heap.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.pos[id] != NIL
+    }
+
+    /// Returns the key of `id` if it is in the heap.
+    pub fn key(&self, id: usize) -> Option<&T> {
+        self.keys[id].as_ref()
+    }
+
+    fn less(&self, a: usize, b: usize) -> bool {
+        self.keys[self.heap[a] as usize] < self.keys[self.heap[b] as usize]
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.pos[self.heap[a] as usize] = a as u32;
+        self.pos[self.heap[b] as usize] = b as u32;
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let p = (i - 1) / 2;
+            if !self.less(i, p) {
+                break;
+            }
+            self.swap(i, p);
+            i = p;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let mut m = i;
+            for c in [2 * i + 1, 2 * i + 2] {
+                if c < self.heap.len() && self.less(c, m) {
+                    m = c;
+                }
+            }
+            if m == i {
+                break;
+            }
+            self.swap(i, m);
+            i = m;
+        }
+    }
+
+    /// Inserts `id` with `key`. A runtime error will occur if `id` is already in the heap.
+    pub fn push(&mut self, id: usize, key: T) {
+        assert!(
+            !self.contains(id),
+            "IndexedHeap: id {} is already present",
+            id
+        );
+        self.keys[id] = Some(key);
+        self.pos[id] = self.heap.len() as u32;
+        self.heap.push(id as u32);
+        self.sift_up(self.heap.len() - 1);
+    }
+
+    /// Lowers the key of `id` to `key`. A runtime error will occur if `id` is not in the heap
+    /// or `key` is greater than its current key.
+    pub fn decrease_key(&mut self, id: usize, key: T) {
+        assert!(self.contains(id));
+        assert!(Some(&key) <= self.keys[id].as_ref());
+        self.keys[id] = Some(key);
+        self.sift_up(self.pos[id] as usize);
+    }
+
+    /// Inserts `id` with `key`, or lowers its key to `key` if that is smaller.
+    /// Returns `true` if the heap changed, which is the relaxation step of Dijkstra.
+    pub fn push_or_decrease(&mut self, id: usize, key: T) -> bool {
+        match &self.keys[id] {
+            None => self.push(id, key),
+            Some(old) if key < *old => self.decrease_key(id, key),
+            Some(_) => return false,
+        }
+        true
+    }
+
+    /// Returns the id with the smallest key and the key.
+    pub fn peek(&self) -> Option<(usize, &T)> {
+        let &id = self.heap.first()?;
+        Some((id as usize, self.keys[id as usize].as_ref().unwrap()))
+    }
+
+    /// Removes and returns the id with the smallest key and the key.
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        let id = *self.heap.first()? as usize;
+        Some((id, self.remove(id).unwrap()))
+    }
+
+    /// Removes `id` from the heap, returning its key if it was present.
+    pub fn remove(&mut self, id: usize) -> Option<T> {
+        if !self.contains(id) {
+            return None;
+        }
+        let i = self.pos[id] as usize;
+        let last = self.heap.len() - 1;
+        self.swap(i, last);
+        self.heap.pop();
+        self.pos[id] = NIL;
+        if i < self.heap.len() {
+            self.sift_down(i);
+            self.sift_up(i);
+        }
+        self.keys[id].take()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dijkstra() {
+        let edges = [
+            (0, 1, 4u64),
+            (0, 2, 1),
+            (2, 1, 2),
+            (1, 3, 1),
+            (2, 3, 5),
+            (3, 4, 3),
+        ];
+        let n = 6;
+        let mut dist = alloc::vec![u64::MAX; n];
+        let mut heap = IndexedHeap::new(n);
+        heap.push(0, 0);
+        while let Some((u, d)) = heap.pop() {
+            dist[u] = d;
+            for &(a, b, w) in &edges {
+                if a == u && dist[b] == u64::MAX {
+                    heap.push_or_decrease(b, d + w);
+                }
+            }
+        }
+        assert_eq!([0, 3, 1, 4, 7, u64::MAX], dist[..]);
+    }
+
+    #[test]
+    fn pops_in_order_after_updates() {
+        let mut heap = IndexedHeap::new(10);
+        for id in 0..10 {
+            heap.push(id, (id * 7) % 10);
+        }
+        heap.decrease_key(9, 0);
+        assert!(!heap.push_or_decrease(3, 5));
+        assert_eq!(Some(7), heap.remove(1));
+        assert_eq!(None, heap.remove(1));
+        let mut out = Vec::new();
+        while let Some((id, key)) = heap.pop() {
+            out.push((key, id));
+        }
+        assert!(out.windows(2).all(|w| w[0].0 <= w[1].0));
+        out.sort();
+        assert_eq!(
+            [
+                (0, 0),
+                (0, 9),
+                (1, 3),
+                (2, 6),
+                (4, 2),
+                (5, 5),
+                (6, 8),
+                (8, 4),
+                (9, 7)
+            ],
+            out[..]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use alloc::collections::BTreeMap;
+
+/// A set of disjoint half-open intervals `[l, r)`, merging intervals that overlap or touch.
+///
+/// Each interval is stored once in a `BTreeMap` from its start to its end,
+/// so every operation takes amortized O(log n) time.
+pub struct IntervalSet<K: Ord + Copy> {
+    map: BTreeMap<K, K>,
+}
+
+impl<K: Ord + Copy> Default for IntervalSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Copy> IntervalSet<K> {
+    pub const fn new() -> Self {
+        Self {
+            map: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of intervals.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Adds `[l, r)`, merging it with the intervals it overlaps or touches.
+    pub fn insert(&mut self, mut l: K, mut r: K) {
+        if l >= r {
+            return;
+        }
+        if let Some((&s, &e)) = self.map.range(..=l).next_back() {
+            if e >= l {
+                l = s;
+                r = r.max(e);
+            }
+        }
+        while let Some((&s, &e)) = self.map.range(l..).next() {
+            if s > r {
+                break;
+            }
+            r = r.max(e);
+            self.map.remove(&s);
+        }
+        self.map.insert(l, r);
+    }
+
+    /// Removes `[l, r)`, splitting the intervals that stick out of it.
+    pub fn remove(&mut self, l: K, r: K) {
+        if l >= r {
+            return;
+        }
+        if let Some((&s, &e)) = self.map.range(..l).next_back() {
+            if e > l {
+                self.map.insert(s, l);
+                if e > r {
+                    self.map.insert(r, e);
+                }
+            }
+        }
+        while let Some((&s, &e)) = self.map.range(l..).next() {
+            if s >= r {
+                break;
+            }
+            self.map.remove(&s);
+            if e > r {
+                self.map.insert(r, e);
+            }
+        }
+    }
+
+    /// Returns the interval containing `x`.
+    pub fn covering(&self, x: K) -> Option<(K, K)> {
+        match self.map.range(..=x).next_back() {
+            Some((&s, &e)) if x < e => Some((s, e)),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, x: K) -> bool {
+        self.covering(x).is_some()
+    }
+
+    /// Returns an iterator over the intervals in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = (K, K)> + '_ {
+        self.map.iter().map(|(&s, &e)| (s, e))
+    }
+}
+
+/// A Chtholly tree: a partition of `[lo, hi)` into intervals, each carrying a value.
+///
+/// [`assign`](IntervalMap::assign) replaces a range with a single interval, so when the updates
+/// are mostly assignments, the number of intervals stays small and iterating over a range
+/// with [`for_each`](IntervalMap::for_each) is fast.
+pub struct IntervalMap<K: Ord + Copy, V: Clone + PartialEq> {
+    map: BTreeMap<K, (K, V)>,
+    lo: K,
+    hi: K,
+}
+
+impl<K: Ord + Copy, V: Clone + PartialEq> IntervalMap<K, V> {
+    /// Creates a map where all of `[lo, hi)` has the value `val`.
+    pub fn new(lo: K, hi: K, val: V) -> Self {
+        assert!(lo < hi);
+        let mut map = BTreeMap::new();
+        map.insert(lo, (hi, val));
+        Self { map, lo, hi }
+    }
+
+    /// Returns the number of intervals.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Makes `x` the start of an interval, splitting the interval containing it if needed.
+    fn split(&mut self, x: K) {
+        if x <= self.lo || x >= self.hi {
+            return;
+        }
+        let (&s, (e, v)) = self.map.range(..=x).next_back().unwrap();
+        if s < x {
+            let (e, v) = (*e, v.clone());
+            self.map.get_mut(&s).unwrap().0 = x;
+            self.map.insert(x, (e, v));
+        }
+    }
+
+    /// Returns the value at `x`.
+    pub fn get(&self, x: K) -> &V {
+        assert!(self.lo <= x && x < self.hi);
+        &self.map.range(..=x).next_back().unwrap().1 .1
+    }
+
+    /// Sets the value of `[l, r)` to `val`, merging it with the neighbors of the same value.
+    pub fn assign(&mut self, mut l: K, mut r: K, val: V) {
+        assert!(self.lo <= l && r <= self.hi);
+        if l >= r {
+            return;
+        }
+        self.split(l);
+        self.split(r);
+        while let Some((&s, _)) = self.map.range(l..r).next() {
+            self.map.remove(&s);
+        }
+        if let Some((&s, (e, v))) = self.map.range(..l).next_back() {
+            if *e == l && *v == val {
+                self.map.remove(&s);
+                l = s;
+            }
+        }
+        if let Some((e, v)) = self.map.get(&r) {
+            if *v == val {
+                let e = *e;
+                self.map.remove(&r);
+                r = e;
+            }
+        }
+        self.map.insert(l, (r, val));
+    }
+
+    /// Calls `f(s, e, &mut v)` for each interval `[s, e)` within `[l, r)`, in increasing order,
+    /// after splitting the intervals at `l` and `r`.
+    pub fn for_each<F: FnMut(K, K, &mut V)>(&mut self, l: K, r: K, mut f: F) {
+        assert!(self.lo <= l && r <= self.hi);
+        if l >= r {
+            return;
+        }
+        self.split(l);
+        self.split(r);
+        for (&s, (e, v)) in self.map.range_mut(l..r) {
+            f(s, *e, v);
+        }
+    }
+
+    /// Returns an iterator over the intervals `(s, e, &v)` in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = (K, K, &V)> {
+        self.map.iter().map(|(&s, (e, v))| (s, *e, v))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn set_matches_naive() {
+        let mut set = IntervalSet::new();
+        let mut naive = [false; 64];
+        let mut rng = TestRng::new(5);
+        for _ in 0..500 {
+            let (a, b) = (rng.below(64) as i32, rng.below(64) as i32);
+            let (l, r) = (a.min(b), a.max(b));
+            let on = rng.below(2) == 0;
+            if on {
+                set.insert(l, r);
+            } else {
+                set.remove(l, r);
+            }
+            naive[l as usize..r as usize].fill(on);
+            let mut runs = Vec::new();
+            let mut i = 0;
+            while i < 64 {
+                if naive[i] {
+                    let s = i;
+                    while i < 64 && naive[i] {
+                        i += 1;
+                    }
+                    runs.push((s as i32, i as i32));
+                } else {
+                    i += 1;
+                }
+            }
+            assert_eq!(runs, set.iter().collect::<Vec<_>>());
+            for x in 0..64 {
+                let run = runs.iter().find(|&&(s, e)| s <= x && x < e).copied();
+                assert_eq!(run, set.covering(x));
+            }
+        }
+    }
+
+    #[test]
+    fn chtholly_assign_and_iterate() {
+        let mut map = IntervalMap::new(0, 100, 0u64);
+        map.assign(10, 20, 5);
+        map.assign(15, 30, 7);
+        map.assign(30, 40, 7);
+        assert_eq!(
+            [(0, 10, &0), (10, 15, &5), (15, 40, &7), (40, 100, &0)],
+            map.iter().collect::<Vec<_>>()[..]
+        );
+        let mut sum = 0;
+        map.for_each(12, 35, |s, e, v| {
+            sum += (e - s) as u64 * *v;
+            *v += 1;
+        });
+        assert_eq!(3 * 5 + 20 * 7, sum);
+        assert_eq!(6, *map.get(14));
+        assert_eq!(7, *map.get(35));
+        assert_eq!(8, *map.get(34));
+        map.assign(0, 100, 1);
+        assert_eq!(1, map.len());
+    }
+}
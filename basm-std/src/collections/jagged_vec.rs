@@ -1,18 +1,26 @@
-use alloc::{vec, vec::Vec};
+use crate::platform::allocator::api::{self, Allocator, Global};
 
 /// Jagged Array using Vec.
 ///
 /// Jagged Array is like an 2d array but length of each row is all different.
-pub struct JaggedVec<T> {
-    pub(crate) head: Vec<u32>,
-    pub(crate) link: Vec<(u32, T)>,
+/// The rows and the elements are stored with the allocator `A` (see [`api`](crate::platform::allocator::api)).
+pub struct JaggedVec<T, A: Allocator + Clone = Global> {
+    pub(crate) head: api::Vec<u32, A>,
+    pub(crate) link: api::Vec<(u32, T), A>,
 }
 
 impl<T> JaggedVec<T> {
     pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<T, A: Allocator + Clone> JaggedVec<T, A> {
+    /// Same as [`JaggedVec::new`], but the rows and the elements are allocated with `alloc` (e.g., `&arena`).
+    pub fn new_in(alloc: A) -> Self {
         Self {
-            head: vec![],
-            link: vec![],
+            head: api::vec_with_capacity_in(0, alloc.clone()),
+            link: api::vec_with_capacity_in(0, alloc),
         }
     }
 
@@ -46,7 +54,7 @@ impl<T> JaggedVec<T> {
         self.link.push((prev, data));
     }
 
-    pub fn row_iter(&self, row: usize) -> RowIter<T> {
+    pub fn row_iter(&self, row: usize) -> RowIter<T, A> {
         RowIter {
             vec: self,
             idx: self.head[row],
@@ -73,18 +81,18 @@ impl<T> Default for JaggedVec<T> {
     }
 }
 
-pub struct RowIter<'a, T> {
-    vec: &'a JaggedVec<T>,
+pub struct RowIter<'a, T, A: Allocator + Clone = Global> {
+    vec: &'a JaggedVec<T, A>,
     idx: u32,
 }
 
-impl<'a, T> RowIter<'a, T> {
+impl<'a, T, A: Allocator + Clone> RowIter<'a, T, A> {
     pub fn id(&self) -> Option<usize> {
         (self.idx != u32::MAX).then_some(self.idx as usize)
     }
 }
 
-impl<'a, T> Iterator for RowIter<'a, T> {
+impl<'a, T, A: Allocator + Clone> Iterator for RowIter<'a, T, A> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -99,6 +107,7 @@ impl<'a, T> Iterator for RowIter<'a, T> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use alloc::{vec, vec::Vec};
 
     #[test]
     fn new_initializes_empty_head_and_link() {
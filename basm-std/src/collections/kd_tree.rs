@@ -0,0 +1,154 @@
+use alloc::vec::Vec;
+
+/// A static KD-tree over points in `D` dimensions, for nearest-neighbor and box queries.
+///
+/// The tree is laid out implicitly in one array: the node of a subarray `[lo, hi)` is its
+/// median at `(lo + hi) / 2`, split along the axis where the subarray is widest.
+/// Coordinates must satisfy `|x| < 2^62` so that squared distances fit in `u128`.
+pub struct KdTree<const D: usize> {
+    pts: Vec<([i64; D], usize)>,
+    axis: Vec<u8>,
+}
+
+fn dist2<const D: usize>(p: &[i64; D], q: &[i64; D]) -> u128 {
+    let mut d = 0;
+    for k in 0..D {
+        let x = p[k].abs_diff(q[k]) as u128;
+        d += x * x;
+    }
+    d
+}
+
+impl<const D: usize> KdTree<D> {
+    /// Builds a tree over `points` in O(n log n). Queries report points by their index in `points`.
+    pub fn new(points: &[[i64; D]]) -> Self {
+        let mut pts: Vec<_> = points.iter().copied().zip(0..).collect();
+        let mut axis = alloc::vec![0u8; pts.len()];
+        Self::build(&mut pts, &mut axis);
+        Self { pts, axis }
+    }
+
+    fn build(pts: &mut [([i64; D], usize)], axis: &mut [u8]) {
+        if pts.len() <= 1 {
+            return;
+        }
+        let mut best = (0, 0);
+        for k in 0..D {
+            let lo = pts.iter().map(|p| p.0[k]).min().unwrap();
+            let hi = pts.iter().map(|p| p.0[k]).max().unwrap();
+            let spread = hi.abs_diff(lo);
+            if spread > best.1 {
+                best = (k, spread);
+            }
+        }
+        let (k, mid) = (best.0, pts.len() / 2);
+        pts.select_nth_unstable_by_key(mid, |p| p.0[k]);
+        axis[mid] = k as u8;
+        let (pl, pr) = pts.split_at_mut(mid);
+        let (al, ar) = axis.split_at_mut(mid);
+        Self::build(pl, al);
+        Self::build(&mut pr[1..], &mut ar[1..]);
+    }
+
+    pub fn len(&self) -> usize {
+        self.pts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pts.is_empty()
+    }
+
+    /// Returns the index of a point nearest to `q` and its squared distance,
+    /// or `None` if the tree is empty.
+    pub fn nearest(&self, q: &[i64; D]) -> Option<(usize, u128)> {
+        let mut best = None;
+        self.nearest_in(0, self.pts.len(), q, &mut best);
+        best
+    }
+
+    fn nearest_in(&self, lo: usize, hi: usize, q: &[i64; D], best: &mut Option<(usize, u128)>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let (p, i) = &self.pts[mid];
+        let d = dist2(p, q);
+        if best.is_none_or(|(_, b)| d < b) {
+            *best = Some((*i, d));
+        }
+        let k = self.axis[mid] as usize;
+        let near_left = q[k] < p[k];
+        let (near, far) = if near_left {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.nearest_in(near.0, near.1, q, best);
+        let gap = q[k].abs_diff(p[k]) as u128;
+        if best.is_none_or(|(_, b)| gap * gap < b) {
+            self.nearest_in(far.0, far.1, q, best);
+        }
+    }
+
+    /// Calls `f(i)` for every point `i` with `lo[k] <= x[k] <= hi[k]` on every axis `k`.
+    pub fn range<F: FnMut(usize)>(&self, lo: &[i64; D], hi: &[i64; D], mut f: F) {
+        self.range_in(0, self.pts.len(), lo, hi, &mut f);
+    }
+
+    fn range_in<F: FnMut(usize)>(
+        &self,
+        l: usize,
+        r: usize,
+        lo: &[i64; D],
+        hi: &[i64; D],
+        f: &mut F,
+    ) {
+        if l >= r {
+            return;
+        }
+        let mid = (l + r) / 2;
+        let (p, i) = &self.pts[mid];
+        if (0..D).all(|k| lo[k] <= p[k] && p[k] <= hi[k]) {
+            f(*i);
+        }
+        let k = self.axis[mid] as usize;
+        if lo[k] <= p[k] {
+            self.range_in(l, mid, lo, hi, f);
+        }
+        if p[k] <= hi[k] {
+            self.range_in(mid + 1, r, lo, hi, f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+
+    #[test]
+    fn matches_naive() {
+        let mut rng = TestRng::new(17);
+        let mut rand = || rng.below(200) as i64 - 100;
+        let pts: Vec<[i64; 3]> = (0..300).map(|_| [rand(), rand(), rand()]).collect();
+        let tree = KdTree::new(&pts);
+        for _ in 0..100 {
+            let q = [rand(), rand(), rand()];
+            let (i, d) = tree.nearest(&q).unwrap();
+            assert_eq!(dist2(&pts[i], &q), d);
+            assert_eq!(pts.iter().map(|p| dist2(p, &q)).min().unwrap(), d);
+
+            let (a, b) = ([rand(), rand(), rand()], [rand(), rand(), rand()]);
+            let lo = [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])];
+            let hi = [a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])];
+            let mut found = Vec::new();
+            tree.range(&lo, &hi, |i| found.push(i));
+            found.sort();
+            let naive: Vec<usize> = (0..pts.len())
+                .filter(|&i| (0..3).all(|k| lo[k] <= pts[i][k] && pts[i][k] <= hi[k]))
+                .collect();
+            assert_eq!(naive, found);
+        }
+        assert_eq!(None, KdTree::<2>::new(&[]).nearest(&[0, 0]));
+    }
+}
@@ -0,0 +1,320 @@
+use super::segment_tree::bounds;
+use super::Monoid;
+use alloc::vec::Vec;
+
+/// Operators acting on the elements of the monoid `M`, closed under composition.
+///
+/// `apply(f, combine(x, y))` must equal `combine(apply(f, x), apply(f, y))`,
+/// and `apply(compose(f, g), x)` must equal `apply(f, apply(g, x))`.
+pub trait Mapping<M: Monoid> {
+    type F: Clone;
+    fn id() -> Self::F;
+    /// Returns the operator that applies `g` first and then `f`.
+    fn compose(f: &Self::F, g: &Self::F) -> Self::F;
+    fn apply(f: &Self::F, x: &M::T) -> M::T;
+}
+
+/// A segment tree with lazy propagation, supporting range application of operators `F`
+/// and range products over the monoid `M` in O(log n) each.
+///
+/// For the common cases, see [`RangeAddSum`] and [`RangeAssignMin`].
+pub struct LazySegTree<M: Monoid, F: Mapping<M>> {
+    d: Vec<M::T>,
+    lz: Vec<F::F>,
+    n: usize,
+    size: usize,
+    log: u32,
+}
+
+impl<M: Monoid, F: Mapping<M>> LazySegTree<M, F> {
+    /// Creates a tree of `n` identities.
+    pub fn new(n: usize) -> Self {
+        Self::from_slice(&alloc::vec![M::e(); n])
+    }
+
+    pub fn from_slice(a: &[M::T]) -> Self {
+        a.iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    fn pull(&mut self, k: usize) {
+        self.d[k] = M::combine(&self.d[2 * k], &self.d[2 * k + 1]);
+    }
+
+    fn apply_node(&mut self, k: usize, f: &F::F) {
+        self.d[k] = F::apply(f, &self.d[k]);
+        if k < self.size {
+            self.lz[k] = F::compose(f, &self.lz[k]);
+        }
+    }
+
+    fn push(&mut self, k: usize) {
+        let f = core::mem::replace(&mut self.lz[k], F::id());
+        self.apply_node(2 * k, &f);
+        self.apply_node(2 * k + 1, &f);
+    }
+
+    /// Pushes the pending operators down to the leaf `i` (offset by `size`).
+    fn push_path(&mut self, k: usize) {
+        for i in (1..=self.log).rev() {
+            self.push(k >> i);
+        }
+    }
+
+    /// Replaces the `i`-th element with `x`.
+    pub fn set(&mut self, i: usize, x: M::T) {
+        assert!(i < self.n);
+        let k = i + self.size;
+        self.push_path(k);
+        self.d[k] = x;
+        for i in 1..=self.log {
+            self.pull(k >> i);
+        }
+    }
+
+    pub fn get(&mut self, i: usize) -> M::T {
+        assert!(i < self.n);
+        let k = i + self.size;
+        self.push_path(k);
+        self.d[k].clone()
+    }
+
+    /// Returns the product of the elements in `range`, or `M::e()` if it is empty.
+    pub fn query<B>(&mut self, range: B) -> M::T
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = bounds(range, self.n);
+        if l == r {
+            return M::e();
+        }
+        let (mut l, mut r) = (l + self.size, r + self.size);
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i);
+            }
+        }
+        let mut lsum = M::e();
+        let mut rsum = M::e();
+        while l < r {
+            if l & 1 != 0 {
+                lsum = M::combine(&lsum, &self.d[l]);
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                rsum = M::combine(&self.d[r], &rsum);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        M::combine(&lsum, &rsum)
+    }
+
+    /// Returns the product of all elements.
+    pub fn all(&self) -> &M::T {
+        &self.d[1]
+    }
+
+    /// Applies `f` to every element in `range`.
+    pub fn apply<B>(&mut self, range: B, f: F::F)
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = bounds(range, self.n);
+        if l == r {
+            return;
+        }
+        let (l, r) = (l + self.size, r + self.size);
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i);
+            }
+        }
+        let (mut a, mut b) = (l, r);
+        while a < b {
+            if a & 1 != 0 {
+                self.apply_node(a, &f);
+                a += 1;
+            }
+            if b & 1 != 0 {
+                b -= 1;
+                self.apply_node(b, &f);
+            }
+            a >>= 1;
+            b >>= 1;
+        }
+        for i in 1..=self.log {
+            if ((l >> i) << i) != l {
+                self.pull(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.pull((r - 1) >> i);
+            }
+        }
+    }
+}
+
+impl<M: Monoid, F: Mapping<M>> FromIterator<M::T> for LazySegTree<M, F> {
+    fn from_iter<I: IntoIterator<Item = M::T>>(iter: I) -> Self {
+        let a: Vec<M::T> = iter.into_iter().collect();
+        let n = a.len();
+        let size = n.next_power_of_two();
+        let mut d = alloc::vec![M::e(); size];
+        d.extend(a);
+        d.resize(size * 2, M::e());
+        let mut tree = Self {
+            d,
+            lz: alloc::vec![F::id(); size],
+            n,
+            size,
+            log: size.trailing_zeros(),
+        };
+        for k in (1..size).rev() {
+            tree.pull(k);
+        }
+        tree
+    }
+}
+
+/// The monoid of `(sum, length)` pairs, so that adding to a range can update its sum.
+pub struct SumLen;
+
+impl Monoid for SumLen {
+    type T = (i64, i64);
+    fn e() -> Self::T {
+        (0, 0)
+    }
+    fn combine(l: &Self::T, r: &Self::T) -> Self::T {
+        (l.0 + r.0, l.1 + r.1)
+    }
+}
+
+/// Adding a constant to each element of a range, acting on [`SumLen`].
+pub struct AddI64;
+
+impl Mapping<SumLen> for AddI64 {
+    type F = i64;
+    fn id() -> i64 {
+        0
+    }
+    fn compose(f: &i64, g: &i64) -> i64 {
+        f + g
+    }
+    fn apply(f: &i64, x: &(i64, i64)) -> (i64, i64) {
+        (x.0 + f * x.1, x.1)
+    }
+}
+
+/// The minimum over `i64`, with the identity `i64::MAX`.
+pub struct MinI64;
+
+impl Monoid for MinI64 {
+    type T = i64;
+    fn e() -> i64 {
+        i64::MAX
+    }
+    fn combine(l: &i64, r: &i64) -> i64 {
+        *l.min(r)
+    }
+}
+
+/// Assigning a constant to each element of a range (`None` leaves them unchanged), acting on [`MinI64`].
+pub struct AssignI64;
+
+impl Mapping<MinI64> for AssignI64 {
+    type F = Option<i64>;
+    fn id() -> Option<i64> {
+        None
+    }
+    fn compose(f: &Option<i64>, g: &Option<i64>) -> Option<i64> {
+        f.or(*g)
+    }
+    fn apply(f: &Option<i64>, x: &i64) -> i64 {
+        f.unwrap_or(*x)
+    }
+}
+
+/// Range add and range sum over `i64`.
+pub type RangeAddSum = LazySegTree<SumLen, AddI64>;
+
+/// Range assignment and range minimum over `i64`.
+pub type RangeAssignMin = LazySegTree<MinI64, AssignI64>;
+
+impl LazySegTree<SumLen, AddI64> {
+    /// Creates a tree holding the values of `a`.
+    pub fn from_values(a: &[i64]) -> Self {
+        a.iter().map(|&x| (x, 1)).collect()
+    }
+
+    /// Returns the sum of the elements in `range`.
+    pub fn sum<B>(&mut self, range: B) -> i64
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        self.query(range).0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+
+    #[test]
+    fn range_add_sum_matches_naive() {
+        let mut rng = TestRng::new(1);
+        let mut a: Vec<i64> = (0..37).map(|_| rng.below(100) as i64).collect();
+        let mut tree = RangeAddSum::from_values(&a);
+        for _ in 0..500 {
+            let l = rng.index(a.len() + 1);
+            let r = l + rng.index(a.len() + 1 - l);
+            if rng.below(2) == 0 {
+                let x = rng.below(21) as i64 - 10;
+                tree.apply(l..r, x);
+                a[l..r].iter_mut().for_each(|v| *v += x);
+            } else {
+                assert_eq!(a[l..r].iter().sum::<i64>(), tree.sum(l..r));
+            }
+        }
+        assert_eq!(a[5], tree.get(5).0);
+    }
+
+    #[test]
+    fn range_assign_min_matches_naive() {
+        let mut rng = TestRng::new(2);
+        let mut a: Vec<i64> = (0..20).map(|_| rng.below(100) as i64).collect();
+        let mut tree = RangeAssignMin::from_slice(&a);
+        for _ in 0..500 {
+            let l = rng.index(a.len());
+            let r = l + 1 + rng.index(a.len() - l);
+            match rng.below(3) {
+                0 => {
+                    let x = rng.below(100) as i64;
+                    tree.apply(l..r, Some(x));
+                    a[l..r].iter_mut().for_each(|v| *v = x);
+                }
+                1 => {
+                    let x = rng.below(100) as i64;
+                    tree.set(l, x);
+                    a[l] = x;
+                }
+                _ => assert_eq!(*a[l..r].iter().min().unwrap(), tree.query(l..r)),
+            }
+        }
+        assert_eq!(*a.iter().min().unwrap(), *tree.all());
+    }
+}
@@ -0,0 +1,219 @@
+use alloc::vec::Vec;
+
+const NIL: u32 = u32::MAX;
+
+struct Node {
+    line: Option<(i64, i64)>,
+    l: u32,
+    r: u32,
+}
+
+/// A Li Chao tree over the integer coordinates `lo..=hi`, holding lines `y = a * x + b`
+/// (or segments of them) and answering the minimum or the maximum at a point.
+///
+/// Nodes are created on demand, so the coordinate range can be huge (e.g., `-10^18..=10^18`); inserting
+/// a line takes O(log(hi - lo)) time and a segment O(log^2(hi - lo)). The values `a * x + b` must fit
+/// in an `i64` for every `x` in the range.
+pub struct LiChaoTree {
+    nodes: Vec<Node>,
+    lo: i64,
+    hi: i64,
+    max: bool,
+}
+
+impl LiChaoTree {
+    /// Creates a tree answering minima over `lo..=hi`.
+    pub fn new_min(lo: i64, hi: i64) -> Self {
+        Self::new(lo, hi, false)
+    }
+
+    /// Creates a tree answering maxima over `lo..=hi`.
+    pub fn new_max(lo: i64, hi: i64) -> Self {
+        Self::new(lo, hi, true)
+    }
+
+    fn new(lo: i64, hi: i64, max: bool) -> Self {
+        assert!(lo <= hi);
+        Self {
+            nodes: alloc::vec![Node {
+                line: None,
+                l: NIL,
+                r: NIL,
+            }],
+            lo,
+            hi,
+            max,
+        }
+    }
+
+    /// Maxima are kept as minima of the negated lines.
+    fn normalize(&self, a: i64, b: i64) -> (i64, i64) {
+        if self.max {
+            (-a, -b)
+        } else {
+            (a, b)
+        }
+    }
+
+    fn eval(line: Option<(i64, i64)>, x: i64) -> i64 {
+        match line {
+            Some((a, b)) => a * x + b,
+            None => i64::MAX,
+        }
+    }
+
+    fn child(&mut self, t: usize, right: bool) -> usize {
+        let c = if right {
+            self.nodes[t].r
+        } else {
+            self.nodes[t].l
+        };
+        if c != NIL {
+            return c as usize;
+        }
+        let c = self.nodes.len();
+        self.nodes.push(Node {
+            line: None,
+            l: NIL,
+            r: NIL,
+        });
+        if right {
+            self.nodes[t].r = c as u32;
+        } else {
+            self.nodes[t].l = c as u32;
+        }
+        c
+    }
+
+    fn insert(&mut self, mut t: usize, mut l: i64, mut r: i64, line: (i64, i64)) {
+        let mut line = Some(line);
+        loop {
+            let m = l + (r - l) / 2;
+            let cur = self.nodes[t].line;
+            if Self::eval(line, m) < Self::eval(cur, m) {
+                self.nodes[t].line = line;
+                line = cur;
+            }
+            if line.is_none() || l == r {
+                return;
+            }
+            let cur = self.nodes[t].line;
+            if Self::eval(line, l) < Self::eval(cur, l) {
+                t = self.child(t, false);
+                r = m;
+            } else if Self::eval(line, r) < Self::eval(cur, r) {
+                t = self.child(t, true);
+                l = m + 1;
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Adds the line `y = a * x + b`.
+    pub fn add_line(&mut self, a: i64, b: i64) {
+        let line = self.normalize(a, b);
+        self.insert(0, self.lo, self.hi, line);
+    }
+
+    /// Adds the segment of `y = a * x + b` over `xl..=xr`.
+    pub fn add_segment(&mut self, a: i64, b: i64, xl: i64, xr: i64) {
+        let line = self.normalize(a, b);
+        let (xl, xr) = (xl.max(self.lo), xr.min(self.hi));
+        if xl <= xr {
+            self.add_segment_rec(0, self.lo, self.hi, xl, xr, line);
+        }
+    }
+
+    fn add_segment_rec(&mut self, t: usize, l: i64, r: i64, xl: i64, xr: i64, line: (i64, i64)) {
+        if xl <= l && r <= xr {
+            self.insert(t, l, r, line);
+            return;
+        }
+        let m = l + (r - l) / 2;
+        if xl <= m {
+            let c = self.child(t, false);
+            self.add_segment_rec(c, l, m, xl, xr, line);
+        }
+        if m < xr {
+            let c = self.child(t, true);
+            self.add_segment_rec(c, m + 1, r, xl, xr, line);
+        }
+    }
+
+    /// Returns the minimum (or the maximum) of the lines at `x`, or `None` if no line covers `x`.
+    pub fn query(&self, x: i64) -> Option<i64> {
+        assert!(self.lo <= x && x <= self.hi);
+        let (mut t, mut l, mut r) = (0u32, self.lo, self.hi);
+        let mut best: Option<i64> = None;
+        while t != NIL {
+            let node = &self.nodes[t as usize];
+            if let Some((a, b)) = node.line {
+                let y = a * x + b;
+                best = Some(best.map_or(y, |v| v.min(y)));
+            }
+            let m = l + (r - l) / 2;
+            if x <= m {
+                t = node.l;
+                r = m;
+            } else {
+                t = node.r;
+                l = m + 1;
+            }
+        }
+        if self.max {
+            best.map(|y| -y)
+        } else {
+            best
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+
+    #[test]
+    fn matches_naive() {
+        let (lo, hi) = (-50, 50);
+        let mut min = LiChaoTree::new_min(lo, hi);
+        let mut max = LiChaoTree::new_max(lo, hi);
+        let mut segs = Vec::new();
+        let mut rng = TestRng::new(3);
+        for _ in 0..200 {
+            let a = rng.below(21) as i64 - 10;
+            let b = rng.below(201) as i64 - 100;
+            let x1 = rng.below(121) as i64 - 60;
+            let x2 = rng.below(121) as i64 - 60;
+            let (xl, xr) = (x1.min(x2), x1.max(x2));
+            if rng.below(4) == 0 {
+                min.add_line(a, b);
+                max.add_line(a, b);
+                segs.push((a, b, i64::MIN, i64::MAX));
+            } else {
+                min.add_segment(a, b, xl, xr);
+                max.add_segment(a, b, xl, xr);
+                segs.push((a, b, xl, xr));
+            }
+            for x in lo..=hi {
+                let ys = segs
+                    .iter()
+                    .filter(|s| s.2 <= x && x <= s.3)
+                    .map(|s| s.0 * x + s.1);
+                assert_eq!(ys.clone().min(), min.query(x));
+                assert_eq!(ys.max(), max.query(x));
+            }
+        }
+    }
+
+    #[test]
+    fn huge_range() {
+        let mut tree = LiChaoTree::new_min(-1_000_000_000_000, 1_000_000_000_000);
+        tree.add_line(3, 0);
+        tree.add_line(-2, 5);
+        assert_eq!(Some(-2_000_000_000_000 + 5), tree.query(1_000_000_000_000));
+        assert_eq!(Some(-3_000_000_000_000), tree.query(-1_000_000_000_000));
+        assert_eq!(Some(3), tree.query(1));
+    }
+}
@@ -0,0 +1,341 @@
+use super::Monoid;
+use alloc::vec::Vec;
+
+const NIL: u32 = u32::MAX;
+
+/// A link-cut tree over a forest of `n` vertices carrying values of the monoid `M`.
+///
+/// Supports [`link`](LinkCutTree::link), [`cut`](LinkCutTree::cut), rerooting, connectivity and
+/// the product of the values along a path, each in amortized O(log n). The products are taken in
+/// the order of the path, so `M` does not need to be commutative.
+pub struct LinkCutTree<M: Monoid> {
+    ch: Vec<[u32; 2]>,
+    p: Vec<u32>,
+    rev: Vec<bool>,
+    val: Vec<M::T>,
+    agg: Vec<M::T>,
+    ragg: Vec<M::T>,
+}
+
+impl<M: Monoid> LinkCutTree<M> {
+    /// Creates a forest of `n` isolated vertices with the identity as their values.
+    pub fn new(n: usize) -> Self {
+        Self::from_slice(&alloc::vec![M::e(); n])
+    }
+
+    /// Creates a forest of isolated vertices with the values in `a`.
+    pub fn from_slice(a: &[M::T]) -> Self {
+        let n = a.len();
+        Self {
+            ch: alloc::vec![[NIL; 2]; n],
+            p: alloc::vec![NIL; n],
+            rev: alloc::vec![false; n],
+            val: a.to_vec(),
+            agg: a.to_vec(),
+            ragg: a.to_vec(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.p.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.p.is_empty()
+    }
+
+    fn is_splay_root(&self, x: usize) -> bool {
+        let p = self.p[x];
+        p == NIL || (self.ch[p as usize][0] != x as u32 && self.ch[p as usize][1] != x as u32)
+    }
+
+    fn reverse(&mut self, x: u32) {
+        if x == NIL {
+            return;
+        }
+        let x = x as usize;
+        self.ch[x].swap(0, 1);
+        core::mem::swap(&mut self.agg[x], &mut self.ragg[x]);
+        self.rev[x] ^= true;
+    }
+
+    fn push(&mut self, x: usize) {
+        if self.rev[x] {
+            self.rev[x] = false;
+            let [l, r] = self.ch[x];
+            self.reverse(l);
+            self.reverse(r);
+        }
+    }
+
+    fn pull(&mut self, x: usize) {
+        let [l, r] = self.ch[x];
+        let mut agg = self.val[x].clone();
+        let mut ragg = self.val[x].clone();
+        if l != NIL {
+            agg = M::combine(&self.agg[l as usize], &agg);
+            ragg = M::combine(&ragg, &self.ragg[l as usize]);
+        }
+        if r != NIL {
+            agg = M::combine(&agg, &self.agg[r as usize]);
+            ragg = M::combine(&self.ragg[r as usize], &ragg);
+        }
+        self.agg[x] = agg;
+        self.ragg[x] = ragg;
+    }
+
+    fn rotate(&mut self, x: usize) {
+        let p = self.p[x] as usize;
+        let g = self.p[p];
+        let d = (self.ch[p][1] == x as u32) as usize;
+        let b = self.ch[x][d ^ 1];
+        if !self.is_splay_root(p) {
+            let g = g as usize;
+            let gd = (self.ch[g][1] == p as u32) as usize;
+            self.ch[g][gd] = x as u32;
+        }
+        self.ch[p][d] = b;
+        if b != NIL {
+            self.p[b as usize] = p as u32;
+        }
+        self.ch[x][d ^ 1] = p as u32;
+        self.p[p] = x as u32;
+        self.p[x] = g;
+        self.pull(p);
+        self.pull(x);
+    }
+
+    fn splay(&mut self, x: usize) {
+        let mut path = alloc::vec![x];
+        let mut y = x;
+        while !self.is_splay_root(y) {
+            y = self.p[y] as usize;
+            path.push(y);
+        }
+        for &y in path.iter().rev() {
+            self.push(y);
+        }
+        while !self.is_splay_root(x) {
+            let p = self.p[x] as usize;
+            if !self.is_splay_root(p) {
+                let g = self.p[p] as usize;
+                if (self.ch[g][0] == p as u32) == (self.ch[p][0] == x as u32) {
+                    self.rotate(p);
+                } else {
+                    self.rotate(x);
+                }
+            }
+            self.rotate(x);
+        }
+    }
+
+    /// Makes the path from the root to `x` preferred, and `x` the root of its splay tree.
+    fn access(&mut self, x: usize) {
+        let mut last = NIL;
+        let mut y = x as u32;
+        while y != NIL {
+            self.splay(y as usize);
+            self.ch[y as usize][1] = last;
+            self.pull(y as usize);
+            last = y;
+            y = self.p[y as usize];
+        }
+        self.splay(x);
+    }
+
+    /// Makes `x` the root of its tree.
+    pub fn make_root(&mut self, x: usize) {
+        self.access(x);
+        self.reverse(x as u32);
+    }
+
+    /// Returns the root of the tree containing `x`.
+    pub fn find_root(&mut self, x: usize) -> usize {
+        self.access(x);
+        let mut r = x;
+        loop {
+            self.push(r);
+            match self.ch[r][0] {
+                NIL => break,
+                l => r = l as usize,
+            }
+        }
+        self.splay(r);
+        r
+    }
+
+    pub fn connected(&mut self, u: usize, v: usize) -> bool {
+        self.find_root(u) == self.find_root(v)
+    }
+
+    /// Adds the edge `(u, v)`. Returns `false` (and does nothing) if they are already connected.
+    pub fn link(&mut self, u: usize, v: usize) -> bool {
+        self.make_root(u);
+        if self.find_root(v) == u {
+            return false;
+        }
+        self.p[u] = v as u32;
+        true
+    }
+
+    /// Removes the edge `(u, v)`. Returns `false` (and does nothing) if there is no such edge.
+    pub fn cut(&mut self, u: usize, v: usize) -> bool {
+        self.make_root(u);
+        self.access(v);
+        if self.ch[v][0] != u as u32 || self.ch[u][1] != NIL {
+            return false;
+        }
+        self.ch[v][0] = NIL;
+        self.p[u] = NIL;
+        self.pull(v);
+        true
+    }
+
+    /// Returns the parent of `x` when the tree is rooted at `root`, or `None` if `x == root`
+    /// or they are not connected. This reroots the tree at `root`.
+    pub fn parent(&mut self, root: usize, x: usize) -> Option<usize> {
+        if !self.connected(root, x) {
+            return None;
+        }
+        self.make_root(root);
+        self.access(x);
+        let mut y = self.ch[x][0];
+        if y == NIL {
+            return None;
+        }
+        loop {
+            self.push(y as usize);
+            match self.ch[y as usize][1] {
+                NIL => break,
+                r => y = r,
+            }
+        }
+        self.splay(y as usize);
+        Some(y as usize)
+    }
+
+    /// Returns the product of the values on the path from `u` to `v`, in that order,
+    /// or `None` if they are not connected.
+    pub fn path(&mut self, u: usize, v: usize) -> Option<M::T> {
+        if !self.connected(u, v) {
+            return None;
+        }
+        self.make_root(u);
+        self.access(v);
+        Some(self.agg[v].clone())
+    }
+
+    pub fn get(&self, x: usize) -> &M::T {
+        &self.val[x]
+    }
+
+    /// Replaces the value of `x`.
+    pub fn set(&mut self, x: usize, val: M::T) {
+        self.access(x);
+        self.val[x] = val;
+        self.pull(x);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::string::String;
+
+    struct Concat;
+    impl Monoid for Concat {
+        type T = String;
+        fn e() -> String {
+            String::new()
+        }
+        fn combine(l: &String, r: &String) -> String {
+            alloc::format!("{}{}", l, r)
+        }
+    }
+
+    struct Sum;
+    impl Monoid for Sum {
+        type T = i64;
+        fn e() -> i64 {
+            0
+        }
+        fn combine(l: &i64, r: &i64) -> i64 {
+            l + r
+        }
+    }
+
+    #[test]
+    fn paths_are_ordered() {
+        let names: Vec<String> = "abcdef".chars().map(String::from).collect();
+        let mut lct = LinkCutTree::<Concat>::from_slice(&names);
+        for (u, v) in [(0, 1), (1, 2), (1, 3), (3, 4)] {
+            assert!(lct.link(u, v));
+        }
+        assert!(!lct.link(2, 4));
+        assert_eq!(Some("cbde".into()), lct.path(2, 4));
+        assert_eq!(Some("edbc".into()), lct.path(4, 2));
+        assert_eq!(None, lct.path(0, 5));
+        assert_eq!(Some(1), lct.parent(0, 3));
+        assert!(lct.cut(3, 1));
+        assert!(!lct.cut(3, 1));
+        assert!(!lct.connected(2, 4));
+        assert!(lct.link(4, 5));
+        lct.set(3, "X".into());
+        assert_eq!(Some("Xef".into()), lct.path(3, 5));
+    }
+
+    #[test]
+    fn matches_naive_forest() {
+        let n = 12;
+        let mut lct = LinkCutTree::<Sum>::from_slice(&(0..n as i64).collect::<Vec<_>>());
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut vals: Vec<i64> = (0..n as i64).collect();
+        let naive_path = |edges: &Vec<(usize, usize)>, vals: &Vec<i64>, u: usize, v: usize| {
+            let mut prev = alloc::vec![usize::MAX; n];
+            prev[u] = u;
+            let mut stack = alloc::vec![u];
+            while let Some(x) = stack.pop() {
+                for &(a, b) in edges {
+                    for (s, t) in [(a, b), (b, a)] {
+                        if s == x && prev[t] == usize::MAX {
+                            prev[t] = x;
+                            stack.push(t);
+                        }
+                    }
+                }
+            }
+            if prev[v] == usize::MAX {
+                return None;
+            }
+            let (mut x, mut sum) = (v, vals[v]);
+            while x != u {
+                x = prev[x];
+                sum += vals[x];
+            }
+            Some(sum)
+        };
+        let mut rng = TestRng::new(11);
+        for _ in 0..3000 {
+            let (u, v) = (rng.index(n), rng.index(n));
+            match rng.below(4) {
+                0 if u != v => {
+                    let linked = naive_path(&edges, &vals, u, v).is_none();
+                    assert_eq!(linked, lct.link(u, v));
+                    if linked {
+                        edges.push((u, v));
+                    }
+                }
+                1 if !edges.is_empty() => {
+                    let (a, b) = edges.swap_remove(u % edges.len());
+                    assert!(lct.cut(b, a));
+                }
+                2 => {
+                    vals[u] = v as i64 * 10;
+                    lct.set(u, v as i64 * 10);
+                }
+                _ => assert_eq!(naive_path(&edges, &vals, u, v), lct.path(u, v)),
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use super::TreapSet;
+
+/// A multiset with order statistics, for the "k-th smallest so far" pattern.
+///
+/// Each element is stored in a [`TreapSet`] together with a sequence number that tells apart
+/// the equal elements, so every operation takes expected O(log n) time.
+pub struct OrderedMultiset<K: Ord + Clone> {
+    set: TreapSet<(K, u32)>,
+    seq: u32,
+}
+
+impl<K: Ord + Clone> Default for OrderedMultiset<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone> OrderedMultiset<K> {
+    pub const fn new() -> Self {
+        Self {
+            set: TreapSet::new(),
+            seq: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    pub fn insert(&mut self, key: K) {
+        self.seq += 1;
+        self.set.insert((key, self.seq));
+    }
+
+    /// Removes one occurrence of `key`. Returns `false` if there is none.
+    pub fn erase_one(&mut self, key: &K) -> bool {
+        match self.set.lower_bound(&(key.clone(), 0)) {
+            Some(e) if e.0 == *key => {
+                let e = e.clone();
+                self.set.remove(&e)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the `i`-th (0-based) smallest element, counting duplicates.
+    pub fn kth(&self, i: usize) -> Option<&K> {
+        self.set.kth(i).map(|(k, _)| k)
+    }
+
+    /// Returns the number of elements less than `key`.
+    pub fn count_less(&self, key: &K) -> usize {
+        self.set.rank(&(key.clone(), 0))
+    }
+
+    /// Returns the number of occurrences of `key`.
+    pub fn count(&self, key: &K) -> usize {
+        self.set
+            .count_range((key.clone(), 0)..=(key.clone(), u32::MAX))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        self.set.iter().map(|(k, _)| k)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn matches_sorted_vec() {
+        let mut ms = OrderedMultiset::new();
+        let mut v: Vec<u32> = Vec::new();
+        let mut rng = TestRng::new(9);
+        for _ in 0..2000 {
+            let x = rng.below(30) as u32;
+            match rng.below(3) {
+                0 => {
+                    ms.insert(x);
+                    let i = v.partition_point(|&y| y <= x);
+                    v.insert(i, x);
+                }
+                1 => {
+                    let i = v.partition_point(|&y| y < x);
+                    let present = v.get(i) == Some(&x);
+                    if present {
+                        v.remove(i);
+                    }
+                    assert_eq!(present, ms.erase_one(&x));
+                }
+                _ => {
+                    assert_eq!(v.partition_point(|&y| y < x), ms.count_less(&x));
+                    assert_eq!(v.iter().filter(|&&y| y == x).count(), ms.count(&x));
+                    assert_eq!(v.get(x as usize), ms.kth(x as usize));
+                }
+            }
+        }
+        assert!(v.iter().eq(ms.iter()));
+    }
+}
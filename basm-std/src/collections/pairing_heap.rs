@@ -0,0 +1,152 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+struct Node<T> {
+    val: T,
+    child: Option<Box<Node<T>>>,
+    sibling: Option<Box<Node<T>>>,
+}
+
+fn link<T: Ord>(mut a: Box<Node<T>>, mut b: Box<Node<T>>) -> Box<Node<T>> {
+    if b.val < a.val {
+        core::mem::swap(&mut a, &mut b);
+    }
+    b.sibling = a.child.take();
+    a.child = Some(b);
+    a
+}
+
+/// A min-heap based on a pairing heap, with O(1) [`push`](PairingHeap::push) and
+/// [`meld`](PairingHeap::meld), and amortized O(log n) [`pop`](PairingHeap::pop).
+///
+/// Melding in O(1) makes merging the heaps of the children in tree DP O(n log n) overall.
+pub struct PairingHeap<T: Ord> {
+    root: Option<Box<Node<T>>>,
+    len: usize,
+}
+
+impl<T: Ord> Default for PairingHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> PairingHeap<T> {
+    pub const fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, val: T) {
+        let node = Box::new(Node {
+            val,
+            child: None,
+            sibling: None,
+        });
+        self.root = Some(match self.root.take() {
+            Some(root) => link(root, node),
+            None => node,
+        });
+        self.len += 1;
+    }
+
+    /// Returns the smallest element.
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|r| &r.val)
+    }
+
+    /// Removes and returns the smallest element.
+    pub fn pop(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        let Node { val, mut child, .. } = *root;
+        // Two-pass pairing: link the children in pairs from left to right,
+        // then link the pairs from right to left.
+        let mut pairs = Vec::new();
+        while let Some(mut a) = child {
+            child = a.sibling.take();
+            match child {
+                Some(mut b) => {
+                    child = b.sibling.take();
+                    pairs.push(link(a, b));
+                }
+                None => pairs.push(a),
+            }
+        }
+        self.root = pairs.into_iter().rev().reduce(|acc, p| link(p, acc));
+        self.len -= 1;
+        Some(val)
+    }
+
+    /// Moves all elements of `other` into `self` in O(1).
+    pub fn meld(&mut self, mut other: Self) {
+        self.len += other.len;
+        other.len = 0;
+        self.root = match (self.root.take(), other.root.take()) {
+            (Some(a), Some(b)) => Some(link(a, b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+impl<T: Ord> Drop for PairingHeap<T> {
+    fn drop(&mut self) {
+        // Dropping the nodes recursively could overflow the stack on long sibling chains.
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.child.take());
+            stack.extend(node.sibling.take());
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for PairingHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = Self::new();
+        for x in iter {
+            heap.push(x);
+        }
+        heap
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pops_in_order_after_melds() {
+        let mut heaps: Vec<PairingHeap<u32>> = (0..10)
+            .map(|i| (0..100).map(|j| (j * 37 + i * 11) % 1000).collect())
+            .collect();
+        let mut expected = Vec::new();
+        for i in 0..10 {
+            expected.extend((0..100).map(|j| (j * 37 + i * 11) % 1000));
+        }
+        expected.sort();
+        let mut all = PairingHeap::new();
+        for h in heaps.drain(..) {
+            all.meld(h);
+        }
+        assert_eq!(1000, all.len());
+        assert_eq!(Some(&expected[0]), all.peek());
+        let mut out = Vec::new();
+        while let Some(x) = all.pop() {
+            out.push(x);
+        }
+        assert_eq!(expected, out);
+        assert!(all.is_empty());
+    }
+
+    #[test]
+    fn long_chains_drop_without_recursion() {
+        let heap: PairingHeap<u32> = (0..1_000_000).rev().collect();
+        assert_eq!(Some(&0), heap.peek());
+    }
+}
@@ -0,0 +1,294 @@
+use super::segment_tree::bounds;
+use super::Monoid;
+use alloc::vec::Vec;
+
+struct Node<T> {
+    l: u32,
+    r: u32,
+    val: T,
+}
+
+/// A persistent segment tree over the monoid `M`. Every update leaves the previous versions intact
+/// and returns the root of a new version, sharing all but O(log n) nodes with the old one.
+///
+/// Versions are `u32` root handles, and all nodes live in a single pool, so no allocation is made
+/// per node. [`PersistentSegTree::empty`] is the version holding `n` identities.
+pub struct PersistentSegTree<M: Monoid> {
+    nodes: Vec<Node<M::T>>,
+    n: usize,
+}
+
+impl<M: Monoid> PersistentSegTree<M> {
+    /// Creates a tree of `n` elements. Node 0 represents a subtree of identities and is its own child,
+    /// so the empty version takes a single node.
+    pub fn new(n: usize) -> Self {
+        Self::with_capacity(n, 1)
+    }
+
+    /// Same as [`PersistentSegTree::new`], but reserves room for `capacity` nodes.
+    /// Each update creates about `log2(n) + 1` nodes.
+    pub fn with_capacity(n: usize, capacity: usize) -> Self {
+        let mut nodes = Vec::with_capacity(capacity.max(1));
+        nodes.push(Node {
+            l: 0,
+            r: 0,
+            val: M::e(),
+        });
+        Self { nodes, n }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Returns the number of nodes in the pool.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the version holding `n` identities.
+    pub fn empty(&self) -> u32 {
+        0
+    }
+
+    fn push(&mut self, l: u32, r: u32, val: M::T) -> u32 {
+        let h = self.nodes.len() as u32;
+        self.nodes.push(Node { l, r, val });
+        h
+    }
+
+    fn join(&mut self, l: u32, r: u32) -> u32 {
+        let val = M::combine(&self.nodes[l as usize].val, &self.nodes[r as usize].val);
+        self.push(l, r, val)
+    }
+
+    /// Returns a new version holding the elements of `a`. `a.len()` must equal `len()`.
+    pub fn build(&mut self, a: &[M::T]) -> u32 {
+        assert_eq!(self.n, a.len());
+        if a.is_empty() {
+            return 0;
+        }
+        self.build_rec(a)
+    }
+
+    fn build_rec(&mut self, a: &[M::T]) -> u32 {
+        if a.len() == 1 {
+            return self.push(0, 0, a[0].clone());
+        }
+        let mid = a.len() / 2;
+        let l = self.build_rec(&a[..mid]);
+        let r = self.build_rec(&a[mid..]);
+        self.join(l, r)
+    }
+
+    /// Returns a new version where the `i`-th element of version `root` is replaced with `f` of it.
+    pub fn update<F>(&mut self, root: u32, i: usize, f: F) -> u32
+    where
+        F: FnOnce(&M::T) -> M::T,
+    {
+        assert!(i < self.n);
+        self.update_rec(root, 0, self.n, i, f)
+    }
+
+    fn update_rec<F>(&mut self, k: u32, lo: usize, hi: usize, i: usize, f: F) -> u32
+    where
+        F: FnOnce(&M::T) -> M::T,
+    {
+        if hi - lo == 1 {
+            let val = f(&self.nodes[k as usize].val);
+            return self.push(0, 0, val);
+        }
+        let mid = lo + (hi - lo) / 2;
+        let Node { l, r, .. } = self.nodes[k as usize];
+        if i < mid {
+            let l = self.update_rec(l, lo, mid, i, f);
+            self.join(l, r)
+        } else {
+            let r = self.update_rec(r, mid, hi, i, f);
+            self.join(l, r)
+        }
+    }
+
+    /// Returns a new version where the `i`-th element of version `root` is replaced with `x`.
+    pub fn set(&mut self, root: u32, i: usize, x: M::T) -> u32 {
+        self.update(root, i, |_| x)
+    }
+
+    /// Returns the `i`-th element of version `root`.
+    pub fn get(&self, root: u32, i: usize) -> &M::T {
+        assert!(i < self.n);
+        let (mut k, mut lo, mut hi) = (root, 0, self.n);
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if i < mid {
+                k = self.nodes[k as usize].l;
+                hi = mid;
+            } else {
+                k = self.nodes[k as usize].r;
+                lo = mid;
+            }
+        }
+        &self.nodes[k as usize].val
+    }
+
+    /// Returns the product of the elements of version `root` in `range`.
+    pub fn query<B>(&self, root: u32, range: B) -> M::T
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = bounds(range, self.n);
+        if l == r {
+            return M::e();
+        }
+        self.query_rec(root, 0, self.n, l, r)
+    }
+
+    fn query_rec(&self, k: u32, lo: usize, hi: usize, l: usize, r: usize) -> M::T {
+        if l <= lo && hi <= r {
+            return self.nodes[k as usize].val.clone();
+        }
+        let mid = lo + (hi - lo) / 2;
+        let node = &self.nodes[k as usize];
+        if r <= mid {
+            self.query_rec(node.l, lo, mid, l, r)
+        } else if mid <= l {
+            self.query_rec(node.r, mid, hi, l, r)
+        } else {
+            M::combine(
+                &self.query_rec(node.l, lo, mid, l, r),
+                &self.query_rec(node.r, mid, hi, l, r),
+            )
+        }
+    }
+}
+
+struct Count;
+
+impl Monoid for Count {
+    type T = u32;
+    fn e() -> u32 {
+        0
+    }
+    fn combine(l: &u32, r: &u32) -> u32 {
+        l + r
+    }
+}
+
+/// Answers "the k-th smallest value in `a[l..r]`" for a static array in O(log n) per query,
+/// using a persistent segment tree over the compressed values with one version per prefix.
+pub struct KthSmallest<T: Ord + Clone> {
+    values: Vec<T>,
+    roots: Vec<u32>,
+    tree: PersistentSegTree<Count>,
+}
+
+impl<T: Ord + Clone> KthSmallest<T> {
+    pub fn new(a: &[T]) -> Self {
+        let mut values = a.to_vec();
+        values.sort_unstable();
+        values.dedup();
+        let m = values.len();
+        let mut tree =
+            PersistentSegTree::with_capacity(m, a.len() * (m.max(1).ilog2() as usize + 2) + 1);
+        let mut roots = Vec::with_capacity(a.len() + 1);
+        roots.push(tree.empty());
+        for x in a {
+            let i = values.binary_search(x).unwrap();
+            let root = tree.update(*roots.last().unwrap(), i, |&c| c + 1);
+            roots.push(root);
+        }
+        Self {
+            values,
+            roots,
+            tree,
+        }
+    }
+
+    /// Returns the `k`-th (0-based) smallest value in `a[l..r]`, or `None` if `k >= r - l`.
+    pub fn kth(&self, l: usize, r: usize, k: usize) -> Option<&T> {
+        assert!(l <= r && r < self.roots.len());
+        if k >= r - l {
+            return None;
+        }
+        let nodes = &self.tree.nodes;
+        let (mut a, mut b) = (self.roots[l], self.roots[r]);
+        let (mut lo, mut hi) = (0, self.values.len());
+        let mut k = k as u32;
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            let left =
+                nodes[nodes[b as usize].l as usize].val - nodes[nodes[a as usize].l as usize].val;
+            if k < left {
+                (a, b) = (nodes[a as usize].l, nodes[b as usize].l);
+                hi = mid;
+            } else {
+                k -= left;
+                (a, b) = (nodes[a as usize].r, nodes[b as usize].r);
+                lo = mid;
+            }
+        }
+        Some(&self.values[lo])
+    }
+
+    /// Returns the number of values less than `x` in `a[l..r]`.
+    pub fn count_less(&self, l: usize, r: usize, x: &T) -> usize {
+        let i = self.values.partition_point(|v| v < x);
+        (self.tree.query(self.roots[r], ..i) - self.tree.query(self.roots[l], ..i)) as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Sum;
+    impl Monoid for Sum {
+        type T = i64;
+        fn e() -> i64 {
+            0
+        }
+        fn combine(l: &i64, r: &i64) -> i64 {
+            l + r
+        }
+    }
+
+    #[test]
+    fn old_versions_are_kept() {
+        let mut tree = PersistentSegTree::<Sum>::new(5);
+        let v1 = tree.build(&[1, 2, 3, 4, 5]);
+        let v2 = tree.set(v1, 2, 10);
+        let v3 = tree.update(v2, 4, |x| x * 2);
+        assert_eq!(15, tree.query(v1, ..));
+        assert_eq!(22, tree.query(v2, ..));
+        assert_eq!(27, tree.query(v3, ..));
+        assert_eq!(16, tree.query(v3, 1..4));
+        assert_eq!(3, *tree.get(v1, 2));
+        assert_eq!(10, *tree.get(v3, 2));
+        assert_eq!(0, tree.query(tree.empty(), ..));
+        let v4 = tree.set(tree.empty(), 0, 7);
+        assert_eq!(7, tree.query(v4, ..=0));
+    }
+
+    #[test]
+    fn kth_smallest_matches_naive() {
+        let a = [5, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        let ks = KthSmallest::new(&a);
+        for l in 0..=a.len() {
+            for r in l..=a.len() {
+                let mut sorted = a[l..r].to_vec();
+                sorted.sort();
+                for k in 0..=sorted.len() {
+                    assert_eq!(sorted.get(k), ks.kth(l, r, k));
+                }
+                assert_eq!(
+                    sorted.iter().filter(|&&x| x < 5).count(),
+                    ks.count_less(l, r, &5)
+                );
+            }
+        }
+    }
+}
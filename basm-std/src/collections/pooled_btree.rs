@@ -0,0 +1,406 @@
+use super::{ArrayVec, Slab};
+use core::ops::{Bound, RangeBounds};
+
+const CAP: usize = 32;
+const NIL: u32 = Slab::<()>::NIL;
+
+struct Leaf<K, V> {
+    keys: ArrayVec<K, CAP>,
+    vals: ArrayVec<V, CAP>,
+    next: u32,
+}
+
+struct Internal<K> {
+    keys: ArrayVec<K, CAP>,
+    children: ArrayVec<u32, { CAP + 1 }>,
+}
+
+impl<K, V> Leaf<K, V> {
+    fn new() -> Self {
+        Self {
+            keys: ArrayVec::new(),
+            vals: ArrayVec::new(),
+            next: NIL,
+        }
+    }
+}
+
+/// An ordered map stored as a B+ tree whose nodes come from two [`Slab`]s and link to each other
+/// with `u32` handles.
+///
+/// Each node holds up to 32 keys in a contiguous array, and the leaves are chained for fast
+/// in-order iteration. [`PooledBTreeMap::from_sorted`] builds a tree from sorted entries in O(n).
+/// Removal does not merge nodes, so the height depends on the largest size the map has reached.
+pub struct PooledBTreeMap<K: Ord + Clone, V> {
+    leaves: Slab<Leaf<K, V>>,
+    internals: Slab<Internal<K>>,
+    root: u32,
+    height: usize,
+    len: usize,
+}
+
+impl<K: Ord + Clone, V> Default for PooledBTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone, V> PooledBTreeMap<K, V> {
+    pub fn new() -> Self {
+        let mut leaves = Slab::new();
+        let root = leaves.insert(Leaf::new());
+        Self {
+            leaves,
+            internals: Slab::new(),
+            root,
+            height: 0,
+            len: 0,
+        }
+    }
+
+    /// Builds a map from entries whose keys are strictly increasing, filling the nodes completely.
+    pub fn from_sorted<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        let (mut level, mut last) = (alloc::vec![map.root], map.root);
+        for (k, v) in iter {
+            let leaf = &map.leaves[last];
+            if let Some(prev) = leaf.keys.last() {
+                assert!(*prev < k, "keys must be strictly increasing");
+            }
+            if leaf.keys.is_full() {
+                let h = map.leaves.insert(Leaf::new());
+                map.leaves[last].next = h;
+                level.push(h);
+                last = h;
+            }
+            let leaf = &mut map.leaves[last];
+            leaf.keys.push(k);
+            leaf.vals.push(v);
+            map.len += 1;
+        }
+        while level.len() > 1 {
+            let mut up = alloc::vec::Vec::new();
+            for chunk in level.chunks(CAP) {
+                let mut node = Internal {
+                    keys: ArrayVec::new(),
+                    children: ArrayVec::new(),
+                };
+                for (i, &c) in chunk.iter().enumerate() {
+                    if i > 0 {
+                        node.keys.push(map.first_key(c, map.height));
+                    }
+                    node.children.push(c);
+                }
+                up.push(map.internals.insert(node));
+            }
+            level = up;
+            map.height += 1;
+        }
+        map.root = level[0];
+        map
+    }
+
+    fn first_key(&self, mut h: u32, height: usize) -> K {
+        for _ in 0..height {
+            h = self.internals[h].children[0];
+        }
+        self.leaves[h].keys[0].clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the leaf that may contain `key`.
+    fn leaf_of(&self, key: &K) -> u32 {
+        let mut h = self.root;
+        for _ in 0..self.height {
+            let node = &self.internals[h];
+            h = node.children[node.keys.partition_point(|k| k <= key)];
+        }
+        h
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let leaf = &self.leaves[self.leaf_of(key)];
+        let i = leaf.keys.binary_search(key).ok()?;
+        Some(&leaf.vals[i])
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let h = self.leaf_of(key);
+        let leaf = &mut self.leaves[h];
+        let i = leaf.keys.binary_search(key).ok()?;
+        Some(&mut leaf.vals[i])
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts `value` at `key`, returning the previous value if there was one.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (old, split) = self.insert_rec(self.root, self.height, key, value);
+        if let Some((sep, right)) = split {
+            let mut node = Internal {
+                keys: ArrayVec::new(),
+                children: ArrayVec::new(),
+            };
+            node.keys.push(sep);
+            node.children.push(self.root);
+            node.children.push(right);
+            self.root = self.internals.insert(node);
+            self.height += 1;
+        }
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Inserts into the subtree `h`, returning the old value and, if the node was split,
+    /// the first key of the new right sibling together with its handle.
+    fn insert_rec(
+        &mut self,
+        h: u32,
+        height: usize,
+        key: K,
+        value: V,
+    ) -> (Option<V>, Option<(K, u32)>) {
+        if height == 0 {
+            let leaf = &mut self.leaves[h];
+            let i = match leaf.keys.binary_search(&key) {
+                Ok(i) => return (Some(core::mem::replace(&mut leaf.vals[i], value)), None),
+                Err(i) => i,
+            };
+            if !leaf.keys.is_full() {
+                leaf.keys.insert(i, key);
+                leaf.vals.insert(i, value);
+                return (None, None);
+            }
+            let mut right = Leaf::new();
+            while leaf.keys.len() > CAP / 2 {
+                right.keys.insert(0, leaf.keys.pop().unwrap());
+                right.vals.insert(0, leaf.vals.pop().unwrap());
+            }
+            right.next = leaf.next;
+            if i <= CAP / 2 {
+                leaf.keys.insert(i, key);
+                leaf.vals.insert(i, value);
+            } else {
+                right.keys.insert(i - CAP / 2, key);
+                right.vals.insert(i - CAP / 2, value);
+            }
+            let sep = right.keys[0].clone();
+            let r = self.leaves.insert(right);
+            self.leaves[h].next = r;
+            return (None, Some((sep, r)));
+        }
+        let node = &self.internals[h];
+        let i = node.keys.partition_point(|k| *k <= key);
+        let (old, split) = self.insert_rec(node.children[i], height - 1, key, value);
+        let Some((sep, child)) = split else {
+            return (old, None);
+        };
+        let node = &mut self.internals[h];
+        if !node.keys.is_full() {
+            node.keys.insert(i, sep);
+            node.children.insert(i + 1, child);
+            return (old, None);
+        }
+        let mut right = Internal {
+            keys: ArrayVec::new(),
+            children: ArrayVec::new(),
+        };
+        while node.keys.len() > CAP / 2 + 1 {
+            right.keys.insert(0, node.keys.pop().unwrap());
+            right.children.insert(0, node.children.pop().unwrap());
+        }
+        right.children.insert(0, node.children.pop().unwrap());
+        let up = node.keys.pop().unwrap();
+        if i <= CAP / 2 {
+            node.keys.insert(i, sep);
+            node.children.insert(i + 1, child);
+        } else {
+            right.keys.insert(i - CAP / 2 - 1, sep);
+            right.children.insert(i - CAP / 2, child);
+        }
+        let r = self.internals.insert(right);
+        (old, Some((up, r)))
+    }
+
+    /// Removes `key`, returning its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let h = self.leaf_of(key);
+        let leaf = &mut self.leaves[h];
+        let i = leaf.keys.binary_search(key).ok()?;
+        leaf.keys.remove(i);
+        self.len -= 1;
+        Some(leaf.vals.remove(i))
+    }
+
+    /// Returns an iterator over the entries with keys in `range`, in increasing order.
+    pub fn range<'a, R: RangeBounds<K> + 'a>(
+        &'a self,
+        range: R,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + 'a {
+        let (mut leaf, mut pos) = match range.start_bound() {
+            Bound::Unbounded => {
+                let mut h = self.root;
+                for _ in 0..self.height {
+                    h = self.internals[h].children[0];
+                }
+                (h, 0)
+            }
+            Bound::Included(k) => {
+                let h = self.leaf_of(k);
+                (h, self.leaves[h].keys.partition_point(|x| x < k))
+            }
+            Bound::Excluded(k) => {
+                let h = self.leaf_of(k);
+                (h, self.leaves[h].keys.partition_point(|x| x <= k))
+            }
+        };
+        core::iter::from_fn(move || loop {
+            if leaf == NIL {
+                return None;
+            }
+            let node = &self.leaves[leaf];
+            if pos < node.keys.len() {
+                pos += 1;
+                return Some((&node.keys[pos - 1], &node.vals[pos - 1]));
+            }
+            leaf = node.next;
+            pos = 0;
+        })
+        .take_while(move |(k, _)| match range.end_bound() {
+            Bound::Unbounded => true,
+            Bound::Included(e) => *k <= e,
+            Bound::Excluded(e) => *k < e,
+        })
+    }
+
+    /// Returns an iterator over the entries in increasing order of keys.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.range(..)
+    }
+
+    /// Returns the entry with the smallest key that is at least `key`.
+    pub fn lower_bound(&self, key: &K) -> Option<(&K, &V)> {
+        self.range(key.clone()..).next()
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+}
+
+/// An ordered set backed by a [`PooledBTreeMap`].
+#[derive(Default)]
+pub struct PooledBTreeSet<K: Ord + Clone> {
+    map: PooledBTreeMap<K, ()>,
+}
+
+impl<K: Ord + Clone> PooledBTreeSet<K> {
+    pub fn new() -> Self {
+        Self {
+            map: PooledBTreeMap::new(),
+        }
+    }
+
+    /// Builds a set from strictly increasing keys.
+    pub fn from_sorted<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        Self {
+            map: PooledBTreeMap::from_sorted(iter.into_iter().map(|k| (k, ()))),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Adds `key` to the set. Returns `true` if it was not present.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    /// Removes `key` from the set. Returns `true` if it was present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.map.remove(key).is_some()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Returns the smallest key that is at least `key`.
+    pub fn lower_bound(&self, key: &K) -> Option<&K> {
+        self.map.lower_bound(key).map(|(k, _)| k)
+    }
+
+    pub fn range<'a, R: RangeBounds<K> + 'a>(
+        &'a self,
+        range: R,
+    ) -> impl Iterator<Item = &'a K> + 'a {
+        self.map.range(range).map(|(k, _)| k)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &K> + '_ {
+        self.map.iter().map(|(k, _)| k)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn matches_btree_map() {
+        let mut map = PooledBTreeMap::new();
+        let mut naive = BTreeMap::new();
+        let mut rng = TestRng::new(13);
+        for i in 0..20000 {
+            let k = rng.below(3000);
+            match rng.below(4) {
+                0 | 1 => assert_eq!(naive.insert(k, i), map.insert(k, i)),
+                2 => assert_eq!(naive.remove(&k), map.remove(&k)),
+                _ => {
+                    assert_eq!(naive.get(&k), map.get(&k));
+                    let e = k + rng.below(100);
+                    assert!(naive.range(k..e).eq(map.range(k..e)));
+                    assert_eq!(naive.range(k..).next(), map.lower_bound(&k));
+                }
+            }
+            assert_eq!(naive.len(), map.len());
+        }
+        assert!(naive.iter().eq(map.iter()));
+    }
+
+    #[test]
+    fn bulk_load_then_insert() {
+        let mut set = PooledBTreeSet::from_sorted((0..5000).map(|x| x * 2));
+        assert_eq!(5000, set.len());
+        assert!(set.contains(&1234) && !set.contains(&1235));
+        for x in (1..2000).step_by(2) {
+            assert!(set.insert(x));
+        }
+        assert!(!set.insert(10));
+        assert!(set.remove(&10) && !set.remove(&10));
+        assert_eq!(Some(&11), set.lower_bound(&10));
+        let v: Vec<_> = set.range(7..=12).copied().collect();
+        assert_eq!([7, 8, 9, 11, 12], v[..]);
+        assert_eq!(5999, set.iter().count());
+        assert!(PooledBTreeSet::<u32>::from_sorted([]).is_empty());
+    }
+}
@@ -0,0 +1,325 @@
+use super::segment_tree::bounds;
+use alloc::vec::Vec;
+
+#[derive(Clone, Copy)]
+struct Node {
+    sum: i64,
+    max1: i64,
+    max2: i64,
+    maxc: i64,
+    min1: i64,
+    min2: i64,
+    minc: i64,
+    add: i64,
+}
+
+impl Node {
+    const EMPTY: Node = Node {
+        sum: 0,
+        max1: i64::MIN,
+        max2: i64::MIN,
+        maxc: 0,
+        min1: i64::MAX,
+        min2: i64::MAX,
+        minc: 0,
+        add: 0,
+    };
+
+    fn leaf(x: i64) -> Node {
+        Node {
+            sum: x,
+            max1: x,
+            max2: i64::MIN,
+            maxc: 1,
+            min1: x,
+            min2: i64::MAX,
+            minc: 1,
+            add: 0,
+        }
+    }
+}
+
+/// A segment tree beats (Ji Driver segment tree) over `i64`, supporting range chmin, range chmax,
+/// range add and range sum/max/min in amortized O(log^2 n) per operation.
+///
+/// `chmin` and `chmax` are not expressible with [`LazySegTree`](super::LazySegTree), since their
+/// effect on the sum depends on more than the sum itself.
+pub struct SegTreeBeats {
+    t: Vec<Node>,
+    n: usize,
+}
+
+impl SegTreeBeats {
+    pub fn new(a: &[i64]) -> Self {
+        let mut tree = Self {
+            t: alloc::vec![Node::EMPTY; 4 * a.len().max(1)],
+            n: a.len(),
+        };
+        if !a.is_empty() {
+            tree.build(1, 0, a.len(), a);
+        }
+        tree
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    fn build(&mut self, k: usize, lo: usize, hi: usize, a: &[i64]) {
+        if hi - lo == 1 {
+            self.t[k] = Node::leaf(a[lo]);
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        self.build(2 * k, lo, mid, a);
+        self.build(2 * k + 1, mid, hi, a);
+        self.pull(k);
+    }
+
+    fn pull(&mut self, k: usize) {
+        let (l, r) = (self.t[2 * k], self.t[2 * k + 1]);
+        let node = &mut self.t[k];
+        node.sum = l.sum + r.sum;
+        if l.max1 == r.max1 {
+            node.max1 = l.max1;
+            node.maxc = l.maxc + r.maxc;
+            node.max2 = l.max2.max(r.max2);
+        } else if l.max1 > r.max1 {
+            node.max1 = l.max1;
+            node.maxc = l.maxc;
+            node.max2 = l.max2.max(r.max1);
+        } else {
+            node.max1 = r.max1;
+            node.maxc = r.maxc;
+            node.max2 = l.max1.max(r.max2);
+        }
+        if l.min1 == r.min1 {
+            node.min1 = l.min1;
+            node.minc = l.minc + r.minc;
+            node.min2 = l.min2.min(r.min2);
+        } else if l.min1 < r.min1 {
+            node.min1 = l.min1;
+            node.minc = l.minc;
+            node.min2 = l.min2.min(r.min1);
+        } else {
+            node.min1 = r.min1;
+            node.minc = r.minc;
+            node.min2 = l.min1.min(r.min2);
+        }
+    }
+
+    fn apply_add(&mut self, k: usize, len: usize, x: i64) {
+        let node = &mut self.t[k];
+        node.sum += x * len as i64;
+        node.max1 += x;
+        if node.max2 != i64::MIN {
+            node.max2 += x;
+        }
+        node.min1 += x;
+        if node.min2 != i64::MAX {
+            node.min2 += x;
+        }
+        node.add += x;
+    }
+
+    /// Lowers the maximum of node `k` to `x`, where `max2 < x < max1`.
+    fn apply_chmin(&mut self, k: usize, x: i64) {
+        let node = &mut self.t[k];
+        if x >= node.max1 {
+            return;
+        }
+        node.sum -= (node.max1 - x) * node.maxc;
+        if node.min1 == node.max1 {
+            node.min1 = x;
+        } else if node.min2 == node.max1 {
+            node.min2 = x;
+        }
+        node.max1 = x;
+    }
+
+    /// Raises the minimum of node `k` to `x`, where `min1 < x < min2`.
+    fn apply_chmax(&mut self, k: usize, x: i64) {
+        let node = &mut self.t[k];
+        if x <= node.min1 {
+            return;
+        }
+        node.sum += (x - node.min1) * node.minc;
+        if node.max1 == node.min1 {
+            node.max1 = x;
+        } else if node.max2 == node.min1 {
+            node.max2 = x;
+        }
+        node.min1 = x;
+    }
+
+    fn push(&mut self, k: usize, lo: usize, hi: usize) {
+        let mid = (lo + hi) / 2;
+        let add = core::mem::replace(&mut self.t[k].add, 0);
+        if add != 0 {
+            self.apply_add(2 * k, mid - lo, add);
+            self.apply_add(2 * k + 1, hi - mid, add);
+        }
+        let (max1, min1) = (self.t[k].max1, self.t[k].min1);
+        for c in [2 * k, 2 * k + 1] {
+            self.apply_chmin(c, max1);
+            self.apply_chmax(c, min1);
+        }
+    }
+
+    fn chmin_rec(&mut self, k: usize, lo: usize, hi: usize, l: usize, r: usize, x: i64) {
+        if r <= lo || hi <= l || self.t[k].max1 <= x {
+            return;
+        }
+        if l <= lo && hi <= r && self.t[k].max2 < x {
+            self.apply_chmin(k, x);
+            return;
+        }
+        self.push(k, lo, hi);
+        let mid = (lo + hi) / 2;
+        self.chmin_rec(2 * k, lo, mid, l, r, x);
+        self.chmin_rec(2 * k + 1, mid, hi, l, r, x);
+        self.pull(k);
+    }
+
+    fn chmax_rec(&mut self, k: usize, lo: usize, hi: usize, l: usize, r: usize, x: i64) {
+        if r <= lo || hi <= l || self.t[k].min1 >= x {
+            return;
+        }
+        if l <= lo && hi <= r && self.t[k].min2 > x {
+            self.apply_chmax(k, x);
+            return;
+        }
+        self.push(k, lo, hi);
+        let mid = (lo + hi) / 2;
+        self.chmax_rec(2 * k, lo, mid, l, r, x);
+        self.chmax_rec(2 * k + 1, mid, hi, l, r, x);
+        self.pull(k);
+    }
+
+    fn add_rec(&mut self, k: usize, lo: usize, hi: usize, l: usize, r: usize, x: i64) {
+        if r <= lo || hi <= l {
+            return;
+        }
+        if l <= lo && hi <= r {
+            self.apply_add(k, hi - lo, x);
+            return;
+        }
+        self.push(k, lo, hi);
+        let mid = (lo + hi) / 2;
+        self.add_rec(2 * k, lo, mid, l, r, x);
+        self.add_rec(2 * k + 1, mid, hi, l, r, x);
+        self.pull(k);
+    }
+
+    fn query_rec(&mut self, k: usize, lo: usize, hi: usize, l: usize, r: usize) -> Node {
+        if r <= lo || hi <= l {
+            return Node::EMPTY;
+        }
+        if l <= lo && hi <= r {
+            return self.t[k];
+        }
+        self.push(k, lo, hi);
+        let mid = (lo + hi) / 2;
+        let a = self.query_rec(2 * k, lo, mid, l, r);
+        let b = self.query_rec(2 * k + 1, mid, hi, l, r);
+        Node {
+            sum: a.sum + b.sum,
+            max1: a.max1.max(b.max1),
+            min1: a.min1.min(b.min1),
+            ..Node::EMPTY
+        }
+    }
+
+    /// Replaces each `a[i]` in `range` with `min(a[i], x)`.
+    pub fn chmin<B: core::ops::RangeBounds<usize>>(&mut self, range: B, x: i64) {
+        let (l, r) = bounds(range, self.n);
+        if l < r {
+            self.chmin_rec(1, 0, self.n, l, r, x);
+        }
+    }
+
+    /// Replaces each `a[i]` in `range` with `max(a[i], x)`.
+    pub fn chmax<B: core::ops::RangeBounds<usize>>(&mut self, range: B, x: i64) {
+        let (l, r) = bounds(range, self.n);
+        if l < r {
+            self.chmax_rec(1, 0, self.n, l, r, x);
+        }
+    }
+
+    /// Adds `x` to each `a[i]` in `range`.
+    pub fn add<B: core::ops::RangeBounds<usize>>(&mut self, range: B, x: i64) {
+        let (l, r) = bounds(range, self.n);
+        if l < r {
+            self.add_rec(1, 0, self.n, l, r, x);
+        }
+    }
+
+    /// Returns the sum of the elements in `range`.
+    pub fn sum<B: core::ops::RangeBounds<usize>>(&mut self, range: B) -> i64 {
+        let (l, r) = bounds(range, self.n);
+        if l == r {
+            return 0;
+        }
+        self.query_rec(1, 0, self.n, l, r).sum
+    }
+
+    /// Returns the maximum of the elements in `range`, or `i64::MIN` if it is empty.
+    pub fn max<B: core::ops::RangeBounds<usize>>(&mut self, range: B) -> i64 {
+        let (l, r) = bounds(range, self.n);
+        if l == r {
+            return i64::MIN;
+        }
+        self.query_rec(1, 0, self.n, l, r).max1
+    }
+
+    /// Returns the minimum of the elements in `range`, or `i64::MAX` if it is empty.
+    pub fn min<B: core::ops::RangeBounds<usize>>(&mut self, range: B) -> i64 {
+        let (l, r) = bounds(range, self.n);
+        if l == r {
+            return i64::MAX;
+        }
+        self.query_rec(1, 0, self.n, l, r).min1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+
+    #[test]
+    fn matches_naive() {
+        let mut rng = TestRng::new(7);
+        let mut rand = |m: u64| rng.below(m);
+        for n in [1, 2, 5, 33] {
+            let mut a: Vec<i64> = (0..n).map(|_| rand(100) as i64 - 50).collect();
+            let mut tree = SegTreeBeats::new(&a);
+            for _ in 0..2000 {
+                let l = rand(n as u64) as usize;
+                let r = l + 1 + rand((n - l) as u64) as usize;
+                let x = rand(100) as i64 - 50;
+                match rand(6) {
+                    0 => {
+                        tree.chmin(l..r, x);
+                        a[l..r].iter_mut().for_each(|v| *v = (*v).min(x));
+                    }
+                    1 => {
+                        tree.chmax(l..r, x);
+                        a[l..r].iter_mut().for_each(|v| *v = (*v).max(x));
+                    }
+                    2 => {
+                        tree.add(l..r, x);
+                        a[l..r].iter_mut().for_each(|v| *v += x);
+                    }
+                    3 => assert_eq!(a[l..r].iter().sum::<i64>(), tree.sum(l..r)),
+                    4 => assert_eq!(*a[l..r].iter().max().unwrap(), tree.max(l..r)),
+                    _ => assert_eq!(*a[l..r].iter().min().unwrap(), tree.min(l..r)),
+                }
+            }
+        }
+    }
+}
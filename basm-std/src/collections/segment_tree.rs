@@ -1,40 +1,128 @@
-use alloc::{vec, vec::Vec};
+use crate::platform::allocator::api::{self, Allocator, Global};
 
-pub trait SegmentOp {
+/// A monoid: an associative operation `combine` with the identity `e`.
+pub trait Monoid {
     type T: Clone;
-    type U;
     fn e() -> Self::T;
     fn combine(l: &Self::T, r: &Self::T) -> Self::T;
+}
+
+/// A monoid with updates `U` that are applied to a single element.
+pub trait SegmentOp: Monoid {
+    type U;
     fn apply(v: &mut Self::T, u: &Self::U);
 }
 
-pub struct SegmentTree<Op: SegmentOp> {
-    v: Vec<Op::T>,
+pub(super) fn bounds<B: core::ops::RangeBounds<usize>>(range: B, n: usize) -> (usize, usize) {
+    use core::ops::Bound::*;
+    let l = match range.start_bound() {
+        Included(&x) => x,
+        Excluded(&x) => x + 1,
+        Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        Included(&x) => x + 1,
+        Excluded(&x) => x,
+        Unbounded => n,
+    };
+    assert!(l <= r && r <= n, "range out of bounds");
+    (l, r)
+}
+
+/// A segment tree over `Op`. The nodes are stored with the allocator `A`
+/// (see [`api`](crate::platform::allocator::api)).
+pub struct SegmentTree<Op: SegmentOp, A: Allocator = Global> {
+    v: api::Vec<Op::T, A>,
     n: usize,
+    len: usize,
 }
 
+/// The [`SegmentOp`] whose updates replace an element, so that only the monoid `M` is needed.
+pub struct Assign<M>(core::marker::PhantomData<M>);
+
+impl<M: Monoid> Monoid for Assign<M> {
+    type T = M::T;
+    fn e() -> M::T {
+        M::e()
+    }
+    fn combine(l: &M::T, r: &M::T) -> M::T {
+        M::combine(l, r)
+    }
+}
+
+impl<M: Monoid> SegmentOp for Assign<M> {
+    type U = M::T;
+    fn apply(v: &mut M::T, u: &M::T) {
+        *v = u.clone();
+    }
+}
+
+/// A segment tree over the monoid `M` whose updates replace an element.
+pub type SegTree<M> = SegmentTree<Assign<M>>;
+
 impl<Op: SegmentOp> SegmentTree<Op> {
     pub fn new(n: usize) -> Self {
-        let n = n.next_power_of_two();
-        let v = vec![Op::e(); n * 2];
-        Self { v, n }
+        Self::new_in(n, Global)
     }
 
-    #[allow(clippy::uninit_vec)]
     pub fn from_iter<I>(n: usize, iter: I) -> Self
+    where
+        I: IntoIterator<Item = Op::T>,
+    {
+        Self::from_iter_in(n, iter, Global)
+    }
+
+    /// Creates a tree holding a copy of `a`.
+    pub fn from_slice(a: &[Op::T]) -> Self {
+        Self::from_iter(a.len(), a.iter().cloned())
+    }
+}
+
+impl<Op: SegmentOp, A: Allocator> SegmentTree<Op, A> {
+    /// Same as [`SegmentTree::new`], but the nodes are allocated with `alloc` (e.g., `&arena`).
+    pub fn new_in(n: usize, alloc: A) -> Self {
+        let len = n;
+        let n = n.next_power_of_two();
+        let v = api::vec_from_elem_in(Op::e(), n * 2, alloc);
+        Self { v, n, len }
+    }
+
+    /// Same as [`SegmentTree::from_iter`], but the nodes are allocated with `alloc` (e.g., `&arena`).
+    pub fn from_iter_in<I>(n: usize, iter: I, alloc: A) -> Self
     where
         I: IntoIterator<Item = Op::T>,
     {
         let off = n.next_power_of_two();
-        let mut v = Vec::with_capacity(off * 2);
-        // Safety: initializes right before return
-        unsafe { v.set_len(off) };
+        let mut v = api::vec_with_capacity_in(off * 2, alloc);
+        // The internal nodes are overwritten below, but must hold valid values since assigning drops them.
+        v.resize(off, Op::e());
         v.extend(iter.into_iter().take(n));
         v.resize(off * 2, Op::e());
         for i in (1..off).rev() {
             v[i] = Op::combine(&v[i * 2], &v[i * 2 + 1]);
         }
-        Self { v, n: off }
+        Self { v, n: off, len: n }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if and only if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the `i`-th element.
+    pub fn get(&self, i: usize) -> &Op::T {
+        assert!(i < self.len);
+        &self.v[i + self.n]
+    }
+
+    /// Returns the product of all elements.
+    pub fn all(&self) -> &Op::T {
+        &self.v[1]
     }
 
     pub fn query<B>(&self, range: B) -> Op::T
@@ -103,6 +191,89 @@ impl<Op: SegmentOp> SegmentTree<Op> {
             p - self.n
         }
     }
+
+    /// Returns the largest `r` such that `pred(&self.query(l..r))` holds, assuming `pred` is monotone
+    /// (true up to some point and false afterwards). `pred(&Op::e())` must be true.
+    pub fn max_right<P>(&self, l: usize, pred: P) -> usize
+    where
+        P: Fn(&Op::T) -> bool,
+    {
+        assert!(l <= self.len);
+        debug_assert!(pred(&Op::e()));
+        if l == self.len {
+            return self.len;
+        }
+        let mut l = l + self.n;
+        let mut sum = Op::e();
+        loop {
+            while l & 1 == 0 {
+                l >>= 1;
+            }
+            let test = Op::combine(&sum, &self.v[l]);
+            if !pred(&test) {
+                while l < self.n {
+                    l *= 2;
+                    let test = Op::combine(&sum, &self.v[l]);
+                    if pred(&test) {
+                        sum = test;
+                        l += 1;
+                    }
+                }
+                // The padding holds identities, so the answer never lies inside it.
+                return (l - self.n).min(self.len);
+            }
+            sum = test;
+            l += 1;
+            if l & l.wrapping_neg() == l {
+                return self.len;
+            }
+        }
+    }
+
+    /// Returns the smallest `l` such that `pred(&self.query(l..r))` holds, assuming `pred` is monotone
+    /// (true from some point to `r` and false before). `pred(&Op::e())` must be true.
+    pub fn min_left<P>(&self, r: usize, pred: P) -> usize
+    where
+        P: Fn(&Op::T) -> bool,
+    {
+        assert!(r <= self.len);
+        debug_assert!(pred(&Op::e()));
+        if r == 0 {
+            return 0;
+        }
+        let mut r = r + self.n;
+        let mut sum = Op::e();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 != 0 {
+                r >>= 1;
+            }
+            let test = Op::combine(&self.v[r], &sum);
+            if !pred(&test) {
+                while r < self.n {
+                    r = 2 * r + 1;
+                    let test = Op::combine(&self.v[r], &sum);
+                    if pred(&test) {
+                        sum = test;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.n;
+            }
+            sum = test;
+            if r & r.wrapping_neg() == r {
+                return 0;
+            }
+        }
+    }
+}
+
+impl<M: Monoid, A: Allocator> SegmentTree<Assign<M>, A> {
+    /// Replaces the `i`-th element with `x`.
+    pub fn set(&mut self, i: usize, x: M::T) {
+        assert!(i < self.len);
+        self.update(i, x);
+    }
 }
 
 #[cfg(test)]
@@ -111,15 +282,17 @@ mod test {
 
     struct Add {}
 
-    impl SegmentOp for Add {
+    impl Monoid for Add {
         type T = usize;
-        type U = usize;
         fn e() -> Self::T {
             0
         }
         fn combine(l: &Self::T, r: &Self::T) -> Self::T {
             l + r
         }
+    }
+    impl SegmentOp for Add {
+        type U = usize;
         fn apply(_v: &mut Self::T, _u: &Self::U) {
             unimplemented!()
         }
@@ -130,4 +303,71 @@ mod test {
         assert_eq!(tree.n, 4);
         assert_eq!(&tree.v[1..], [6, 3, 3, 1, 2, 3, 0]);
     }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn nodes_can_be_placed_in_an_arena() {
+        let arena = crate::platform::allocator::Arena::new();
+        let tree: SegmentTree<Add, _> = SegmentTree::from_iter_in(3, [1, 2, 3], &arena);
+        assert_eq!(8 * core::mem::size_of::<usize>(), arena.used_bytes());
+        assert_eq!(5, tree.query(1..));
+        assert_eq!(2, tree.partition_point(|&x| x < 4));
+    }
+
+    struct Sum;
+    impl Monoid for Sum {
+        type T = u64;
+        fn e() -> u64 {
+            0
+        }
+        fn combine(l: &u64, r: &u64) -> u64 {
+            l + r
+        }
+    }
+
+    struct Concat;
+    impl Monoid for Concat {
+        type T = alloc::string::String;
+        fn e() -> Self::T {
+            Self::T::new()
+        }
+        fn combine(l: &Self::T, r: &Self::T) -> Self::T {
+            alloc::format!("{}{}", l, r)
+        }
+    }
+
+    #[test]
+    fn query_is_ordered() {
+        let words = ["a", "b", "c", "d", "e"].map(alloc::string::String::from);
+        let mut tree = SegTree::<Concat>::from_slice(&words);
+        assert_eq!("bcd", tree.query(1..4));
+        tree.set(2, "x".into());
+        assert_eq!("abx", tree.query(..=2));
+        assert_eq!("abxde", tree.all());
+        assert_eq!("", tree.query(3..3));
+    }
+
+    #[test]
+    fn binary_search_matches_naive() {
+        let a = [3u64, 1, 4, 1, 5, 9, 2, 6, 5];
+        let tree = SegTree::<Sum>::from_slice(&a);
+        for l in 0..=a.len() {
+            for x in 0..30 {
+                let r = tree.max_right(l, |&s| s <= x);
+                let naive = (l..=a.len())
+                    .rev()
+                    .find(|&r| a[l..r].iter().sum::<u64>() <= x)
+                    .unwrap();
+                assert_eq!(naive, r);
+            }
+        }
+        for r in 0..=a.len() {
+            for x in 0..30 {
+                let l = tree.min_left(r, |&s| s <= x);
+                let naive = (0..=r).find(|&l| a[l..r].iter().sum::<u64>() <= x).unwrap();
+                assert_eq!(naive, l);
+            }
+        }
+        assert_eq!(0, SegTree::<Sum>::new(0).max_right(0, |_| true));
+    }
 }
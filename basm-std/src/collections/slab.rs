@@ -0,0 +1,225 @@
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut};
+
+enum Slot<T> {
+    Occupied(T),
+    Vacant(u32),
+}
+
+/// A pool of uniform nodes addressed by `u32` handles, with O(1) insertion and removal.
+///
+/// Removed slots are reused in LIFO order, and the nodes are stored contiguously in a `Vec`.
+/// This suits pointer-heavy structures (treaps, link-cut trees, etc.), where replacing
+/// `Option<Box<Node>>` links with `u32` handles halves the size of the links and avoids
+/// an allocator call for each node. [`Slab::NIL`] is never returned as a handle,
+/// so it can be used as a null link.
+pub struct Slab<T> {
+    slots: Vec<Slot<T>>,
+    free: u32,
+    len: usize,
+}
+
+impl<T> Slab<T> {
+    /// A handle that is never returned by [`Slab::insert`].
+    pub const NIL: u32 = u32::MAX;
+
+    pub const fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Self::NIL,
+            len: 0,
+        }
+    }
+
+    /// Creates an empty slab that can hold `capacity` nodes without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free: Self::NIL,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of nodes in the slab.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of nodes the slab can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Stores `x` and returns its handle. The handle of a removed node may be reused.
+    pub fn insert(&mut self, x: T) -> u32 {
+        self.len += 1;
+        if self.free != Self::NIL {
+            let h = self.free;
+            match core::mem::replace(&mut self.slots[h as usize], Slot::Occupied(x)) {
+                Slot::Vacant(next) => self.free = next,
+                Slot::Occupied(_) => unreachable!(),
+            }
+            h
+        } else {
+            let h = self.slots.len() as u32;
+            assert!(h != Self::NIL, "Slab is full");
+            self.slots.push(Slot::Occupied(x));
+            h
+        }
+    }
+
+    /// Removes and returns the node of handle `h`. A runtime error will occur if there is no such node.
+    pub fn remove(&mut self, h: u32) -> T {
+        match core::mem::replace(&mut self.slots[h as usize], Slot::Vacant(self.free)) {
+            Slot::Occupied(x) => {
+                self.free = h;
+                self.len -= 1;
+                x
+            }
+            Slot::Vacant(next) => {
+                self.slots[h as usize] = Slot::Vacant(next);
+                panic!("Slab: no node with handle {}", h)
+            }
+        }
+    }
+
+    /// Returns `true` if there is a node of handle `h`.
+    pub fn contains(&self, h: u32) -> bool {
+        matches!(self.slots.get(h as usize), Some(Slot::Occupied(_)))
+    }
+
+    pub fn get(&self, h: u32) -> Option<&T> {
+        match self.slots.get(h as usize) {
+            Some(Slot::Occupied(x)) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, h: u32) -> Option<&mut T> {
+        match self.slots.get_mut(h as usize) {
+            Some(Slot::Occupied(x)) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Returns mutable references to the distinct nodes of handles `a` and `b`.
+    /// A runtime error will occur if `a == b` or either node does not exist.
+    pub fn get2_mut(&mut self, a: u32, b: u32) -> (&mut T, &mut T) {
+        assert!(a != b);
+        let (lo, hi) = (a.min(b) as usize, a.max(b) as usize);
+        let (left, right) = self.slots.split_at_mut(hi);
+        match (&mut left[lo], &mut right[0]) {
+            (Slot::Occupied(x), Slot::Occupied(y)) => {
+                if a < b {
+                    (x, y)
+                } else {
+                    (y, x)
+                }
+            }
+            _ => panic!("Slab: no node with handle {} or {}", a, b),
+        }
+    }
+
+    /// Removes all nodes. The memory is kept for the subsequent insertions.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.free = Self::NIL;
+        self.len = 0;
+    }
+
+    /// Returns an iterator over the handles and the nodes, in the order of the handles.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &T)> {
+        self.slots.iter().enumerate().filter_map(|(i, s)| match s {
+            Slot::Occupied(x) => Some((i as u32, x)),
+            Slot::Vacant(_) => None,
+        })
+    }
+}
+
+impl<T> Default for Slab<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<u32> for Slab<T> {
+    type Output = T;
+    fn index(&self, h: u32) -> &T {
+        match &self.slots[h as usize] {
+            Slot::Occupied(x) => x,
+            Slot::Vacant(_) => panic!("Slab: no node with handle {}", h),
+        }
+    }
+}
+
+impl<T> IndexMut<u32> for Slab<T> {
+    fn index_mut(&mut self, h: u32) -> &mut T {
+        match &mut self.slots[h as usize] {
+            Slot::Occupied(x) => x,
+            Slot::Vacant(_) => panic!("Slab: no node with handle {}", h),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn removed_handles_are_reused_in_lifo_order() {
+        let mut slab = Slab::new();
+        let a = slab.insert("a");
+        let b = slab.insert("b");
+        let c = slab.insert("c");
+        assert_eq!((0, 1, 2), (a, b, c));
+        assert_eq!("a", slab.remove(a));
+        assert_eq!("c", slab.remove(c));
+        assert_eq!(1, slab.len());
+        assert!(!slab.contains(a) && slab.contains(b));
+        assert_eq!(c, slab.insert("d"));
+        assert_eq!(a, slab.insert("e"));
+        assert_eq!(3, slab.insert("f"));
+        assert_eq!(
+            vec![(0, &"e"), (1, &"b"), (2, &"d"), (3, &"f")],
+            slab.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn nodes_link_through_handles() {
+        struct Node {
+            val: u32,
+            next: u32,
+        }
+        let mut slab = Slab::with_capacity(10);
+        let mut head = Slab::<Node>::NIL;
+        for val in 0..10 {
+            head = slab.insert(Node { val, next: head });
+        }
+        let (x, y) = slab.get2_mut(9, 3);
+        core::mem::swap(&mut x.val, &mut y.val);
+        let mut vals = vec![];
+        while head != Slab::<Node>::NIL {
+            vals.push(slab[head].val);
+            head = slab[head].next;
+        }
+        assert_eq!(vec![3, 8, 7, 6, 5, 4, 9, 2, 1, 0], vals);
+        assert!(slab.get(10).is_none());
+        slab.clear();
+        assert!(slab.is_empty() && slab.capacity() >= 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn removing_twice_panics() {
+        let mut slab = Slab::new();
+        let a = slab.insert(1);
+        slab.remove(a);
+        slab.remove(a);
+    }
+}
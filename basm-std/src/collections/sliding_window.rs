@@ -0,0 +1,174 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+/// A queue that reports the minimum and the maximum of its elements in O(1),
+/// with amortized O(1) [`push`](SlidingWindow::push) and [`pop`](SlidingWindow::pop).
+///
+/// Only the candidates for the minimum and the maximum are kept, so the elements themselves
+/// cannot be accessed.
+pub struct SlidingWindow<T: Ord + Clone> {
+    min_q: VecDeque<(usize, T)>,
+    max_q: VecDeque<(usize, T)>,
+    head: usize,
+    tail: usize,
+}
+
+impl<T: Ord + Clone> Default for SlidingWindow<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone> SlidingWindow<T> {
+    pub const fn new() -> Self {
+        Self {
+            min_q: VecDeque::new(),
+            max_q: VecDeque::new(),
+            head: 0,
+            tail: 0,
+        }
+    }
+
+    /// Returns the number of elements in the window.
+    pub fn len(&self) -> usize {
+        self.tail - self.head
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head == self.tail
+    }
+
+    /// Appends `x` to the back of the window.
+    pub fn push(&mut self, x: T) {
+        while matches!(self.min_q.back(), Some((_, y)) if *y >= x) {
+            self.min_q.pop_back();
+        }
+        while matches!(self.max_q.back(), Some((_, y)) if *y <= x) {
+            self.max_q.pop_back();
+        }
+        self.min_q.push_back((self.tail, x.clone()));
+        self.max_q.push_back((self.tail, x));
+        self.tail += 1;
+    }
+
+    /// Removes the element at the front of the window. A runtime error will occur if it is empty.
+    pub fn pop(&mut self) {
+        assert!(!self.is_empty());
+        if self.min_q.front().unwrap().0 == self.head {
+            self.min_q.pop_front();
+        }
+        if self.max_q.front().unwrap().0 == self.head {
+            self.max_q.pop_front();
+        }
+        self.head += 1;
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        self.min_q.front().map(|(_, x)| x)
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        self.max_q.front().map(|(_, x)| x)
+    }
+}
+
+/// Returns the minimum of every window of `k` consecutive elements of `a`, in O(n).
+pub fn window_minima<T: Ord + Clone>(a: &[T], k: usize) -> Vec<T> {
+    assert!(k > 0);
+    let mut w = SlidingWindow::new();
+    let mut out = Vec::with_capacity((a.len() + 1).saturating_sub(k));
+    for (i, x) in a.iter().enumerate() {
+        w.push(x.clone());
+        if i >= k {
+            w.pop();
+        }
+        if i + 1 >= k {
+            out.push(w.min().unwrap().clone());
+        }
+    }
+    out
+}
+
+/// Returns the maximum of every window of `k` consecutive elements of `a`, in O(n).
+pub fn window_maxima<T: Ord + Clone>(a: &[T], k: usize) -> Vec<T> {
+    assert!(k > 0);
+    let mut w = SlidingWindow::new();
+    let mut out = Vec::with_capacity((a.len() + 1).saturating_sub(k));
+    for (i, x) in a.iter().enumerate() {
+        w.push(x.clone());
+        if i >= k {
+            w.pop();
+        }
+        if i + 1 >= k {
+            out.push(w.max().unwrap().clone());
+        }
+    }
+    out
+}
+
+/// For each `i`, returns the largest `j < i` with `a[j] < a[i]`, using a monotonic stack in O(n).
+pub fn prev_smaller<T: Ord>(a: &[T]) -> Vec<Option<usize>> {
+    let mut stack: Vec<usize> = Vec::new();
+    let mut out = Vec::with_capacity(a.len());
+    for (i, x) in a.iter().enumerate() {
+        while matches!(stack.last(), Some(&j) if a[j] >= *x) {
+            stack.pop();
+        }
+        out.push(stack.last().copied());
+        stack.push(i);
+    }
+    out
+}
+
+/// For each `i`, returns the smallest `j > i` with `a[j] < a[i]`, using a monotonic stack in O(n).
+pub fn next_smaller<T: Ord>(a: &[T]) -> Vec<Option<usize>> {
+    let mut stack: Vec<usize> = Vec::new();
+    let mut out = alloc::vec![None; a.len()];
+    for (i, x) in a.iter().enumerate().rev() {
+        while matches!(stack.last(), Some(&j) if a[j] >= *x) {
+            stack.pop();
+        }
+        out[i] = stack.last().copied();
+        stack.push(i);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn window_extrema_match_naive() {
+        let a = [5, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8];
+        for k in 1..=a.len() {
+            let mins: Vec<i32> = a.windows(k).map(|w| *w.iter().min().unwrap()).collect();
+            let maxs: Vec<i32> = a.windows(k).map(|w| *w.iter().max().unwrap()).collect();
+            assert_eq!(mins, window_minima(&a, k));
+            assert_eq!(maxs, window_maxima(&a, k));
+        }
+        assert!(window_minima(&a, a.len() + 1).is_empty());
+    }
+
+    #[test]
+    fn nearest_smaller_elements() {
+        let a = [3, 1, 4, 1, 5, 9, 2, 6];
+        assert_eq!(
+            [
+                None,
+                None,
+                Some(1),
+                None,
+                Some(3),
+                Some(4),
+                Some(3),
+                Some(6)
+            ],
+            prev_smaller(&a)[..]
+        );
+        assert_eq!(
+            [Some(1), None, Some(3), None, Some(6), Some(6), None, None],
+            next_smaller(&a)[..]
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use super::{Monoid, Slab};
+use alloc::vec::Vec;
+
+const NIL: u32 = Slab::<()>::NIL;
+
+struct Node<T> {
+    l: u32,
+    r: u32,
+    val: T,
+}
+
+/// A segment tree over the coordinates `lo..hi` whose nodes are created on demand,
+/// so coordinates as large as 10^18 can be used without compressing them offline.
+///
+/// Each update creates at most `log2(hi - lo) + 1` nodes, which live in a [`Slab`] and link to
+/// their children with `u32` handles. Absent subtrees hold identities.
+pub struct SparseSegTree<M: Monoid> {
+    nodes: Slab<Node<M::T>>,
+    root: u32,
+    lo: i64,
+    hi: i64,
+}
+
+impl<M: Monoid> SparseSegTree<M> {
+    /// Creates a tree over `lo..hi` holding identities. `hi - lo` must fit in an `i64`.
+    pub fn new(lo: i64, hi: i64) -> Self {
+        Self::with_capacity(lo, hi, 0)
+    }
+
+    /// Same as [`SparseSegTree::new`], but reserves room for `capacity` nodes.
+    pub fn with_capacity(lo: i64, hi: i64, capacity: usize) -> Self {
+        assert!(lo < hi);
+        Self {
+            nodes: Slab::with_capacity(capacity),
+            root: NIL,
+            lo,
+            hi,
+        }
+    }
+
+    /// Returns the number of nodes created so far.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn val(&self, k: u32) -> M::T {
+        if k == NIL {
+            M::e()
+        } else {
+            self.nodes[k].val.clone()
+        }
+    }
+
+    /// Replaces the element at `i` with `f` of it.
+    pub fn update<F: FnOnce(&M::T) -> M::T>(&mut self, i: i64, f: F) {
+        assert!(self.lo <= i && i < self.hi, "index out of bounds");
+        let mut path = Vec::new();
+        let (mut k, mut lo, mut hi) = (self.root, self.lo, self.hi);
+        if k == NIL {
+            k = self.nodes.insert(Node {
+                l: NIL,
+                r: NIL,
+                val: M::e(),
+            });
+            self.root = k;
+        }
+        while hi - lo > 1 {
+            path.push(k);
+            let mid = lo + (hi - lo) / 2;
+            let next = if i < mid {
+                hi = mid;
+                self.nodes[k].l
+            } else {
+                lo = mid;
+                self.nodes[k].r
+            };
+            let next = if next == NIL {
+                let h = self.nodes.insert(Node {
+                    l: NIL,
+                    r: NIL,
+                    val: M::e(),
+                });
+                if i < mid {
+                    self.nodes[k].l = h;
+                } else {
+                    self.nodes[k].r = h;
+                }
+                h
+            } else {
+                next
+            };
+            k = next;
+        }
+        self.nodes[k].val = f(&self.nodes[k].val);
+        for &k in path.iter().rev() {
+            let val = M::combine(&self.val(self.nodes[k].l), &self.val(self.nodes[k].r));
+            self.nodes[k].val = val;
+        }
+    }
+
+    /// Replaces the element at `i` with `x`.
+    pub fn set(&mut self, i: i64, x: M::T) {
+        self.update(i, |_| x)
+    }
+
+    /// Returns the element at `i`.
+    pub fn get(&self, i: i64) -> M::T {
+        assert!(self.lo <= i && i < self.hi, "index out of bounds");
+        let (mut k, mut lo, mut hi) = (self.root, self.lo, self.hi);
+        while k != NIL && hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if i < mid {
+                k = self.nodes[k].l;
+                hi = mid;
+            } else {
+                k = self.nodes[k].r;
+                lo = mid;
+            }
+        }
+        self.val(k)
+    }
+
+    /// Returns the product of the elements in `l..r`.
+    pub fn query(&self, l: i64, r: i64) -> M::T {
+        assert!(
+            self.lo <= l && l <= r && r <= self.hi,
+            "range out of bounds"
+        );
+        self.query_rec(self.root, self.lo, self.hi, l, r)
+    }
+
+    fn query_rec(&self, k: u32, lo: i64, hi: i64, l: i64, r: i64) -> M::T {
+        if k == NIL || r <= lo || hi <= l {
+            return M::e();
+        }
+        if l <= lo && hi <= r {
+            return self.nodes[k].val.clone();
+        }
+        let mid = lo + (hi - lo) / 2;
+        let node = &self.nodes[k];
+        M::combine(
+            &self.query_rec(node.l, lo, mid, l, r),
+            &self.query_rec(node.r, mid, hi, l, r),
+        )
+    }
+
+    /// Returns the product of all elements.
+    pub fn all(&self) -> M::T {
+        self.val(self.root)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::collections::BTreeMap;
+
+    struct Sum;
+    impl Monoid for Sum {
+        type T = i64;
+        fn e() -> i64 {
+            0
+        }
+        fn combine(l: &i64, r: &i64) -> i64 {
+            l + r
+        }
+    }
+
+    #[test]
+    fn matches_naive_over_huge_range() {
+        let (lo, hi) = (-1_000_000_000_000_000_000, 1_000_000_000_000_000_000);
+        let mut tree = SparseSegTree::<Sum>::new(lo, hi);
+        let mut naive = BTreeMap::new();
+        let mut rng = TestRng::new(3);
+        let mut rand = || lo + rng.below((hi - lo) as u64) as i64;
+        for _ in 0..500 {
+            let (i, x) = (rand(), rand() % 1000);
+            tree.update(i, |v| v + x);
+            *naive.entry(i).or_insert(0) += x;
+            let (a, b) = (rand(), rand());
+            let (l, r) = (a.min(b), a.max(b));
+            assert_eq!(
+                naive.range(l..r).map(|(_, v)| v).sum::<i64>(),
+                tree.query(l, r)
+            );
+            assert_eq!(naive[&i], tree.get(i));
+        }
+        assert_eq!(naive.values().sum::<i64>(), tree.all());
+        assert_eq!(0, tree.get(12345));
+        assert!(tree.node_count() <= 500 * 62);
+    }
+}
@@ -0,0 +1,69 @@
+use super::segment_tree::bounds;
+use alloc::vec::Vec;
+
+/// A sparse table answering range queries over an immutable array in O(1),
+/// after O(n log n) preprocessing.
+///
+/// `op` must be associative and idempotent (`op(x, x) == x`), such as min, max, gcd, bitwise and/or.
+/// For operations that are not idempotent, use [`DisjointSparseTable`](super::DisjointSparseTable).
+pub struct SparseTable<T, Op> {
+    table: Vec<Vec<T>>,
+    op: Op,
+}
+
+impl<T: Clone, Op: Fn(&T, &T) -> T> SparseTable<T, Op> {
+    /// Example: `SparseTable::new(&a, |x, y| *x.min(y))`
+    pub fn new(a: &[T], op: Op) -> Self {
+        let mut table = alloc::vec![a.to_vec()];
+        let mut w = 1;
+        while 2 * w <= a.len() {
+            let prev = table.last().unwrap();
+            let next = (0..=a.len() - 2 * w)
+                .map(|i| op(&prev[i], &prev[i + w]))
+                .collect();
+            table.push(next);
+            w *= 2;
+        }
+        Self { table, op }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table[0].is_empty()
+    }
+
+    /// Returns the result of `op` over the elements in `range`.
+    /// A runtime error will occur if `range` is empty.
+    pub fn query<B>(&self, range: B) -> T
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = bounds(range, self.len());
+        assert!(l < r, "SparseTable: empty range");
+        let k = (r - l).ilog2() as usize;
+        (self.op)(&self.table[k][l], &self.table[k][r - (1 << k)])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn queries_match_naive() {
+        let a = [12u64, 18, 6, 9, 27, 3, 30, 15, 45, 10, 20];
+        let min = SparseTable::new(&a, |x, y| *x.min(y));
+        let gcd = SparseTable::new(&a, |&x, &y| crate::math::gcd(x, y));
+        for l in 0..a.len() {
+            for r in l + 1..=a.len() {
+                assert_eq!(*a[l..r].iter().min().unwrap(), min.query(l..r));
+                let g = a[l..r].iter().fold(0, |g, &x| crate::math::gcd(g, x));
+                assert_eq!(g, gcd.query(l..r));
+            }
+        }
+        assert_eq!(3, min.query(..));
+    }
+}
@@ -0,0 +1,316 @@
+use super::Slab;
+use alloc::vec::Vec;
+use core::ops::{Bound, RangeBounds};
+
+const NIL: u32 = Slab::<()>::NIL;
+
+struct Node<K> {
+    key: K,
+    size: u32,
+    l: u32,
+    r: u32,
+    p: u32,
+}
+
+/// An ordered set based on a splay tree, with the same operations as [`TreapSet`](super::TreapSet).
+///
+/// Every access moves the accessed node to the root, so each operation takes amortized O(log n) time,
+/// and repeatedly accessing nearby keys is cheaper than that. Since lookups restructure the tree,
+/// they take `&mut self`.
+pub struct SplaySet<K: Ord> {
+    nodes: Slab<Node<K>>,
+    root: u32,
+}
+
+impl<K: Ord> Default for SplaySet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord> SplaySet<K> {
+    pub const fn new() -> Self {
+        Self {
+            nodes: Slab::new(),
+            root: NIL,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == NIL
+    }
+
+    fn size(&self, t: u32) -> usize {
+        if t == NIL {
+            0
+        } else {
+            self.nodes[t].size as usize
+        }
+    }
+
+    fn pull(&mut self, t: u32) {
+        let (l, r) = (self.nodes[t].l, self.nodes[t].r);
+        self.nodes[t].size = (1 + self.size(l) + self.size(r)) as u32;
+    }
+
+    fn rotate(&mut self, x: u32) {
+        let p = self.nodes[x].p;
+        let g = self.nodes[p].p;
+        if self.nodes[p].l == x {
+            let b = self.nodes[x].r;
+            self.nodes[p].l = b;
+            if b != NIL {
+                self.nodes[b].p = p;
+            }
+            self.nodes[x].r = p;
+        } else {
+            let b = self.nodes[x].l;
+            self.nodes[p].r = b;
+            if b != NIL {
+                self.nodes[b].p = p;
+            }
+            self.nodes[x].l = p;
+        }
+        self.nodes[p].p = x;
+        self.nodes[x].p = g;
+        if g != NIL {
+            if self.nodes[g].l == p {
+                self.nodes[g].l = x;
+            } else {
+                self.nodes[g].r = x;
+            }
+        }
+        self.pull(p);
+        self.pull(x);
+    }
+
+    fn splay(&mut self, x: u32) {
+        while self.nodes[x].p != NIL {
+            let p = self.nodes[x].p;
+            let g = self.nodes[p].p;
+            if g != NIL {
+                if (self.nodes[g].l == p) == (self.nodes[p].l == x) {
+                    self.rotate(p);
+                } else {
+                    self.rotate(x);
+                }
+            }
+            self.rotate(x);
+        }
+        self.root = x;
+    }
+
+    /// Walks down from the root to the node of `key` (or where it would be), splays the last node
+    /// visited and returns it.
+    fn access(&mut self, key: &K) -> u32 {
+        let mut t = self.root;
+        let mut last = NIL;
+        while t != NIL {
+            last = t;
+            t = match key.cmp(&self.nodes[t].key) {
+                core::cmp::Ordering::Less => self.nodes[t].l,
+                core::cmp::Ordering::Greater => self.nodes[t].r,
+                core::cmp::Ordering::Equal => break,
+            };
+        }
+        if last != NIL {
+            self.splay(last);
+        }
+        last
+    }
+
+    /// Returns `true` if `key` was not present.
+    pub fn insert(&mut self, key: K) -> bool {
+        let t = self.access(&key);
+        if t != NIL && self.nodes[t].key == key {
+            return false;
+        }
+        let x = self.nodes.insert(Node {
+            key,
+            size: 1,
+            l: NIL,
+            r: NIL,
+            p: NIL,
+        });
+        if t != NIL {
+            // `t` is the root, and `x` goes between its predecessor and successor.
+            if self.nodes[x].key < self.nodes[t].key {
+                let l = core::mem::replace(&mut self.nodes[t].l, NIL);
+                self.nodes[x].l = l;
+                self.nodes[x].r = t;
+                if l != NIL {
+                    self.nodes[l].p = x;
+                }
+            } else {
+                let r = core::mem::replace(&mut self.nodes[t].r, NIL);
+                self.nodes[x].r = r;
+                self.nodes[x].l = t;
+                if r != NIL {
+                    self.nodes[r].p = x;
+                }
+            }
+            self.nodes[t].p = x;
+            self.pull(t);
+            self.pull(x);
+        }
+        self.root = x;
+        true
+    }
+
+    /// Returns `true` if `key` was present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        let t = self.access(key);
+        if t == NIL || self.nodes[t].key != *key {
+            return false;
+        }
+        let Node { l, r, .. } = self.nodes.remove(t);
+        if l != NIL {
+            self.nodes[l].p = NIL;
+        }
+        if r != NIL {
+            self.nodes[r].p = NIL;
+        }
+        if l == NIL {
+            self.root = r;
+            return true;
+        }
+        let mut m = l;
+        while self.nodes[m].r != NIL {
+            m = self.nodes[m].r;
+        }
+        self.splay(m);
+        self.nodes[m].r = r;
+        if r != NIL {
+            self.nodes[r].p = m;
+        }
+        self.pull(m);
+        true
+    }
+
+    pub fn contains(&mut self, key: &K) -> bool {
+        let t = self.access(key);
+        t != NIL && self.nodes[t].key == *key
+    }
+
+    /// Returns the `i`-th (0-based) smallest key.
+    pub fn kth(&mut self, mut i: usize) -> Option<&K> {
+        if i >= self.len() {
+            return None;
+        }
+        let mut t = self.root;
+        loop {
+            let ls = self.size(self.nodes[t].l);
+            if i < ls {
+                t = self.nodes[t].l;
+            } else if i == ls {
+                break;
+            } else {
+                i -= ls + 1;
+                t = self.nodes[t].r;
+            }
+        }
+        self.splay(t);
+        Some(&self.nodes[t].key)
+    }
+
+    /// Returns the number of keys for which `left` holds, where `left` holds for a prefix of the keys.
+    fn count_prefix<P: Fn(&K) -> bool>(&mut self, left: P) -> usize {
+        let mut t = self.root;
+        let mut last = NIL;
+        let mut count = 0;
+        while t != NIL {
+            last = t;
+            if left(&self.nodes[t].key) {
+                count += self.size(self.nodes[t].l) + 1;
+                t = self.nodes[t].r;
+            } else {
+                t = self.nodes[t].l;
+            }
+        }
+        if last != NIL {
+            self.splay(last);
+        }
+        count
+    }
+
+    /// Returns the number of keys less than `key`.
+    pub fn rank(&mut self, key: &K) -> usize {
+        self.count_prefix(|k| k < key)
+    }
+
+    /// Returns the smallest key not less than `key`.
+    pub fn lower_bound(&mut self, key: &K) -> Option<&K> {
+        let i = self.rank(key);
+        self.kth(i)
+    }
+
+    /// Returns the number of keys in `range`.
+    pub fn count_range<R: RangeBounds<K>>(&mut self, range: R) -> usize {
+        let lo = match range.start_bound() {
+            Bound::Included(x) => self.count_prefix(|k| k < x),
+            Bound::Excluded(x) => self.count_prefix(|k| k <= x),
+            Bound::Unbounded => 0,
+        };
+        let hi = match range.end_bound() {
+            Bound::Included(x) => self.count_prefix(|k| k <= x),
+            Bound::Excluded(x) => self.count_prefix(|k| k < x),
+            Bound::Unbounded => self.len(),
+        };
+        hi.saturating_sub(lo)
+    }
+
+    /// Returns an iterator over the keys in order. It does not splay.
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        let mut stack = Vec::new();
+        let mut t = self.root;
+        core::iter::from_fn(move || {
+            while t != NIL {
+                stack.push(t);
+                t = self.nodes[t].l;
+            }
+            let u = stack.pop()?;
+            t = self.nodes[u].r;
+            Some(&self.nodes[u].key)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::collections::BTreeSet;
+
+    #[test]
+    fn matches_btree_set() {
+        let mut splay = SplaySet::new();
+        let mut btree = BTreeSet::new();
+        let mut rng = TestRng::new(3);
+        for _ in 0..5000 {
+            let key = rng.below(300);
+            match rng.below(5) {
+                0 | 1 => assert_eq!(btree.insert(key), splay.insert(key)),
+                2 => assert_eq!(btree.remove(&key), splay.remove(&key)),
+                3 => {
+                    let rank = btree.range(..key).count();
+                    assert_eq!(rank, splay.rank(&key));
+                    assert_eq!(btree.iter().nth(rank), splay.kth(rank));
+                    assert_eq!(btree.range(key..).next(), splay.lower_bound(&key));
+                }
+                _ => {
+                    assert_eq!(btree.contains(&key), splay.contains(&key));
+                    assert_eq!(
+                        btree.range(key..=key + 50).count(),
+                        splay.count_range(key..=key + 50)
+                    );
+                }
+            }
+            assert_eq!(btree.len(), splay.len());
+        }
+        assert!(btree.iter().eq(splay.iter()));
+    }
+}
@@ -0,0 +1,452 @@
+use super::Slab;
+use core::ops::{Bound, RangeBounds};
+
+const NIL: u32 = Slab::<()>::NIL;
+
+/// Generates the priorities of the treap nodes.
+pub(super) fn xorshift32(seed: &mut u32) -> u32 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 17;
+    *seed ^= *seed << 5;
+    *seed
+}
+
+struct Node<K, V> {
+    key: K,
+    val: V,
+    pri: u32,
+    size: u32,
+    l: u32,
+    r: u32,
+}
+
+/// An ordered map based on a treap, with order statistics ([`TreapMap::kth`], [`TreapMap::rank`])
+/// and range operations ([`TreapMap::count_range`], [`TreapMap::remove_range`], [`TreapMap::range`]).
+///
+/// The nodes live in a [`Slab`] and are linked with `u32` handles.
+/// The priorities come from a deterministic xorshift generator, so the runs are reproducible.
+pub struct TreapMap<K: Ord, V> {
+    nodes: Slab<Node<K, V>>,
+    root: u32,
+    seed: u32,
+}
+
+impl<K: Ord, V> Default for TreapMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> TreapMap<K, V> {
+    pub const fn new() -> Self {
+        Self {
+            nodes: Slab::new(),
+            root: NIL,
+            seed: 2463534242,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == NIL
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.root = NIL;
+    }
+
+    fn size(&self, t: u32) -> usize {
+        if t == NIL {
+            0
+        } else {
+            self.nodes[t].size as usize
+        }
+    }
+
+    fn pull(&mut self, t: u32) {
+        let (l, r) = (self.nodes[t].l, self.nodes[t].r);
+        self.nodes[t].size = (1 + self.size(l) + self.size(r)) as u32;
+    }
+
+    fn next_priority(&mut self) -> u32 {
+        xorshift32(&mut self.seed)
+    }
+
+    /// Splits `t` into the nodes whose keys satisfy `left` and the rest.
+    /// `left` must be true for a prefix of the keys.
+    fn split<P: Fn(&K) -> bool>(&mut self, t: u32, left: &P) -> (u32, u32) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        if left(&self.nodes[t].key) {
+            let (a, b) = self.split(self.nodes[t].r, left);
+            self.nodes[t].r = a;
+            self.pull(t);
+            (t, b)
+        } else {
+            let (a, b) = self.split(self.nodes[t].l, left);
+            self.nodes[t].l = b;
+            self.pull(t);
+            (a, t)
+        }
+    }
+
+    /// Joins `a` and `b`, where every key in `a` is smaller than every key in `b`.
+    fn merge(&mut self, a: u32, b: u32) -> u32 {
+        if a == NIL {
+            return b;
+        }
+        if b == NIL {
+            return a;
+        }
+        if self.nodes[a].pri > self.nodes[b].pri {
+            let r = self.merge(self.nodes[a].r, b);
+            self.nodes[a].r = r;
+            self.pull(a);
+            a
+        } else {
+            let l = self.merge(a, self.nodes[b].l);
+            self.nodes[b].l = l;
+            self.pull(b);
+            b
+        }
+    }
+
+    fn find(&self, key: &K) -> u32 {
+        let mut t = self.root;
+        while t != NIL {
+            match key.cmp(&self.nodes[t].key) {
+                core::cmp::Ordering::Less => t = self.nodes[t].l,
+                core::cmp::Ordering::Greater => t = self.nodes[t].r,
+                core::cmp::Ordering::Equal => break,
+            }
+        }
+        t
+    }
+
+    /// Inserts `val` with `key`, returning the previous value of `key` if any.
+    pub fn insert(&mut self, key: K, val: V) -> Option<V> {
+        let t = self.find(&key);
+        if t != NIL {
+            return Some(core::mem::replace(&mut self.nodes[t].val, val));
+        }
+        let (l, r) = self.split(self.root, &|k: &K| *k < key);
+        let pri = self.next_priority();
+        let t = self.nodes.insert(Node {
+            key,
+            val,
+            pri,
+            size: 1,
+            l: NIL,
+            r: NIL,
+        });
+        let l = self.merge(l, t);
+        self.root = self.merge(l, r);
+        None
+    }
+
+    /// Removes `key`, returning its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        if self.find(key) == NIL {
+            return None;
+        }
+        let (l, r) = self.split(self.root, &|k: &K| k < key);
+        let (m, r) = self.split(r, &|k: &K| k <= key);
+        self.root = self.merge(l, r);
+        Some(self.nodes.remove(m).val)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key) != NIL
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let t = self.find(key);
+        (t != NIL).then(|| &self.nodes[t].val)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let t = self.find(key);
+        (t != NIL).then(|| &mut self.nodes[t].val)
+    }
+
+    /// Returns the entry with the `i`-th (0-based) smallest key.
+    pub fn kth(&self, mut i: usize) -> Option<(&K, &V)> {
+        let mut t = self.root;
+        while t != NIL {
+            let ls = self.size(self.nodes[t].l);
+            if i < ls {
+                t = self.nodes[t].l;
+            } else if i == ls {
+                let node = &self.nodes[t];
+                return Some((&node.key, &node.val));
+            } else {
+                i -= ls + 1;
+                t = self.nodes[t].r;
+            }
+        }
+        None
+    }
+
+    /// Returns the number of keys for which `left` holds, where `left` holds for a prefix of the keys.
+    fn count_prefix<P: Fn(&K) -> bool>(&self, left: P) -> usize {
+        let mut t = self.root;
+        let mut count = 0;
+        while t != NIL {
+            if left(&self.nodes[t].key) {
+                count += self.size(self.nodes[t].l) + 1;
+                t = self.nodes[t].r;
+            } else {
+                t = self.nodes[t].l;
+            }
+        }
+        count
+    }
+
+    /// Returns the number of keys less than `key`.
+    pub fn rank(&self, key: &K) -> usize {
+        self.count_prefix(|k| k < key)
+    }
+
+    /// Returns the number of keys before `range` and the number of keys before or in `range`.
+    fn range_ranks<R: RangeBounds<K>>(&self, range: &R) -> (usize, usize) {
+        let lo = match range.start_bound() {
+            Bound::Included(x) => self.count_prefix(|k| k < x),
+            Bound::Excluded(x) => self.count_prefix(|k| k <= x),
+            Bound::Unbounded => 0,
+        };
+        let hi = match range.end_bound() {
+            Bound::Included(x) => self.count_prefix(|k| k <= x),
+            Bound::Excluded(x) => self.count_prefix(|k| k < x),
+            Bound::Unbounded => self.len(),
+        };
+        (lo, hi.max(lo))
+    }
+
+    /// Returns the number of keys in `range`.
+    pub fn count_range<R: RangeBounds<K>>(&self, range: R) -> usize {
+        let (lo, hi) = self.range_ranks(&range);
+        hi - lo
+    }
+
+    /// Returns the entry with the smallest key not less than `key`.
+    pub fn lower_bound(&self, key: &K) -> Option<(&K, &V)> {
+        self.kth(self.rank(key))
+    }
+
+    /// Removes the entries in `range` and returns how many were removed.
+    pub fn remove_range<R: RangeBounds<K>>(&mut self, range: R) -> usize {
+        let (lo, hi) = self.range_ranks(&range);
+        let (l, r) = self.split_at(self.root, lo);
+        let (m, r) = self.split_at(r, hi - lo);
+        self.root = self.merge(l, r);
+        let mut stack = alloc::vec![m];
+        while let Some(t) = stack.pop() {
+            if t != NIL {
+                let node = self.nodes.remove(t);
+                stack.push(node.l);
+                stack.push(node.r);
+            }
+        }
+        hi - lo
+    }
+
+    /// Splits `t` into its first `i` nodes and the rest.
+    fn split_at(&mut self, t: u32, i: usize) -> (u32, u32) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        let ls = self.size(self.nodes[t].l);
+        if i <= ls {
+            let (a, b) = self.split_at(self.nodes[t].l, i);
+            self.nodes[t].l = b;
+            self.pull(t);
+            (a, t)
+        } else {
+            let (a, b) = self.split_at(self.nodes[t].r, i - ls - 1);
+            self.nodes[t].r = a;
+            self.pull(t);
+            (t, b)
+        }
+    }
+
+    fn iter_from(&self, mut i: usize, remaining: usize) -> Iter<'_, K, V> {
+        let mut stack = alloc::vec![];
+        let mut t = self.root;
+        while t != NIL {
+            let ls = self.size(self.nodes[t].l);
+            if i <= ls {
+                stack.push(t);
+                t = self.nodes[t].l;
+            } else {
+                i -= ls + 1;
+                t = self.nodes[t].r;
+            }
+        }
+        Iter {
+            map: self,
+            stack,
+            remaining,
+        }
+    }
+
+    /// Returns an iterator over the entries in the order of the keys.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.iter_from(0, self.len())
+    }
+
+    /// Returns an iterator over the entries in `range` in the order of the keys.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Iter<'_, K, V> {
+        let (lo, hi) = self.range_ranks(&range);
+        self.iter_from(lo, hi - lo)
+    }
+}
+
+pub struct Iter<'a, K: Ord, V> {
+    map: &'a TreapMap<K, V>,
+    stack: alloc::vec::Vec<u32>,
+    remaining: usize,
+}
+
+impl<'a, K: Ord, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let t = self.stack.pop()?;
+        let nodes = &self.map.nodes;
+        let mut c = nodes[t].r;
+        while c != NIL {
+            self.stack.push(c);
+            c = nodes[c].l;
+        }
+        Some((&nodes[t].key, &nodes[t].val))
+    }
+}
+
+/// An ordered set based on [`TreapMap`], with order statistics and range operations.
+#[derive(Default)]
+pub struct TreapSet<K: Ord> {
+    map: TreapMap<K, ()>,
+}
+
+impl<K: Ord> TreapSet<K> {
+    pub const fn new() -> Self {
+        Self {
+            map: TreapMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns `true` if `key` was not present.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    /// Returns `true` if `key` was present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.map.remove(key).is_some()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Returns the `i`-th (0-based) smallest key.
+    pub fn kth(&self, i: usize) -> Option<&K> {
+        self.map.kth(i).map(|(k, _)| k)
+    }
+
+    /// Returns the number of keys less than `key`.
+    pub fn rank(&self, key: &K) -> usize {
+        self.map.rank(key)
+    }
+
+    /// Returns the smallest key not less than `key`.
+    pub fn lower_bound(&self, key: &K) -> Option<&K> {
+        self.map.lower_bound(key).map(|(k, _)| k)
+    }
+
+    /// Returns the number of keys in `range`.
+    pub fn count_range<R: RangeBounds<K>>(&self, range: R) -> usize {
+        self.map.count_range(range)
+    }
+
+    /// Removes the keys in `range` and returns how many were removed.
+    pub fn remove_range<R: RangeBounds<K>>(&mut self, range: R) -> usize {
+        self.map.remove_range(range)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        self.map.iter().map(|(k, _)| k)
+    }
+
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl Iterator<Item = &K> {
+        self.map.range(range).map(|(k, _)| k)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn matches_btree_map() {
+        let mut treap = TreapMap::new();
+        let mut btree = BTreeMap::new();
+        let mut rng = TestRng::new(1);
+        for _ in 0..3000 {
+            let (key, val) = (rng.below(200), rng.next_u64());
+            match rng.below(4) {
+                0 | 1 => assert_eq!(btree.insert(key, val), treap.insert(key, val)),
+                2 => assert_eq!(btree.remove(&key), treap.remove(&key)),
+                _ => {
+                    let rank = btree.range(..key).count();
+                    assert_eq!(rank, treap.rank(&key));
+                    assert_eq!(btree.iter().nth(rank), treap.kth(rank));
+                    assert_eq!(btree.get(&key), treap.get(&key));
+                }
+            }
+            assert_eq!(btree.len(), treap.len());
+        }
+        assert!(btree.iter().eq(treap.iter()));
+        assert!(btree.range(50..=120).eq(treap.range(50..=120)));
+        assert_eq!(btree.range(50..120).count(), treap.count_range(50..120));
+    }
+
+    #[test]
+    fn set_range_removal() {
+        let mut set: TreapSet<u32> = TreapSet::new();
+        for x in (0..100).rev() {
+            assert!(set.insert(x * 2));
+        }
+        assert!(!set.insert(10));
+        assert_eq!(Some(&42), set.lower_bound(&41));
+        assert_eq!(10, set.remove_range(20..40));
+        assert_eq!(90, set.len());
+        assert_eq!(Some(&40), set.kth(10));
+        assert_eq!(
+            [16, 18, 40, 42],
+            set.range(15..=42).copied().collect::<Vec<_>>()[..]
+        );
+        assert_eq!(0, set.remove_range(20..40));
+        assert_eq!(90, set.remove_range(..));
+        assert!(set.is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use super::RollbackDsu;
+use alloc::vec::Vec;
+
+/// A structure whose operations can be undone in LIFO order.
+pub trait Undoable {
+    type Op: Clone;
+    /// Applies `op`.
+    fn apply(&mut self, op: &Self::Op);
+    /// Undoes the last applied operation that was not undone yet.
+    fn undo(&mut self);
+}
+
+impl Undoable for RollbackDsu {
+    type Op = (usize, usize);
+    fn apply(&mut self, op: &(usize, usize)) {
+        self.union(op.0, op.1);
+    }
+    fn undo(&mut self) {
+        RollbackDsu::undo(self);
+    }
+}
+
+/// Turns a structure supporting LIFO undo into one supporting FIFO undo: operations are pushed
+/// to the back of a queue and the oldest one can be removed, as in a sliding window.
+///
+/// The operations are kept on a stack, each marked as either pushed (B) or reordered for removal (A),
+/// and `pop_front` reorders a suffix of the stack so that the oldest operation is on top.
+/// Each operation is applied and undone O(log n) times amortized.
+pub struct UndoQueue<S: Undoable> {
+    inner: S,
+    stack: Vec<(S::Op, bool)>,
+    a_count: usize,
+}
+
+impl<S: Undoable> UndoQueue<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            stack: Vec::new(),
+            a_count: 0,
+        }
+    }
+
+    /// Returns the underlying structure, with all operations in the queue applied.
+    pub fn get(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns the number of operations in the queue.
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Applies `op` and pushes it to the back of the queue.
+    pub fn push_back(&mut self, op: S::Op) {
+        self.inner.apply(&op);
+        self.stack.push((op, false));
+    }
+
+    /// Undoes the oldest operation in the queue and returns it.
+    pub fn pop_front(&mut self) -> Option<S::Op> {
+        if self.stack.is_empty() {
+            return None;
+        }
+        if self.a_count == 0 {
+            for _ in 0..self.stack.len() {
+                self.inner.undo();
+            }
+            self.stack.reverse();
+            for (op, a) in self.stack.iter_mut() {
+                self.inner.apply(op);
+                *a = true;
+            }
+            self.a_count = self.stack.len();
+        } else if !self.stack.last().unwrap().1 {
+            let (mut a_ops, mut b_ops) = (Vec::new(), Vec::new());
+            while a_ops.len() < self.a_count && (a_ops.is_empty() || a_ops.len() != b_ops.len()) {
+                let (op, a) = self.stack.pop().unwrap();
+                self.inner.undo();
+                if a {
+                    a_ops.push(op);
+                } else {
+                    b_ops.push(op);
+                }
+            }
+            for op in b_ops.into_iter().rev() {
+                self.inner.apply(&op);
+                self.stack.push((op, false));
+            }
+            for op in a_ops.into_iter().rev() {
+                self.inner.apply(&op);
+                self.stack.push((op, true));
+            }
+        }
+        let (op, _) = self.stack.pop().unwrap();
+        self.inner.undo();
+        self.a_count -= 1;
+        Some(op)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::collections::Dsu;
+    use crate::utils::TestRng;
+
+    #[test]
+    fn sliding_window_connectivity() {
+        let n = 8;
+        let mut rng = TestRng::new(11);
+        let mut edges = Vec::new();
+        for _ in 0..200 {
+            edges.push((rng.index(n), rng.index(n)));
+        }
+        let mut queue = UndoQueue::new(RollbackDsu::new(n));
+        let mut front = 0;
+        for (i, &e) in edges.iter().enumerate() {
+            queue.push_back(e);
+            while i + 1 - front > 1 + (i * 7 + front) % 5 {
+                assert_eq!(Some(edges[front]), queue.pop_front());
+                front += 1;
+            }
+            let mut naive = Dsu::new(n);
+            for &(u, v) in &edges[front..=i] {
+                naive.try_union(u, v);
+            }
+            assert_eq!(i + 1 - front, queue.len());
+            assert_eq!(naive.cc_count(), queue.get().connected_component_count());
+            for u in 0..n {
+                assert_eq!(naive.size(u), queue.get().size(u));
+            }
+        }
+    }
+}
@@ -86,7 +86,7 @@ impl RemUnionFind {
 #[derive(Default)]
 pub struct UnionFind {
     up: Vec<u32>,
-    rank: Vec<u32>,
+    size: Vec<u32>,
     connected_component_count: usize,
 }
 
@@ -97,7 +97,7 @@ impl UnionFind {
     pub fn new(n: usize) -> Self {
         Self {
             up: (0..n as u32).collect(),
-            rank: vec![1; n],
+            size: vec![1; n],
             connected_component_count: n,
         }
     }
@@ -134,13 +134,13 @@ impl UnionFind {
             i += 1;
             v as u32
         });
-        self.rank.resize(n, 1);
+        self.size.resize(n, 1);
     }
 
     /// Increases the number of elements by exactly one.
     pub fn push(&mut self) {
         self.up.push(self.up.len() as u32);
-        self.rank.push(1);
+        self.size.push(1);
         self.connected_component_count += 1;
     }
 
@@ -153,7 +153,18 @@ impl UnionFind {
         u
     }
 
-    /// Unites `pu` and `pv`.
+    /// Returns `true` if `u` and `v` are in the same set.
+    pub fn same(&mut self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+
+    /// Returns the size of the set containing `u`.
+    pub fn size(&mut self, u: usize) -> usize {
+        let pu = self.find(u);
+        self.size[pu] as usize
+    }
+
+    /// Unites `pu` and `pv`, attaching the smaller tree to the larger one.
     ///
     /// Returns the new parent of the united tree.
     ///
@@ -163,13 +174,11 @@ impl UnionFind {
         assert!(pu < self.len() && pv < self.len());
         assert!(self.up[pu] as usize == pu && self.up[pv] as usize == pv);
         if pu != pv {
-            if self.rank[pu] < self.rank[pv] {
+            if self.size[pu] < self.size[pv] {
                 core::mem::swap(&mut pu, &mut pv);
             }
             self.up[pv] = pu as u32;
-            if self.rank[pu] == self.rank[pv] {
-                self.rank[pu] += 1;
-            }
+            self.size[pu] += self.size[pv];
             self.connected_component_count -= 1;
         }
         pu
@@ -187,3 +196,265 @@ impl UnionFind {
         pu != pv
     }
 }
+
+/// The disjoint-set union with path compression and union by size; see [`UnionFind`].
+pub type Dsu = UnionFind;
+
+/// A disjoint-set union whose unions can be undone in LIFO order, for offline dynamic connectivity
+/// and divide and conquer over time.
+///
+/// It uses union by size without path compression, so `find` takes O(log n) time
+/// and every union can be reverted in O(1).
+#[derive(Default)]
+pub struct RollbackDsu {
+    up: Vec<u32>,
+    size: Vec<u32>,
+    history: Vec<(u32, u32)>,
+    connected_component_count: usize,
+}
+
+impl RollbackDsu {
+    /// Creates a new instance of `RollbackDsu` with `n` singletons.
+    pub fn new(n: usize) -> Self {
+        Self {
+            up: (0..n as u32).collect(),
+            size: vec![1; n],
+            history: vec![],
+            connected_component_count: n,
+        }
+    }
+
+    /// Returns the number of elements in the current instance.
+    pub fn len(&self) -> usize {
+        self.up.len()
+    }
+
+    /// Returns `true` if the current instance contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.up.is_empty()
+    }
+
+    /// Returns the number of connected components.
+    pub fn connected_component_count(&self) -> usize {
+        self.connected_component_count
+    }
+
+    /// Finds the representative of `u`.
+    pub fn find(&self, mut u: usize) -> usize {
+        while u != self.up[u] as usize {
+            u = self.up[u] as usize;
+        }
+        u
+    }
+
+    /// Returns `true` if `u` and `v` are in the same set.
+    pub fn same(&self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+
+    /// Returns the size of the set containing `u`.
+    pub fn size(&self, u: usize) -> usize {
+        self.size[self.find(u)] as usize
+    }
+
+    /// Unites the sets containing `u` and `v`.
+    ///
+    /// Returns `true` if a new union is created, `false` otherwise.
+    /// Either way, one entry is recorded, so each call can be undone by one [`RollbackDsu::undo`].
+    pub fn union(&mut self, u: usize, v: usize) -> bool {
+        let (mut pu, mut pv) = (self.find(u), self.find(v));
+        if pu == pv {
+            self.history.push((pu as u32, pu as u32));
+            return false;
+        }
+        if self.size[pu] < self.size[pv] {
+            core::mem::swap(&mut pu, &mut pv);
+        }
+        self.up[pv] = pu as u32;
+        self.size[pu] += self.size[pv];
+        self.connected_component_count -= 1;
+        self.history.push((pu as u32, pv as u32));
+        true
+    }
+
+    /// Returns the number of recorded unions, to be passed to [`RollbackDsu::rollback`] later.
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undoes the last call to `union`. Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some((pu, pv)) = self.history.pop() else {
+            return false;
+        };
+        if pu != pv {
+            let (pu, pv) = (pu as usize, pv as usize);
+            self.up[pv] = pv as u32;
+            self.size[pu] -= self.size[pv];
+            self.connected_component_count += 1;
+        }
+        true
+    }
+
+    /// Undoes the unions until only `snapshot` of them remain.
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+}
+
+/// A partially persistent disjoint-set union, which answers queries about any past version.
+///
+/// Every call to `union` advances the time by one, so version `t` is the state after the first `t`
+/// calls. It uses union by size without path compression, and each node remembers when it was
+/// attached to its parent, so a query at time `t` takes O(log n) time by ignoring later links.
+#[derive(Default)]
+pub struct PersistentDsu {
+    up: Vec<u32>,
+    since: Vec<u32>,
+    sizes: Vec<Vec<(u32, u32)>>,
+    now: u32,
+}
+
+impl PersistentDsu {
+    /// Creates a new instance of `PersistentDsu` with `n` singletons at time 0.
+    pub fn new(n: usize) -> Self {
+        Self {
+            up: (0..n as u32).collect(),
+            since: vec![u32::MAX; n],
+            sizes: vec![vec![(0, 1)]; n],
+            now: 0,
+        }
+    }
+
+    /// Returns the number of elements in the current instance.
+    pub fn len(&self) -> usize {
+        self.up.len()
+    }
+
+    /// Returns `true` if the current instance contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.up.is_empty()
+    }
+
+    /// Returns the current time, which is the number of calls to `union` so far.
+    pub fn now(&self) -> usize {
+        self.now as usize
+    }
+
+    /// Finds the representative of `u` at time `t`.
+    pub fn find(&self, mut u: usize, t: usize) -> usize {
+        while u != self.up[u] as usize && self.since[u] as usize <= t {
+            u = self.up[u] as usize;
+        }
+        u
+    }
+
+    /// Returns `true` if `u` and `v` were in the same set at time `t`.
+    pub fn same(&self, u: usize, v: usize, t: usize) -> bool {
+        self.find(u, t) == self.find(v, t)
+    }
+
+    /// Returns the size of the set containing `u` at time `t`.
+    pub fn size(&self, u: usize, t: usize) -> usize {
+        let sizes = &self.sizes[self.find(u, t)];
+        let i = sizes.partition_point(|&(s, _)| s as usize <= t);
+        sizes[i - 1].1 as usize
+    }
+
+    /// Returns the first time at which `u` and `v` were in the same set,
+    /// or `None` if they are not connected yet.
+    pub fn connected_since(&self, u: usize, v: usize) -> Option<usize> {
+        if !self.same(u, v, self.now()) {
+            return None;
+        }
+        let (mut lo, mut hi) = (0, self.now());
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if self.same(u, v, mid) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        Some(lo)
+    }
+
+    /// Unites the sets containing `u` and `v` and advances the time by one.
+    ///
+    /// Returns `true` if a new union is created, `false` otherwise.
+    pub fn union(&mut self, u: usize, v: usize) -> bool {
+        self.now += 1;
+        let (mut pu, mut pv) = (self.find(u, self.now()), self.find(v, self.now()));
+        if pu == pv {
+            return false;
+        }
+        let (su, sv) = (
+            self.sizes[pu].last().unwrap().1,
+            self.sizes[pv].last().unwrap().1,
+        );
+        if su < sv {
+            core::mem::swap(&mut pu, &mut pv);
+        }
+        self.up[pv] = pu as u32;
+        self.since[pv] = self.now;
+        self.sizes[pu].push((self.now, su + sv));
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dsu_tracks_sizes() {
+        let mut dsu = Dsu::new(5);
+        dsu.push();
+        assert!(dsu.try_union(0, 1));
+        assert!(dsu.try_union(2, 3));
+        assert!(dsu.try_union(1, 3));
+        assert!(!dsu.try_union(0, 2));
+        assert_eq!(4, dsu.size(2));
+        assert_eq!(1, dsu.size(5));
+        assert!(dsu.same(0, 3) && !dsu.same(0, 4));
+        assert_eq!(3, dsu.cc_count());
+    }
+
+    #[test]
+    fn rollback_restores_earlier_state() {
+        let mut dsu = RollbackDsu::new(5);
+        dsu.union(0, 1);
+        let snapshot = dsu.snapshot();
+        dsu.union(1, 2);
+        dsu.union(0, 2);
+        dsu.union(3, 4);
+        assert_eq!(3, dsu.size(0));
+        assert_eq!(2, dsu.connected_component_count());
+        dsu.rollback(snapshot);
+        assert!(dsu.same(0, 1) && !dsu.same(1, 2) && !dsu.same(3, 4));
+        assert_eq!(4, dsu.connected_component_count());
+        assert!(dsu.undo());
+        assert_eq!(1, dsu.size(0));
+        assert!(!dsu.undo());
+    }
+
+    #[test]
+    fn persistent_answers_past_queries() {
+        let mut dsu = PersistentDsu::new(5);
+        dsu.union(0, 1);
+        dsu.union(2, 3);
+        dsu.union(0, 1);
+        dsu.union(1, 3);
+        assert_eq!(4, dsu.now());
+        assert!(!dsu.same(0, 1, 0) && dsu.same(0, 1, 1));
+        assert!(!dsu.same(0, 3, 3) && dsu.same(0, 3, 4));
+        assert_eq!(2, dsu.size(2, 3));
+        assert_eq!(4, dsu.size(2, 4));
+        assert_eq!(1, dsu.size(2, 1));
+        assert_eq!(Some(4), dsu.connected_since(0, 2));
+        assert_eq!(Some(0), dsu.connected_since(4, 4));
+        assert_eq!(None, dsu.connected_since(0, 4));
+    }
+}
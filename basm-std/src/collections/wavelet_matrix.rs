@@ -0,0 +1,247 @@
+use alloc::vec::Vec;
+
+/// A bit vector with O(1) rank.
+struct BitRank {
+    words: Vec<u64>,
+    acc: Vec<u32>,
+}
+
+impl BitRank {
+    fn new(bits: impl ExactSizeIterator<Item = bool>) -> Self {
+        let mut words = alloc::vec![0u64; bits.len() / 64 + 1];
+        for (i, b) in bits.enumerate() {
+            words[i / 64] |= (b as u64) << (i % 64);
+        }
+        let mut acc = Vec::with_capacity(words.len());
+        let mut sum = 0;
+        for w in &words {
+            acc.push(sum);
+            sum += w.count_ones();
+        }
+        Self { words, acc }
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.words[i / 64] >> (i % 64) & 1 != 0
+    }
+
+    /// Returns the number of ones in `[0, i)`.
+    fn rank1(&self, i: usize) -> usize {
+        let mask = (1u64 << (i % 64)) - 1;
+        self.acc[i / 64] as usize + (self.words[i / 64] & mask).count_ones() as usize
+    }
+
+    fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+}
+
+/// A wavelet matrix over a static array of `u64`, answering rank, select, k-th smallest in a range
+/// and counting in a value range, each in O(log σ) where σ is the largest value.
+///
+/// It takes about `n log σ` bits plus `n log σ / 16` bytes, much less than a
+/// [`PersistentSegTree`](super::PersistentSegTree), and needs no coordinate compression.
+pub struct WaveletMatrix {
+    levels: Vec<BitRank>,
+    zeros: Vec<usize>,
+    n: usize,
+}
+
+impl WaveletMatrix {
+    pub fn new(a: &[u64]) -> Self {
+        let max = a.iter().copied().max().unwrap_or(0);
+        let height = (64 - max.leading_zeros()).max(1) as usize;
+        let mut cur = a.to_vec();
+        let mut levels = Vec::with_capacity(height);
+        let mut zeros = Vec::with_capacity(height);
+        for b in (0..height).rev() {
+            levels.push(BitRank::new(cur.iter().map(|x| x >> b & 1 != 0)));
+            let (mut lo, hi): (Vec<u64>, Vec<u64>) = cur.iter().partition(|&&x| x >> b & 1 == 0);
+            zeros.push(lo.len());
+            lo.extend(hi);
+            cur = lo;
+        }
+        Self {
+            levels,
+            zeros,
+            n: a.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    fn height(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns the `i`-th element.
+    pub fn get(&self, mut i: usize) -> u64 {
+        assert!(i < self.n);
+        let mut x = 0;
+        for (d, bits) in self.levels.iter().enumerate() {
+            if bits.get(i) {
+                x = x << 1 | 1;
+                i = self.zeros[d] + bits.rank1(i);
+            } else {
+                x <<= 1;
+                i = bits.rank0(i);
+            }
+        }
+        x
+    }
+
+    /// Returns the number of occurrences of `x` in `a[l..r]`.
+    pub fn rank(&self, x: u64, l: usize, r: usize) -> usize {
+        assert!(l <= r && r <= self.n);
+        if self.height() < 64 && x >> self.height() != 0 {
+            return 0;
+        }
+        let (mut l, mut r) = (l, r);
+        for (d, bits) in self.levels.iter().enumerate() {
+            if x >> (self.height() - 1 - d) & 1 != 0 {
+                l = self.zeros[d] + bits.rank1(l);
+                r = self.zeros[d] + bits.rank1(r);
+            } else {
+                l = bits.rank0(l);
+                r = bits.rank0(r);
+            }
+        }
+        r - l
+    }
+
+    /// Returns the position of the `k`-th (0-based) occurrence of `x`, or `None` if there are not
+    /// that many. Takes O(log σ log n) time.
+    pub fn select(&self, x: u64, k: usize) -> Option<usize> {
+        if self.rank(x, 0, self.n) <= k {
+            return None;
+        }
+        let (mut lo, mut hi) = (0, self.n);
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            if self.rank(x, 0, mid) <= k {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        Some(lo)
+    }
+
+    /// Returns the `k`-th (0-based) smallest value in `a[l..r]`, or `None` if `k >= r - l`.
+    pub fn kth_smallest(&self, l: usize, r: usize, mut k: usize) -> Option<u64> {
+        assert!(l <= r && r <= self.n);
+        if k >= r - l {
+            return None;
+        }
+        let (mut l, mut r) = (l, r);
+        let mut x = 0;
+        for (d, bits) in self.levels.iter().enumerate() {
+            let (l0, r0) = (bits.rank0(l), bits.rank0(r));
+            if k < r0 - l0 {
+                x <<= 1;
+                (l, r) = (l0, r0);
+            } else {
+                k -= r0 - l0;
+                x = x << 1 | 1;
+                l = self.zeros[d] + (l - l0);
+                r = self.zeros[d] + (r - r0);
+            }
+        }
+        Some(x)
+    }
+
+    /// Returns the number of values less than `x` in `a[l..r]`.
+    pub fn count_less(&self, l: usize, r: usize, x: u64) -> usize {
+        assert!(l <= r && r <= self.n);
+        if self.height() < 64 && x >> self.height() != 0 {
+            return r - l;
+        }
+        let (mut l, mut r) = (l, r);
+        let mut count = 0;
+        for (d, bits) in self.levels.iter().enumerate() {
+            let (l0, r0) = (bits.rank0(l), bits.rank0(r));
+            if x >> (self.height() - 1 - d) & 1 != 0 {
+                count += r0 - l0;
+                l = self.zeros[d] + (l - l0);
+                r = self.zeros[d] + (r - r0);
+            } else {
+                (l, r) = (l0, r0);
+            }
+        }
+        count
+    }
+
+    /// Returns the number of values in `lo..hi` in `a[l..r]`.
+    pub fn count_range(&self, l: usize, r: usize, lo: u64, hi: u64) -> usize {
+        if lo >= hi {
+            return 0;
+        }
+        self.count_less(l, r, hi) - self.count_less(l, r, lo)
+    }
+}
+
+impl From<&[u32]> for WaveletMatrix {
+    fn from(a: &[u32]) -> Self {
+        Self::new(&a.iter().map(|&x| x as u64).collect::<Vec<_>>())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn queries_match_naive() {
+        let a: Vec<u64> = (0..70u64).map(|i| (i * i * 31 + 7) % 23).collect();
+        let wm = WaveletMatrix::new(&a);
+        for (i, &x) in a.iter().enumerate() {
+            assert_eq!(x, wm.get(i));
+        }
+        for l in (0..=a.len()).step_by(3) {
+            for r in l..=a.len() {
+                let mut sorted = a[l..r].to_vec();
+                sorted.sort();
+                for k in 0..=sorted.len() {
+                    assert_eq!(sorted.get(k).copied(), wm.kth_smallest(l, r, k));
+                }
+                for x in 0..25 {
+                    assert_eq!(
+                        a[l..r].iter().filter(|&&y| y == x).count(),
+                        wm.rank(x, l, r)
+                    );
+                    assert_eq!(
+                        a[l..r].iter().filter(|&&y| y < x).count(),
+                        wm.count_less(l, r, x)
+                    );
+                }
+                assert_eq!(
+                    a[l..r].iter().filter(|&&y| (5..12).contains(&y)).count(),
+                    wm.count_range(l, r, 5, 12)
+                );
+            }
+        }
+        for x in 0..25 {
+            let positions: Vec<usize> = (0..a.len()).filter(|&i| a[i] == x).collect();
+            for k in 0..=positions.len() {
+                assert_eq!(positions.get(k).copied(), wm.select(x, k));
+            }
+        }
+    }
+
+    #[test]
+    fn full_width_values() {
+        let a = [u64::MAX, 0, 1 << 63, 5];
+        let wm = WaveletMatrix::new(&a);
+        assert_eq!(Some(1 << 63), wm.kth_smallest(0, 4, 2));
+        assert_eq!(3, wm.count_less(0, 4, u64::MAX));
+        assert_eq!(1, wm.rank(u64::MAX, 0, 4));
+        let wm = WaveletMatrix::from(&[3u32, 1, 2][..]);
+        assert_eq!(Some(2), wm.kth_smallest(0, 3, 1));
+    }
+}
@@ -1,15 +1,14 @@
-#![feature(fn_align)]
-#![feature(maybe_uninit_slice)]
-#![feature(maybe_uninit_uninit_array)]
-#![feature(maybe_uninit_array_assume_init)]
-#![feature(naked_functions)]
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(feature = "nightly", feature(fn_align))]
+#![cfg_attr(feature = "nightly", feature(naked_functions))]
+#![cfg_attr(feature = "nightly", feature(allocator_api))]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 extern crate alloc;
 
 pub mod collections;
 pub mod graph;
 pub mod math;
 pub mod platform;
+pub mod scratch;
 pub mod serialization;
 pub mod sorts;
 pub mod strings;
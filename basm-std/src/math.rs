@@ -1,17 +1,30 @@
 pub mod miller_rabin;
 pub use miller_rabin::*;
 mod sieve;
-pub use sieve::LinearSieve;
+pub use sieve::{LinearSieve, Sieve};
 mod pollard_rho;
-pub use pollard_rho::factorize;
+pub use pollard_rho::{factorize, factorize_grouped, factorize_u128};
 mod reeds_sloane;
 pub use reeds_sloane::{linear_fit, reeds_sloane};
 
 pub mod ntt;
 pub use ntt::*;
+pub mod convolution;
+pub use convolution::*;
+pub mod fft;
+pub use fft::*;
+pub mod bigint;
+pub use bigint::*;
+pub mod matrix;
+pub use matrix::*;
 
 mod modmul;
 
+pub mod modint;
+pub use modint::*;
+pub mod montgomery;
+pub use montgomery::*;
+
 // reference: https://nyaannyaan.github.io/library/trial/fast-gcd.hpp.html
 
 use core::ops::*;
@@ -413,6 +426,36 @@ pub fn checked_moddiv<T: ModOps<T>>(x: T, y: T, modulo: T) -> Option<T> {
     moddiv(x, y, modulo)
 }
 
+/// Solves the system `x = r (mod m)` for every `(r, m)` in `congruences`, where the moduli need not
+/// be pairwise coprime. Returns `(x, l)` with `l` the LCM of the moduli and `0 <= x < l`, or `None`
+/// if the system is inconsistent or `l` does not fit in `u64`. An empty system yields `(0, 1)`.
+///
+/// Intermediate products are taken in `u128`, so no step overflows.
+///
+/// This function will panic if any modulus is zero.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let (mut x, mut l) = (0u64, 1u64);
+    for &(r, m) in congruences {
+        assert!(m != 0);
+        let g = gcd(l, m);
+        let diff = modsub(r % m, x % m, m);
+        if diff % g != 0 {
+            return None;
+        }
+        // Solve l * k = diff (mod m), i.e. (l / g) * k = diff / g (mod m / g).
+        let mg = m / g;
+        let k = if mg == 1 {
+            0
+        } else {
+            modmul(diff / g, modinv((l / g) % mg, mg).unwrap(), mg)
+        };
+        let lcm = (l / g).checked_mul(m)?;
+        x = (x as u128 + l as u128 * k as u128) as u64;
+        l = lcm;
+    }
+    Some((x, l))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -533,4 +576,26 @@ mod test {
         assert_eq!(Some(17361641481138401521u64), moddiv(1u64, 17u64, 0u64));
         assert_eq!(Some(3i64), moddiv(15i64, 5i64, 0i64));
     }
+
+    #[test]
+    fn crt_returns_crt() {
+        assert_eq!(Some((0, 1)), crt(&[]));
+        assert_eq!(Some((23, 105)), crt(&[(2, 3), (3, 5), (2, 7)]));
+        assert_eq!(Some((10, 12)), crt(&[(4, 6), (2, 4)]));
+        assert_eq!(None, crt(&[(1, 6), (2, 4)]));
+        assert_eq!(Some((5, 8)), crt(&[(13, 8), (1, 4), (5, 8)]));
+        let (p, q) = (4_294_967_291u64, 4_294_967_279u64);
+        let (x, l) = crt(&[(p - 1, p), (q - 2, q)]).unwrap();
+        assert_eq!((p * q, p - 1, q - 2), (l, x % p, x % q));
+        let big = 1_000_000_000_000_000_003;
+        assert_eq!(None, crt(&[(1, big), (2, 1_000_000_007)]));
+        assert_eq!(Some((big - 1, big)), crt(&[(big - 1, big), (big - 1, big)]));
+    }
+
+    #[test]
+    fn modinv_composite_modulus() {
+        assert_eq!(Some(7u64), modinv(13, 30));
+        assert_eq!(None, modinv(12u64, 30));
+        assert_eq!(Some(7i64), modinv(-17, 30));
+    }
 }
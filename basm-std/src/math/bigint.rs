@@ -0,0 +1,1021 @@
+use super::multiply_u64;
+use crate::platform::io::{Print, Readable, ReaderTrait, Writer};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::*;
+
+/// An arbitrary-precision nonnegative integer, stored as little-endian 64-bit limbs
+/// without leading zero limbs.
+///
+/// Multiplication is schoolbook for short operands, Karatsuba up to a few hundred limbs,
+/// and NTT-based ([`multiply_u64`]) beyond that. Division uses Knuth's algorithm D.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    d: Vec<u64>,
+}
+
+const SCHOOL_LIMIT: usize = 32;
+const KARATSUBA_LIMIT: usize = 512;
+
+fn trim(d: &mut Vec<u64>) {
+    while d.last() == Some(&0) {
+        d.pop();
+    }
+}
+
+fn cmp_limbs(a: &[u64], b: &[u64]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// Adds `b` into `a`, returning the carry out of `a`. `a` must be at least as long as `b`.
+fn add_assign_limbs(a: &mut [u64], b: &[u64]) -> bool {
+    let mut carry = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        if i >= b.len() && !carry {
+            break;
+        }
+        let (s, c1) = x.overflowing_add(*b.get(i).unwrap_or(&0));
+        let (s, c2) = s.overflowing_add(carry as u64);
+        *x = s;
+        carry = c1 || c2;
+    }
+    carry
+}
+
+/// Subtracts `b` from `a`, returning the borrow out of `a`. `a` must be at least as long as `b`.
+fn sub_assign_limbs(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        if i >= b.len() && !borrow {
+            break;
+        }
+        let (s, b1) = x.overflowing_sub(*b.get(i).unwrap_or(&0));
+        let (s, b2) = s.overflowing_sub(borrow as u64);
+        *x = s;
+        borrow = b1 || b2;
+    }
+    borrow
+}
+
+fn add_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = Vec::with_capacity(a.len() + 1);
+    out.extend_from_slice(a);
+    out.push(0);
+    add_assign_limbs(&mut out, b);
+    out
+}
+
+fn mul_school(a: &[u64], b: &[u64], out: &mut [u64]) {
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u128 * y as u128 + out[i + j] as u128 + carry;
+            out[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        out[i + b.len()] = carry as u64;
+    }
+}
+
+/// Writes `a * b` into `out`, which must be zeroed and have length `a.len() + b.len()`.
+fn mul_karatsuba(a: &[u64], b: &[u64], out: &mut [u64]) {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    if b.len() <= SCHOOL_LIMIT {
+        mul_school(a, b, out);
+        return;
+    }
+    let k = a.len().div_ceil(2);
+    if b.len() <= k {
+        // a * b = a0 * b + (a1 * b) << k
+        let (a0, a1) = a.split_at(k);
+        mul_karatsuba(a0, b, &mut out[..k + b.len()]);
+        let mut hi = alloc::vec![0; a1.len() + b.len()];
+        mul_karatsuba(a1, b, &mut hi);
+        add_assign_limbs(&mut out[k..], &hi);
+        return;
+    }
+    let (a0, a1) = a.split_at(k);
+    let (b0, b1) = b.split_at(k);
+    let (z0, z2) = out.split_at_mut(2 * k);
+    mul_karatsuba(a0, b0, z0);
+    mul_karatsuba(a1, b1, z2);
+    let (sa, sb) = (add_limbs(a0, a1), add_limbs(b0, b1));
+    let mut z1 = alloc::vec![0; sa.len() + sb.len()];
+    mul_karatsuba(&sa, &sb, &mut z1);
+    sub_assign_limbs(&mut z1, &out[..2 * k]);
+    sub_assign_limbs(&mut z1, &out[2 * k..]);
+    let mut z1 = &z1[..];
+    while z1.last() == Some(&0) {
+        z1 = &z1[..z1.len() - 1];
+    }
+    add_assign_limbs(&mut out[k..], z1);
+}
+
+fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut out = if a.len().min(b.len()) <= KARATSUBA_LIMIT {
+        let mut out = alloc::vec![0; a.len() + b.len()];
+        mul_karatsuba(a, b, &mut out);
+        out
+    } else {
+        multiply_u64(a, b)
+    };
+    trim(&mut out);
+    out
+}
+
+/// Divides `a` by the single limb `b`, returning the quotient limbs and the remainder.
+fn div_rem_limb(a: &[u64], b: u64) -> (Vec<u64>, u64) {
+    assert!(b != 0, "division by zero");
+    let mut q = alloc::vec![0; a.len()];
+    let mut r = 0u128;
+    for i in (0..a.len()).rev() {
+        let cur = r << 64 | a[i] as u128;
+        q[i] = (cur / b as u128) as u64;
+        r = cur % b as u128;
+    }
+    trim(&mut q);
+    (q, r as u64)
+}
+
+/// Knuth's algorithm D. `b` must have at least two limbs and `a >= b`.
+fn div_rem_limbs(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let s = b.last().unwrap().leading_zeros();
+    let v = shl_limbs(b, s as usize);
+    let mut u = shl_limbs(a, s as usize);
+    u.resize(a.len() + 1, 0);
+    let n = v.len();
+    let m = u.len() - n;
+    let mut q = alloc::vec![0u64; m];
+    let (vh, vl) = (v[n - 1] as u128, v[n - 2] as u128);
+    for j in (0..m).rev() {
+        let num = (u[j + n] as u128) << 64 | u[j + n - 1] as u128;
+        let (mut qhat, mut rhat) = (num / vh, num % vh);
+        while qhat >> 64 != 0 || qhat * vl > (rhat << 64 | u[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += vh;
+            if rhat >> 64 != 0 {
+                break;
+            }
+        }
+        let (mut carry, mut borrow) = (0u128, false);
+        for i in 0..=n {
+            let p = if i < n {
+                qhat * v[i] as u128 + carry
+            } else {
+                carry
+            };
+            carry = p >> 64;
+            let (t, b1) = u[i + j].overflowing_sub(p as u64);
+            let (t, b2) = t.overflowing_sub(borrow as u64);
+            u[i + j] = t;
+            borrow = b1 || b2;
+        }
+        if borrow {
+            qhat -= 1;
+            add_assign_limbs(&mut u[j..j + n + 1], &v);
+        }
+        q[j] = qhat as u64;
+    }
+    trim(&mut q);
+    u.truncate(n);
+    let mut r = shr_limbs(&u, s as usize);
+    trim(&mut r);
+    (q, r)
+}
+
+fn shl_limbs(a: &[u64], k: usize) -> Vec<u64> {
+    let (limbs, bits) = (k / 64, k % 64);
+    let mut out = alloc::vec![0; limbs];
+    if bits == 0 {
+        out.extend_from_slice(a);
+    } else {
+        let mut carry = 0;
+        for &x in a {
+            out.push(x << bits | carry);
+            carry = x >> (64 - bits);
+        }
+        out.push(carry);
+    }
+    trim(&mut out);
+    out
+}
+
+fn shr_limbs(a: &[u64], k: usize) -> Vec<u64> {
+    let (limbs, bits) = (k / 64, k % 64);
+    if limbs >= a.len() {
+        return Vec::new();
+    }
+    let a = &a[limbs..];
+    let mut out: Vec<u64> = if bits == 0 {
+        a.to_vec()
+    } else {
+        (0..a.len())
+            .map(|i| a[i] >> bits | a.get(i + 1).map_or(0, |&y| y << (64 - bits)))
+            .collect()
+    };
+    trim(&mut out);
+    out
+}
+
+impl BigUint {
+    pub const fn zero() -> Self {
+        Self { d: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        Self::from(1u64)
+    }
+
+    /// Creates a number from little-endian limbs.
+    pub fn from_limbs(limbs: &[u64]) -> Self {
+        let mut d = limbs.to_vec();
+        trim(&mut d);
+        Self { d }
+    }
+
+    /// Returns the little-endian limbs, without leading zeros.
+    pub fn limbs(&self) -> &[u64] {
+        &self.d
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.d.is_empty()
+    }
+
+    /// Returns the number of bits needed to represent `self`, which is 0 for zero.
+    pub fn bits(&self) -> usize {
+        self.d
+            .last()
+            .map_or(0, |x| 64 * self.d.len() - x.leading_zeros() as usize)
+    }
+
+    /// Returns bit `i`.
+    pub fn bit(&self, i: usize) -> bool {
+        self.d.get(i / 64).is_some_and(|x| x >> (i % 64) & 1 != 0)
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.d.len() {
+            0 => Some(0),
+            1 => Some(self.d[0]),
+            _ => None,
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.d.len() {
+            0..=2 => Some(self.d.iter().rev().fold(0, |acc, &x| acc << 64 | x as u128)),
+            _ => None,
+        }
+    }
+
+    /// Returns the quotient and remainder of `self / rhs`. A runtime error will occur if `rhs` is zero.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        assert!(!rhs.is_zero(), "division by zero");
+        if cmp_limbs(&self.d, &rhs.d) == Ordering::Less {
+            return (Self::zero(), self.clone());
+        }
+        if rhs.d.len() == 1 {
+            let (q, r) = div_rem_limb(&self.d, rhs.d[0]);
+            return (Self { d: q }, Self::from(r));
+        }
+        let (q, r) = div_rem_limbs(&self.d, &rhs.d);
+        (Self { d: q }, Self { d: r })
+    }
+
+    /// Returns the quotient and remainder of `self / rhs` for a single-limb divisor.
+    pub fn div_rem_u64(&self, rhs: u64) -> (Self, u64) {
+        let (q, r) = div_rem_limb(&self.d, rhs);
+        (Self { d: q }, r)
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let (mut base, mut out) = (self.clone(), Self::one());
+        while exp > 0 {
+            if exp & 1 != 0 {
+                out = &out * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        out
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(x: u64) -> Self {
+        Self::from_limbs(&[x])
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(x: u32) -> Self {
+        Self::from(x as u64)
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(x: usize) -> Self {
+        Self::from(x as u64)
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(x: u128) -> Self {
+        Self::from_limbs(&[x as u64, (x >> 64) as u64])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.d, &other.d)
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let mut d = add_limbs(&self.d, &rhs.d);
+        trim(&mut d);
+        BigUint { d }
+    }
+}
+
+impl Sub for &BigUint {
+    type Output = BigUint;
+    /// A runtime error will occur if the result is negative.
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        assert!(*self >= *rhs, "subtraction underflow");
+        let mut d = self.d.clone();
+        sub_assign_limbs(&mut d, &rhs.d);
+        trim(&mut d);
+        BigUint { d }
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        BigUint {
+            d: mul_limbs(&self.d, &rhs.d),
+        }
+    }
+}
+
+impl Div for &BigUint {
+    type Output = BigUint;
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &BigUint {
+    type Output = BigUint;
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($($tr:ident $f:ident $op:tt),*) => { $(
+        impl $tr for &BigUint {
+            type Output = BigUint;
+            fn $f(self, rhs: &BigUint) -> BigUint {
+                let n = self.d.len().max(rhs.d.len());
+                let mut d: Vec<u64> = (0..n)
+                    .map(|i| self.d.get(i).unwrap_or(&0) $op rhs.d.get(i).unwrap_or(&0))
+                    .collect();
+                trim(&mut d);
+                BigUint { d }
+            }
+        }
+    )* };
+}
+impl_bit_op!(BitAnd bitand &, BitOr bitor |, BitXor bitxor ^);
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+    fn shl(self, k: usize) -> BigUint {
+        BigUint {
+            d: shl_limbs(&self.d, k),
+        }
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+    fn shr(self, k: usize) -> BigUint {
+        BigUint {
+            d: shr_limbs(&self.d, k),
+        }
+    }
+}
+
+/// Forwards the operators on references to owned operands and the compound assignments.
+macro_rules! forward_ops {
+    ($ty:ty; $($tr:ident $f:ident $atr:ident $af:ident),*) => { $(
+        impl $tr<$ty> for $ty {
+            type Output = $ty;
+            fn $f(self, rhs: $ty) -> $ty {
+                (&self).$f(&rhs)
+            }
+        }
+        impl $tr<&$ty> for $ty {
+            type Output = $ty;
+            fn $f(self, rhs: &$ty) -> $ty {
+                (&self).$f(rhs)
+            }
+        }
+        impl $atr<&$ty> for $ty {
+            fn $af(&mut self, rhs: &$ty) {
+                *self = (&*self).$f(rhs);
+            }
+        }
+        impl $atr<$ty> for $ty {
+            fn $af(&mut self, rhs: $ty) {
+                *self = (&*self).$f(&rhs);
+            }
+        }
+    )* };
+}
+forward_ops!(BigUint; Add add AddAssign add_assign, Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign, Div div DivAssign div_assign, Rem rem RemAssign rem_assign,
+    BitAnd bitand BitAndAssign bitand_assign, BitOr bitor BitOrAssign bitor_assign,
+    BitXor bitxor BitXorAssign bitxor_assign);
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+    fn shl(self, k: usize) -> BigUint {
+        &self << k
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+    fn shr(self, k: usize) -> BigUint {
+        &self >> k
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(unsafe { core::str::from_utf8_unchecked(&self.to_decimal()) })
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// An arbitrary-precision signed integer, stored as a sign and a [`BigUint`] magnitude.
+///
+/// Division truncates toward zero and the remainder takes the sign of the dividend,
+/// as for the primitive integers.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    neg: bool,
+    mag: BigUint,
+}
+
+impl BigInt {
+    pub const fn zero() -> Self {
+        Self {
+            neg: false,
+            mag: BigUint::zero(),
+        }
+    }
+
+    pub fn one() -> Self {
+        Self::from(1i64)
+    }
+
+    /// Creates a number from a sign and a magnitude. Zero is never negative.
+    pub fn from_parts(neg: bool, mag: BigUint) -> Self {
+        Self {
+            neg: neg && !mag.is_zero(),
+            mag,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.neg
+    }
+
+    pub fn signum(&self) -> i32 {
+        if self.neg {
+            -1
+        } else if self.is_zero() {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Returns the absolute value.
+    pub fn magnitude(&self) -> &BigUint {
+        &self.mag
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.mag.clone())
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        let m = self.mag.to_u64()?;
+        if self.neg {
+            (m <= 1 << 63).then(|| (m as i64).wrapping_neg())
+        } else {
+            i64::try_from(m).ok()
+        }
+    }
+
+    /// Returns the quotient and remainder of `self / rhs`, truncating toward zero.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let (q, r) = self.mag.div_rem(&rhs.mag);
+        (
+            Self::from_parts(self.neg != rhs.neg, q),
+            Self::from_parts(self.neg, r),
+        )
+    }
+
+    pub fn pow(&self, exp: u32) -> Self {
+        Self::from_parts(self.neg && exp & 1 == 1, self.mag.pow(exp))
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(mag: BigUint) -> Self {
+        Self::from_parts(false, mag)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(x: i64) -> Self {
+        Self::from_parts(x < 0, BigUint::from(x.unsigned_abs()))
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(x: i128) -> Self {
+        Self::from_parts(x < 0, BigUint::from(x.unsigned_abs()))
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(x: u64) -> Self {
+        Self::from(BigUint::from(x))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.neg, other.neg) {
+            (false, false) => self.mag.cmp(&other.mag),
+            (true, true) => other.mag.cmp(&self.mag),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        Self::from_parts(!self.neg, self.mag)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.neg == rhs.neg {
+            return BigInt::from_parts(self.neg, &self.mag + &rhs.mag);
+        }
+        match self.mag.cmp(&rhs.mag) {
+            Ordering::Less => BigInt::from_parts(rhs.neg, &rhs.mag - &self.mag),
+            _ => BigInt::from_parts(self.neg, &self.mag - &rhs.mag),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::from_parts(self.neg != rhs.neg, &self.mag * &rhs.mag)
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+    fn div(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+    fn rem(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).1
+    }
+}
+
+forward_ops!(BigInt; Add add AddAssign add_assign, Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign, Div div DivAssign div_assign, Rem rem RemAssign rem_assign);
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(unsafe { core::str::from_utf8_unchecked(&self.to_decimal()) })
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+const TEN19: u64 = 10_000_000_000_000_000_000;
+/// Below this many base-10^19 chunks, conversions fall back to quadratic loops.
+const DECIMAL_BASE_CHUNKS: usize = 16;
+/// Below this many limbs, a division by a power of 10^19 uses algorithm D directly.
+const NEWTON_LIMIT: usize = 64;
+
+/// `pw[k] = 10^(19 * 2^k)`, each with a reciprocal for fast division once it gets large.
+struct DecimalPowers {
+    pw: Vec<(BigUint, Option<(BigUint, usize)>)>,
+}
+
+impl DecimalPowers {
+    fn new() -> Self {
+        Self {
+            pw: alloc::vec![(BigUint::from(TEN19), None)],
+        }
+    }
+
+    fn get(&mut self, k: usize) -> &BigUint {
+        while self.pw.len() <= k {
+            let last = &self.pw.last().unwrap().0;
+            let next = last * last;
+            let recip = (next.d.len() > NEWTON_LIMIT).then(|| {
+                let s = 2 * next.bits() + 2;
+                (reciprocal(&next, s), s)
+            });
+            self.pw.push((next, recip));
+        }
+        &self.pw[k].0
+    }
+
+    /// Divides `x < pw[k]^2` by `pw[k]`.
+    fn div_rem(&mut self, x: &BigUint, k: usize) -> (BigUint, BigUint) {
+        self.get(k);
+        let (p, recip) = &self.pw[k];
+        let Some((m, s)) = recip else {
+            return x.div_rem(p);
+        };
+        let mut q = &(x * m) >> *s;
+        let qp = &q * p;
+        let mut r = x - &qp;
+        while r >= *p {
+            r -= p;
+            q += BigUint::one();
+        }
+        (q, r)
+    }
+}
+
+/// Returns `floor(2^s / p)` with Newton's iteration, doubling the precision at each level.
+/// `s` must be at least `2 * p.bits()`.
+fn reciprocal(p: &BigUint, s: usize) -> BigUint {
+    if p.d.len() <= NEWTON_LIMIT {
+        return &(&BigUint::one() << s) / p;
+    }
+    let h = p.bits() / 2;
+    let x0 = &reciprocal(&(p >> h), s - 2 * h) << h;
+    let one_s = &BigUint::one() << s;
+    let px = p * &x0;
+    let mut x = if px <= one_s {
+        &x0 + &(&(&x0 * &(&one_s - &px)) >> s)
+    } else {
+        let d = &(&x0 * &(&px - &one_s)) >> s;
+        if d >= x0 {
+            BigUint::zero()
+        } else {
+            &x0 - &d
+        }
+    };
+    let mut px = p * &x;
+    while px > one_s {
+        x -= BigUint::one();
+        px -= p;
+    }
+    loop {
+        let next = &px + p;
+        if next > one_s {
+            break;
+        }
+        px = next;
+        x += BigUint::one();
+    }
+    x
+}
+
+impl BigUint {
+    /// Writes `2^k` base-10^19 chunks of `x < 10^(19 * 2^k)` to `out`, least significant first.
+    fn to_chunks(x: &BigUint, k: usize, pw: &mut DecimalPowers, out: &mut Vec<u64>) {
+        if 1 << k <= DECIMAL_BASE_CHUNKS {
+            let mut x = x.clone();
+            for _ in 0..1 << k {
+                let (q, r) = x.div_rem_u64(TEN19);
+                out.push(r);
+                x = q;
+            }
+            return;
+        }
+        let (q, r) = pw.div_rem(x, k - 1);
+        Self::to_chunks(&r, k - 1, pw, out);
+        Self::to_chunks(&q, k - 1, pw, out);
+    }
+
+    /// Returns the value of the base-10^19 chunks `c`, least significant first,
+    /// where `c.len() <= 2^k`.
+    fn from_chunks(c: &[u64], k: usize, pw: &mut DecimalPowers) -> BigUint {
+        if c.len() <= DECIMAL_BASE_CHUNKS {
+            let mut x = BigUint::zero();
+            for &v in c.iter().rev() {
+                x = &(&x * &BigUint::from(TEN19)) + &BigUint::from(v);
+            }
+            return x;
+        }
+        let half = 1 << (k - 1);
+        if c.len() <= half {
+            return Self::from_chunks(c, k - 1, pw);
+        }
+        let lo = Self::from_chunks(&c[..half], k - 1, pw);
+        let hi = Self::from_chunks(&c[half..], k - 1, pw);
+        &(&hi * pw.get(k - 1)) + &lo
+    }
+
+    /// Returns the decimal digits of `self` as ASCII bytes, in O(M(n) log n) time
+    /// where M(n) is the cost of a multiplication.
+    pub fn to_decimal(&self) -> Vec<u8> {
+        let mut pw = DecimalPowers::new();
+        let mut k = 0;
+        while *pw.get(k) <= *self {
+            k += 1;
+        }
+        let mut chunks = Vec::with_capacity(1 << k);
+        Self::to_chunks(self, k, &mut pw, &mut chunks);
+        while chunks.len() > 1 && chunks.last() == Some(&0) {
+            chunks.pop();
+        }
+        let mut out = Vec::with_capacity(19 * chunks.len());
+        let mut buf = [0u8; 20];
+        let mut first = true;
+        for &c in chunks.iter().rev() {
+            let mut v = c;
+            for b in buf[..19].iter_mut().rev() {
+                *b = b'0' + (v % 10) as u8;
+                v /= 10;
+            }
+            let skip = if first {
+                buf[..18].iter().take_while(|&&b| b == b'0').count()
+            } else {
+                0
+            };
+            out.extend_from_slice(&buf[skip..19]);
+            first = false;
+        }
+        out
+    }
+
+    /// Parses decimal digits. Returns `None` if `s` is empty or contains a non-digit.
+    pub fn from_decimal(s: &[u8]) -> Option<Self> {
+        if s.is_empty() || !s.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let chunks: Vec<u64> = s
+            .rchunks(19)
+            .map(|c| c.iter().fold(0, |acc, &b| acc * 10 + (b - b'0') as u64))
+            .collect();
+        let k = chunks.len().next_power_of_two().trailing_zeros() as usize;
+        Some(Self::from_chunks(&chunks, k, &mut DecimalPowers::new()))
+    }
+}
+
+impl BigInt {
+    /// Returns the decimal representation of `self` as ASCII bytes, with a leading `-` if negative.
+    pub fn to_decimal(&self) -> Vec<u8> {
+        let digits = self.mag.to_decimal();
+        if !self.neg {
+            return digits;
+        }
+        let mut out = Vec::with_capacity(digits.len() + 1);
+        out.push(b'-');
+        out.extend_from_slice(&digits);
+        out
+    }
+
+    /// Parses decimal digits with an optional leading `+` or `-`.
+    pub fn from_decimal(s: &[u8]) -> Option<Self> {
+        match s.first() {
+            Some(b'-') => Some(Self::from_parts(true, BigUint::from_decimal(&s[1..])?)),
+            Some(b'+') => Some(BigUint::from_decimal(&s[1..])?.into()),
+            _ => Some(BigUint::from_decimal(s)?.into()),
+        }
+    }
+}
+
+/// The error returned when parsing a big integer fails.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParseBigIntError;
+
+impl core::str::FromStr for BigUint {
+    type Err = ParseBigIntError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_decimal(s.as_bytes()).ok_or(ParseBigIntError)
+    }
+}
+
+impl core::str::FromStr for BigInt {
+    type Err = ParseBigIntError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_decimal(s.as_bytes()).ok_or(ParseBigIntError)
+    }
+}
+
+impl Readable for BigUint {
+    fn read(reader: &mut impl ReaderTrait) -> Self {
+        Self::from_decimal(reader.word().as_bytes()).expect("invalid integer")
+    }
+}
+
+impl Readable for BigInt {
+    fn read(reader: &mut impl ReaderTrait) -> Self {
+        Self::from_decimal(reader.word().as_bytes()).expect("invalid integer")
+    }
+}
+
+macro_rules! impl_print_big {
+    ($($ty:ty),*) => { $(
+        impl<const N: usize> Print<&$ty> for Writer<N> {
+            fn print(&mut self, x: &$ty) {
+                self.print(&x.to_decimal()[..]);
+            }
+            fn println(&mut self, x: &$ty) {
+                self.println(&x.to_decimal()[..]);
+            }
+        }
+    )* };
+}
+impl_print_big!(BigUint, BigInt);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+    use alloc::string::ToString;
+
+    fn rand_big(rng: &mut TestRng, limbs: usize) -> BigUint {
+        let d: Vec<u64> = (0..limbs).map(|_| rng.next_u64()).collect();
+        BigUint::from_limbs(&d)
+    }
+
+    #[test]
+    fn small_values_match_u128() {
+        let mut rng = TestRng::new(1);
+        for _ in 0..2000 {
+            let a = rand_big(&mut rng, 2).to_u128().unwrap();
+            let limbs = 1 + rng.index(2);
+            let b = rand_big(&mut rng, limbs).to_u128().unwrap() >> rng.below(100);
+            let (x, y) = (BigUint::from(a), BigUint::from(b));
+            if let Some(q) = a.checked_div(b) {
+                assert_eq!(Some(q), (&x / &y).to_u128());
+                assert_eq!(Some(a % b), (&x % &y).to_u128());
+            }
+            if let Some(s) = a.checked_add(b) {
+                assert_eq!(Some(s), (&x + &y).to_u128());
+            }
+            if a >= b {
+                assert_eq!(Some(a - b), (&x - &y).to_u128());
+            }
+            if let Some(p) = a.checked_mul(b) {
+                assert_eq!(Some(p), (&x * &y).to_u128());
+            }
+            assert_eq!(Some(a ^ b), (&x ^ &y).to_u128());
+            assert_eq!(Some(a & b), (&x & &y).to_u128());
+            assert_eq!(Some(a >> 7), (&x >> 7).to_u128());
+            assert_eq!(a.cmp(&b), x.cmp(&y));
+            assert_eq!(a.to_string(), x.to_string());
+        }
+    }
+
+    #[test]
+    fn large_identities() {
+        let mut rng = TestRng::new(7);
+        for (n, m) in [(3, 2), (40, 35), (100, 7), (700, 600), (1500, 1200)] {
+            let (a, b) = (rand_big(&mut rng, n), rand_big(&mut rng, m));
+            let p = &a * &b;
+            assert_eq!(p, &b * &a);
+            let (q, r) = p.div_rem(&b);
+            assert_eq!((a.clone(), BigUint::zero()), (q, r));
+            let c = &p + &rand_big(&mut rng, m - 1);
+            let (q, r) = c.div_rem(&b);
+            assert!(r < b);
+            assert_eq!(c, &(&q * &b) + &r);
+            assert_eq!(a, &(&a << 100) >> 100);
+        }
+        let school = {
+            let (a, b) = (rand_big(&mut rng, 600), rand_big(&mut rng, 600));
+            let mut out = alloc::vec![0; 1200];
+            mul_school(&a.d, &b.d, &mut out);
+            (BigUint::from_limbs(&out), a, b)
+        };
+        assert_eq!(school.0, &school.1 * &school.2);
+    }
+
+    #[test]
+    fn signed_arithmetic() {
+        for a in [-300i64, -7, -1, 0, 1, 5, 1 << 40] {
+            for b in [-13i64, -1, 1, 4, 999] {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                assert_eq!(Some(a + b), (&x + &y).to_i64());
+                assert_eq!(Some(a - b), (&x - &y).to_i64());
+                assert_eq!(Some(a * b), (&x * &y).to_i64());
+                assert_eq!(Some(a / b), (&x / &y).to_i64());
+                assert_eq!(Some(a % b), (&x % &y).to_i64());
+                assert_eq!(a.cmp(&b), x.cmp(&y));
+            }
+        }
+        assert_eq!(
+            "-2535301200456458802993406410752",
+            BigInt::from(-2i64).pow(101).to_string()
+        );
+        assert_eq!(Some(i64::MIN), BigInt::from(i64::MIN).to_i64());
+    }
+
+    #[test]
+    fn decimal_round_trip() {
+        let mut rng = TestRng::new(11);
+        for limbs in [0, 1, 2, 5, 40, 300, 3000] {
+            let a = rand_big(&mut rng, limbs);
+            let s = a.to_decimal();
+            let t = a.to_string();
+            assert_eq!(t.as_bytes(), &s[..]);
+            assert_eq!(Some(a.clone()), BigUint::from_decimal(&s));
+            if limbs <= 2 {
+                assert_eq!(a.to_u128().unwrap().to_string(), t);
+            }
+        }
+        let ten = BigUint::from(10u64).pow(5000);
+        let digits = ten.to_decimal();
+        assert_eq!(5001, digits.len());
+        assert!(digits[0] == b'1' && digits[1..].iter().all(|&b| b == b'0'));
+        assert_eq!(ten, BigUint::from_decimal(&digits).unwrap());
+        let nines = &ten - &BigUint::one();
+        assert!(nines.to_decimal().iter().all(|&b| b == b'9'));
+        assert_eq!(Ok(BigInt::from(-120i64)), "-000120".parse());
+        assert_eq!("0", "-0".parse::<BigInt>().unwrap().to_string());
+        assert!("12a".parse::<BigUint>().is_err() && "".parse::<BigUint>().is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use super::{polymul_u64, ModInt};
+use alloc::vec::Vec;
+
+/// Returns a generator of the multiplicative group modulo the prime `m`.
+fn primitive_root(m: u32) -> u32 {
+    let mut factors = Vec::new();
+    let (mut x, mut p) = (m - 1, 2);
+    while p * p <= x {
+        if x % p == 0 {
+            factors.push(p);
+            while x % p == 0 {
+                x /= p;
+            }
+        }
+        p += 1;
+    }
+    if x > 1 {
+        factors.push(x);
+    }
+    (2..)
+        .find(|&g| {
+            factors
+                .iter()
+                .all(|&q| super::modpow(g as u64, ((m - 1) / q) as u64, m as u64) != Some(1))
+        })
+        .unwrap()
+}
+
+/// Twiddle factors `w^0, .., w^(len-1)` for each butterfly width `len`, where `w` is a primitive
+/// `2 * len`-th root of unity.
+fn twiddles<const M: u32>(n: usize, root: ModInt<M>) -> Vec<Vec<ModInt<M>>> {
+    let mut out = Vec::new();
+    let mut len = 1;
+    while len < n {
+        let w = root.pow(((M - 1) as usize / (2 * len)) as u64);
+        let mut t = Vec::with_capacity(len);
+        let mut x = ModInt::one();
+        for _ in 0..len {
+            t.push(x);
+            x *= w;
+        }
+        out.push(t);
+        len *= 2;
+    }
+    out
+}
+
+/// In-place decimation-in-frequency NTT. The output is in bit-reversed order.
+fn ntt_dif<const M: u32>(a: &mut [ModInt<M>], tw: &[Vec<ModInt<M>>]) {
+    for t in tw.iter().rev() {
+        let len = t.len();
+        for block in a.chunks_exact_mut(2 * len) {
+            let (lo, hi) = block.split_at_mut(len);
+            for ((x, y), &w) in lo.iter_mut().zip(hi.iter_mut()).zip(t) {
+                let (u, v) = (*x, *y);
+                *x = u + v;
+                *y = (u - v) * w;
+            }
+        }
+    }
+}
+
+/// In-place decimation-in-time NTT taking its input in bit-reversed order.
+fn ntt_dit<const M: u32>(a: &mut [ModInt<M>], tw: &[Vec<ModInt<M>>]) {
+    for t in tw {
+        let len = t.len();
+        for block in a.chunks_exact_mut(2 * len) {
+            let (lo, hi) = block.split_at_mut(len);
+            for ((x, y), &w) in lo.iter_mut().zip(hi.iter_mut()).zip(t) {
+                let (u, v) = (*x, *y * w);
+                *x = u + v;
+                *y = u - v;
+            }
+        }
+    }
+}
+
+/// Returns the convolution of `a` and `b`, that is, the coefficients of the product
+/// of the polynomials with coefficients `a` and `b`.
+///
+/// If `M` is a prime such that `2^k` divides `M - 1` for `2^k >= a.len() + b.len() - 1`
+/// (e.g. 998244353), a radix-2 NTT modulo `M` is used. The butterflies run over contiguous slices,
+/// and the DIF/DIT pair avoids the bit-reversal permutation. For other moduli this falls back
+/// to [`polymul_u64`]. Small inputs are multiplied naively.
+pub fn convolution<const M: u32>(a: &[ModInt<M>], b: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let out_len = a.len() + b.len() - 1;
+    if a.len().min(b.len()) <= 32 {
+        let mut out = alloc::vec![ModInt::zero(); out_len];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                out[i + j] += x * y;
+            }
+        }
+        return out;
+    }
+    let n = out_len.next_power_of_two();
+    if (M - 1) as usize & (n - 1) != 0 || !super::is_prime_u32(M) {
+        let x: Vec<u64> = a.iter().map(|x| x.val() as u64).collect();
+        let y: Vec<u64> = b.iter().map(|x| x.val() as u64).collect();
+        return polymul_u64(&x, &y, M as u64)
+            .into_iter()
+            .map(ModInt::from)
+            .collect();
+    }
+    let g = ModInt::<M>::new(primitive_root(M) as u64);
+    let mut x = alloc::vec![ModInt::zero(); n];
+    let mut y = alloc::vec![ModInt::zero(); n];
+    x[..a.len()].copy_from_slice(a);
+    y[..b.len()].copy_from_slice(b);
+    let tw = twiddles(n, g);
+    ntt_dif(&mut x, &tw);
+    ntt_dif(&mut y, &tw);
+    let inv_n = ModInt::<M>::from(n).inv();
+    for (p, q) in x.iter_mut().zip(&y) {
+        *p *= *q * inv_n;
+    }
+    ntt_dit(&mut x, &twiddles(n, g.inv()));
+    x.truncate(out_len);
+    x
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+
+    fn naive<const M: u32>(a: &[ModInt<M>], b: &[ModInt<M>]) -> Vec<ModInt<M>> {
+        let mut out = alloc::vec![ModInt::zero(); a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                out[i + j] += x * y;
+            }
+        }
+        out
+    }
+
+    fn check<const M: u32>() {
+        let mut rng = TestRng::new(5);
+        let mut rand =
+            |n: usize| -> Vec<ModInt<M>> { (0..n).map(|_| ModInt::new(rng.next_u64())).collect() };
+        for (n, m) in [(1, 1), (40, 33), (100, 300), (257, 1000)] {
+            let (a, b) = (rand(n), rand(m));
+            assert_eq!(naive(&a, &b), convolution(&a, &b));
+        }
+    }
+
+    #[test]
+    fn matches_naive() {
+        assert_eq!(3, primitive_root(998_244_353));
+        check::<998_244_353>();
+        check::<1_000_000_007>();
+        check::<167_772_161>();
+        assert!(convolution::<7>(&[], &[ModInt::one()]).is_empty());
+    }
+}
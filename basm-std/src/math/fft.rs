@@ -0,0 +1,210 @@
+use alloc::vec::Vec;
+use core::ops::{Add, AddAssign, Mul, Sub};
+
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub const fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    fn scale(self, k: f64) -> Self {
+        Self::new(self.re * k, self.im * k)
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl AddAssign for Complex {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+/// Computes the discrete Fourier transform `A[k] = sum a[j] * exp(2 pi i j k / n)` in place.
+/// `a.len()` must be a power of two.
+///
+/// The inverse transform is obtained by reversing `a[1..]` of the output and dividing by `n`,
+/// which is what `invert` does.
+pub fn fft(a: &mut [Complex], invert: bool) {
+    let n = a.len();
+    assert!(n.is_power_of_two());
+    if n == 1 {
+        return;
+    }
+    // rt[k + j] = exp(pi i j / k) for every power of two k and j < k
+    let mut rt = alloc::vec![Complex::new(1.0, 0.0); n];
+    let mut k = 2;
+    while k < n {
+        for j in 0..k {
+            let t = core::f64::consts::PI * j as f64 / k as f64;
+            rt[k + j] = Complex::new(libm::cos(t), libm::sin(t));
+        }
+        k *= 2;
+    }
+    let shift = usize::BITS - n.trailing_zeros();
+    for i in 0..n {
+        let r = i.reverse_bits() >> shift;
+        if i < r {
+            a.swap(i, r);
+        }
+    }
+    let mut k = 1;
+    while k < n {
+        for block in a.chunks_exact_mut(2 * k) {
+            let (lo, hi) = block.split_at_mut(k);
+            for ((x, y), &w) in lo.iter_mut().zip(hi.iter_mut()).zip(&rt[k..2 * k]) {
+                let z = w * *y;
+                *y = *x - z;
+                *x += z;
+            }
+        }
+        k *= 2;
+    }
+    if invert {
+        a[1..].reverse();
+        let inv = 1.0 / n as f64;
+        a.iter_mut().for_each(|x| *x = x.scale(inv));
+    }
+}
+
+/// Returns the convolution of `a` and `b` computed with a single complex FFT and its inverse.
+/// The error grows with the magnitude of the result; round it if the inputs are integers.
+pub fn convolution_f64(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let out_len = a.len() + b.len() - 1;
+    let n = out_len.next_power_of_two();
+    let mut x = alloc::vec![Complex::default(); n];
+    for (i, &v) in a.iter().enumerate() {
+        x[i].re = v;
+    }
+    for (i, &v) in b.iter().enumerate() {
+        x[i].im = v;
+    }
+    fft(&mut x, false);
+    x.iter_mut().for_each(|v| *v = *v * *v);
+    let mut y: Vec<Complex> = (0..n)
+        .map(|i| x[i.wrapping_neg() & (n - 1)] - x[i].conj())
+        .collect();
+    fft(&mut y, false);
+    y[..out_len].iter().map(|v| v.im / (4 * n) as f64).collect()
+}
+
+/// Returns the convolution of `a` and `b` modulo an arbitrary `m < 2^31`, such as 1e9+7,
+/// where the elements must be less than `m`.
+///
+/// Each element is split into two halves below `sqrt(m)`, and the four partial products are
+/// computed with four complex FFTs of length `n`, which is accurate for `n` up to about 2^20.
+/// [`polymul_u64`](super::polymul_u64) covers larger inputs and moduli with a multi-prime NTT.
+pub fn convolution_mod(a: &[u32], b: &[u32], m: u32) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let out_len = a.len() + b.len() - 1;
+    let n = (out_len + 1).next_power_of_two();
+    let cut = m.isqrt().max(1);
+    let split = |v: &[u32]| {
+        let mut out = alloc::vec![Complex::default(); n];
+        for (o, &x) in out.iter_mut().zip(v) {
+            *o = Complex::new((x / cut) as f64, (x % cut) as f64);
+        }
+        fft(&mut out, false);
+        out
+    };
+    let (l, r) = (split(a), split(b));
+    let mut outl = alloc::vec![Complex::default(); n];
+    let mut outs = alloc::vec![Complex::default(); n];
+    let k = 1.0 / (2 * n) as f64;
+    for i in 0..n {
+        let j = i.wrapping_neg() & (n - 1);
+        outl[j] = ((l[i] + l[j].conj()) * r[i]).scale(k);
+        let s = ((l[i] - l[j].conj()) * r[i]).scale(k);
+        outs[j] = Complex::new(s.im, -s.re);
+    }
+    fft(&mut outl, false);
+    fft(&mut outs, false);
+    let (m, cut) = (m as u64, cut as u64);
+    let round = |x: f64| (libm::round(x) as i64).rem_euclid(m as i64) as u64;
+    (0..out_len)
+        .map(|i| {
+            let av = round(outl[i].re);
+            let bv = (round(outl[i].im) + round(outs[i].re)) % m;
+            let cv = round(outs[i].im);
+            ((av * cut % m + bv) % m * cut % m + cv) as u32 % m as u32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+
+    #[test]
+    fn fft_round_trip() {
+        let orig: Vec<Complex> = (0..16)
+            .map(|i| Complex::new(i as f64, (i * i) as f64))
+            .collect();
+        let mut a = orig.clone();
+        fft(&mut a, false);
+        assert!((a[0].re - 120.0).abs() < 1e-9);
+        fft(&mut a, true);
+        for (x, y) in a.iter().zip(&orig) {
+            assert!((x.re - y.re).abs() < 1e-9 && (x.im - y.im).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn convolutions_match_naive() {
+        let out = convolution_f64(&[1.0, 2.0, 3.0], &[4.0, 5.0]);
+        for (x, y) in out.iter().zip([4.0, 13.0, 22.0, 15.0]) {
+            assert!((x - y).abs() < 1e-9);
+        }
+        let m = 1_000_000_007;
+        let mut rng = TestRng::new(3);
+        let mut rand = |n: usize| -> Vec<u32> { (0..n).map(|_| rng.below(m) as u32).collect() };
+        for (n, k) in [(1, 1), (5, 300), (1000, 1000)] {
+            let (a, b) = (rand(n), rand(k));
+            let mut naive = alloc::vec![0u64; n + k - 1];
+            for (i, &x) in a.iter().enumerate() {
+                for (j, &y) in b.iter().enumerate() {
+                    naive[i + j] = (naive[i + j] + x as u64 * y as u64) % m;
+                }
+            }
+            let naive: Vec<u32> = naive.into_iter().map(|x| x as u32).collect();
+            assert_eq!(naive, convolution_mod(&a, &b, m as u32));
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use alloc::vec::Vec;
+use core::ops::{Add, Index, IndexMut, Mul, MulAssign};
+
+/// Side length of the square tiles used by the blocked multiplication.
+const BLOCK: usize = 64;
+
+/// A dense row-major matrix over any ring-like `T` such as `ModInt`, `i64` or `f64`.
+///
+/// Zero and one are taken as `T::from(0u8)` and `T::from(1u8)`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Matrix<T> {
+    n: usize,
+    m: usize,
+    a: Vec<T>,
+}
+
+impl<T> Matrix<T>
+where
+    T: Copy + PartialEq + From<u8> + Add<Output = T> + Mul<Output = T>,
+{
+    /// Creates an `n` by `m` zero matrix.
+    pub fn new(n: usize, m: usize) -> Self {
+        Self {
+            n,
+            m,
+            a: alloc::vec![T::from(0); n * m],
+        }
+    }
+
+    /// Creates the `n` by `n` identity matrix.
+    pub fn identity(n: usize) -> Self {
+        let mut out = Self::new(n, n);
+        for i in 0..n {
+            out[(i, i)] = T::from(1);
+        }
+        out
+    }
+
+    /// Creates an `n` by `m` matrix from its entries in row-major order.
+    pub fn from_vec(n: usize, m: usize, a: Vec<T>) -> Self {
+        assert_eq!(n * m, a.len());
+        Self { n, m, a }
+    }
+
+    /// Creates a matrix from its rows, which must all have the same length.
+    pub fn from_rows(rows: &[Vec<T>]) -> Self {
+        let m = rows.first().map_or(0, |r| r.len());
+        assert!(rows.iter().all(|r| r.len() == m));
+        Self {
+            n: rows.len(),
+            m,
+            a: rows.concat(),
+        }
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of columns.
+    pub fn cols(&self) -> usize {
+        self.m
+    }
+
+    /// Returns the `i`-th row as a slice.
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.a[i * self.m..(i + 1) * self.m]
+    }
+
+    /// Returns the transposed matrix.
+    pub fn transpose(&self) -> Self {
+        let mut out = Self::new(self.m, self.n);
+        for i in 0..self.n {
+            for j in 0..self.m {
+                out[(j, i)] = self[(i, j)];
+            }
+        }
+        out
+    }
+
+    /// Returns the product `self * x` for a column vector `x`.
+    pub fn mul_vec(&self, x: &[T]) -> Vec<T> {
+        assert_eq!(self.m, x.len());
+        (0..self.n)
+            .map(|i| {
+                self.row(i)
+                    .iter()
+                    .zip(x)
+                    .fold(T::from(0), |acc, (&a, &b)| acc + a * b)
+            })
+            .collect()
+    }
+
+    /// Returns `self` raised to the `k`-th power by repeated squaring, using O(n^3 log k) operations.
+    /// The matrix must be square.
+    pub fn pow(&self, mut k: u64) -> Self {
+        assert_eq!(self.n, self.m);
+        let mut out = Self::identity(self.n);
+        let mut base = self.clone();
+        while k > 0 {
+            if k & 1 == 1 {
+                out = &out * &base;
+            }
+            k >>= 1;
+            if k > 0 {
+                base = &base * &base;
+            }
+        }
+        out
+    }
+
+    /// Multiplies in `BLOCK`-sized tiles so that the working set of the inner loops stays in cache.
+    /// Zero entries of `self` are skipped, which speeds up sparse adjacency matrices.
+    fn mul_blocked(&self, rhs: &Self) -> Self {
+        assert_eq!(self.m, rhs.n);
+        let (n, k, m) = (self.n, self.m, rhs.m);
+        let zero = T::from(0);
+        let mut out = Self::new(n, m);
+        for i0 in (0..n).step_by(BLOCK) {
+            for k0 in (0..k).step_by(BLOCK) {
+                for j0 in (0..m).step_by(BLOCK) {
+                    let j1 = (j0 + BLOCK).min(m);
+                    for i in i0..(i0 + BLOCK).min(n) {
+                        let c = &mut out.a[i * m + j0..i * m + j1];
+                        for kk in k0..(k0 + BLOCK).min(k) {
+                            let x = self.a[i * k + kk];
+                            if x == zero {
+                                continue;
+                            }
+                            let b = &rhs.a[kk * m + j0..kk * m + j1];
+                            for (c, &b) in c.iter_mut().zip(b) {
+                                *c = *c + x * b;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        debug_assert!(j < self.m);
+        &self.a[i * self.m + j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        debug_assert!(j < self.m);
+        &mut self.a[i * self.m + j]
+    }
+}
+
+impl<T> Mul<&Matrix<T>> for &Matrix<T>
+where
+    T: Copy + PartialEq + From<u8> + Add<Output = T> + Mul<Output = T>,
+{
+    type Output = Matrix<T>;
+    fn mul(self, rhs: &Matrix<T>) -> Matrix<T> {
+        self.mul_blocked(rhs)
+    }
+}
+
+impl<T> Mul for Matrix<T>
+where
+    T: Copy + PartialEq + From<u8> + Add<Output = T> + Mul<Output = T>,
+{
+    type Output = Matrix<T>;
+    fn mul(self, rhs: Matrix<T>) -> Matrix<T> {
+        self.mul_blocked(&rhs)
+    }
+}
+
+impl<T> MulAssign<&Matrix<T>> for Matrix<T>
+where
+    T: Copy + PartialEq + From<u8> + Add<Output = T> + Mul<Output = T>,
+{
+    fn mul_assign(&mut self, rhs: &Matrix<T>) {
+        *self = self.mul_blocked(rhs);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::math::ModInt1000000007;
+    use crate::utils::TestRng;
+
+    #[test]
+    fn fibonacci() {
+        type Z = ModInt1000000007;
+        let q = Matrix::<Z>::from_rows(&[
+            alloc::vec![1u8.into(), 1u8.into()],
+            alloc::vec![1u8.into(), 0u8.into()],
+        ]);
+        assert_eq!(Matrix::identity(2), q.pow(0));
+        assert_eq!(55, q.pow(10)[(0, 1)].val());
+        // F(10^18) mod 1e9+7
+        assert_eq!(209_783_453, q.pow(1_000_000_000_000_000_000)[(0, 1)].val());
+        let f = Matrix::<f64>::from_vec(2, 2, alloc::vec![1.0, 1.0, 1.0, 0.0]).pow(20);
+        assert_eq!(6765.0, f[(0, 1)]);
+    }
+
+    #[test]
+    fn blocked_matches_naive() {
+        let mut rng = TestRng::new(3);
+        let mut rand = || rng.below(7) as i64 - 3;
+        for (n, k, m) in [(1, 1, 1), (3, 5, 2), (130, 70, 150)] {
+            let a = Matrix::from_vec(n, k, (0..n * k).map(|_| rand()).collect());
+            let b = Matrix::from_vec(k, m, (0..k * m).map(|_| rand()).collect());
+            let c = &a * &b;
+            for i in 0..n {
+                for j in 0..m {
+                    let expected: i64 = (0..k).map(|t| a[(i, t)] * b[(t, j)]).sum();
+                    assert_eq!(expected, c[(i, j)]);
+                }
+            }
+            assert_eq!(c.transpose(), &b.transpose() * &a.transpose());
+            let x: Vec<i64> = (0..m).map(|_| rand()).collect();
+            assert_eq!(c.mul_vec(&x), a.mul_vec(&b.mul_vec(&x)));
+        }
+    }
+}
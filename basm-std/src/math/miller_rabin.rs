@@ -6,17 +6,18 @@ pub struct M<T> {
     pub v: T,
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct OddMont<T> {
-    n: T,
-    ni: T,
-    r2: T,
-    r1: T,
+    pub(super) n: T,
+    pub(super) ni: T,
+    pub(super) r2: T,
+    pub(super) r1: T,
 }
 
 macro_rules! impl_mont {
     ($ty: ident, $ty_large: ident) => {
         impl OddMont<$ty> {
-            pub fn new(a: $ty) -> OddMont<$ty> {
+            pub const fn new(a: $ty) -> OddMont<$ty> {
                 let n = a;
                 let ni = (0 as $ty).wrapping_sub(Self::inv_word(a));
                 let r2 = (0 as $ty_large).wrapping_sub(a as $ty_large) % a as $ty_large;
@@ -39,7 +40,7 @@ macro_rules! impl_mont {
                 ans
             }
 
-            fn redc_given(a: $ty_large, n: $ty, ni: $ty) -> $ty {
+            const fn redc_given(a: $ty_large, n: $ty, ni: $ty) -> $ty {
                 let m = (a as $ty).wrapping_mul(ni);
                 let mn_neg = ((0 as $ty).wrapping_sub(m) as $ty_large).wrapping_mul(n as $ty_large);
                 if a >= mn_neg {
@@ -49,20 +50,55 @@ macro_rules! impl_mont {
                 }
             }
 
-            pub fn redc(&self, a: $ty_large) -> M<$ty> {
+            pub const fn redc(&self, a: $ty_large) -> M<$ty> {
                 M {
                     v: Self::redc_given(a, self.n, self.ni),
                 }
             }
 
-            pub fn mul(&self, a: M<$ty>, b: M<$ty>) -> M<$ty> {
+            pub const fn mul(&self, a: M<$ty>, b: M<$ty>) -> M<$ty> {
                 self.redc(a.v as $ty_large * b.v as $ty_large)
             }
 
-            pub fn to_mont(&self, a: $ty) -> M<$ty> {
+            pub const fn to_mont(&self, a: $ty) -> M<$ty> {
                 self.redc(self.r2 as $ty_large * a as $ty_large)
             }
 
+            pub const fn from_mont(&self, a: M<$ty>) -> $ty {
+                self.redc(a.v as $ty_large).v
+            }
+
+            pub const fn modulus(&self) -> $ty {
+                self.n
+            }
+
+            // Montgomery form of 1
+            pub const fn one(&self) -> M<$ty> {
+                M { v: self.r1 }
+            }
+
+            pub const fn add(&self, a: M<$ty>, b: M<$ty>) -> M<$ty> {
+                let (v, carry) = a.v.overflowing_add(b.v);
+                if carry || v >= self.n {
+                    M {
+                        v: v.wrapping_sub(self.n),
+                    }
+                } else {
+                    M { v }
+                }
+            }
+
+            pub const fn sub(&self, a: M<$ty>, b: M<$ty>) -> M<$ty> {
+                let (v, borrow) = a.v.overflowing_sub(b.v);
+                if borrow {
+                    M {
+                        v: v.wrapping_add(self.n),
+                    }
+                } else {
+                    M { v }
+                }
+            }
+
             #[allow(dead_code)]
             fn powmul(&self, base: M<$ty>, exp: $ty, v: $ty) -> $ty {
                 let mut ans = M { v };
@@ -171,6 +207,51 @@ pub fn is_prime_u64(x: u64) -> bool {
     }
 }
 
+/// Returns whether `x` is prime. The witness set makes this exact for every `u64`.
+pub fn is_prime(x: u64) -> bool {
+    is_prime_u64(x)
+}
+
+/// Returns whether `x` is prime. This is exact below `3.3 * 10^24`, where the first 13 prime
+/// bases are known to suffice; above it, `x` is a strong probable prime to the first 20 primes.
+pub fn is_prime_u128(x: u128) -> bool {
+    use super::modmul::modmul128;
+    if x <= u64::MAX as u128 {
+        return is_prime_u64(x as u64);
+    }
+    if x & 1 == 0 {
+        return false;
+    }
+    let s = (x - 1).trailing_zeros();
+    let d = (x - 1) >> s;
+    'outer: for a in [
+        2u128, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+    ] {
+        if x.is_multiple_of(a) {
+            return false;
+        }
+        let (mut y, mut b, mut e) = (1, a, d);
+        while e > 0 {
+            if e & 1 == 1 {
+                y = modmul128(y, b, x);
+            }
+            b = modmul128(b, b, x);
+            e >>= 1;
+        }
+        if y == 1 || y == x - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            y = modmul128(y, y, x);
+            if y == x - 1 {
+                continue 'outer;
+            }
+        }
+        return false;
+    }
+    true
+}
+
 mod test {
     #[cfg(test)]
     use super::*;
@@ -189,4 +270,15 @@ mod test {
         assert!(!is_prime_u64(598963177));
         assert!(!is_prime_u64(162319020967));
     }
+
+    #[test]
+    fn check_is_prime_u128() {
+        assert!(is_prime(18446744073709551557));
+        assert!(!is_prime(3825123056546413051));
+        assert!(is_prime_u128((1 << 127) - 1));
+        assert!(is_prime_u128(18446744073709551629));
+        assert!(!is_prime_u128(18446744073709551557 * 18446744073709551557));
+        assert!(!is_prime_u128(4611686018427387847 * 9223372036854775783));
+        assert!(!is_prime_u128(1 << 100));
+    }
 }
@@ -0,0 +1,333 @@
+use crate::platform::io::{Print, Readable, ReaderTrait, Writer};
+use core::fmt;
+use core::iter::{Product, Sum};
+use core::ops::*;
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// An integer modulo the compile-time constant `M`, kept reduced in `0..M`.
+///
+/// `M` must be at least 1 and less than 2^31, so that a sum of two values never overflows.
+/// Division and [`ModInt::inv`] require the divisor to be coprime with `M`, which always holds
+/// for a nonzero divisor when `M` is prime.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct ModInt<const M: u32>(u32);
+
+pub type ModInt998244353 = ModInt<998_244_353>;
+pub type ModInt1000000007 = ModInt<1_000_000_007>;
+
+impl<const M: u32> ModInt<M> {
+    const CHECK: () = assert!(M >= 1 && M < 1 << 31, "modulus out of range");
+
+    /// Returns `x` as a `ModInt` without reducing it. `x` must be less than `M`.
+    pub const fn raw(x: u32) -> Self {
+        let () = Self::CHECK;
+        debug_assert!(x < M);
+        Self(x)
+    }
+
+    pub const fn new(x: u64) -> Self {
+        Self::raw((x % M as u64) as u32)
+    }
+
+    pub const fn modulus() -> u32 {
+        M
+    }
+}
+
+impl<const M: u32> Mul for ModInt<M> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::raw((self.0 as u64 * rhs.0 as u64 % M as u64) as u32)
+    }
+}
+
+/// Barrett reduction for a fixed modulus `m < 2^31`, which replaces the division in `a * b % m`
+/// with two multiplications.
+#[derive(Clone, Copy, Debug)]
+pub struct Barrett {
+    m: u32,
+    im: u64,
+}
+
+impl Barrett {
+    pub const fn new(m: u32) -> Self {
+        assert!(m >= 1 && m < 1 << 31, "modulus out of range");
+        Self {
+            m,
+            im: (u64::MAX / m as u64).wrapping_add(1),
+        }
+    }
+
+    pub const fn modulus(&self) -> u32 {
+        self.m
+    }
+
+    /// Computes `a * b % m` for `a, b < m`.
+    pub const fn mul(&self, a: u32, b: u32) -> u32 {
+        let z = a as u64 * b as u64;
+        let x = ((z as u128 * self.im as u128) >> 64) as u64;
+        let (v, borrow) = z.overflowing_sub(x.wrapping_mul(self.m as u64));
+        (if borrow {
+            v.wrapping_add(self.m as u64)
+        } else {
+            v
+        }) as u32
+    }
+}
+
+static DYN_MOD: AtomicU32 = AtomicU32::new(998_244_353);
+static DYN_IM: AtomicU64 = AtomicU64::new(Barrett::new(998_244_353).im);
+
+/// An integer modulo a modulus chosen at runtime with [`DynModInt::set_modulus`],
+/// multiplied with [`Barrett`] reduction.
+///
+/// The modulus is global, so all `DynModInt` values share it; it must be at least 1
+/// and less than 2^31, and it defaults to 998244353. Values created before the modulus
+/// is changed become meaningless.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct DynModInt(u32);
+
+impl DynModInt {
+    /// Sets the modulus shared by all `DynModInt` values.
+    pub fn set_modulus(m: u32) {
+        let b = Barrett::new(m);
+        DYN_MOD.store(b.m, Ordering::Relaxed);
+        DYN_IM.store(b.im, Ordering::Relaxed);
+    }
+
+    pub fn modulus() -> u32 {
+        DYN_MOD.load(Ordering::Relaxed)
+    }
+
+    fn barrett() -> Barrett {
+        Barrett {
+            m: DYN_MOD.load(Ordering::Relaxed),
+            im: DYN_IM.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns `x` as a `DynModInt` without reducing it. `x` must be less than the modulus.
+    pub fn raw(x: u32) -> Self {
+        debug_assert!(x < Self::modulus());
+        Self(x)
+    }
+
+    pub fn new(x: u64) -> Self {
+        Self((x % Self::modulus() as u64) as u32)
+    }
+}
+
+impl Mul for DynModInt {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self(Self::barrett().mul(self.0, rhs.0))
+    }
+}
+
+macro_rules! impl_modint {
+    ([$($gen:tt)*] $ty:ty) => {
+        impl<$($gen)*> $ty {
+            pub fn zero() -> Self {
+                Self::raw(0)
+            }
+
+            pub fn one() -> Self {
+                Self::new(1)
+            }
+
+            /// Returns the representative in `0..modulus()`.
+            pub const fn val(self) -> u32 {
+                self.0
+            }
+
+            /// Computes `self ** exp` in `O(lg exp)` time.
+            pub fn pow(self, mut exp: u64) -> Self {
+                let (mut base, mut out) = (self, Self::one());
+                while exp > 0 {
+                    if exp & 1 != 0 {
+                        out *= base;
+                    }
+                    base *= base;
+                    exp >>= 1;
+                }
+                out
+            }
+
+            /// Returns the multiplicative inverse, or `None` if `self` is not coprime with the modulus.
+            pub fn checked_inv(self) -> Option<Self> {
+                super::modinv(self.0, Self::modulus()).map(Self::raw)
+            }
+
+            /// Returns the multiplicative inverse. A runtime error will occur if it does not exist.
+            pub fn inv(self) -> Self {
+                self.checked_inv().expect("not invertible")
+            }
+        }
+
+        impl_modint!(@from_unsigned [$($gen)*] $ty; u8, u16, u32, u64, u128, usize);
+        impl_modint!(@from_signed [$($gen)*] $ty; i8, i16, i32, i64, i128, isize);
+
+        impl<$($gen)*> From<$ty> for u32 {
+            fn from(x: $ty) -> u32 {
+                x.0
+            }
+        }
+
+        impl<$($gen)*> Add for $ty {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                let x = self.0 + rhs.0;
+                Self::raw(if x >= Self::modulus() { x - Self::modulus() } else { x })
+            }
+        }
+
+        impl<$($gen)*> Sub for $ty {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                let (x, borrow) = self.0.overflowing_sub(rhs.0);
+                Self::raw(if borrow { x.wrapping_add(Self::modulus()) } else { x })
+            }
+        }
+
+        impl<$($gen)*> Div for $ty {
+            type Output = Self;
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn div(self, rhs: Self) -> Self {
+                self * rhs.inv()
+            }
+        }
+
+        impl<$($gen)*> Neg for $ty {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self::zero() - self
+            }
+        }
+
+        impl_modint!(@assign [$($gen)*] $ty; AddAssign add_assign +, SubAssign sub_assign -, MulAssign mul_assign *, DivAssign div_assign /);
+
+        impl<$($gen)*> Sum for $ty {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), |a, b| a + b)
+            }
+        }
+
+        impl<$($gen)*> Product for $ty {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::one(), |a, b| a * b)
+            }
+        }
+
+        impl<$($gen)*> fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl<$($gen)*> fmt::Debug for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        /// Reads a possibly negative integer and reduces it.
+        impl<$($gen)*> Readable for $ty {
+            fn read(reader: &mut impl ReaderTrait) -> Self {
+                reader.i128().into()
+            }
+        }
+
+        impl<const N: usize, $($gen)*> Print<$ty> for Writer<N> {
+            fn print(&mut self, x: $ty) {
+                self.print(x.0);
+            }
+            fn println(&mut self, x: $ty) {
+                self.println(x.0);
+            }
+        }
+    };
+    (@from_unsigned [$($gen:tt)*] $ty:ty;) => {};
+    (@from_unsigned [$($gen:tt)*] $ty:ty; $u:ty $(, $rest:ty)*) => {
+        impl<$($gen)*> From<$u> for $ty {
+            fn from(x: $u) -> Self {
+                Self::raw((x as u128 % Self::modulus() as u128) as u32)
+            }
+        }
+        impl_modint!(@from_unsigned [$($gen)*] $ty; $($rest),*);
+    };
+    (@from_signed [$($gen:tt)*] $ty:ty;) => {};
+    (@from_signed [$($gen:tt)*] $ty:ty; $i:ty $(, $rest:ty)*) => {
+        impl<$($gen)*> From<$i> for $ty {
+            fn from(x: $i) -> Self {
+                Self::raw((x as i128).rem_euclid(Self::modulus() as i128) as u32)
+            }
+        }
+        impl_modint!(@from_signed [$($gen)*] $ty; $($rest),*);
+    };
+    (@assign [$($gen:tt)*] $ty:ty;) => {};
+    (@assign [$($gen:tt)*] $ty:ty; $tr:ident $f:ident $op:tt $(, $($rest:tt)*)?) => {
+        impl<$($gen)*> $tr for $ty {
+            fn $f(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+        impl_modint!(@assign [$($gen)*] $ty; $($($rest)*)?);
+    };
+}
+
+impl_modint!([const M: u32] ModInt<M>);
+impl_modint!([] DynModInt);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+
+    type Mint = ModInt998244353;
+
+    #[test]
+    fn arithmetic() {
+        let a = Mint::from(-1i32);
+        assert_eq!(998244352, a.val());
+        assert_eq!(Mint::zero(), a + Mint::one());
+        assert_eq!(Mint::one(), a * a);
+        assert_eq!(Mint::new(3), Mint::new(5) - Mint::new(2));
+        assert_eq!(Mint::new(998244351), Mint::new(2) - Mint::new(4));
+        assert_eq!(Mint::new(7), Mint::new(7) / Mint::new(3) * Mint::new(3));
+        assert_eq!(Mint::one(), Mint::new(3).pow(998244352));
+        assert_eq!(Mint::new(1 << 20), Mint::new(2).pow(20));
+        let f: Mint = (1..=10u32).map(Mint::from).product();
+        assert_eq!(3628800, f.val());
+        assert_eq!(Mint::new(55), (1..=10u64).map(Mint::new).sum());
+        assert_eq!(Mint::from(u64::MAX), Mint::from(u64::MAX as u128));
+        assert_eq!(None, ModInt::<12>::new(8).checked_inv());
+        assert_eq!(
+            Some(ModInt::<12>::new(5)),
+            ModInt::<12>::new(5).checked_inv()
+        );
+    }
+
+    #[test]
+    fn barrett_matches_naive() {
+        let mut rng = TestRng::new(1);
+        for m in [1, 2, 3, 1_000_000_007, (1 << 31) - 1] {
+            let b = Barrett::new(m);
+            for _ in 0..1000 {
+                let (x, y) = (rng.below(m as u64) as u32, rng.below(m as u64) as u32);
+                assert_eq!((x as u64 * y as u64 % m as u64) as u32, b.mul(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn dyn_mod_int() {
+        DynModInt::set_modulus(1_000_000_007);
+        let a = DynModInt::from(-2i64);
+        assert_eq!(1_000_000_005, a.val());
+        assert_eq!(DynModInt::new(4), a * a);
+        assert_eq!(DynModInt::one(), DynModInt::new(5) / DynModInt::new(5));
+        assert_eq!(DynModInt::one(), DynModInt::new(2).pow(1_000_000_006));
+    }
+}
@@ -0,0 +1,287 @@
+use crate::platform::io::{Print, Readable, ReaderTrait, Writer};
+use core::fmt;
+use core::iter::{Product, Sum};
+use core::ops::*;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use super::miller_rabin::{OddMont, M};
+
+/// An integer modulo the compile-time odd constant `P`, stored in Montgomery form with `R = 2^64`.
+///
+/// It has the same interface as `ModInt`, but for 64-bit moduli, where multiplication would
+/// otherwise need a slow 128-bit division. The arithmetic is that of [`OddMont`], which
+/// Miller–Rabin and Pollard's rho also use.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct MontModInt<const P: u64>(u64);
+
+impl<const P: u64> MontModInt<P> {
+    const MONT: OddMont<u64> = {
+        assert!(P & 1 == 1, "modulus must be odd");
+        OddMont::<u64>::new(P)
+    };
+
+    fn mont() -> OddMont<u64> {
+        Self::MONT
+    }
+
+    pub const fn modulus() -> u64 {
+        P
+    }
+
+    pub const fn new(x: u64) -> Self {
+        Self(Self::MONT.to_mont(x % P).v)
+    }
+}
+
+static DYN_MONT: [AtomicU64; 4] = {
+    let m = OddMont::<u64>::new(998_244_353);
+    [
+        AtomicU64::new(m.n),
+        AtomicU64::new(m.ni),
+        AtomicU64::new(m.r2),
+        AtomicU64::new(m.r1),
+    ]
+};
+
+/// An integer modulo an odd modulus chosen at runtime with
+/// [`DynMontModInt::set_modulus`], stored in Montgomery form.
+///
+/// The modulus is global and defaults to 998244353, as for `DynModInt`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct DynMontModInt(u64);
+
+impl DynMontModInt {
+    /// Sets the modulus shared by all `DynMontModInt` values.
+    pub fn set_modulus(m: u64) {
+        assert!(m & 1 == 1, "modulus must be odd");
+        let mont = OddMont::<u64>::new(m);
+        DYN_MONT[0].store(mont.n, Ordering::Relaxed);
+        DYN_MONT[1].store(mont.ni, Ordering::Relaxed);
+        DYN_MONT[2].store(mont.r2, Ordering::Relaxed);
+        DYN_MONT[3].store(mont.r1, Ordering::Relaxed);
+    }
+
+    fn mont() -> OddMont<u64> {
+        OddMont {
+            n: DYN_MONT[0].load(Ordering::Relaxed),
+            ni: DYN_MONT[1].load(Ordering::Relaxed),
+            r2: DYN_MONT[2].load(Ordering::Relaxed),
+            r1: DYN_MONT[3].load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn modulus() -> u64 {
+        DYN_MONT[0].load(Ordering::Relaxed)
+    }
+
+    pub fn new(x: u64) -> Self {
+        let mont = Self::mont();
+        Self(mont.to_mont(x % mont.n).v)
+    }
+}
+
+macro_rules! impl_mont_modint {
+    ([$($gen:tt)*] $ty:ty) => {
+        impl<$($gen)*> $ty {
+            pub fn zero() -> Self {
+                Self(0)
+            }
+
+            pub fn one() -> Self {
+                Self(Self::mont().one().v)
+            }
+
+            /// Returns the representative in `0..modulus()`.
+            pub fn val(self) -> u64 {
+                Self::mont().from_mont(M { v: self.0 })
+            }
+
+            /// Computes `self ** exp` in `O(lg exp)` time.
+            pub fn pow(self, mut exp: u64) -> Self {
+                let (mut base, mut out) = (self, Self::one());
+                while exp > 0 {
+                    if exp & 1 != 0 {
+                        out *= base;
+                    }
+                    base *= base;
+                    exp >>= 1;
+                }
+                out
+            }
+
+            /// Returns the multiplicative inverse, or `None` if `self` is not coprime with the modulus.
+            pub fn checked_inv(self) -> Option<Self> {
+                super::modinv(self.val(), Self::modulus()).map(Self::new)
+            }
+
+            /// Returns the multiplicative inverse. A runtime error will occur if it does not exist.
+            pub fn inv(self) -> Self {
+                self.checked_inv().expect("not invertible")
+            }
+        }
+
+        impl_mont_modint!(@from_unsigned [$($gen)*] $ty; u8, u16, u32, u64, u128, usize);
+        impl_mont_modint!(@from_signed [$($gen)*] $ty; i8, i16, i32, i64, i128, isize);
+
+        impl<$($gen)*> Add for $ty {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Self(Self::mont().add(M { v: self.0 }, M { v: rhs.0 }).v)
+            }
+        }
+
+        impl<$($gen)*> Sub for $ty {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self(Self::mont().sub(M { v: self.0 }, M { v: rhs.0 }).v)
+            }
+        }
+
+        impl<$($gen)*> Mul for $ty {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                Self(Self::mont().mul(M { v: self.0 }, M { v: rhs.0 }).v)
+            }
+        }
+
+        impl<$($gen)*> Div for $ty {
+            type Output = Self;
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn div(self, rhs: Self) -> Self {
+                self * rhs.inv()
+            }
+        }
+
+        impl<$($gen)*> Neg for $ty {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self::zero() - self
+            }
+        }
+
+        impl_mont_modint!(@assign [$($gen)*] $ty; AddAssign add_assign +, SubAssign sub_assign -, MulAssign mul_assign *, DivAssign div_assign /);
+
+        impl<$($gen)*> Sum for $ty {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), |a, b| a + b)
+            }
+        }
+
+        impl<$($gen)*> Product for $ty {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::one(), |a, b| a * b)
+            }
+        }
+
+        impl<$($gen)*> fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.val().fmt(f)
+            }
+        }
+
+        impl<$($gen)*> fmt::Debug for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.val().fmt(f)
+            }
+        }
+
+        /// Reads a possibly negative integer and reduces it.
+        impl<$($gen)*> Readable for $ty {
+            fn read(reader: &mut impl ReaderTrait) -> Self {
+                reader.i128().into()
+            }
+        }
+
+        impl<const N: usize, $($gen)*> Print<$ty> for Writer<N> {
+            fn print(&mut self, x: $ty) {
+                self.print(x.val());
+            }
+            fn println(&mut self, x: $ty) {
+                self.println(x.val());
+            }
+        }
+    };
+    (@from_unsigned [$($gen:tt)*] $ty:ty;) => {};
+    (@from_unsigned [$($gen:tt)*] $ty:ty; $u:ty $(, $rest:ty)*) => {
+        impl<$($gen)*> From<$u> for $ty {
+            fn from(x: $u) -> Self {
+                Self::new((x as u128 % Self::modulus() as u128) as u64)
+            }
+        }
+        impl_mont_modint!(@from_unsigned [$($gen)*] $ty; $($rest),*);
+    };
+    (@from_signed [$($gen:tt)*] $ty:ty;) => {};
+    (@from_signed [$($gen:tt)*] $ty:ty; $i:ty $(, $rest:ty)*) => {
+        impl<$($gen)*> From<$i> for $ty {
+            fn from(x: $i) -> Self {
+                Self::new((x as i128).rem_euclid(Self::modulus() as i128) as u64)
+            }
+        }
+        impl_mont_modint!(@from_signed [$($gen)*] $ty; $($rest),*);
+    };
+    (@assign [$($gen:tt)*] $ty:ty;) => {};
+    (@assign [$($gen:tt)*] $ty:ty; $tr:ident $f:ident $op:tt $(, $($rest:tt)*)?) => {
+        impl<$($gen)*> $tr for $ty {
+            fn $f(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+        impl_mont_modint!(@assign [$($gen)*] $ty; $($($rest)*)?);
+    };
+}
+
+impl_mont_modint!([const P: u64] MontModInt<P>);
+impl_mont_modint!([] DynMontModInt);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::TestRng;
+
+    #[test]
+    fn montgomery_matches_naive() {
+        let mut rng = TestRng::new(1);
+        for m in [
+            1,
+            3,
+            998_244_353,
+            4_611_686_018_427_387_847,
+            (1 << 63) - 1,
+            u64::MAX,
+        ] {
+            let mont = OddMont::<u64>::new(m);
+            for _ in 0..1000 {
+                let (x, y) = (rng.below(m), rng.below(m));
+                let (xm, ym) = (mont.to_mont(x), mont.to_mont(y));
+                let z = mont.from_mont(mont.mul(xm, ym));
+                assert_eq!((x as u128 * y as u128 % m as u128) as u64, z);
+                assert_eq!(x, mont.from_mont(xm));
+                let sum = (x as u128 + y as u128) % m as u128;
+                assert_eq!(sum as u64, mont.from_mont(mont.add(xm, ym)));
+                let diff = (x as u128 + m as u128 - y as u128) % m as u128;
+                assert_eq!(diff as u64, mont.from_mont(mont.sub(xm, ym)));
+            }
+        }
+    }
+
+    #[test]
+    fn mont_mod_int() {
+        type Mint = MontModInt<4_611_686_018_427_387_847>;
+        let a = Mint::from(-1i32);
+        assert_eq!(4_611_686_018_427_387_846, a.val());
+        assert_eq!(Mint::one(), a * a);
+        assert_eq!(Mint::new(7), Mint::new(7) / Mint::new(3) * Mint::new(3));
+        assert_eq!(Mint::new(1 << 40), Mint::new(2).pow(40));
+        assert_eq!(Mint::new(55), (1..=10u64).map(Mint::new).sum());
+        DynMontModInt::set_modulus(1_000_000_007);
+        let b = DynMontModInt::from(-2i64);
+        assert_eq!(1_000_000_005, b.val());
+        assert_eq!(DynMontModInt::new(4), b * b);
+        assert_eq!(
+            DynMontModInt::one(),
+            DynMontModInt::new(2).pow(1_000_000_006)
+        );
+    }
+}
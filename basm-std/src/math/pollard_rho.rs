@@ -1,8 +1,9 @@
 // This is a Rust port of the implementation originally written in C++ by wider93.
 
 use crate::math::{
-    gcd, is_prime_u64,
+    gcd, is_prime_u128, is_prime_u64,
     miller_rabin::{OddMont, M},
+    modmul::modmul128,
 };
 use alloc::{vec, vec::Vec};
 
@@ -107,6 +108,108 @@ pub fn factorize(mut n: u64) -> Vec<u64> {
     v
 }
 
+/// Returns the prime factorization of `n` as `(prime, exponent)` pairs in increasing order of prime.
+/// Returns an empty `Vec` for `n = 1`.
+///
+/// This function will panic if `n` is zero.
+pub fn factorize_grouped(n: u64) -> Vec<(u64, u32)> {
+    assert!(n != 0);
+    group(factorize(n))
+}
+
+fn group<T: PartialEq + Copy>(v: Vec<T>) -> Vec<(T, u32)> {
+    let mut out: Vec<(T, u32)> = Vec::new();
+    for p in v {
+        match out.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => out.push((p, 1)),
+        }
+    }
+    out
+}
+
+/// Finds a nontrivial factor of the odd composite `n` with Brent's variant of Pollard's rho,
+/// batching the gcds over `STEP` iterations.
+fn brent_u128(n: u128) -> u128 {
+    const STEP: u32 = 128;
+    let f = |x: u128, c: u128| {
+        let y = modmul128(x, x, n);
+        if y >= n - c {
+            y - (n - c)
+        } else {
+            y + c
+        }
+    };
+    for c in 1.. {
+        let (mut x, mut y, mut ys) = (0, 2, 2);
+        let (mut q, mut g, mut r) = (1, 1, 1);
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y, c);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..STEP.min(r - k) {
+                    y = f(y, c);
+                    q = modmul128(q, x.abs_diff(y), n);
+                }
+                g = gcd(q, n);
+                k += STEP;
+            }
+            r <<= 1;
+        }
+        if g == n {
+            loop {
+                ys = f(ys, c);
+                g = gcd(x.abs_diff(ys), n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+/// Returns the prime factorization of `n` as `(prime, exponent)` pairs in increasing order of prime.
+/// Cofactors that fit in `u64` go through [`factorize`]; larger ones use Brent's rho with 128-bit
+/// modular multiplication, which is practical while the second largest prime factor stays below
+/// about `2^50`. Returns an empty `Vec` for `n = 1`.
+///
+/// This function will panic if `n` is zero.
+pub fn factorize_u128(mut n: u128) -> Vec<(u128, u32)> {
+    assert!(n != 0);
+    let mut v = Vec::new();
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        while n % p == 0 {
+            v.push(p);
+            n /= p;
+        }
+    }
+    let mut q = vec![n];
+    while let Some(t) = q.pop() {
+        if t == 1 {
+            continue;
+        }
+        if t <= u64::MAX as u128 {
+            v.extend(factorize(t as u64).into_iter().map(|p| p as u128));
+        } else if is_prime_u128(t) {
+            v.push(t);
+        } else {
+            let a = brent_u128(t);
+            q.push(a);
+            q.push(t / a);
+        }
+    }
+    v.sort_unstable();
+    group(v)
+}
+
 mod test {
     #[cfg(test)]
     use super::*;
@@ -133,4 +236,45 @@ mod test {
             factorize(4611686018427387903)
         );
     }
+
+    #[test]
+    fn check_factorize_grouped() {
+        assert_eq!(Vec::<(u64, u32)>::new(), factorize_grouped(1));
+        assert_eq!(vec![(2, 2), (3, 1)], factorize_grouped(12));
+        assert_eq!(vec![(2, 63)], factorize_grouped(1 << 63));
+        assert_eq!(
+            vec![(3, 2), (13, 1), (179, 1), (271, 1), (1381, 1), (2423, 1)],
+            factorize_grouped(18991325453139)
+        );
+        assert_eq!(
+            vec![(4294967291, 2)],
+            factorize_grouped(4294967291 * 4294967291)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn factorize_u128_rejects_zero() {
+        factorize_u128(0);
+    }
+
+    #[test]
+    fn check_factorize_u128() {
+        assert_eq!(Vec::<(u128, u32)>::new(), factorize_u128(1));
+        assert_eq!(vec![(2, 100)], factorize_u128(1 << 100));
+        assert_eq!(
+            vec![(18446744073709551629, 1)],
+            factorize_u128(18446744073709551629)
+        );
+        assert_eq!(
+            vec![(1000000007, 1), (1000000009, 1), (18446744073709551557, 1)],
+            factorize_u128(1000000007 * 1000000009 * 18446744073709551557)
+        );
+        assert_eq!(
+            vec![(3, 1), (998244353, 2), (18446744073709551557, 1)],
+            factorize_u128(3 * 998244353 * 998244353 * 18446744073709551557)
+        );
+        let (p, q) = (1099511627791u128, 1125899906842679u128);
+        assert_eq!(vec![(p, 1), (q, 1)], factorize_u128(p * q));
+    }
 }
@@ -227,6 +227,201 @@ impl Default for LinearSieve {
     }
 }
 
+/// Number of odd integers handled per segment of [`Sieve`], sized so that a segment stays in L2.
+const SEGMENT: usize = 1 << 16;
+
+/// A fixed-range segmented sieve for `[0, n]` with `n < 2^32`, meant for limits up to around `10^8`.
+///
+/// Primality is bit-packed over odd integers (`n / 16` bytes), and the smallest prime factor of
+/// an odd composite, which never exceeds `2^16`, is stored as a `u16` (`n` bytes). Euler's phi
+/// (`4n` bytes) and the Mobius function (`n` bytes) are computed only when requested.
+/// The sieve itself runs one cache-sized segment at a time.
+pub struct Sieve {
+    n: usize,
+    bits: Vec<u64>,
+    primes: Vec<u32>,
+    spf: Vec<u16>,
+    phi: Vec<u32>,
+    mu: Vec<i8>,
+}
+
+impl Sieve {
+    /// Sieves primes up to `n`.
+    pub fn new(n: usize) -> Self {
+        Self::build(n, false, false)
+    }
+
+    /// Sieves primes and smallest prime factors up to `n`.
+    pub fn with_spf(n: usize) -> Self {
+        Self::build(n, true, false)
+    }
+
+    /// Sieves primes, smallest prime factors, Euler's phi and the Mobius function up to `n`.
+    pub fn with_all(n: usize) -> Self {
+        Self::build(n, true, true)
+    }
+
+    fn build(n: usize, spf: bool, multiplicative: bool) -> Self {
+        assert!(n <= u32::MAX as usize);
+        let r = n.isqrt();
+        let mut small = vec![true; r + 1];
+        let mut base = vec![];
+        for i in 2..=r {
+            if small[i] {
+                base.push(i);
+                for j in (i * i..=r).step_by(i) {
+                    small[j] = false;
+                }
+            }
+        }
+        let odd_count = n.div_ceil(2);
+        let mut out = Self {
+            n,
+            bits: vec![0; odd_count.div_ceil(64)],
+            primes: if n >= 2 { vec![2] } else { vec![] },
+            spf: if spf { vec![0; odd_count] } else { vec![] },
+            phi: vec![],
+            mu: vec![],
+        };
+        // `next[k]` is the index `j` of the next odd multiple `2j + 1` of the k-th odd base prime.
+        let odd_base = &base[base.len().min(1)..];
+        let mut next: Vec<usize> = odd_base.iter().map(|&p| p * p / 2).collect();
+        let mut seg = vec![0u8; SEGMENT];
+        for lo in (0..odd_count).step_by(SEGMENT) {
+            let hi = (lo + SEGMENT).min(odd_count);
+            let seg = &mut seg[..hi - lo];
+            seg.fill(1);
+            for (&p, j) in odd_base.iter().zip(next.iter_mut()) {
+                while *j < hi {
+                    if seg[*j - lo] != 0 {
+                        seg[*j - lo] = 0;
+                        if spf {
+                            out.spf[*j] = p as u16;
+                        }
+                    }
+                    *j += p;
+                }
+            }
+            if lo == 0 {
+                seg[0] = 0;
+            }
+            for (i, _) in seg.iter().enumerate().filter(|(_, &b)| b != 0) {
+                let j = lo + i;
+                out.bits[j / 64] |= 1 << (j % 64);
+                out.primes.push((2 * j + 1) as u32);
+            }
+        }
+        if multiplicative {
+            out.fill_multiplicative();
+        }
+        out
+    }
+
+    /// Computes phi and mu in increasing order from the values at `x / p`, where `p` is the
+    /// smallest prime factor of `x`: `x / p` is still divisible by `p` exactly when `p^2 | x`.
+    fn fill_multiplicative(&mut self) {
+        let n = self.n;
+        let mut phi = vec![0; n + 1];
+        let mut mu = vec![0; n + 1];
+        if n >= 1 {
+            phi[1] = 1;
+            mu[1] = 1;
+        }
+        let spf = |x: usize| match x & 1 {
+            0 => 2,
+            _ => match self.spf[x / 2] {
+                0 => x,
+                p => p as usize,
+            },
+        };
+        for x in 2..=n {
+            let p = spf(x);
+            let y = x / p;
+            let (v, m) = if y == 1 {
+                (p as u32 - 1, -1)
+            } else if spf(y) == p {
+                (phi[y] * p as u32, 0)
+            } else {
+                (phi[y] * (p as u32 - 1), -mu[y])
+            };
+            phi[x] = v;
+            mu[x] = m;
+        }
+        self.phi = phi;
+        self.mu = mu;
+    }
+
+    /// Returns the upper bound `n` of the sieved range.
+    pub fn limit(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the primes up to `n` in ascending order.
+    pub fn primes(&self) -> &[u32] {
+        &self.primes
+    }
+
+    /// Returns true if and only if `x` is a prime number. Requires `x <= n`.
+    pub fn is_prime(&self, x: usize) -> bool {
+        assert!(x <= self.n);
+        if x & 1 == 0 {
+            x == 2
+        } else {
+            self.bits[x / 128] >> (x / 2 % 64) & 1 != 0
+        }
+    }
+
+    /// Returns the smallest prime factor of `x`, or `x` itself if `x <= 1`.
+    /// Requires `x <= n` and a sieve built with [`Sieve::with_spf`] or [`Sieve::with_all`].
+    pub fn smallest_prime_factor(&self, x: usize) -> usize {
+        assert!(
+            !self.spf.is_empty() || self.n == 0,
+            "smallest prime factors were not sieved"
+        );
+        assert!(x <= self.n);
+        if x & 1 == 0 {
+            if x == 0 {
+                0
+            } else {
+                2
+            }
+        } else {
+            match self.spf[x / 2] {
+                0 => x,
+                p => p as usize,
+            }
+        }
+    }
+
+    /// Returns the prime factorization of `x >= 1` as `(prime, exponent)` pairs in ascending order.
+    /// Requires the smallest prime factors to be sieved.
+    pub fn factorize(&self, mut x: usize) -> Vec<(usize, u32)> {
+        assert!(x >= 1);
+        let mut out: Vec<(usize, u32)> = vec![];
+        while x > 1 {
+            let p = self.smallest_prime_factor(x);
+            x /= p;
+            match out.last_mut() {
+                Some((q, e)) if *q == p => *e += 1,
+                _ => out.push((p, 1)),
+            }
+        }
+        out
+    }
+
+    /// Euler's totient function. Requires a sieve built with [`Sieve::with_all`].
+    pub fn phi(&self, x: usize) -> usize {
+        assert!(!self.phi.is_empty(), "phi was not sieved");
+        self.phi[x] as usize
+    }
+
+    /// Mobius function. Requires a sieve built with [`Sieve::with_all`].
+    pub fn mu(&self, x: usize) -> i8 {
+        assert!(!self.mu.is_empty(), "mu was not sieved");
+        self.mu[x]
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -282,4 +477,30 @@ mod test {
         assert_eq!(vec![1, 2, 3, 4, 6, 12], ls.divisors(12));
         assert_eq!(vec![1, 2, 4, 5, 10, 20, 25, 50, 100], ls.divisors(100));
     }
+
+    #[test]
+    fn check_sieve() {
+        let n = 300_001;
+        let sv = Sieve::with_all(n);
+        let mut ls = LinearSieve::new();
+        let mut count = 0;
+        for x in 1..=n {
+            assert_eq!(ls.is_prime(x), sv.is_prime(x));
+            assert_eq!(ls.smallest_prime_factor(x), sv.smallest_prime_factor(x));
+            assert_eq!(ls.phi(x), sv.phi(x));
+            assert_eq!(ls.mu(x), sv.mu(x));
+            count += sv.is_prime(x) as usize;
+        }
+        assert_eq!(count, sv.primes().len());
+        assert_eq!(vec![(2, 2), (3, 1), (5, 2)], sv.factorize(300));
+        assert_eq!(vec![(299_993, 1)], sv.factorize(299_993));
+        for n in 0..40 {
+            let sv = Sieve::with_spf(n);
+            let expected: Vec<u32> = (0..=n as u32)
+                .filter(|&x| ls.is_prime(x as usize))
+                .collect();
+            assert_eq!(expected, sv.primes());
+        }
+        assert_eq!(664_579, Sieve::new(10_000_000).primes().len());
+    }
 }
@@ -0,0 +1,142 @@
+use alloc::alloc::{alloc_zeroed, dealloc, handle_alloc_error};
+use core::alloc::Layout;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+
+/// Types that are valid when all of their bytes are zero, so that [`alloc_aligned`] can hand out
+/// zeroed memory without initializing each element.
+///
+/// # Safety
+///
+/// The all-zero bit pattern must be a valid value of the type.
+pub unsafe trait Zeroable: Copy {}
+
+macro_rules! impl_zeroable {
+    ($($t:ty),*) => {
+        $(unsafe impl Zeroable for $t {})*
+    };
+}
+impl_zeroable!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {}
+
+/// A heap buffer of `len` elements whose start is aligned to a given alignment (e.g., 32 or 64 bytes
+/// for SIMD loads and stores). It is returned by [`alloc_aligned`] and derefs to `[T]`.
+pub struct AlignedBuf<T: Zeroable> {
+    ptr: NonNull<T>,
+    len: usize,
+    align: usize,
+}
+
+/// Allocates a buffer of `len` zero-initialized elements aligned to `align` bytes
+/// (or the alignment of `T`, whichever is larger). A runtime error will occur if `align` is not a power of two.
+///
+/// Example: `let mut buf = alloc_aligned::<f64>(n, 64);`
+pub fn alloc_aligned<T: Zeroable>(len: usize, align: usize) -> AlignedBuf<T> {
+    let layout = AlignedBuf::<T>::layout(len, align);
+    let ptr = if layout.size() == 0 {
+        layout.align() as *mut T
+    } else {
+        let ptr = unsafe { alloc_zeroed(layout) };
+        if ptr.is_null() {
+            handle_alloc_error(layout);
+        }
+        ptr as *mut T
+    };
+    AlignedBuf {
+        ptr: unsafe { NonNull::new_unchecked(ptr) },
+        len,
+        align: layout.align(),
+    }
+}
+
+impl<T: Zeroable> AlignedBuf<T> {
+    fn layout(len: usize, align: usize) -> Layout {
+        Layout::array::<T>(len)
+            .and_then(|l| l.align_to(align))
+            .expect("invalid length or alignment")
+    }
+
+    /// Returns the alignment of the buffer in bytes.
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+}
+
+impl<T: Zeroable> Deref for AlignedBuf<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Zeroable> DerefMut for AlignedBuf<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Zeroable> Clone for AlignedBuf<T> {
+    fn clone(&self) -> Self {
+        let mut buf = alloc_aligned(self.len, self.align);
+        buf.copy_from_slice(self);
+        buf
+    }
+}
+
+impl<T: Zeroable + core::fmt::Debug> core::fmt::Debug for AlignedBuf<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: Zeroable> Drop for AlignedBuf<T> {
+    fn drop(&mut self) {
+        let layout = Self::layout(self.len, self.align);
+        if layout.size() != 0 {
+            unsafe { dealloc(self.ptr.as_ptr() as *mut u8, layout) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn buffers_are_aligned_and_zeroed() {
+        for align in [1, 16, 32, 64, 4096] {
+            for len in [0, 1, 7, 1000] {
+                let buf = alloc_aligned::<u32>(len, align);
+                assert_eq!(len, buf.len());
+                assert_eq!(0, buf.as_ptr() as usize % align);
+                assert!(buf.iter().all(|&x| x == 0));
+            }
+        }
+        assert_eq!(8, alloc_aligned::<u64>(3, 2).align());
+    }
+
+    #[test]
+    fn clone_keeps_contents_and_alignment() {
+        let mut buf = alloc_aligned::<f64>(100, 64);
+        for (i, x) in buf.iter_mut().enumerate() {
+            *x = i as f64;
+        }
+        let copy = buf.clone();
+        assert_eq!(0, copy.as_ptr() as usize % 64);
+        assert_eq!(&buf[..], &copy[..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn non_power_of_two_alignment_panics() {
+        alloc_aligned::<u8>(10, 48);
+    }
+}
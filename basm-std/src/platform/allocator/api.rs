@@ -0,0 +1,79 @@
+#[cfg(feature = "nightly")]
+pub use alloc::alloc::Global;
+#[cfg(feature = "nightly")]
+pub use core::alloc::Allocator;
+
+#[cfg(feature = "nightly")]
+pub(crate) type Vec<T, A> = alloc::vec::Vec<T, A>;
+
+#[cfg(feature = "nightly")]
+pub(crate) fn vec_with_capacity_in<T, A: Allocator>(capacity: usize, alloc: A) -> Vec<T, A> {
+    Vec::with_capacity_in(capacity, alloc)
+}
+
+#[cfg(feature = "nightly")]
+pub(crate) fn vec_from_vec<T>(v: alloc::vec::Vec<T>) -> Vec<T, Global> {
+    v
+}
+
+#[cfg(not(feature = "nightly"))]
+pub trait Allocator {}
+
+#[cfg(not(feature = "nightly"))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Global;
+
+#[cfg(not(feature = "nightly"))]
+impl Allocator for Global {}
+#[cfg(not(feature = "nightly"))]
+impl<A: Allocator + ?Sized> Allocator for &A {}
+#[cfg(not(feature = "nightly"))]
+impl Allocator for super::Arena {}
+#[cfg(not(feature = "nightly"))]
+impl<B: core::alloc::GlobalAlloc> Allocator for super::SizeClasses<B> {}
+
+/// A `Vec` that carries the allocator type without using it.
+#[cfg(not(feature = "nightly"))]
+pub(crate) struct Vec<T, A>(alloc::vec::Vec<T>, core::marker::PhantomData<A>);
+
+#[cfg(not(feature = "nightly"))]
+impl<T, A> core::ops::Deref for Vec<T, A> {
+    type Target = alloc::vec::Vec<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<T, A> core::ops::DerefMut for Vec<T, A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<T: Clone, A: Clone> Clone for Vec<T, A> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), core::marker::PhantomData)
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+pub(crate) fn vec_with_capacity_in<T, A: Allocator>(capacity: usize, _alloc: A) -> Vec<T, A> {
+    Vec(
+        alloc::vec::Vec::with_capacity(capacity),
+        core::marker::PhantomData,
+    )
+}
+
+#[cfg(not(feature = "nightly"))]
+pub(crate) fn vec_from_vec<T>(v: alloc::vec::Vec<T>) -> Vec<T, Global> {
+    Vec(v, core::marker::PhantomData)
+}
+
+/// Returns a vector of `n` clones of `elem` allocated with `alloc`.
+pub(crate) fn vec_from_elem_in<T: Clone, A: Allocator>(elem: T, n: usize, alloc: A) -> Vec<T, A> {
+    let mut v = vec_with_capacity_in(n, alloc);
+    v.resize(n, elem);
+    v
+}
@@ -0,0 +1,370 @@
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::Cell;
+use core::ptr::{self, NonNull};
+
+const MIN_CHUNK_SIZE: usize = 64 * 1024;
+const CHUNK_ALIGN: usize = 16;
+
+/// Placed at the beginning of each chunk; the chunks form a singly linked list from the newest one.
+#[repr(C)]
+struct ChunkHeader {
+    prev: *mut ChunkHeader,
+    size: usize,
+}
+
+const HEADER_SIZE: usize = core::mem::size_of::<ChunkHeader>();
+
+#[cfg(not(any(test, feature = "std")))]
+unsafe fn chunk_alloc(size: usize) -> *mut u8 {
+    super::PTR_ALLOC(size, CHUNK_ALIGN)
+}
+#[cfg(not(any(test, feature = "std")))]
+unsafe fn chunk_dealloc(ptr: *mut u8, size: usize) {
+    super::PTR_DEALLOC(ptr, size, CHUNK_ALIGN)
+}
+#[cfg(any(test, feature = "std"))]
+unsafe fn chunk_alloc(size: usize) -> *mut u8 {
+    std::alloc::System.alloc(Layout::from_size_align_unchecked(size, CHUNK_ALIGN))
+}
+#[cfg(any(test, feature = "std"))]
+unsafe fn chunk_dealloc(ptr: *mut u8, size: usize) {
+    std::alloc::System.dealloc(ptr, Layout::from_size_align_unchecked(size, CHUNK_ALIGN))
+}
+
+/// A bump allocator that hands out memory from large chunks and frees everything at once.
+///
+/// Allocation only advances a pointer, and deallocation is a no-op unless the most recent allocation
+/// is freed (or resized), in which case the space is reclaimed. [`Arena::reset`] discards all allocations
+/// and keeps the largest chunk for reuse, so a solution with many test cases can allocate freely
+/// in each test case without returning memory piece by piece.
+///
+/// There are two ways to use an arena:
+/// * As an explicit handle: allocate values with [`Arena::alloc`] and friends.
+///   The values live as long as the arena is borrowed, and their destructors are never run.
+/// * As the global allocator: enable the `arena` feature, and then every heap allocation is served
+///   by [`global_arena()`](super::global_arena). Call `unsafe { global_arena().reset_unchecked() }`
+///   between test cases once every collection from the previous test case is gone.
+///
+/// Since `Arena` implements [`GlobalAlloc`], it can also be used as `#[global_allocator]` directly.
+/// Note that the inherent [`Arena::alloc`] shadows [`GlobalAlloc::alloc`] in method calls.
+pub struct Arena {
+    chunk: Cell<*mut ChunkHeader>,
+    ptr: Cell<usize>,
+    end: Cell<usize>,
+    last: Cell<usize>,
+}
+
+// SAFETY: `chunk`, `ptr`, `end` and `last` are plain `Cell`s updated without synchronization.
+// basm runs solutions on a single thread, so the arena is never accessed concurrently.
+unsafe impl Sync for Arena {}
+
+impl Arena {
+    pub const fn new() -> Self {
+        Self {
+            chunk: Cell::new(ptr::null_mut()),
+            ptr: Cell::new(0),
+            end: Cell::new(0),
+            last: Cell::new(0),
+        }
+    }
+
+    /// Moves `val` into the arena and returns a reference to it.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc<T>(&self, val: T) -> &mut T {
+        let ptr = self.alloc_or_abort(Layout::new::<T>()) as *mut T;
+        unsafe {
+            ptr.write(val);
+            &mut *ptr
+        }
+    }
+
+    /// Allocates a slice of length `len` whose `i`-th element is `f(i)`.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_fill_with<T>(&self, len: usize, mut f: impl FnMut(usize) -> T) -> &mut [T] {
+        let layout = Layout::array::<T>(len).unwrap();
+        let ptr = self.alloc_or_abort(layout) as *mut T;
+        for i in 0..len {
+            unsafe { ptr.add(i).write(f(i)) };
+        }
+        unsafe { core::slice::from_raw_parts_mut(ptr, len) }
+    }
+
+    /// Allocates a copy of `src`.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_copy<T: Copy>(&self, src: &[T]) -> &mut [T] {
+        let layout = Layout::for_value(src);
+        let ptr = self.alloc_or_abort(layout) as *mut T;
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), ptr, src.len());
+            core::slice::from_raw_parts_mut(ptr, src.len())
+        }
+    }
+
+    /// Returns the number of bytes handed out from the current chunk.
+    pub fn used_bytes(&self) -> usize {
+        let chunk = self.chunk.get();
+        if chunk.is_null() {
+            0
+        } else {
+            self.ptr.get() - (chunk as usize + HEADER_SIZE)
+        }
+    }
+
+    /// Discards all allocations. The largest chunk is kept for the subsequent allocations
+    /// and the others are returned to the system.
+    pub fn reset(&mut self) {
+        unsafe { self.reset_unchecked() }
+    }
+
+    /// Same as [`Arena::reset`], but takes `&self` so that it can be applied to a static arena
+    /// (e.g., the global allocator).
+    ///
+    /// # Safety
+    ///
+    /// No memory allocated from this arena may be accessed (or deallocated) afterwards.
+    pub unsafe fn reset_unchecked(&self) {
+        let chunk = self.chunk.get();
+        if chunk.is_null() {
+            return;
+        }
+        let mut prev = (*chunk).prev;
+        while !prev.is_null() {
+            let next = (*prev).prev;
+            chunk_dealloc(prev as *mut u8, (*prev).size);
+            prev = next;
+        }
+        (*chunk).prev = ptr::null_mut();
+        self.ptr.set(chunk as usize + HEADER_SIZE);
+        self.last.set(0);
+    }
+
+    fn alloc_or_abort(&self, layout: Layout) -> *mut u8 {
+        if layout.size() == 0 {
+            return NonNull::<u8>::dangling()
+                .as_ptr()
+                .wrapping_add(layout.align() - 1);
+        }
+        let ptr = unsafe { self.alloc_layout(layout) };
+        if ptr.is_null() {
+            alloc::alloc::handle_alloc_error(layout);
+        }
+        ptr
+    }
+
+    #[inline(always)]
+    unsafe fn alloc_layout(&self, layout: Layout) -> *mut u8 {
+        let start = (self.ptr.get() + layout.align() - 1) & !(layout.align() - 1);
+        match start.checked_add(layout.size()) {
+            Some(new_ptr) if new_ptr <= self.end.get() => {
+                self.ptr.set(new_ptr);
+                self.last.set(start);
+                start as *mut u8
+            }
+            _ => self.alloc_slow(layout),
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    unsafe fn alloc_slow(&self, layout: Layout) -> *mut u8 {
+        let chunk = self.chunk.get();
+        let prev_size = if chunk.is_null() { 0 } else { (*chunk).size };
+        let Some(required) = layout
+            .size()
+            .checked_add(layout.align().max(CHUNK_ALIGN) + HEADER_SIZE)
+        else {
+            return ptr::null_mut();
+        };
+        let size = (prev_size * 2).max(MIN_CHUNK_SIZE).max(required);
+        let size = (size + CHUNK_ALIGN - 1) & !(CHUNK_ALIGN - 1);
+        let new_chunk = chunk_alloc(size) as *mut ChunkHeader;
+        if new_chunk.is_null() {
+            return ptr::null_mut();
+        }
+        new_chunk.write(ChunkHeader { prev: chunk, size });
+        self.chunk.set(new_chunk);
+        self.ptr.set(new_chunk as usize + HEADER_SIZE);
+        self.end.set(new_chunk as usize + size);
+        self.alloc_layout(layout)
+    }
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        let mut chunk = self.chunk.get();
+        while !chunk.is_null() {
+            unsafe {
+                let prev = (*chunk).prev;
+                chunk_dealloc(chunk as *mut u8, (*chunk).size);
+                chunk = prev;
+            }
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for Arena {
+    #[inline(always)]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.alloc_layout(layout)
+    }
+    #[inline(always)]
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        if ptr as usize == self.last.get() {
+            self.ptr.set(ptr as usize);
+        }
+    }
+    #[inline(always)]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        /* grow or shrink the most recent allocation in place */
+        if ptr as usize == self.last.get() && new_size <= self.end.get() - ptr as usize {
+            self.ptr.set(ptr as usize + new_size);
+            return ptr;
+        }
+        let new_ptr =
+            self.alloc_layout(Layout::from_size_align_unchecked(new_size, layout.align()));
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+        }
+        new_ptr
+    }
+}
+
+#[cfg(feature = "nightly")]
+unsafe impl core::alloc::Allocator for Arena {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, core::alloc::AllocError> {
+        let ptr = if layout.size() == 0 {
+            NonNull::<u8>::dangling()
+                .as_ptr()
+                .wrapping_add(layout.align() - 1)
+        } else {
+            unsafe { self.alloc_layout(layout) }
+        };
+        NonNull::new(ptr)
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, layout.size()))
+            .ok_or(core::alloc::AllocError)
+    }
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            GlobalAlloc::dealloc(self, ptr.as_ptr(), layout)
+        }
+    }
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, core::alloc::AllocError> {
+        /* keep the most recent allocation in place when possible */
+        if old_layout.size() == 0 || old_layout.align() != new_layout.align() {
+            let new_ptr = self.allocate(new_layout)?;
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr() as *mut u8, old_layout.size());
+            return Ok(new_ptr);
+        }
+        let new_ptr = GlobalAlloc::realloc(self, ptr.as_ptr(), old_layout, new_layout.size());
+        NonNull::new(new_ptr)
+            .map(|ptr| NonNull::slice_from_raw_parts(ptr, new_layout.size()))
+            .ok_or(core::alloc::AllocError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn alloc_returns_distinct_aligned_values() {
+        let arena = Arena::new();
+        let a = arena.alloc(1u8);
+        let b = arena.alloc(2u64);
+        let c = arena.alloc([3u32; 5]);
+        assert_eq!(0, b as *mut u64 as usize % core::mem::align_of::<u64>());
+        assert_eq!((1, 2, [3; 5]), (*a, *b, *c));
+        *a = 4;
+        assert_eq!((4, 2), (*a, *b));
+    }
+
+    #[test]
+    fn alloc_slice_fills_and_copies() {
+        let arena = Arena::new();
+        let s = arena.alloc_slice_fill_with(5, |i| i * i);
+        assert_eq!(&[0, 1, 4, 9, 16], s);
+        let t = arena.alloc_slice_copy(&s[1..4]);
+        assert_eq!(&[1, 4, 9], t);
+        assert!(arena.alloc_slice_fill_with(0, |_| 0u64).is_empty());
+        let _ = arena.alloc(());
+    }
+
+    #[test]
+    fn large_allocations_span_chunks() {
+        let arena = Arena::new();
+        let mut v = Vec::new();
+        for i in 0..100 {
+            let s = arena.alloc_slice_fill_with(1000 + i, |j| (i * j) as u32);
+            v.push(s);
+        }
+        let s = arena.alloc_slice_fill_with(1 << 20, |j| j as u8);
+        assert_eq!(255, s[255]);
+        for (i, s) in v.iter().enumerate() {
+            assert_eq!(1000 + i, s.len());
+            assert_eq!((i * 999) as u32, s[999]);
+        }
+    }
+
+    #[test]
+    fn reset_reuses_memory() {
+        let mut arena = Arena::new();
+        for _ in 0..3 {
+            arena.alloc_slice_fill_with(100_000, |i| i as u32);
+        }
+        arena.reset();
+        assert_eq!(0, arena.used_bytes());
+        let chunk = arena.chunk.get();
+        for round in 0..10 {
+            let s = arena.alloc_slice_fill_with(100_000, |i| (i + round) as u32);
+            assert_eq!(99_999 + round as u32, s[99_999]);
+            arena.reset();
+        }
+        assert_eq!(chunk, arena.chunk.get());
+        assert!(unsafe { (*chunk).prev.is_null() });
+    }
+
+    #[test]
+    fn global_alloc_reclaims_and_grows_last_allocation() {
+        let arena = Arena::new();
+        unsafe {
+            let layout = Layout::from_size_align(16, 8).unwrap();
+            let p = GlobalAlloc::alloc(&arena, layout);
+            p.write_bytes(7, 16);
+            let q = arena.realloc(p, layout, 64);
+            assert_eq!(p, q);
+            assert_eq!(64, arena.used_bytes());
+            let r = GlobalAlloc::alloc(&arena, layout);
+            let q2 = arena.realloc(q, Layout::from_size_align(64, 8).unwrap(), 128);
+            assert_ne!(q, q2);
+            assert_eq!(7, *q2.add(15));
+            arena.dealloc(q2, Layout::from_size_align(128, 8).unwrap());
+            assert_eq!(r.add(16) as usize, arena.ptr.get());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn vec_in_arena_grows_in_place() {
+        let arena = Arena::new();
+        let mut v = Vec::with_capacity_in(4, &arena);
+        let ptr = v.as_ptr();
+        v.extend(0..1000u32);
+        assert_eq!(ptr, v.as_ptr());
+        assert_eq!(4000, arena.used_bytes());
+        let w: Vec<u8, _> = Vec::with_capacity_in(0, &arena);
+        drop(w);
+        assert_eq!(499500, v.iter().sum::<u32>());
+    }
+}
@@ -0,0 +1,77 @@
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::Cell;
+use core::ptr;
+
+/// Chunks are requested in multiples of this size.
+const CHUNK_SIZE: usize = 4 << 20;
+
+/// A minimal allocator for size-critical submissions, selected by the `bump-alloc` feature.
+///
+/// Each allocation only advances a pointer within a chunk obtained with `PTR_ALLOC_ZEROED`,
+/// and nothing is ever freed, except that the most recent allocation can grow in place.
+/// The pointer never moves backwards, so memory past it has never been handed out and is still zero;
+/// hence `alloc_zeroed` costs the same as `alloc`.
+/// On Linux, the chunks are mapped directly with mmap and dlmalloc is left out of the binary.
+pub(super) struct Bump {
+    cur: Cell<usize>,
+    end: Cell<usize>,
+    last: Cell<usize>,
+}
+
+// SAFETY: `cur`, `end` and `last` are plain `Cell`s updated without synchronization.
+// basm runs solutions on a single thread, so the allocator is never accessed concurrently.
+unsafe impl Sync for Bump {}
+
+impl Bump {
+    pub(super) const fn new() -> Self {
+        Self {
+            cur: Cell::new(0),
+            end: Cell::new(0),
+            last: Cell::new(0),
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    unsafe fn refill(&self, layout: Layout) -> *mut u8 {
+        let size = (layout.size() + layout.align() + CHUNK_SIZE - 1) & !(CHUNK_SIZE - 1);
+        let chunk = super::PTR_ALLOC_ZEROED(size, 16);
+        if chunk.is_null() {
+            return chunk;
+        }
+        self.cur.set(chunk as usize);
+        self.end.set(chunk as usize + size);
+        self.alloc(layout)
+    }
+}
+
+unsafe impl GlobalAlloc for Bump {
+    #[inline(always)]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let start = (self.cur.get() + layout.align() - 1) & !(layout.align() - 1);
+        if start > self.end.get() || self.end.get() - start < layout.size() {
+            return self.refill(layout);
+        }
+        self.cur.set(start + layout.size());
+        self.last.set(start);
+        start as *mut u8
+    }
+    #[inline(always)]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.alloc(layout)
+    }
+    #[inline(always)]
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+    #[inline(always)]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if ptr as usize == self.last.get() && new_size <= self.end.get() - ptr as usize {
+            self.cur.set(self.cur.get().max(ptr as usize + new_size));
+            return ptr;
+        }
+        let new_ptr = self.alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+        }
+        new_ptr
+    }
+}
@@ -0,0 +1,214 @@
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::ptr;
+
+const CANARY: u64 = 0xb45a_c0de_b45a_c0de;
+const POISON: u8 = 0xdd;
+
+/// Placed right before each allocation; the live allocations form a doubly linked list.
+#[repr(C)]
+struct Header {
+    prev: *mut Header,
+    next: *mut Header,
+    size: usize,
+    canary: u64,
+}
+
+const HEADER_SIZE: usize = core::mem::size_of::<Header>();
+
+/// A wrapper allocator that catches heap corruption in debug builds.
+///
+/// Each allocation is surrounded by canaries: one in the header placed before it, and one right after it.
+/// The canaries are validated when the allocation is freed, and [`HeapCheck::check_all`] validates every
+/// live allocation (the `heap-check` feature calls it at exit). Freed memory is filled with `0xdd`,
+/// so a use after free tends to produce conspicuous values rather than plausible ones.
+/// When corruption is found, the address and the size of the allocation are reported and the process exits.
+///
+/// Each allocation takes at least 40 bytes more than requested.
+pub struct HeapCheck<B: GlobalAlloc> {
+    head: UnsafeCell<*mut Header>,
+    backend: B,
+}
+
+// SAFETY: the list of live allocations starting at `head` is linked and unlinked without synchronization.
+// basm runs solutions on a single thread, so it is never modified concurrently.
+unsafe impl<B: GlobalAlloc> Sync for HeapCheck<B> {}
+
+impl<B: GlobalAlloc> HeapCheck<B> {
+    pub const fn new(backend: B) -> Self {
+        Self {
+            head: UnsafeCell::new(ptr::null_mut()),
+            backend,
+        }
+    }
+
+    /// Returns the number of bytes before the user data, keeping the user data aligned.
+    fn prefix(layout: Layout) -> usize {
+        layout.align().max(HEADER_SIZE)
+    }
+
+    fn outer_layout(layout: Layout) -> Option<Layout> {
+        let size = Self::prefix(layout)
+            .checked_add(layout.size())?
+            .checked_add(8)?;
+        Layout::from_size_align(size, layout.align().max(core::mem::align_of::<Header>())).ok()
+    }
+
+    unsafe fn validate(header: *mut Header) {
+        let user = (header as *mut u8).add(HEADER_SIZE);
+        if (*header).canary != CANARY {
+            corrupted("the canary before an allocation is overwritten", user, None);
+        }
+        let size = (*header).size;
+        if (user.add(size) as *const u64).read_unaligned() != CANARY {
+            corrupted(
+                "the canary after an allocation is overwritten",
+                user,
+                Some(size),
+            );
+        }
+    }
+
+    /// Validates the canaries of every live allocation.
+    pub fn check_all(&self) {
+        let mut header = unsafe { *self.head.get() };
+        while !header.is_null() {
+            unsafe {
+                Self::validate(header);
+                header = (*header).next;
+            }
+        }
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn corrupted(what: &str, ptr: *mut u8, size: Option<usize>) -> ! {
+    #[cfg(all(not(any(test, feature = "std")), feature = "codegen"))]
+    {
+        use crate::collections::ArrayString;
+        use core::fmt::Write;
+        let mut msg = ArrayString::<192>::new();
+        let _ = write!(msg, "heap corruption: {} at {:p}", what, ptr);
+        if let Some(size) = size {
+            let _ = write!(msg, " ({} bytes)", size);
+        }
+        let _ = writeln!(msg);
+        crate::platform::services::write_stdio(2, msg.as_bytes());
+        unsafe { crate::platform::codegen::exit_with_failure() }
+    }
+    #[cfg(any(test, feature = "std", not(feature = "codegen")))]
+    panic!("heap corruption: {} at {:p} ({:?} bytes)", what, ptr, size)
+}
+
+unsafe impl<B: GlobalAlloc> GlobalAlloc for HeapCheck<B> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Some(outer) = Self::outer_layout(layout) else {
+            return ptr::null_mut();
+        };
+        let base = self.backend.alloc(outer);
+        if base.is_null() {
+            return base;
+        }
+        let user = base.add(Self::prefix(layout));
+        let header = user.sub(HEADER_SIZE) as *mut Header;
+        let head = &mut *self.head.get();
+        header.write(Header {
+            prev: ptr::null_mut(),
+            next: *head,
+            size: layout.size(),
+            canary: CANARY,
+        });
+        if !head.is_null() {
+            (**head).prev = header;
+        }
+        *head = header;
+        (user.add(layout.size()) as *mut u64).write_unaligned(CANARY);
+        user
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let header = ptr.sub(HEADER_SIZE) as *mut Header;
+        Self::validate(header);
+        if (*header).size != layout.size() {
+            corrupted(
+                "an allocation is freed with a wrong size",
+                ptr,
+                Some(layout.size()),
+            );
+        }
+        let Header { prev, next, .. } = header.read();
+        if prev.is_null() {
+            *self.head.get() = next;
+        } else {
+            (*prev).next = next;
+        }
+        if !next.is_null() {
+            (*next).prev = prev;
+        }
+        let outer = Self::outer_layout(layout).unwrap();
+        let base = ptr.sub(Self::prefix(layout));
+        ptr::write_bytes(base, POISON, outer.size());
+        self.backend.dealloc(base, outer);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn allocations_are_aligned_and_unlinked_on_free() {
+        let hc = HeapCheck::new(std::alloc::System);
+        unsafe {
+            let layouts = [(1, 1), (24, 8), (100, 64), (4096, 4096)];
+            let ptrs: Vec<*mut u8> = layouts
+                .iter()
+                .map(|&(size, align)| {
+                    let p = hc.alloc(Layout::from_size_align(size, align).unwrap());
+                    assert_eq!(0, p as usize % align);
+                    ptr::write_bytes(p, 0x11, size);
+                    p
+                })
+                .collect();
+            hc.check_all();
+            let layout = Layout::from_size_align(24, 8).unwrap();
+            hc.dealloc(ptrs[1], layout);
+            hc.check_all();
+            let p = hc.realloc(ptrs[0], Layout::from_size_align(1, 1).unwrap(), 1000);
+            assert_eq!(0x11, *p);
+            for (&p, &(size, align)) in
+                [p, ptrs[2], ptrs[3]]
+                    .iter()
+                    .zip(&[(1000, 1), (100, 64), (4096, 4096)])
+            {
+                hc.dealloc(p, Layout::from_size_align(size, align).unwrap());
+            }
+            assert!((*hc.head.get()).is_null());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "canary after")]
+    fn overrun_is_detected_on_free() {
+        let hc = HeapCheck::new(std::alloc::System);
+        unsafe {
+            let layout = Layout::from_size_align(10, 2).unwrap();
+            let p = hc.alloc(layout);
+            *p.add(10) = 0;
+            hc.dealloc(p, layout);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "canary before")]
+    fn underrun_is_detected_by_check_all() {
+        let hc = HeapCheck::new(std::alloc::System);
+        unsafe {
+            let p = hc.alloc(Layout::from_size_align(16, 8).unwrap()) as *mut u64;
+            let _ = hc.alloc(Layout::from_size_align(16, 8).unwrap());
+            *p.sub(1) = 0;
+        }
+        hc.check_all();
+    }
+}
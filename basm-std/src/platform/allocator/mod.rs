@@ -0,0 +1,252 @@
+mod aligned;
+/// Allocator parameters for the containers of this crate (e.g., `SegmentTree::new_in(n, &arena)`).
+///
+/// With the `nightly` feature, [`Allocator`](api::Allocator) and [`Global`](api::Global) are those of the unstable `allocator_api`,
+/// and [`Arena`] and [`SizeClasses`] implement it, so that one structure
+/// can be placed in an arena while the rest of the heap uses the global allocator.
+/// The standard containers accept them too (e.g., `Vec::new_in(&arena)`).
+///
+/// Without the `nightly` feature, these are placeholders so that the same code compiles:
+/// the allocator arguments are ignored and the global allocator is used.
+pub mod api;
+pub use aligned::{alloc_aligned, AlignedBuf, Zeroable};
+mod arena;
+pub use arena::Arena;
+#[cfg(all(not(any(test, feature = "std")), feature = "bump-alloc"))]
+mod bump;
+mod heap_check;
+pub use heap_check::HeapCheck;
+mod size_classes;
+pub use size_classes::SizeClasses;
+#[cfg(feature = "alloc-stats")]
+mod stats;
+#[cfg(feature = "alloc-stats")]
+pub use stats::{report, stats, AllocStats};
+#[cfg(all(not(any(test, feature = "std")), feature = "oom-diagnostics"))]
+mod oom;
+
+#[cfg(not(any(test, feature = "std")))]
+use core::alloc::GlobalAlloc;
+
+#[cfg(all(
+    feature = "bump-alloc",
+    any(feature = "arena", feature = "fixed-heap", feature = "prefault-heap")
+))]
+compile_error!(
+    "The `bump-alloc` feature cannot be combined with `arena`, `fixed-heap`, or `prefault-heap`."
+);
+
+#[cfg(not(any(test, feature = "std")))]
+static mut PTR_ALLOC: unsafe fn(usize, usize) -> *mut u8 = super::services::alloc;
+#[cfg(not(any(test, feature = "std")))]
+static mut PTR_ALLOC_ZEROED: unsafe fn(usize, usize) -> *mut u8 = super::services::alloc_zeroed;
+#[cfg(not(any(test, feature = "std")))]
+static mut PTR_DEALLOC: unsafe fn(*mut u8, usize, usize) = super::services::dealloc;
+#[cfg(not(any(test, feature = "std")))]
+static mut PTR_REALLOC: unsafe fn(*mut u8, usize, usize, usize) -> *mut u8 =
+    super::services::realloc;
+
+#[cfg(not(any(test, feature = "std")))]
+pub unsafe fn install_malloc_impl(
+    ptr_alloc: unsafe fn(usize, usize) -> *mut u8,
+    ptr_alloc_zeroed: unsafe fn(usize, usize) -> *mut u8,
+    ptr_dealloc: unsafe fn(*mut u8, usize, usize),
+    ptr_realloc: unsafe fn(*mut u8, usize, usize, usize) -> *mut u8,
+) {
+    PTR_ALLOC = ptr_alloc;
+    PTR_ALLOC_ZEROED = ptr_alloc_zeroed;
+    PTR_DEALLOC = ptr_dealloc;
+    PTR_REALLOC = ptr_realloc;
+}
+
+/// The global allocator of basm, installed by `#[global_allocator]` in `basm/src/bin/lang_items.rs`.
+///
+/// It forwards to the malloc implementation chosen by the platform (see [`install_malloc_impl`]),
+/// or to [`global_arena()`] if the `arena` feature is enabled,
+/// or to a minimal bump allocator that never frees if the `bump-alloc` feature is enabled.
+/// With the `alloc-stats` feature, it also keeps track of the heap usage (see [`stats()`]),
+/// and with the `size-classes` feature, small requests are served by [`SizeClasses`] in front of it.
+/// With the `heap-check` feature, every allocation is guarded by canaries (see [`HeapCheck`]).
+/// With the `oom-diagnostics` feature, a failed request is reported to stderr before the process exits.
+#[cfg(not(any(test, feature = "std")))]
+pub struct Allocator;
+
+#[cfg(not(any(test, feature = "std")))]
+struct Backend;
+
+#[cfg(all(not(any(test, feature = "std")), not(feature = "size-classes")))]
+type Pooled = Backend;
+#[cfg(all(not(any(test, feature = "std")), feature = "size-classes"))]
+type Pooled = SizeClasses<Backend>;
+
+#[cfg(not(any(test, feature = "std")))]
+const fn pooled() -> Pooled {
+    #[cfg(not(feature = "size-classes"))]
+    {
+        Backend
+    }
+    #[cfg(feature = "size-classes")]
+    {
+        SizeClasses::new(Backend)
+    }
+}
+
+#[cfg(all(not(any(test, feature = "std")), not(feature = "heap-check")))]
+static FRONTEND: Pooled = pooled();
+#[cfg(all(not(any(test, feature = "std")), feature = "heap-check"))]
+static FRONTEND: HeapCheck<Pooled> = HeapCheck::new(pooled());
+
+/// Validates the canaries of every live heap allocation (see [`HeapCheck`]).
+/// This is called at exit when the `heap-check` feature is enabled.
+#[cfg(all(not(any(test, feature = "std")), feature = "heap-check"))]
+pub fn check_heap() {
+    FRONTEND.check_all();
+}
+
+#[cfg(not(any(test, feature = "std")))]
+unsafe impl GlobalAlloc for Allocator {
+    #[inline(always)]
+    unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
+        let ptr = FRONTEND.alloc(layout);
+        #[cfg(feature = "oom-diagnostics")]
+        if ptr.is_null() {
+            oom::out_of_memory(layout);
+        }
+        #[cfg(feature = "alloc-stats")]
+        if !ptr.is_null() {
+            stats::update(|s| s.on_alloc(layout.size()));
+        }
+        ptr
+    }
+    #[inline(always)]
+    unsafe fn alloc_zeroed(&self, layout: core::alloc::Layout) -> *mut u8 {
+        let ptr = FRONTEND.alloc_zeroed(layout);
+        #[cfg(feature = "oom-diagnostics")]
+        if ptr.is_null() {
+            oom::out_of_memory(layout);
+        }
+        #[cfg(feature = "alloc-stats")]
+        if !ptr.is_null() {
+            stats::update(|s| s.on_alloc(layout.size()));
+        }
+        ptr
+    }
+    #[inline(always)]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: core::alloc::Layout) {
+        #[cfg(feature = "alloc-stats")]
+        stats::update(|s| s.on_dealloc(layout.size()));
+        FRONTEND.dealloc(ptr, layout)
+    }
+    #[inline(always)]
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: core::alloc::Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        let new_ptr = FRONTEND.realloc(ptr, layout, new_size);
+        #[cfg(feature = "oom-diagnostics")]
+        if new_ptr.is_null() {
+            oom::out_of_memory(core::alloc::Layout::from_size_align_unchecked(
+                new_size,
+                layout.align(),
+            ));
+        }
+        #[cfg(feature = "alloc-stats")]
+        if !new_ptr.is_null() {
+            stats::update(|s| s.on_realloc(layout.size(), new_size));
+        }
+        new_ptr
+    }
+}
+
+/// The arena that serves every heap allocation when the `arena` feature is enabled.
+/// Its chunks are obtained from the malloc implementation of the platform.
+#[cfg(feature = "arena")]
+static GLOBAL_ARENA: Arena = Arena::new();
+
+/// Returns the arena behind the global allocator. Call `unsafe { global_arena().reset_unchecked() }`
+/// to discard all heap allocations at once, e.g., between test cases.
+///
+/// With `std` (and in tests), the system allocator remains the global allocator, and this arena is left unused.
+#[cfg(feature = "arena")]
+pub fn global_arena() -> &'static Arena {
+    &GLOBAL_ARENA
+}
+
+#[cfg(all(not(any(test, feature = "std")), feature = "arena"))]
+unsafe impl GlobalAlloc for Backend {
+    #[inline(always)]
+    unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
+        GlobalAlloc::alloc(&GLOBAL_ARENA, layout)
+    }
+    #[inline(always)]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: core::alloc::Layout) {
+        GLOBAL_ARENA.dealloc(ptr, layout)
+    }
+    #[inline(always)]
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: core::alloc::Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        GLOBAL_ARENA.realloc(ptr, layout, new_size)
+    }
+}
+
+#[cfg(all(not(any(test, feature = "std")), feature = "bump-alloc"))]
+static BUMP: bump::Bump = bump::Bump::new();
+
+#[cfg(all(not(any(test, feature = "std")), feature = "bump-alloc"))]
+unsafe impl GlobalAlloc for Backend {
+    #[inline(always)]
+    unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
+        BUMP.alloc(layout)
+    }
+    #[inline(always)]
+    unsafe fn alloc_zeroed(&self, layout: core::alloc::Layout) -> *mut u8 {
+        BUMP.alloc_zeroed(layout)
+    }
+    #[inline(always)]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: core::alloc::Layout) {
+        BUMP.dealloc(ptr, layout)
+    }
+    #[inline(always)]
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: core::alloc::Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        BUMP.realloc(ptr, layout, new_size)
+    }
+}
+
+#[cfg(all(
+    not(any(test, feature = "std")),
+    not(any(feature = "arena", feature = "bump-alloc"))
+))]
+unsafe impl GlobalAlloc for Backend {
+    #[inline(always)]
+    unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
+        PTR_ALLOC(layout.size(), layout.align())
+    }
+    #[inline(always)]
+    unsafe fn alloc_zeroed(&self, layout: core::alloc::Layout) -> *mut u8 {
+        PTR_ALLOC_ZEROED(layout.size(), layout.align())
+    }
+    #[inline(always)]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: core::alloc::Layout) {
+        PTR_DEALLOC(ptr, layout.size(), layout.align())
+    }
+    #[inline(always)]
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: core::alloc::Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        PTR_REALLOC(ptr, layout.size(), layout.align(), new_size)
+    }
+}
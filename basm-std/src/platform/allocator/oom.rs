@@ -0,0 +1,29 @@
+use crate::collections::ArrayString;
+use crate::platform::services::write_stdio;
+use core::alloc::Layout;
+use core::fmt::Write;
+
+/// Reports the failed request (and the heap usage with `alloc-stats`) to stderr and terminates the process.
+///
+/// Without the `oom-diagnostics` feature, a failed allocation ends up in the panic handler,
+/// which is `unreachable_unchecked` in submission builds.
+/// Nothing is allocated here, since the heap is exhausted.
+#[cold]
+#[inline(never)]
+pub(super) fn out_of_memory(layout: Layout) -> ! {
+    let mut msg = ArrayString::<192>::new();
+    let _ = writeln!(
+        msg,
+        "memory allocation of {} bytes (align {}) failed",
+        layout.size(),
+        layout.align()
+    );
+    write_stdio(2, msg.as_bytes());
+    #[cfg(feature = "alloc-stats")]
+    {
+        msg.clear();
+        let _ = writeln!(msg, "{}", super::stats());
+        write_stdio(2, msg.as_bytes());
+    }
+    unsafe { crate::platform::codegen::exit_with_failure() }
+}
@@ -0,0 +1,204 @@
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::ptr;
+
+const CLASS_SIZE: usize = 16;
+const NUM_CLASSES: usize = 32;
+/// Requests larger than this go directly to the backend.
+const MAX_SMALL_SIZE: usize = CLASS_SIZE * NUM_CLASSES;
+const SLAB_SIZE: usize = 16 * 1024;
+
+struct FreeNode {
+    next: *mut FreeNode,
+}
+
+/// A front-end allocator with segregated free lists for small requests (up to 512 bytes in 16-byte classes).
+///
+/// Small allocations are popped from and pushed back to the free list of their class in O(1),
+/// which suits node-heavy structures (linked lists, tries, treaps) that allocate and free many nodes
+/// of the same size. Each list is refilled by carving a 16 KiB slab from the backend.
+/// Larger (or over-aligned) requests are forwarded to the backend.
+///
+/// The memory held in the free lists is never returned to the backend, so a solution that frees
+/// many small nodes of one size and then allocates nodes of another size may use more memory.
+pub struct SizeClasses<B: GlobalAlloc> {
+    heads: UnsafeCell<[*mut FreeNode; NUM_CLASSES]>,
+    backend: B,
+}
+
+// SAFETY: the free lists in `heads` are pushed and popped without synchronization.
+// basm runs solutions on a single thread, so they are never modified concurrently.
+unsafe impl<B: GlobalAlloc> Sync for SizeClasses<B> {}
+
+impl<B: GlobalAlloc> SizeClasses<B> {
+    pub const fn new(backend: B) -> Self {
+        Self {
+            heads: UnsafeCell::new([ptr::null_mut(); NUM_CLASSES]),
+            backend,
+        }
+    }
+
+    #[inline(always)]
+    fn class_of(layout: Layout) -> Option<usize> {
+        if layout.size() <= MAX_SMALL_SIZE && layout.align() <= CLASS_SIZE {
+            Some(layout.size().saturating_sub(1) / CLASS_SIZE)
+        } else {
+            None
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    unsafe fn refill(&self, class: usize) -> *mut u8 {
+        let slab = self
+            .backend
+            .alloc(Layout::from_size_align_unchecked(SLAB_SIZE, CLASS_SIZE));
+        if slab.is_null() {
+            return slab;
+        }
+        /* hand out the first block and thread the rest into the free list */
+        let size = (class + 1) * CLASS_SIZE;
+        let count = SLAB_SIZE / size;
+        let heads = &mut *self.heads.get();
+        for i in (1..count).rev() {
+            let node = slab.add(i * size) as *mut FreeNode;
+            (*node).next = heads[class];
+            heads[class] = node;
+        }
+        slab
+    }
+}
+
+unsafe impl<B: GlobalAlloc> GlobalAlloc for SizeClasses<B> {
+    #[inline(always)]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Some(class) = Self::class_of(layout) else {
+            return self.backend.alloc(layout);
+        };
+        let heads = &mut *self.heads.get();
+        let node = heads[class];
+        if node.is_null() {
+            return self.refill(class);
+        }
+        heads[class] = (*node).next;
+        node as *mut u8
+    }
+    #[inline(always)]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if Self::class_of(layout).is_none() {
+            return self.backend.alloc_zeroed(layout);
+        }
+        let ptr = self.alloc(layout);
+        if !ptr.is_null() {
+            ptr::write_bytes(ptr, 0, layout.size());
+        }
+        ptr
+    }
+    #[inline(always)]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let Some(class) = Self::class_of(layout) else {
+            return self.backend.dealloc(ptr, layout);
+        };
+        let heads = &mut *self.heads.get();
+        let node = ptr as *mut FreeNode;
+        (*node).next = heads[class];
+        heads[class] = node;
+    }
+    #[inline(always)]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        match (Self::class_of(layout), Self::class_of(new_layout)) {
+            (None, None) => self.backend.realloc(ptr, layout, new_size),
+            (Some(old), Some(new)) if old == new => ptr,
+            _ => {
+                let new_ptr = self.alloc(new_layout);
+                if !new_ptr.is_null() {
+                    ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+                    self.dealloc(ptr, layout);
+                }
+                new_ptr
+            }
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+unsafe impl<B: GlobalAlloc> core::alloc::Allocator for SizeClasses<B> {
+    fn allocate(&self, layout: Layout) -> Result<ptr::NonNull<[u8]>, core::alloc::AllocError> {
+        let ptr = if layout.size() == 0 {
+            ptr::NonNull::<u8>::dangling()
+                .as_ptr()
+                .wrapping_add(layout.align() - 1)
+        } else {
+            unsafe { self.alloc(layout) }
+        };
+        ptr::NonNull::new(ptr)
+            .map(|ptr| ptr::NonNull::slice_from_raw_parts(ptr, layout.size()))
+            .ok_or(core::alloc::AllocError)
+    }
+    unsafe fn deallocate(&self, ptr: ptr::NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            self.dealloc(ptr.as_ptr(), layout)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn freed_blocks_are_reused_in_lifo_order() {
+        let sc = SizeClasses::new(std::alloc::System);
+        unsafe {
+            let layout = Layout::from_size_align(24, 8).unwrap();
+            let a = sc.alloc(layout);
+            let b = sc.alloc(layout);
+            assert_eq!(32, b as usize - a as usize);
+            sc.dealloc(a, layout);
+            sc.dealloc(b, layout);
+            assert_eq!(b, sc.alloc(Layout::from_size_align(17, 1).unwrap()));
+            assert_eq!(a, sc.alloc(layout));
+        }
+    }
+
+    #[test]
+    fn blocks_do_not_overlap_across_slabs() {
+        let sc = SizeClasses::new(std::alloc::System);
+        for size in [1, 16, 100, 511, 512] {
+            let layout = Layout::from_size_align(size, 1).unwrap();
+            let mut blocks: Vec<*mut u8> = (0..2000).map(|_| unsafe { sc.alloc(layout) }).collect();
+            for (i, &p) in blocks.iter().enumerate() {
+                unsafe { ptr::write_bytes(p, i as u8, size) };
+            }
+            for (i, &p) in blocks.iter().enumerate() {
+                assert_eq!(i as u8, unsafe { *p.add(size - 1) });
+            }
+            blocks.sort();
+            blocks.dedup();
+            assert_eq!(2000, blocks.len());
+        }
+    }
+
+    #[test]
+    fn realloc_moves_between_classes_and_backend() {
+        let sc = SizeClasses::new(std::alloc::System);
+        unsafe {
+            let layout = Layout::from_size_align(8, 8).unwrap();
+            let p = sc.alloc_zeroed(layout);
+            assert_eq!(0, *(p as *mut u64));
+            *(p as *mut u64) = 12345;
+            assert_eq!(p, sc.realloc(p, layout, 16));
+            let q = sc.realloc(p, Layout::from_size_align(16, 8).unwrap(), 4096);
+            assert_eq!(12345, *(q as *mut u64));
+            let r = sc.realloc(q, Layout::from_size_align(4096, 8).unwrap(), 100);
+            assert_eq!(12345, *(r as *mut u64));
+            sc.dealloc(r, Layout::from_size_align(100, 8).unwrap());
+            let big = Layout::from_size_align(64, 64).unwrap();
+            let s = sc.alloc(big);
+            assert_eq!(0, s as usize % 64);
+            sc.dealloc(s, big);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use core::cell::Cell;
+
+/// Heap usage recorded by the global allocator when the `alloc-stats` feature is enabled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Bytes currently allocated.
+    pub live_bytes: usize,
+    /// Maximum of `live_bytes` so far.
+    pub peak_bytes: usize,
+    /// Number of allocations (reallocations included).
+    pub alloc_count: usize,
+}
+
+impl AllocStats {
+    pub const fn new() -> Self {
+        Self {
+            live_bytes: 0,
+            peak_bytes: 0,
+            alloc_count: 0,
+        }
+    }
+
+    pub fn on_alloc(&mut self, size: usize) {
+        self.live_bytes += size;
+        self.peak_bytes = self.peak_bytes.max(self.live_bytes);
+        self.alloc_count += 1;
+    }
+
+    pub fn on_dealloc(&mut self, size: usize) {
+        self.live_bytes -= size;
+    }
+
+    pub fn on_realloc(&mut self, old_size: usize, new_size: usize) {
+        self.on_dealloc(old_size);
+        self.on_alloc(new_size);
+    }
+}
+
+impl core::fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        const MIB: usize = 1 << 20;
+        write!(
+            f,
+            "heap: {} bytes live, {} bytes peak ({}.{:02} MiB), {} allocations",
+            self.live_bytes,
+            self.peak_bytes,
+            self.peak_bytes / MIB,
+            self.peak_bytes % MIB * 100 / MIB,
+            self.alloc_count
+        )
+    }
+}
+
+struct GlobalStats(Cell<AllocStats>);
+
+// SAFETY: the counters in the `Cell` are updated without synchronization.
+// basm runs solutions on a single thread, so `STATS` is never accessed concurrently.
+unsafe impl Sync for GlobalStats {}
+
+static STATS: GlobalStats = GlobalStats(Cell::new(AllocStats::new()));
+
+#[cfg(not(any(test, feature = "std")))]
+#[inline(always)]
+pub(super) fn update(f: impl FnOnce(&mut AllocStats)) {
+    let mut s = STATS.0.get();
+    f(&mut s);
+    STATS.0.set(s);
+}
+
+/// Returns the heap usage so far. The final value is printed to stderr at exit (see [`report()`]).
+pub fn stats() -> AllocStats {
+    STATS.0.get()
+}
+
+/// Prints the heap usage so far to stderr.
+pub fn report() {
+    use alloc::string::ToString;
+    let msg = stats().to_string();
+    super::super::services::write_stdio(2, msg.as_bytes());
+    super::super::services::write_stdio(2, b"\n");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn stats_track_live_and_peak_bytes() {
+        let mut s = AllocStats::new();
+        s.on_alloc(100);
+        s.on_alloc(50);
+        s.on_dealloc(100);
+        s.on_realloc(50, 80);
+        assert_eq!(
+            AllocStats {
+                live_bytes: 80,
+                peak_bytes: 150,
+                alloc_count: 3
+            },
+            s
+        );
+    }
+
+    #[test]
+    fn stats_display_peak_in_mib() {
+        let mut s = AllocStats::new();
+        s.on_alloc(3 << 19);
+        s.on_dealloc(3 << 19);
+        assert_eq!(
+            "heap: 0 bytes live, 1572864 bytes peak (1.50 MiB), 1 allocations",
+            s.to_string()
+        );
+    }
+}
@@ -1,12 +1,15 @@
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), feature = "nightly"))]
 use core::arch::asm;
 
 use crate::platform;
-#[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+#[cfg(not(any(
+    target_arch = "wasm32",
+    all(target_arch = "aarch64", target_os = "macos")
+)))]
 use crate::platform::loader;
 
 /* We need to support multiple scenarios.
- *   1) Architectures: x86, x86-64
+ *   1) Architectures: x86, x86-64 (and AArch64 for local execution)
  *   2) Platforms for build: Windows, Linux
  *   3) Platforms on which the binary can run: Windows, Linux
  *   4) Running without the loader, running with the loader
@@ -50,9 +53,19 @@ use crate::platform::loader;
 compile_error!("The target architecture is not supported.");
 
 #[cfg(all(target_arch = "aarch64", feature = "submit"))]
-compile_error!("AArch64 (aarch64-apple-darwin) is only supported for local execution, not submission; use x86-64 to submit.");
+compile_error!("AArch64 (aarch64-apple-darwin, aarch64-pc-windows-msvc) is only supported for local execution, not submission; use x86-64 to submit.");
 
-#[cfg(all(target_arch = "x86_64", not(target_os = "windows")))]
+#[cfg(all(
+    not(feature = "nightly"),
+    not(all(target_arch = "x86_64", target_os = "linux"))
+))]
+compile_error!("Building without the `nightly` feature (i.e., with the stable toolchain) is only supported for x86_64-unknown-linux-gnu.");
+
+#[cfg(all(
+    target_arch = "x86_64",
+    not(target_os = "windows"),
+    feature = "nightly"
+))]
 #[no_mangle]
 #[naked]
 pub unsafe extern "win64" fn _basm_start() -> ! {
@@ -60,27 +73,29 @@ pub unsafe extern "win64" fn _basm_start() -> ! {
     //   on the 16-byte boundary BEFORE `call` instruction.
     // However, when called as the entrypoint by the Linux OS,
     //   RSP will be 16-byte aligned AFTER `call` instruction.
+    // When running with the loader, we return the exit status to the caller
+    //   and preserve the callee-saved registers (rbx).
     asm!(
         "clc",                              // CF=0 (running without loader) / CF=1 (running with loader)
+        "push   rbx",                       // rbx is callee-saved; this also aligns RSP when called
         "mov    rbx, rcx",                  // Save PLATFORM_DATA table
         "jnc    1f",
         "test   rbx, rbx",
         "jz     1f",
         "jmp    2f",
         "1:",
-        "sub    rsp, 72",                   // 16 + 72 + 8 = 96 = 16*6 -> stack alignment preserved
+        "sub    rsp, 72",                   // 8 + 72 + 16 = 96 = 16*6 -> stack alignment preserved
         "push   3",                         // env_flags = 3 (ENV_FLAGS_LINUX_STYLE_CHKSTK | ENV_FLAGS_NATIVE)
         "push   2",                         // env_id = 2 (ENV_ID_LINUX)
         "lea    rbx, [rsp]",                // rbx = PLATFORM_DATA table
         "2:",
-        "push   rcx",                       // short form of "sub rsp, 8"
         "lea    rdi, [rip + __ehdr_start]",
         "lea    rsi, [rip + _DYNAMIC]",
         "mov    QWORD PTR [rbx + 32], rdi", // overwrite ptr_alloc_rwx with in-memory ImageBase
         "call   {0}",
         "mov    rdi, rbx",
         "call   {1}",
-        "pop    rcx",                       // short form of "add rsp, 8"
+        "pop    rbx",                       // eax = exit status
         "ret",
         sym loader::amd64_elf::relocate,
         sym _start_rust,
@@ -88,18 +103,130 @@ pub unsafe extern "win64" fn _basm_start() -> ! {
     );
 }
 
+/* Naked functions are not available on the stable toolchain.
+ * Instead, we define the same entrypoint as above with global_asm!.
+ */
+#[cfg(all(
+    target_arch = "x86_64",
+    not(target_os = "windows"),
+    not(feature = "nightly")
+))]
+core::arch::global_asm!(
+    ".globl _basm_start",
+    ".type  _basm_start, @function",
+    ".p2align 4",
+    "_basm_start:",
+    "clc",                              // CF=0 (running without loader) / CF=1 (running with loader)
+    "push   rbx",
+    "mov    rbx, rcx",
+    "jnc    1f",
+    "test   rbx, rbx",
+    "jz     1f",
+    "jmp    2f",
+    "1:",
+    "sub    rsp, 72",
+    "push   3",                         // env_flags = 3 (ENV_FLAGS_LINUX_STYLE_CHKSTK | ENV_FLAGS_NATIVE)
+    "push   2",                         // env_id = 2 (ENV_ID_LINUX)
+    "lea    rbx, [rsp]",
+    "2:",
+    "lea    rdi, [rip + __ehdr_start]",
+    "lea    rsi, [rip + _DYNAMIC]",
+    "mov    QWORD PTR [rbx + 32], rdi", // overwrite ptr_alloc_rwx with in-memory ImageBase
+    "call   {0}",
+    "mov    rdi, rbx",
+    "call   {1}",
+    "pop    rbx",
+    "ret",
+    ".size  _basm_start, . - _basm_start",
+    sym loader::amd64_elf::relocate,
+    sym _start_rust,
+);
+
+/* Without the `nightly` feature, compiler_builtins is not built with the `mem` feature.
+ * Since we link without libc, we provide the memory routines that LLVM may emit calls to.
+ */
+#[cfg(all(target_arch = "x86_64", not(feature = "nightly")))]
+mod mem {
+    use core::arch::asm;
+
+    #[no_mangle]
+    pub unsafe extern "C" fn memcpy(dest: *mut u8, src: *const u8, n: usize) -> *mut u8 {
+        asm!(
+            "rep movsb",
+            inout("rcx") n => _,
+            inout("rdi") dest => _,
+            inout("rsi") src => _,
+            options(nostack, preserves_flags)
+        );
+        dest
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn memmove(dest: *mut u8, src: *const u8, n: usize) -> *mut u8 {
+        if (dest as usize).wrapping_sub(src as usize) >= n {
+            // Copying forwards is safe if dest does not start within [src, src + n)
+            memcpy(dest, src, n)
+        } else {
+            asm!(
+                "std",
+                "rep movsb",
+                "cld",
+                inout("rcx") n => _,
+                inout("rdi") dest.add(n - 1) => _,
+                inout("rsi") src.add(n - 1) => _,
+                options(nostack)
+            );
+            dest
+        }
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn memset(dest: *mut u8, c: i32, n: usize) -> *mut u8 {
+        asm!(
+            "rep stosb",
+            inout("rcx") n => _,
+            inout("rdi") dest => _,
+            in("al") c as u8,
+            options(nostack, preserves_flags)
+        );
+        dest
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn memcmp(s1: *const u8, s2: *const u8, n: usize) -> i32 {
+        let mut i = 0;
+        while i < n {
+            let (a, b) = (*s1.add(i), *s2.add(i));
+            if a != b {
+                return a as i32 - b as i32;
+            }
+            i += 1;
+        }
+        0
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn bcmp(s1: *const u8, s2: *const u8, n: usize) -> i32 {
+        memcmp(s1, s2, n)
+    }
+}
+
 #[cfg(target_os = "windows")]
 #[allow(non_snake_case)]
 #[link(name = "kernel32")]
-extern "win64" {
+extern "system" {
     fn LoadLibraryA(lpLibFileName: *const u8) -> usize;
     fn GetProcAddress(hModule: usize, lpProcName: *const u8) -> usize;
 }
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_arch = "x86_64", target_os = "windows"))]
 unsafe extern "sysv64" fn get_kernel32() -> usize {
     LoadLibraryA(b"KERNEL32\0".as_ptr())
 }
+#[cfg(all(target_arch = "aarch64", target_os = "windows"))]
+unsafe extern "C" fn get_kernel32() -> usize {
+    LoadLibraryA(b"KERNEL32\0".as_ptr())
+}
 
 #[cfg(all(target_os = "windows", target_env = "gnu"))]
 mod chkstk_gnu {
@@ -108,7 +235,7 @@ mod chkstk_gnu {
         pub fn ___chkstk();
     }
 }
-#[cfg(all(target_os = "windows", not(target_env = "gnu")))]
+#[cfg(all(target_arch = "x86_64", target_os = "windows", not(target_env = "gnu")))]
 mod chkstk_gnu {
     pub extern "C" fn ___chkstk_ms() {}
     pub extern "C" fn ___chkstk() {}
@@ -126,7 +253,8 @@ pub unsafe extern "win64" fn _basm_start() -> ! {
     asm!(
         "clc",                              // CF=0 (running without loader) / CF=1 (running with loader)
         "enter  64, 0",                     // 64 = 88 - 32 (tables) + 8 (alignment)
-        "mov    rbx, rcx",                  // save rcx as rbx is non-volatile (callee-saved)
+        "mov    QWORD PTR [rbp - 8], rbx",  // rbx is non-volatile (callee-saved); keep it in the alignment slot
+        "mov    rbx, rcx",                  // save rcx in rbx
         "jc     1f",
         "call   {3}",
         "lea    rdi, [rip+{4}]",
@@ -161,7 +289,8 @@ pub unsafe extern "win64" fn _basm_start() -> ! {
         "3:",
         "mov    rcx, rbx",
         "call   {1}",
-        "leave",
+        "mov    rbx, QWORD PTR [rbp - 8]",
+        "leave",                            // eax = exit status
         "ret",
         sym loader::amd64_pe::relocate,
         sym _start_rust,
@@ -249,10 +378,61 @@ pub extern "C" fn _basm_start() {
     _start_rust(&mut pd as *mut platform::services::PlatformData as usize);
 }
 
-#[cfg(target_arch = "aarch64")]
+#[cfg(all(target_arch = "aarch64", target_os = "windows"))]
 #[no_mangle]
 #[naked]
-#[repr(align(8))]
+pub unsafe extern "C" fn _basm_start() -> ! {
+    // AAPCS64 requires SP to be 16-byte aligned at all times,
+    //   and the Windows PE loader honors this when calling the entrypoint.
+    // The first instruction acts as the "clc" in the x86-64 version:
+    //   the loader replaces it with "mov x9, #1" and passes PLATFORM_DATA in x0.
+    asm!(
+        "mov    x9, #0",                    // x9=0 (running without loader) / x9=1 (running with loader)
+        "stp    x29, x30, [sp, #-32]!",
+        "mov    x29, sp",
+        "str    x19, [sp, #16]",            // x19 is callee-saved
+        "mov    x19, x0",                   // save PLATFORM_DATA table
+        "cbnz   x9, 1f",
+        "sub    sp, sp, #96",               // PLATFORM_DATA (88 bytes) + alignment
+        "mov    x19, sp",                   // x19 = PLATFORM_DATA table
+        "bl     {2}",
+        "adrp   x1, {3}",
+        "add    x1, x1, :lo12:{3}",
+        "mov    x2, #1",                    // env_id = 1 (ENV_ID_WINDOWS)
+        "str    x2, [x19, #0]",
+        "mov    x2, #2",                    // env_flags = 2 (ENV_FLAGS_NATIVE)
+        "str    x2, [x19, #8]",
+        "str    x0, [x19, #16]",            // handle to kernel32
+        "str    x1, [x19, #24]",            // GetProcAddress
+        "b      2f",
+        "1:",
+        "adrp   x0, __ImageBase",           // In-memory ImageBase (cf. Preferred ImageBase is set to 0x0 by static-pie-pe2bin.py)
+        "add    x0, x0, :lo12:__ImageBase",
+        "movz   w1, #0x5678",               // [replaced by static-pie-pe2bin.py] Offset of relocation table (relative to the in-memory ImageBase)
+        "movk   w1, #0x1234, lsl #16",
+        "movz   w2, #0x5678",               // [replaced by static-pie-pe2bin.py] Size of relocation table (relative to the in-memory ImageBase)
+        "movk   w2, #0x1234, lsl #16",
+        "str    x0, [x19, #32]",            // overwrite ptr_alloc_rwx with in-memory ImageBase
+        "bl     {0}",
+        "2:",
+        "mov    x0, x19",
+        "bl     {1}",
+        "mov    sp, x29",
+        "ldr    x19, [sp, #16]",
+        "ldp    x29, x30, [sp], #32",
+        "ret",
+        sym loader::aarch64_pe::relocate,
+        sym _start_rust,
+        sym get_kernel32,
+        sym GetProcAddress,
+        options(noreturn)
+    );
+}
+
+#[cfg(all(target_arch = "aarch64", target_os = "macos"))]
+#[no_mangle]
+#[naked]
+#[rustc_align(8)]
 pub unsafe extern "C" fn _basm_start() -> ! {
     asm!(
         "sub    sp, sp, #96",
@@ -281,14 +461,25 @@ fn _call_main() {
 }
 fn _start_rust(platform_data: usize) -> i32 {
     platform::init(platform_data);
+    #[cfg(not(any(
+        target_arch = "wasm32",
+        all(target_arch = "aarch64", target_os = "macos")
+    )))]
+    unsafe {
+        loader::ctors::run_ctors();
+    }
     _call_main();
+    #[cfg(feature = "heap-check")]
+    platform::allocator::check_heap();
+    #[cfg(feature = "alloc-stats")]
+    platform::allocator::report();
     platform::try_exit();
     platform::services::get_exit_status()
 }
 
 #[no_mangle]
 #[naked]
-#[repr(align(4))]
+#[rustc_align(4)]
 #[cfg(all(target_arch = "x86_64", target_os = "windows"))]
 pub unsafe extern "win64" fn __chkstk() -> ! {
     asm!(
@@ -313,18 +504,47 @@ pub unsafe extern "win64" fn __chkstk() -> ! {
     );
 }
 
+/* On Windows ARM64, LLVM emits a call to __chkstk for stack frames larger
+ *   than a page, with the frame size divided by 16 in x15.
+ * The stack pointer is adjusted by the caller afterwards; we only need to touch
+ *   each page from the top downwards so that the guard page is hit in order.
+ * Only x16 and x17 (the intra-procedure-call scratch registers) may be clobbered.
+ * Since AArch64 is not supported for submission, the Linux patch is not needed.
+ */
+#[no_mangle]
+#[naked]
+#[rustc_align(4)]
+#[cfg(all(target_arch = "aarch64", target_os = "windows"))]
+pub unsafe extern "C" fn __chkstk() -> ! {
+    asm!(
+        "lsl    x16, x15, #4",
+        "mov    x17, sp",
+        "1:",
+        "sub    x17, x17, #1, lsl #12",
+        "subs   x16, x16, #1, lsl #12",
+        "ldr    xzr, [x17]", // just touches the memory address; no meaning in itself
+        "b.gt   1b",
+        "ret",
+        options(noreturn)
+    );
+}
+
 pub unsafe fn print_panicinfo_and_exit(_pi: &core::panic::PanicInfo) -> ! {
     use crate::platform::services::write_stdio;
     use alloc::string::ToString;
     write_stdio(2, _pi.to_string().as_bytes());
     write_stdio(2, b"\n");
+    exit_with_failure()
+}
 
+/// Terminates the process with the exit code 101, without running any further code of the solution.
+pub unsafe fn exit_with_failure() -> ! {
     // Rust sets an exit code of 101 when the process panicked.
     // Hence, we follow that practice for maximum compatibility.
     // Reference: https://rust-cli.github.io/book/in-depth/exit-code.html
-    #[cfg(all(windows, target_arch = "x86_64"))]
+    #[cfg(all(windows, any(target_arch = "x86_64", target_arch = "aarch64")))]
     {
-        extern "win64" {
+        extern "system" {
             fn ExitProcess(uExitCode: u32) -> !;
         }
         ExitProcess(101)
@@ -338,7 +558,7 @@ pub unsafe fn print_panicinfo_and_exit(_pi: &core::panic::PanicInfo) -> ! {
         crate::platform::os::macos::syscall::exit_group(101)
     }
     #[cfg(not(any(
-        all(windows, target_arch = "x86_64"),
+        all(windows, any(target_arch = "x86_64", target_arch = "aarch64")),
         target_os = "linux",
         target_os = "macos"
     )))]
@@ -7,3 +7,15 @@ pub use reader_traits::*;
 const DEFAULT_BUF_SIZE: usize = 1 << 16;
 #[allow(dead_code)]
 const MIN_BUF_SIZE: usize = 128;
+
+/* Stable equivalents of the `maybe_uninit_uninit_array` and `maybe_uninit_slice` APIs */
+fn uninit_array<T, const N: usize>() -> [core::mem::MaybeUninit<T>; N] {
+    // An array of `MaybeUninit`s does not require initialization.
+    unsafe { core::mem::MaybeUninit::uninit().assume_init() }
+}
+unsafe fn slice_assume_init_ref<T>(slice: &[core::mem::MaybeUninit<T>]) -> &[T] {
+    &*(slice as *const [core::mem::MaybeUninit<T>] as *const [T])
+}
+unsafe fn slice_assume_init_mut<T>(slice: &mut [core::mem::MaybeUninit<T>]) -> &mut [T] {
+    &mut *(slice as *mut [core::mem::MaybeUninit<T>] as *mut [T])
+}
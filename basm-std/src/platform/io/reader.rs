@@ -26,6 +26,10 @@ pub trait ReaderTrait: Sized {
     fn next<T: Readable>(&mut self) -> T {
         T::read(self)
     }
+    /// Reads a signed decimal integer of any length.
+    fn next_bigint(&mut self) -> crate::math::BigInt {
+        self.next()
+    }
     fn take<T: Readable>(&mut self, n: usize) -> impl Iterator<Item = T> {
         (0..n).map(|_| T::read(self))
     }
@@ -108,7 +112,7 @@ impl<const N: usize> Reader<N> {
     };
     pub fn new() -> Self {
         Self {
-            buf: MaybeUninit::uninit_array(),
+            buf: super::uninit_array(),
             len: 0,
             off: 0,
         }
@@ -146,7 +150,7 @@ impl<const N: usize> Reader<N> {
                      * We try to read as much as possible at once. */
                     rem += services::read_stdio(
                         0,
-                        MaybeUninit::slice_assume_init_mut(&mut self.buf[rem..Self::BUF_LEN]),
+                        super::slice_assume_init_mut(&mut self.buf[rem..Self::BUF_LEN]),
                     );
                 }
                 /* Add a null-terminator, whether or not the read was nonsaturating (for SIMD-accelerated unsafe integer read routines).
@@ -211,7 +215,7 @@ impl<const N: usize> Reader<N> {
         loop {
             let len = self.len - self.off;
             let range =
-                unsafe { MaybeUninit::slice_assume_init_ref(&self.buf[self.off..self.off + len]) };
+                unsafe { super::slice_assume_init_ref(&self.buf[self.off..self.off + len]) };
             if let Some(i) = unsafe { position::memchr(range, delim) } {
                 unsafe { buf.as_mut_vec() }.extend_from_slice(&range[..i]);
                 self.off += i + 1;
@@ -227,7 +231,7 @@ impl<const N: usize> Reader<N> {
         }
     }
     pub fn remain(&self) -> &[u8] {
-        unsafe { MaybeUninit::slice_assume_init_ref(&self.buf[self.off..self.len]) }
+        unsafe { super::slice_assume_init_ref(&self.buf[self.off..self.len]) }
     }
     pub fn discard(&mut self, until: u8) -> usize {
         let mut len = 0;
@@ -494,7 +498,7 @@ impl<const N: usize> ReaderTrait for Reader<N> {
         if end == self.off {
             f64::NAN
         } else {
-            let s_u8 = unsafe { MaybeUninit::slice_assume_init_ref(&self.buf[self.off..end]) };
+            let s_u8 = unsafe { super::slice_assume_init_ref(&self.buf[self.off..end]) };
             let s = unsafe { core::str::from_utf8_unchecked(s_u8) };
             let out = f64::from_str(s);
             self.skip_until_whitespace();
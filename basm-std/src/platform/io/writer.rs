@@ -104,13 +104,13 @@ impl<const N: usize> Writer<N> {
     };
     pub fn new() -> Self {
         Self {
-            buf: MaybeUninit::uninit_array(),
+            buf: super::uninit_array(),
             off: 0,
         }
     }
     pub fn flush(&mut self) {
         services::write_stdio(1, unsafe {
-            MaybeUninit::slice_assume_init_ref(&self.buf[..self.off])
+            super::slice_assume_init_ref(&self.buf[..self.off])
         });
         self.off = 0;
     }
@@ -139,7 +139,7 @@ impl<const N: usize> Writer<N> {
         while !s.is_empty() {
             let rem = s.len().min(self.buf[self.off..].len());
             unsafe {
-                MaybeUninit::slice_assume_init_mut(&mut self.buf[self.off..self.off + rem])
+                super::slice_assume_init_mut(&mut self.buf[self.off..self.off + rem])
                     .copy_from_slice(&s[..rem]);
             }
             self.off += rem;
@@ -199,7 +199,7 @@ impl<const N: usize> Writer<N> {
         }
         let len = 16 - off;
         unsafe {
-            MaybeUninit::slice_assume_init_mut(&mut self.buf[self.off..self.off + len])
+            super::slice_assume_init_mut(&mut self.buf[self.off..self.off + len])
                 .copy_from_slice(&b128.0[off..]);
         }
         self.off += len;
@@ -249,13 +249,13 @@ impl<const N: usize> Writer<N> {
         }
         let len = 16 - hioff;
         unsafe {
-            MaybeUninit::slice_assume_init_mut(&mut self.buf[self.off..self.off + len])
+            super::slice_assume_init_mut(&mut self.buf[self.off..self.off + len])
                 .copy_from_slice(&hi128.0[hioff..]);
         }
         self.off += len;
         let len = 16 - looff;
         unsafe {
-            MaybeUninit::slice_assume_init_mut(&mut self.buf[self.off..self.off + len])
+            super::slice_assume_init_mut(&mut self.buf[self.off..self.off + len])
                 .copy_from_slice(&lo128.0[looff..]);
         }
         self.off += len;
@@ -277,7 +277,7 @@ impl<const N: usize> Writer<N> {
         while j < i {
             i -= 1;
             unsafe {
-                MaybeUninit::slice_assume_init_mut(&mut self.buf).swap(j, i);
+                super::slice_assume_init_mut(&mut self.buf).swap(j, i);
             }
             j += 1;
         }
@@ -291,7 +291,7 @@ impl<const N: usize> Writer<N> {
         }
     }
     pub fn u128(&mut self, mut n: u128) {
-        let mut buf: [MaybeUninit<u8>; 40] = MaybeUninit::uninit_array();
+        let mut buf: [MaybeUninit<u8>; 40] = super::uninit_array();
         let mut offset = buf.len() - 1;
         buf[offset].write(b'0' + (n % 10) as u8);
         n /= 10;
@@ -300,7 +300,7 @@ impl<const N: usize> Writer<N> {
             buf[offset].write(b'0' + (n % 10) as u8);
             n /= 10;
         }
-        self.bytes(unsafe { MaybeUninit::slice_assume_init_ref(&buf[offset..]) });
+        self.bytes(unsafe { super::slice_assume_init_ref(&buf[offset..]) });
     }
     #[cfg(target_pointer_width = "32")]
     pub fn isize(&mut self, n: isize) {
@@ -0,0 +1,45 @@
+use core::ptr;
+
+// Relocation types
+const IMAGE_REL_BASED_ABSOLUTE: u16 = 0; // The base relocation is skipped. This type can be used to pad a block.
+const IMAGE_REL_BASED_DIR64: u16 = 10; // The base relocation applies the difference to the 64-bit field at offset.
+
+/* Unlike x86, ARM64 PE images only carry absolute 64-bit pointers
+ *   in their base relocation table, since all code is position-independent
+ *   (ADRP/ADD pairs are PC-relative and never need fixups).
+ * Hence, we only need to handle IMAGE_REL_BASED_DIR64 (and the padding entries).
+ *
+ * This function assumes the original ImageBase is 0x0,
+ *   which is ensured by `static-pie-pe2bin.py`.
+ * When the executable runs natively, the Windows PE loader
+ *   handles relocation for us, and thus this function is not run.
+ */
+pub unsafe extern "C" fn relocate(addr_image_base: u64, off_reloc: u64, size_reloc: u64) {
+    let mut off = addr_image_base + off_reloc;
+    let end = off + size_reloc;
+    while off < end {
+        let virtual_address: u32 = ptr::read(off as *const u32);
+        let size_of_block: u32 = ptr::read((off + 4) as *const u32);
+        let end_of_block: u64 = off + size_of_block as u64;
+        off += 8;
+        while off < end_of_block {
+            let w_val: u16 = ptr::read(off as *const u16);
+            off += 2;
+            let w_type: u16 = (w_val & 0xF000) >> 12;
+            let w_offset: u16 = w_val & 0x0FFF;
+            let patch_addr: u64 = addr_image_base + virtual_address as u64 + w_offset as u64;
+            match w_type {
+                IMAGE_REL_BASED_DIR64 => {
+                    ptr::write(
+                        patch_addr as *mut u64,
+                        ptr::read(patch_addr as *const u64) + addr_image_base,
+                    );
+                }
+                IMAGE_REL_BASED_ABSOLUTE => (),
+                _ => {
+                    unreachable!()
+                }
+            }
+        }
+    }
+}
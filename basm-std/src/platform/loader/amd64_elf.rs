@@ -54,7 +54,8 @@ use core::mem::MaybeUninit;
 // Dynamic section entry types
 const DT_RELA: u64 = 7;
 const DT_RELASZ: u64 = 8;
-const DT_RELAENT: u64 = 9;
+const DT_RELRSZ: u64 = 35;
+const DT_RELR: u64 = 36;
 
 // Relocation types
 const R_X86_64_NONE: u32 = 0; // none
@@ -77,7 +78,8 @@ pub unsafe extern "sysv64" fn relocate(addr_image_base: u64, addr_dynamic_sectio
     let mut ptr_dyn: *const Elf64Dyn = addr_dynamic_section as *const Elf64Dyn;
     let mut ptr_rela = 0;
     let mut relasz = MaybeUninit::<u64>::uninit();
-    let mut relaent = MaybeUninit::<u64>::uninit();
+    let mut ptr_relr = 0;
+    let mut relrsz = MaybeUninit::<u64>::uninit();
     loop {
         match (*ptr_dyn).d_tag {
             0 => {
@@ -89,8 +91,11 @@ pub unsafe extern "sysv64" fn relocate(addr_image_base: u64, addr_dynamic_sectio
             DT_RELASZ => {
                 relasz.write((*ptr_dyn).d_val_or_ptr);
             }
-            DT_RELAENT => {
-                relaent.write((*ptr_dyn).d_val_or_ptr);
+            DT_RELR => {
+                ptr_relr = addr_image_base + (*ptr_dyn).d_val_or_ptr;
+            }
+            DT_RELRSZ => {
+                relrsz.write((*ptr_dyn).d_val_or_ptr);
             }
             _ => (),
         }
@@ -101,27 +106,80 @@ pub unsafe extern "sysv64" fn relocate(addr_image_base: u64, addr_dynamic_sectio
      * 2) When DT_RELA is present, the other entries DT_RELASZ and DT_RELAENT must exist.
      *    Source: https://docs.oracle.com/cd/E19683-01/817-3677/chapter6-42444/index.html
      *    ("This element requires the DT_RELASZ and DT_RELAENT elements also be present.")
+     *    We ignore DT_RELAENT, since it always equals sizeof(Elf64Rela) = 24 on amd64.
+     *    The same applies to DT_RELR, DT_RELRSZ and DT_RELRENT (= 8).
      */
-    if ptr_rela == 0 {
-        return;
+    if ptr_relr != 0 {
+        relocate_relr(
+            addr_image_base,
+            ptr_relr as *const u64,
+            (ptr_relr + relrsz.assume_init()) as *const u64,
+        );
+    }
+    if ptr_rela != 0 {
+        relocate_rela(
+            addr_image_base,
+            ptr_rela as *const Elf64Rela,
+            (ptr_rela + relasz.assume_init()) as *const Elf64Rela,
+        );
+    }
+}
+
+/* Applies the compact relative relocations (SHT_RELR; `-z pack-relative-relocs`).
+ * An even entry is the offset of a word to relocate; it also sets the base of
+ *   the following bitmap entries to the next word.
+ * An odd entry is a bitmap, whose i-th bit (1 <= i <= 63) indicates that
+ *   the (i - 1)-th word from the base must be relocated; the base is then advanced by 63 words.
+ * Since a single bitmap entry covers up to 63 relocations, this is much faster
+ *   (and smaller) than processing the equivalent R_X86_64_RELATIVE entries one by one.
+ */
+#[inline(always)]
+unsafe fn relocate_relr(addr_image_base: u64, mut ptr: *const u64, end: *const u64) {
+    let mut base: *mut u64 = core::ptr::null_mut();
+    while ptr < end {
+        let entry = *ptr;
+        ptr = ptr.add(1);
+        if entry & 1 == 0 {
+            let target = (addr_image_base + entry) as *mut u64;
+            *target += addr_image_base;
+            base = target.add(1);
+        } else {
+            let mut bitmap = entry >> 1;
+            while bitmap != 0 {
+                let target = base.add(bitmap.trailing_zeros() as usize);
+                *target += addr_image_base;
+                bitmap &= bitmap - 1;
+            }
+            base = base.add(63);
+        }
+    }
+}
+
+/* Applies the ordinary relocations. Since the linker sorts R_X86_64_RELATIVE entries
+ *   to the front (cf. DT_RELACOUNT), the fast path handles them two at a time,
+ *   and falls back to the general path on the first entry of other types.
+ */
+#[inline(always)]
+unsafe fn relocate_rela(addr_image_base: u64, mut ptr: *const Elf64Rela, end: *const Elf64Rela) {
+    while ptr.add(1) < end
+        && (*ptr).r_info as u32 == R_X86_64_RELATIVE
+        && (*ptr.add(1)).r_info as u32 == R_X86_64_RELATIVE
+    {
+        let (r0, r1) = (&*ptr, &*ptr.add(1));
+        *((addr_image_base + r0.r_offset) as *mut u64) = addr_image_base + r0.r_addend;
+        *((addr_image_base + r1.r_offset) as *mut u64) = addr_image_base + r1.r_addend;
+        ptr = ptr.add(2);
     }
-    relasz.write(relasz.assume_init() + ptr_rela);
-
-    while ptr_rela < relasz.assume_init() {
-        let pst_rela = ptr_rela as *mut Elf64Rela;
-        let ul_offset = (*pst_rela).r_offset;
-        let ul_info = (*pst_rela).r_info;
-        let l_addend = (*pst_rela).r_addend;
-        if ul_info as u32 == R_X86_64_RELATIVE {
-            let l_result: u64 = addr_image_base + l_addend;
-            let ptr_target = (addr_image_base + ul_offset) as *mut u64;
-            *ptr_target = l_result;
-        } else if ul_info as u32 == R_X86_64_NONE {
+    while ptr < end {
+        let rela = &*ptr;
+        if rela.r_info as u32 == R_X86_64_RELATIVE {
+            *((addr_image_base + rela.r_offset) as *mut u64) = addr_image_base + rela.r_addend;
+        } else if rela.r_info as u32 == R_X86_64_NONE {
             /* do nothing */
         } else {
             /* not implemented */
             panic!();
         }
-        ptr_rela += relaent.assume_init();
+        ptr = ptr.add(1);
     }
 }
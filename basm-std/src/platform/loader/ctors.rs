@@ -0,0 +1,165 @@
+/* Static initializers (constructors) of the loaded image.
+ *
+ * Crates such as `ctor` register functions to be run before `main`.
+ * On ELF targets, they are placed in `.init_array`; on PE targets, they are
+ * placed in `.CRT$XCU` and are expected to be run by the C runtime.
+ * Since we link without the C runtime (`-nostartfiles`, `/NODEFAULTLIB`) and
+ * the loader stub does not run them either, nobody would invoke them.
+ * Hence, we run them by ourselves right after the relocations are applied
+ * and the platform services are ready (so that initializers may allocate).
+ *
+ * PE TLS callbacks registered for `DLL_PROCESS_ATTACH` are handled the same way:
+ * they live in `.CRT$XLA..XLZ`, and without the C runtime there is no `_tls_used`
+ * to make the linker emit a TLS directory for the OS loader to walk.
+ *
+ * Note that the function pointers in these tables are subject to relocation.
+ * Thus, this must never be called before `relocate`.
+ */
+
+#[cfg(not(target_os = "windows"))]
+pub unsafe fn run_ctors() {
+    extern "C" {
+        // Defined by the default linker scripts of GNU ld and LLD (PROVIDE_HIDDEN).
+        static __init_array_start: [unsafe extern "C" fn(); 0];
+        static __init_array_end: [unsafe extern "C" fn(); 0];
+    }
+    let mut ptr = __init_array_start.as_ptr();
+    let end = __init_array_end.as_ptr();
+    while ptr < end {
+        (*ptr)();
+        ptr = ptr.add(1);
+    }
+}
+
+/* On PE targets, the linker sorts the `.CRT$XC?` sections by their suffixes
+ * and merges them into `.CRT`. We place sentinels in `.CRT$XCA` and `.CRT$XCZ`,
+ * so that every entry in between (e.g., `.CRT$XCU`) belongs to the table.
+ * The TLS callbacks in `.CRT$XL?` are bracketed by `.CRT$XLA` and `.CRT$XLZ` likewise.
+ * The linker may insert zero padding between the sections; null entries are skipped.
+ */
+#[cfg(target_os = "windows")]
+#[used]
+#[link_section = ".CRT$XCA"]
+static __BASM_XCA: Option<unsafe extern "C" fn()> = None;
+#[cfg(target_os = "windows")]
+#[used]
+#[link_section = ".CRT$XCZ"]
+static __BASM_XCZ: Option<unsafe extern "C" fn()> = None;
+
+#[cfg(target_os = "windows")]
+type TlsCallback = unsafe extern "system" fn(*mut u8, u32, *mut u8);
+#[cfg(target_os = "windows")]
+#[used]
+#[link_section = ".CRT$XLA"]
+static __BASM_XLA: Option<TlsCallback> = None;
+#[cfg(target_os = "windows")]
+#[used]
+#[link_section = ".CRT$XLZ"]
+static __BASM_XLZ: Option<TlsCallback> = None;
+
+#[cfg(target_os = "windows")]
+extern "C" {
+    // Defined by the linker; points to the in-memory image even when loaded by our loader.
+    static __ImageBase: u8;
+}
+
+/* If a dependency does supply a TLS directory, the OS loader has already called
+ * the callbacks when running natively, so we must not call them again.
+ * With our loader, `static-pie-pe2bin.py` strips the headers and nobody has called them.
+ */
+#[cfg(target_os = "windows")]
+unsafe fn os_ran_tls_callbacks() -> bool {
+    const IMAGE_DIRECTORY_ENTRY_TLS: usize = 9;
+    if !crate::platform::is_local_env() {
+        return false;
+    }
+    let base = &__ImageBase as *const u8;
+    let nt = base.add(core::ptr::read_unaligned(base.add(0x3c) as *const u32) as usize);
+    // PE32+: the optional header follows the 4-byte signature and the 20-byte file header,
+    // and its data directories start at offset 112.
+    let dir = nt.add(24 + 112 + 8 * IMAGE_DIRECTORY_ENTRY_TLS);
+    let tls_rva = core::ptr::read_unaligned(dir as *const u32);
+    // IMAGE_TLS_DIRECTORY64::AddressOfCallBacks
+    tls_rva != 0 && core::ptr::read_unaligned(base.add(tls_rva as usize + 24) as *const u64) != 0
+}
+
+/* Returns the non-null entries strictly between the sentinels `start` and `end`. */
+#[cfg(target_os = "windows")]
+unsafe fn entries<T: Copy>(
+    start: &'static Option<T>,
+    end: &'static Option<T>,
+) -> impl Iterator<Item = T> {
+    let mut ptr = (start as *const Option<T>).add(1);
+    let end = end as *const Option<T>;
+    core::iter::from_fn(move || {
+        while ptr < end {
+            let entry = core::ptr::read_volatile(ptr);
+            ptr = ptr.add(1);
+            if entry.is_some() {
+                return entry;
+            }
+        }
+        None
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub unsafe fn run_ctors() {
+    const DLL_PROCESS_ATTACH: u32 = 1;
+    if !os_ran_tls_callbacks() {
+        let image_base = &__ImageBase as *const u8 as *mut u8;
+        for f in entries(&__BASM_XLA, &__BASM_XLZ) {
+            f(image_base, DLL_PROCESS_ATTACH, core::ptr::null_mut());
+        }
+    }
+    for f in entries(&__BASM_XCA, &__BASM_XCZ) {
+        f();
+    }
+}
+
+/* The test harness links the C runtime, which runs the constructors by itself,
+ * so this only checks that the sentinels bracket the entries the linker collected.
+ */
+#[cfg(all(test, target_os = "windows"))]
+mod test {
+    use super::*;
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    static CTOR_RUNS: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn ctor() {
+        CTOR_RUNS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    unsafe extern "system" fn tls_callback(_: *mut u8, _: u32, _: *mut u8) {}
+
+    #[used]
+    #[link_section = ".CRT$XCU"]
+    static CTOR: unsafe extern "C" fn() = ctor;
+
+    #[used]
+    #[link_section = ".CRT$XLB"]
+    static TLS_CALLBACK: TlsCallback = tls_callback;
+
+    #[test]
+    fn tables_contain_registered_entries() {
+        assert_eq!(1, CTOR_RUNS.load(Ordering::Relaxed));
+        unsafe {
+            let ctors: Vec<_> = entries(&__BASM_XCA, &__BASM_XCZ)
+                .map(|f| f as usize)
+                .collect();
+            assert!(ctors.contains(&(CTOR as usize)));
+            let callbacks: Vec<_> = entries(&__BASM_XLA, &__BASM_XLZ)
+                .map(|f| f as usize)
+                .collect();
+            assert!(callbacks.contains(&(TLS_CALLBACK as usize)));
+            // The walk yields callable entries
+            for f in entries(&__BASM_XCA, &__BASM_XCZ) {
+                if f as usize == CTOR as usize {
+                    f();
+                }
+            }
+        }
+        assert_eq!(2, CTOR_RUNS.load(Ordering::Relaxed));
+    }
+}
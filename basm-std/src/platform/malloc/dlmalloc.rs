@@ -27,6 +27,7 @@ pub struct Dlmalloc<A> {
     trim_check: usize,
     least_addr: *mut u8,
     release_checks: usize,
+    mmap_threshold: usize,
     system_allocator: A,
 }
 unsafe impl<A: Send> Send for Dlmalloc<A> {}
@@ -41,6 +42,12 @@ const TREEBIN_SHIFT: usize = 8;
 const DEFAULT_GRANULARITY: usize = 64 * 1024;
 const DEFAULT_TRIM_THRESHOLD: usize = 2 * 1024 * 1024;
 const MAX_RELEASE_CHECK_RATE: usize = 4095;
+// Requests of at least `mmap_threshold` bytes get their own mappings if the system can remap them,
+// so that growing them (e.g., a large Vec) moves pages instead of copying bytes.
+// As in glibc, the threshold rises up to the size of a freed mapping, up to `MAX_MMAP_THRESHOLD`,
+// so that repeatedly allocating and freeing large buffers does not cost a system call each time.
+const DEFAULT_MMAP_THRESHOLD: usize = 1024 * 1024;
+const MAX_MMAP_THRESHOLD: usize = 32 * 1024 * 1024;
 
 #[repr(C)]
 struct Chunk {
@@ -111,6 +118,7 @@ impl<A> Dlmalloc<A> {
             trim_check: 0,
             least_addr: 0 as *mut _,
             release_checks: 0,
+            mmap_threshold: DEFAULT_MMAP_THRESHOLD,
             system_allocator,
         }
     }
@@ -359,6 +367,12 @@ impl<A: DlmallocAllocator> Dlmalloc<A> {
     /// allocates system resources
     unsafe fn sys_alloc(&mut self, size: usize) -> *mut u8 {
         self.check_malloc_state();
+        if size >= self.mmap_threshold && self.topsize != 0 && self.system_allocator.can_remap() {
+            let mem = self.mmap_alloc(size);
+            if !mem.is_null() {
+                return mem;
+            }
+        }
         // keep in sync with max_request
         let asize = align_up(
             size + self.top_foot_size() + self.malloc_alignment(),
@@ -456,6 +470,51 @@ impl<A: DlmallocAllocator> Dlmalloc<A> {
         ptr
     }
 
+    /// Resizes the allocation at `oldmem` to `bytes` without moving it, which succeeds when the chunk
+    /// is followed by enough free space (including the top chunk, which may be grown in place).
+    /// Returns `false` if the allocation is left untouched.
+    pub unsafe fn realloc_in_place(&mut self, oldmem: *mut u8, bytes: usize) -> bool {
+        if bytes >= self.max_request() {
+            return false;
+        }
+        let nb = self.request2size(bytes);
+        let oldp = Chunk::from_mem(oldmem);
+        let newp = self.try_realloc_chunk(oldp, nb, false);
+        if !newp.is_null() {
+            self.check_inuse_chunk(newp);
+        }
+        !newp.is_null()
+    }
+
+    /// Enlarges the top chunk so that it has more than `size` bytes, by growing its segment in place
+    /// with the system allocator (e.g., mremap without moving). This lets the allocation right before
+    /// the top chunk (typically a growing Vec) be extended without copying.
+    unsafe fn grow_top_in_place(&mut self, size: usize) -> bool {
+        let sp = self.segment_holding(self.top as *mut u8);
+        if sp.is_null() || Segment::is_extern(sp) {
+            return false;
+        }
+        let tsize = align_up(
+            size - self.topsize + self.top_foot_size() + self.min_chunk_size(),
+            DEFAULT_GRANULARITY,
+        );
+        let Some(newsize) = (*sp).size.checked_add(tsize) else {
+            return false;
+        };
+        let base = self
+            .system_allocator
+            .remap((*sp).base, (*sp).size, newsize, false);
+        if base.is_null() {
+            return false;
+        }
+        debug_assert_eq!(base, (*sp).base);
+        (*sp).size = newsize;
+        self.footprint += tsize;
+        self.max_footprint = cmp::max(self.max_footprint, self.footprint);
+        self.init_top(self.top, self.topsize + tsize);
+        true
+    }
+
     unsafe fn try_realloc_chunk(&mut self, p: *mut Chunk, nb: usize, can_move: bool) -> *mut Chunk {
         let oldsize = Chunk::size(p);
         let next = Chunk::plus_offset(p, oldsize);
@@ -473,7 +532,7 @@ impl<A: DlmallocAllocator> Dlmalloc<A> {
             p
         } else if next == self.top {
             // extend into top
-            if oldsize + self.topsize <= nb {
+            if oldsize + self.topsize <= nb && !self.grow_top_in_place(nb - oldsize) {
                 return ptr::null_mut();
             }
             let newsize = oldsize + self.topsize;
@@ -530,6 +589,32 @@ impl<A: DlmallocAllocator> Dlmalloc<A> {
         }
     }
 
+    unsafe fn mmap_alloc(&mut self, nb: usize) -> *mut u8 {
+        let mmsize =
+            self.mmap_align(nb + 6 * mem::size_of::<usize>() + self.malloc_alignment() - 1);
+        if mmsize <= nb {
+            return ptr::null_mut();
+        }
+        let (ptr, mmsize, _flags) = self.system_allocator.alloc(mmsize);
+        if ptr.is_null() {
+            return ptr;
+        }
+        let offset = self.align_offset(Chunk::to_mem(ptr as *mut Chunk));
+        let psize = mmsize - offset - self.mmap_foot_pad();
+        let p = ptr.add(offset) as *mut Chunk;
+        (*p).prev_foot = offset;
+        (*p).head = psize;
+        (*Chunk::plus_offset(p, psize)).head = Chunk::fencepost_head();
+        (*Chunk::plus_offset(p, psize + mem::size_of::<usize>())).head = 0;
+        if self.least_addr.is_null() || ptr < self.least_addr {
+            self.least_addr = ptr;
+        }
+        self.footprint += mmsize;
+        self.max_footprint = cmp::max(self.max_footprint, self.footprint);
+        self.check_mmapped_chunk(p);
+        Chunk::to_mem(p)
+    }
+
     unsafe fn mmap_resize(&mut self, oldp: *mut Chunk, nb: usize, can_move: bool) -> *mut Chunk {
         let oldsize = Chunk::size(oldp);
         // Can't shrink mmap regions below a small size
@@ -574,6 +659,9 @@ impl<A: DlmallocAllocator> Dlmalloc<A> {
     // Only call this with power-of-two alignment and alignment >
     // `self.malloc_alignment()`
     pub unsafe fn memalign(&mut self, mut alignment: usize, bytes: usize) -> *mut u8 {
+        if alignment <= self.malloc_alignment() {
+            return self.malloc(bytes);
+        }
         if alignment < self.min_chunk_size() {
             alignment = self.min_chunk_size();
         }
@@ -1174,6 +1262,8 @@ impl<A: DlmallocAllocator> Dlmalloc<A> {
             let prevsize = (*p).prev_foot;
 
             if Chunk::mmapped(p) {
+                self.mmap_threshold =
+                    cmp::min(cmp::max(self.mmap_threshold, psize + 1), MAX_MMAP_THRESHOLD);
                 psize += prevsize + self.mmap_foot_pad();
                 if self
                     .system_allocator
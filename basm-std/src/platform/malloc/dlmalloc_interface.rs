@@ -35,4 +35,10 @@ pub unsafe trait DlmallocAllocator: Send {
 
     /// Returns the page size. Must be a power of two
     fn page_size(&self) -> usize;
+
+    /// Indicates whether `remap` can move a region (e.g., mremap on Linux). If so, large requests
+    /// are mapped individually so that they can be resized without copying.
+    fn can_remap(&self) -> bool {
+        false
+    }
 }
@@ -13,6 +13,172 @@ impl System {
     }
 }
 
+/// Size of the heap reserved at startup by the `fixed-heap` feature in MiB.
+/// It is given by the environment variable `BASM_HEAP_MIB` at build time (256 by default).
+#[cfg(feature = "fixed-heap")]
+pub const FIXED_HEAP_MIB: usize = match option_env!("BASM_HEAP_MIB") {
+    Some(s) => parse_mib(s.as_bytes()),
+    None => 256,
+};
+
+/// Size of the heap prefaulted at startup by the `prefault-heap` feature in MiB.
+/// It is given by the environment variable `BASM_PREFAULT_MIB` at build time (64 by default).
+#[cfg(feature = "prefault-heap")]
+pub const PREFAULT_MIB: usize = match option_env!("BASM_PREFAULT_MIB") {
+    Some(s) => parse_mib(s.as_bytes()),
+    None => 64,
+};
+
+#[cfg(any(feature = "fixed-heap", feature = "prefault-heap"))]
+const fn parse_mib(s: &[u8]) -> usize {
+    let mut i = 0;
+    let mut out = 0;
+    while i < s.len() {
+        assert!(
+            s[i].is_ascii_digit(),
+            "BASM_HEAP_MIB and BASM_PREFAULT_MIB must be positive integers"
+        );
+        out = out * 10 + (s[i] - b'0') as usize;
+        i += 1;
+    }
+    assert!(
+        out > 0,
+        "BASM_HEAP_MIB and BASM_PREFAULT_MIB must be positive integers"
+    );
+    // The sizes are computed as `out << 20`, which would wrap around with a 32-bit usize
+    assert!(
+        out <= usize::MAX >> 20,
+        "BASM_HEAP_MIB and BASM_PREFAULT_MIB must fit in the address space of the target"
+    );
+    out
+}
+
+/// The heap reserved by [`reserve_fixed_heap`], which is handed out to dlmalloc as a single segment.
+#[cfg(feature = "fixed-heap")]
+static mut FIXED_HEAP: *mut u8 = core::ptr::null_mut();
+
+/// Reserves the whole heap up front for the `fixed-heap` feature.
+/// Afterwards, the allocator never calls mmap again, and the process terminates with an error message
+/// when the heap is exhausted.
+#[cfg(feature = "fixed-heap")]
+pub unsafe fn reserve_fixed_heap() {
+    let addr = syscall::mmap(
+        core::ptr::null_mut(),
+        FIXED_HEAP_MIB << 20,
+        syscall::PROT_WRITE | syscall::PROT_READ,
+        syscall::MAP_ANON | syscall::MAP_PRIVATE,
+        -1,
+        0,
+    );
+    if mmap_failed(addr) {
+        fixed_heap_failure(b"basm: failed to reserve the fixed heap (BASM_HEAP_MIB)\n");
+    }
+    #[cfg(feature = "huge-pages")]
+    syscall::madvise(addr, FIXED_HEAP_MIB << 20, syscall::MADV_HUGEPAGE);
+    FIXED_HEAP = addr;
+}
+
+/// The prefaulted region mapped by [`prefault_heap`], which becomes the first segment of dlmalloc.
+#[cfg(all(feature = "prefault-heap", not(feature = "fixed-heap")))]
+static mut PREFAULTED: *mut u8 = core::ptr::null_mut();
+
+/// Maps and populates the first `PREFAULT_MIB` MiB of the heap for the `prefault-heap` feature,
+/// so that the page faults are taken at startup rather than during the first queries.
+/// With `fixed-heap`, the beginning of the fixed heap is touched instead.
+/// This is best-effort: if the memory cannot be obtained, the heap grows on demand as usual.
+#[cfg(feature = "prefault-heap")]
+pub unsafe fn prefault_heap() {
+    #[cfg(feature = "fixed-heap")]
+    {
+        let heap = FIXED_HEAP;
+        if !heap.is_null() {
+            for off in (0..PREFAULT_MIB.min(FIXED_HEAP_MIB) << 20).step_by(4096) {
+                core::ptr::write_volatile(heap.add(off), 0);
+            }
+        }
+    }
+    #[cfg(not(feature = "fixed-heap"))]
+    {
+        let addr = syscall::mmap(
+            core::ptr::null_mut(),
+            PREFAULT_MIB << 20,
+            syscall::PROT_WRITE | syscall::PROT_READ,
+            syscall::MAP_ANON | syscall::MAP_PRIVATE | syscall::MAP_POPULATE,
+            -1,
+            0,
+        );
+        if !mmap_failed(addr) {
+            PREFAULTED = addr;
+        }
+    }
+}
+
+#[cfg(feature = "fixed-heap")]
+#[cold]
+fn fixed_heap_failure(msg: &[u8]) -> ! {
+    unsafe {
+        syscall::write(2, msg.as_ptr(), msg.len());
+        /* same as a panic */
+        syscall::exit_group(101)
+    }
+}
+
+/// Under the `huge-pages` feature, segments of at least this size are backed by huge pages.
+#[cfg(all(feature = "huge-pages", not(feature = "fixed-heap")))]
+const HUGE_PAGE_THRESHOLD: usize = 32 << 20;
+#[cfg(all(feature = "huge-pages", not(feature = "fixed-heap")))]
+const HUGE_PAGE_SIZE: usize = 2 << 20;
+/// Segment flag (shifted by one, as in dlmalloc) marking the segments from hugetlbfs,
+/// which must not be merged with the other segments or released partially.
+#[cfg(feature = "huge-pages")]
+const HUGETLB_FLAG: u32 = 1;
+
+/// Maps a segment backed by huge pages, which reduces TLB misses on large tables.
+/// First tries explicit huge pages (MAP_HUGETLB), which are available only when reserved
+/// by the administrator; then falls back to a 2 MiB-aligned region with transparent huge pages
+/// requested by madvise. Returns a null pointer if both fail.
+#[cfg(all(feature = "huge-pages", not(feature = "fixed-heap")))]
+unsafe fn alloc_huge(size: usize) -> (*mut u8, usize, u32) {
+    let size = (size + HUGE_PAGE_SIZE - 1) & !(HUGE_PAGE_SIZE - 1);
+    let prot = syscall::PROT_WRITE | syscall::PROT_READ;
+    let flags = syscall::MAP_ANON | syscall::MAP_PRIVATE;
+    let addr = syscall::mmap(
+        core::ptr::null_mut(),
+        size,
+        prot,
+        flags | syscall::MAP_HUGETLB,
+        -1,
+        0,
+    );
+    if !mmap_failed(addr) {
+        return (addr, size, HUGETLB_FLAG << 1);
+    }
+    let addr = syscall::mmap(
+        core::ptr::null_mut(),
+        size + HUGE_PAGE_SIZE,
+        prot,
+        flags,
+        -1,
+        0,
+    );
+    if mmap_failed(addr) {
+        return (core::ptr::null_mut(), 0, 0);
+    }
+    let head = addr.align_offset(HUGE_PAGE_SIZE);
+    if head > 0 {
+        syscall::munmap(addr, head);
+    }
+    let start = addr.add(head);
+    syscall::munmap(start.add(size), HUGE_PAGE_SIZE - head);
+    syscall::madvise(start, size, syscall::MADV_HUGEPAGE);
+    (start, size, 0)
+}
+
+/// The raw system call returns `-errno` on failure rather than `MAP_FAILED`.
+fn mmap_failed(addr: *mut u8) -> bool {
+    addr as usize > -4096isize as usize
+}
+
 impl Default for System {
     fn default() -> Self {
         Self::new()
@@ -20,7 +186,43 @@ impl Default for System {
 }
 
 unsafe impl DlmallocAllocator for System {
+    #[cfg(feature = "fixed-heap")]
     fn alloc(&self, size: usize) -> (*mut u8, usize, u32) {
+        let addr = unsafe {
+            core::mem::replace(
+                &mut *core::ptr::addr_of_mut!(FIXED_HEAP),
+                core::ptr::null_mut(),
+            )
+        };
+        if addr.is_null() || size > FIXED_HEAP_MIB << 20 {
+            fixed_heap_failure(b"basm: the fixed heap is exhausted (increase BASM_HEAP_MIB)\n");
+        }
+        /* EXTERN_BIT: dlmalloc never releases or merges this segment */
+        (addr, FIXED_HEAP_MIB << 20, 1)
+    }
+
+    #[cfg(not(feature = "fixed-heap"))]
+    fn alloc(&self, size: usize) -> (*mut u8, usize, u32) {
+        #[cfg(feature = "prefault-heap")]
+        if size <= PREFAULT_MIB << 20 {
+            let addr = unsafe {
+                core::mem::replace(
+                    &mut *core::ptr::addr_of_mut!(PREFAULTED),
+                    core::ptr::null_mut(),
+                )
+            };
+            if !addr.is_null() {
+                /* EXTERN_BIT: keep the prefaulted pages from being trimmed */
+                return (addr, PREFAULT_MIB << 20, 1);
+            }
+        }
+        #[cfg(feature = "huge-pages")]
+        if size >= HUGE_PAGE_THRESHOLD {
+            let ret = unsafe { alloc_huge(size) };
+            if !ret.0.is_null() {
+                return ret;
+            }
+        }
         let addr = unsafe {
             syscall::mmap(
                 core::ptr::null_mut(),
@@ -31,7 +233,7 @@ unsafe impl DlmallocAllocator for System {
                 0,
             )
         };
-        if core::ptr::eq(addr, syscall::MAP_FAILED) {
+        if mmap_failed(addr) {
             (core::ptr::null_mut(), 0, 0)
         } else {
             (addr, size, 0)
@@ -41,7 +243,7 @@ unsafe impl DlmallocAllocator for System {
     fn remap(&self, ptr: *mut u8, oldsize: usize, newsize: usize, can_move: bool) -> *mut u8 {
         let flags = if can_move { syscall::MREMAP_MAYMOVE } else { 0 };
         let ptr = unsafe { syscall::mremap(ptr as *mut _, oldsize, newsize, flags) };
-        if core::ptr::eq(ptr, syscall::MAP_FAILED) {
+        if mmap_failed(ptr) {
             core::ptr::null_mut()
         } else {
             ptr
@@ -51,7 +253,7 @@ unsafe impl DlmallocAllocator for System {
     fn free_part(&self, ptr: *mut u8, oldsize: usize, newsize: usize) -> bool {
         unsafe {
             let rc = syscall::mremap(ptr as *mut _, oldsize, newsize, 0);
-            if !core::ptr::eq(rc, syscall::MAP_FAILED) {
+            if !mmap_failed(rc) {
                 return true;
             }
             syscall::munmap(ptr.add(newsize) as *mut _, oldsize - newsize).is_null()
@@ -63,6 +265,10 @@ unsafe impl DlmallocAllocator for System {
     }
 
     fn can_release_part(&self, _flags: u32) -> bool {
+        #[cfg(feature = "huge-pages")]
+        if _flags & HUGETLB_FLAG != 0 {
+            return false;
+        }
         true
     }
 
@@ -73,4 +279,9 @@ unsafe impl DlmallocAllocator for System {
     fn page_size(&self) -> usize {
         4096
     }
+
+    fn can_remap(&self) -> bool {
+        /* the fixed heap is a single segment that is never remapped */
+        cfg!(not(feature = "fixed-heap"))
+    }
 }
@@ -2,9 +2,12 @@ pub mod dlmalloc;
 pub mod dlmalloc_interface;
 #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
 pub mod dlmalloc_linux;
-#[cfg(target_arch = "aarch64")]
+#[cfg(all(target_arch = "aarch64", target_os = "macos"))]
 pub mod dlmalloc_macos;
 #[cfg(target_arch = "wasm32")]
 pub mod dlmalloc_wasm32;
-#[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+#[cfg(any(
+    not(any(target_arch = "wasm32", target_arch = "aarch64")),
+    all(target_arch = "aarch64", target_os = "windows")
+))]
 pub mod dlmalloc_windows;
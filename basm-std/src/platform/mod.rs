@@ -1,25 +1,32 @@
-#[cfg(not(test))]
 pub mod allocator;
-#[cfg(all(not(test), feature = "codegen"))]
+#[cfg(all(not(any(test, feature = "std")), feature = "codegen"))]
 pub mod codegen;
 pub mod io;
-#[cfg(not(test))]
+#[cfg(not(any(test, feature = "std")))]
 pub mod loader;
-#[cfg(not(test))]
+// The constructor tables are tested natively, where the test harness links the C runtime
+#[cfg(all(test, target_os = "windows"))]
+#[path = "loader/ctors.rs"]
+#[allow(dead_code)]
+mod ctors;
+#[cfg(not(any(test, feature = "std")))]
 pub mod malloc;
-#[cfg(not(test))]
+#[cfg(not(any(test, feature = "std")))]
 pub mod os;
-#[cfg_attr(test, path = "services_std.rs")]
+#[cfg_attr(any(test, feature = "std"), path = "services_std.rs")]
 pub mod services;
 
-#[cfg(not(test))]
+#[cfg(not(any(test, feature = "std")))]
 pub fn init(platform_data_by_loader: usize) {
     services::install(platform_data_by_loader);
 
     let pd = services::platform_data();
     unsafe {
         match pd.env_id {
-            #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+            #[cfg(any(
+                not(any(target_arch = "wasm32", target_arch = "aarch64")),
+                all(target_arch = "aarch64", target_os = "windows")
+            ))]
             #[cfg(not(feature = "short"))]
             services::ENV_ID_WINDOWS => {
                 /* use OS APIs directly */
@@ -30,7 +37,7 @@ pub fn init(platform_data_by_loader: usize) {
                 /* use syscalls directly */
                 os::linux::init();
             }
-            #[cfg(target_arch = "aarch64")]
+            #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
             services::ENV_ID_MACOS => {
                 os::macos::init();
             }
@@ -49,9 +56,13 @@ pub fn init(platform_data_by_loader: usize) {
         }
     }
 }
-#[cfg(not(test))]
+#[cfg(not(any(test, feature = "std")))]
 pub fn try_exit() {
     let pd = services::platform_data();
+    if cfg!(feature = "embed") && (pd.env_flags & services::ENV_FLAGS_NATIVE) == 0 {
+        /* the host program called us; return the exit status to it */
+        return;
+    }
     if (pd.env_id == services::ENV_ID_LINUX || pd.env_id == services::ENV_ID_MACOS)
         && (pd.env_flags & services::ENV_FLAGS_NO_EXIT) == 0
     {
@@ -59,23 +70,23 @@ pub fn try_exit() {
         unsafe {
             os::linux::syscall::exit_group(services::get_exit_status() as usize);
         }
-        #[cfg(target_arch = "aarch64")]
+        #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
         unsafe {
             os::macos::syscall::exit_group(services::get_exit_status() as usize);
         }
     }
 }
-#[cfg(not(test))]
+#[cfg(not(any(test, feature = "std")))]
 pub fn is_local_env() -> bool {
     let pd = services::platform_data();
     (pd.env_flags & services::ENV_FLAGS_NATIVE) != 0
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 pub fn init(_platform_data_by_loader: usize) {}
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 pub fn try_exit() {}
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 pub fn is_local_env() -> bool {
     true
 }
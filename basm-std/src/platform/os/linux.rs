@@ -1,4 +1,5 @@
 use super::super::allocator;
+#[cfg(not(feature = "bump-alloc"))]
 use super::super::malloc::{dlmalloc, dlmalloc_linux};
 
 pub mod syscall {
@@ -7,6 +8,9 @@ pub mod syscall {
     pub const PROT_WRITE: i32 = 0x02;
     pub const MAP_PRIVATE: i32 = 0x02;
     pub const MAP_ANON: i32 = 0x20;
+    pub const MAP_POPULATE: i32 = 0x8000;
+    pub const MAP_HUGETLB: i32 = 0x40000;
+    pub const MADV_HUGEPAGE: usize = 14;
     pub const MREMAP_MAYMOVE: i32 = 0x01;
     pub const MAP_FAILED: *mut u8 = usize::MAX as *mut u8;
     pub const RLIMIT_STACK: usize = 3;
@@ -18,6 +22,7 @@ pub mod syscall {
         pub const MMAP: usize = 9;
         pub const MREMAP: usize = 25;
         pub const MUNMAP: usize = 11;
+        pub const MADVISE: usize = 28;
         pub const EXIT_GROUP: usize = 231;
         pub const GETRLIMIT: usize = 97;
         pub const SETRLIMIT: usize = 160;
@@ -29,6 +34,7 @@ pub mod syscall {
         pub const MMAP: usize = 90;
         pub const MREMAP: usize = 163;
         pub const MUNMAP: usize = 91;
+        pub const MADVISE: usize = 219;
         pub const EXIT_GROUP: usize = 252;
         pub const GETRLIMIT: usize = 76;
         pub const SETRLIMIT: usize = 75;
@@ -40,6 +46,7 @@ pub mod syscall {
         pub const MMAP: usize = 222;
         pub const MREMAP: usize = 216;
         pub const MUNMAP: usize = 215;
+        pub const MADVISE: usize = 233;
         pub const EXIT_GROUP: usize = 94;
         pub const GETRLIMIT: usize = 163;
         pub const SETRLIMIT: usize = 164;
@@ -269,6 +276,10 @@ pub mod syscall {
         syscall(id_list::MUNMAP, addr as usize, len, 0, 0, 0, 0) as *mut u8
     }
     #[inline(always)]
+    pub unsafe fn madvise(addr: *mut u8, len: usize, advice: usize) -> usize {
+        syscall3(id_list::MADVISE, addr as usize, len, advice)
+    }
+    #[inline(always)]
     pub unsafe fn read(fd: usize, buf: *mut u8, count: usize) -> usize {
         syscall3(id_list::READ, fd, buf as usize, count)
     }
@@ -307,11 +318,14 @@ pub mod syscall {
     }
 }
 
+#[cfg(not(feature = "bump-alloc"))]
 static mut DLMALLOC: dlmalloc::Dlmalloc<dlmalloc_linux::System> =
     dlmalloc::Dlmalloc::new(dlmalloc_linux::System::new());
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_alloc(size: usize, align: usize) -> *mut u8 {
     DLMALLOC.memalign(align, size)
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
     let ptr = DLMALLOC.memalign(align, size);
     if !ptr.is_null() && DLMALLOC.calloc_must_clear(ptr) {
@@ -319,9 +333,11 @@ unsafe fn dlmalloc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
     }
     ptr
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_dealloc(ptr: *mut u8, _size: usize, _align: usize) {
     DLMALLOC.free(ptr);
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_realloc(
     ptr: *mut u8,
     old_size: usize,
@@ -330,6 +346,8 @@ unsafe fn dlmalloc_realloc(
 ) -> *mut u8 {
     if old_align <= DLMALLOC.malloc_alignment() {
         DLMALLOC.realloc(ptr, new_size)
+    } else if DLMALLOC.realloc_in_place(ptr, new_size) {
+        ptr
     } else {
         let ptr_new = DLMALLOC.memalign(old_align, new_size);
         if !ptr_new.is_null() {
@@ -340,6 +358,38 @@ unsafe fn dlmalloc_realloc(
     }
 }
 
+/* With `bump-alloc`, the global allocator only asks for large zeroed chunks and never frees them,
+ * so fresh mappings are handed out directly without linking dlmalloc. */
+#[cfg(feature = "bump-alloc")]
+unsafe fn mmap_alloc(size: usize, _align: usize) -> *mut u8 {
+    let addr = syscall::mmap(
+        core::ptr::null_mut(),
+        size,
+        syscall::PROT_WRITE | syscall::PROT_READ,
+        syscall::MAP_ANON | syscall::MAP_PRIVATE,
+        -1,
+        0,
+    );
+    if addr as usize > -4096isize as usize {
+        core::ptr::null_mut()
+    } else {
+        addr
+    }
+}
+#[cfg(feature = "bump-alloc")]
+unsafe fn mmap_dealloc(ptr: *mut u8, size: usize, _align: usize) {
+    syscall::munmap(ptr, size);
+}
+#[cfg(feature = "bump-alloc")]
+unsafe fn mmap_realloc(
+    _ptr: *mut u8,
+    _old_size: usize,
+    _old_align: usize,
+    _new_size: usize,
+) -> *mut u8 {
+    core::ptr::null_mut()
+}
+
 #[cfg(not(all(feature = "short", target_os = "linux")))]
 #[cfg(target_arch = "x86_64")]
 mod services_override {
@@ -388,12 +438,20 @@ pub unsafe fn init() {
         }
     }
 
+    #[cfg(feature = "fixed-heap")]
+    dlmalloc_linux::reserve_fixed_heap();
+    #[cfg(feature = "prefault-heap")]
+    dlmalloc_linux::prefault_heap();
+
+    #[cfg(not(feature = "bump-alloc"))]
     allocator::install_malloc_impl(
         dlmalloc_alloc,
         dlmalloc_alloc_zeroed,
         dlmalloc_dealloc,
         dlmalloc_realloc,
     );
+    #[cfg(feature = "bump-alloc")]
+    allocator::install_malloc_impl(mmap_alloc, mmap_alloc, mmap_dealloc, mmap_realloc);
 
     /* "short" on "Linux" will use syscalls directly to reduce code size */
     #[cfg(not(all(feature = "short", target_os = "linux")))]
@@ -60,6 +60,8 @@ unsafe fn dlmalloc_realloc(
 ) -> *mut u8 {
     if old_align <= DLMALLOC.malloc_alignment() {
         DLMALLOC.realloc(ptr, new_size)
+    } else if DLMALLOC.realloc_in_place(ptr, new_size) {
+        ptr
     } else {
         let ptr_new = DLMALLOC.memalign(old_align, new_size);
         if !ptr_new.is_null() {
@@ -1,9 +1,12 @@
 #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
 pub mod linux;
-#[cfg(target_arch = "aarch64")]
+#[cfg(all(target_arch = "aarch64", target_os = "macos"))]
 pub mod macos;
 pub mod unknown;
 #[cfg(target_arch = "wasm32")]
 pub mod wasm32;
-#[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+#[cfg(any(
+    not(any(target_arch = "wasm32", target_arch = "aarch64")),
+    all(target_arch = "aarch64", target_os = "windows")
+))]
 pub mod windows;
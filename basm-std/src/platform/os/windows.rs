@@ -9,11 +9,16 @@ macro_rules! ms_abi {
     (fn $args:tt) => { extern "win64" fn $args };
     (fn $args:tt $arrow: tt $rettype: ty) => { extern "win64" fn $args $arrow $rettype };
 }
-#[cfg(not(target_arch = "x86_64"))]
+#[cfg(target_arch = "x86")]
 macro_rules! ms_abi {
     (fn $args:tt) => { extern "stdcall" fn $args };
     (fn $args:tt $arrow: tt $return_type: ty) => { extern "stdcall" fn $args $arrow $return_type };
 }
+#[cfg(target_arch = "aarch64")]
+macro_rules! ms_abi {
+    (fn $args:tt) => { extern "C" fn $args };
+    (fn $args:tt $arrow: tt $rettype: ty) => { extern "C" fn $args $arrow $rettype };
+}
 // Note: to return nothing with basm_abi, use the equivalent form '-> ()'
 #[cfg(target_arch = "x86_64")]
 macro_rules! basm_abi {
@@ -189,6 +194,8 @@ unsafe fn dlmalloc_realloc(
 ) -> *mut u8 {
     if old_align <= DLMALLOC.malloc_alignment() {
         DLMALLOC.realloc(ptr, new_size)
+    } else if DLMALLOC.realloc_in_place(ptr, new_size) {
+        ptr
     } else {
         let ptr_new = DLMALLOC.memalign(old_align, new_size);
         if !ptr_new.is_null() {
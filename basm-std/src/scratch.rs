@@ -0,0 +1,174 @@
+use alloc::alloc::{alloc, dealloc, handle_alloc_error};
+use core::alloc::Layout;
+use core::cell::Cell;
+use core::ptr;
+
+const BLOCK_ALIGN: usize = 16;
+
+/// A growable block of memory shared by all calls to [`with_buffer`].
+///
+/// `top` is the number of bytes in use by the calls currently running, so nested calls
+/// take the space after the buffers of the outer calls.
+struct Scratch {
+    ptr: Cell<*mut u8>,
+    cap: Cell<usize>,
+    top: Cell<usize>,
+}
+
+// SAFETY: `ptr`, `cap` and `top` are plain `Cell`s updated without synchronization.
+// basm runs solutions on a single thread, so `SCRATCH` is never accessed concurrently.
+unsafe impl Sync for Scratch {}
+
+static SCRATCH: Scratch = Scratch {
+    ptr: Cell::new(ptr::null_mut()),
+    cap: Cell::new(0),
+    top: Cell::new(0),
+};
+
+/// Restores `top` when a call to [`with_buffer`] returns (or unwinds),
+/// and frees the temporary allocation if the call did not fit in the shared block.
+struct Guard {
+    top: usize,
+    temp: Option<(*mut u8, Layout)>,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        SCRATCH.top.set(self.top);
+        if let Some((ptr, layout)) = self.temp {
+            unsafe { dealloc(ptr, layout) };
+        }
+    }
+}
+
+/// Calls `f` with a buffer of `len` elements, each initialized to `T::default()`.
+///
+/// The buffer is carved from a static block that is kept across calls and only grows,
+/// so calling this in a per-query loop (e.g., for a BFS queue) does not go through the allocator
+/// once the block is large enough. Calls can be nested; the inner call gets the space after the outer buffer.
+/// If a nested call does not fit in the block, or `T` needs an alignment over 16 bytes,
+/// the buffer is allocated and freed for that call instead.
+pub fn with_buffer<T: Copy + Default, R>(len: usize, f: impl FnOnce(&mut [T]) -> R) -> R {
+    let layout = Layout::array::<T>(len).unwrap();
+    let top = SCRATCH.top.get();
+    let mut guard = Guard { top, temp: None };
+    let buf = if layout.size() == 0 {
+        ptr::NonNull::<T>::dangling().as_ptr()
+    } else if layout.align() > BLOCK_ALIGN {
+        let ptr = unsafe { alloc(layout) };
+        if ptr.is_null() {
+            handle_alloc_error(layout);
+        }
+        guard.temp = Some((ptr, layout));
+        ptr as *mut T
+    } else {
+        let start = (top + layout.align() - 1) & !(layout.align() - 1);
+        let end = start + layout.size();
+        if end > SCRATCH.cap.get() {
+            if top == 0 {
+                grow(end);
+            } else {
+                let ptr = unsafe { alloc(layout) };
+                if ptr.is_null() {
+                    handle_alloc_error(layout);
+                }
+                guard.temp = Some((ptr, layout));
+            }
+        }
+        match guard.temp {
+            Some((ptr, _)) => ptr as *mut T,
+            None => {
+                SCRATCH.top.set(end);
+                unsafe { SCRATCH.ptr.get().add(start) as *mut T }
+            }
+        }
+    };
+    let buf = unsafe {
+        for i in 0..len {
+            buf.add(i).write(T::default());
+        }
+        core::slice::from_raw_parts_mut(buf, len)
+    };
+    f(buf)
+}
+
+/// Replaces the (unused) block with one of at least `size` bytes.
+#[cold]
+#[inline(never)]
+fn grow(size: usize) {
+    let cap = size.max(SCRATCH.cap.get() * 2);
+    let cap = (cap + BLOCK_ALIGN - 1) & !(BLOCK_ALIGN - 1);
+    release();
+    let layout = Layout::from_size_align(cap, BLOCK_ALIGN).unwrap();
+    let ptr = unsafe { alloc(layout) };
+    if ptr.is_null() {
+        handle_alloc_error(layout);
+    }
+    SCRATCH.ptr.set(ptr);
+    SCRATCH.cap.set(cap);
+}
+
+/// Frees the static block. The next call to [`with_buffer`] allocates a new one.
+/// This has no effect when called inside [`with_buffer`].
+pub fn release() {
+    let ptr = SCRATCH.ptr.get();
+    if SCRATCH.top.get() != 0 || ptr.is_null() {
+        return;
+    }
+    unsafe {
+        dealloc(
+            ptr,
+            Layout::from_size_align_unchecked(SCRATCH.cap.get(), BLOCK_ALIGN),
+        )
+    };
+    SCRATCH.ptr.set(ptr::null_mut());
+    SCRATCH.cap.set(0);
+}
+
+/// Returns the size in bytes of the static block.
+pub fn capacity() -> usize {
+    SCRATCH.cap.get()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /* The scratch block is shared by the whole process, so everything is checked in one test
+    to keep the other tests from running concurrently on it. */
+    #[test]
+    fn buffers_are_reused_and_nest_without_overlap() {
+        let first = with_buffer(1000, |buf: &mut [u32]| {
+            assert!(buf.iter().all(|&x| x == 0));
+            buf.fill(7);
+            buf.as_ptr() as usize
+        });
+        let cap = capacity();
+        assert!(cap >= 4000);
+        for n in [1, 10, 500, 1000] {
+            let ptr = with_buffer(n, |buf: &mut [u32]| {
+                assert!(buf.iter().all(|&x| x == 0));
+                buf.as_ptr() as usize
+            });
+            assert_eq!(first, ptr);
+        }
+        assert_eq!(cap, capacity());
+
+        with_buffer(100, |outer: &mut [u8]| {
+            outer.fill(1);
+            with_buffer(10, |inner: &mut [u64]| {
+                assert_eq!(0, inner.as_ptr() as usize % 8);
+                assert!(inner.as_ptr() as usize >= outer.as_ptr() as usize + 100);
+                inner.fill(u64::MAX);
+            });
+            with_buffer(1 << 20, |big: &mut [u8]| big.fill(2));
+            assert!(outer.iter().all(|&x| x == 1));
+        });
+        assert!(with_buffer(0, |buf: &mut [u16]| buf.is_empty()));
+
+        release();
+        assert_eq!(0, capacity());
+        with_buffer(3, |buf: &mut [i64]| assert_eq!(&[0, 0, 0], buf));
+        assert!(capacity() > 0);
+    }
+}
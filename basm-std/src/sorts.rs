@@ -41,13 +41,13 @@ fn sort_radix_by_key_rec<T, F: (Fn(&T) -> K) + Copy, K: Binary>(arr: &mut [T], k
     for v in arr.iter() {
         count[key_f(v).get_byte_at(pos) as usize] += 1;
     }
-    let mut last: [MaybeUninit<u32>; 257] = MaybeUninit::uninit_array();
+    let mut last: [MaybeUninit<u32>; 257] = unsafe { MaybeUninit::uninit().assume_init() };
     last[0].write(0);
     last[1].write(0);
     for i in 2..=256 {
         last[i].write(unsafe { last[i - 1].assume_init_read() } + count[i - 2]);
     }
-    let mut last = unsafe { MaybeUninit::array_assume_init(last) };
+    let mut last = unsafe { core::mem::transmute::<[MaybeUninit<u32>; 257], [u32; 257]>(last) };
     for i in 0..256 {
         let end = last[i] + count[i];
         if end == arr.len() as u32 {
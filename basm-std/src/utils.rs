@@ -1,2 +1,10 @@
 pub mod f64;
 pub use f64::*;
+pub mod table;
+pub use table::*;
+pub mod mo;
+pub use mo::*;
+#[cfg(test)]
+mod test_rng;
+#[cfg(test)]
+pub(crate) use test_rng::TestRng;
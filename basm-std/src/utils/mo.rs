@@ -0,0 +1,143 @@
+use alloc::vec::Vec;
+
+/// The order in which [`mo`] visits the queries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoOrder {
+    /// Sorts by the block of `l`, then by `r` alternating up and down between blocks.
+    /// The pointers move O(n sqrt q) times in total.
+    Block,
+    /// Sorts along a Hilbert curve over `(l, r)`, which usually moves the pointers less
+    /// than [`MoOrder::Block`] when there are many queries.
+    Hilbert,
+}
+
+fn hilbert(mut x: u64, mut y: u64, bits: u32) -> u64 {
+    let mut d = 0;
+    let mut s = 1u64 << bits.saturating_sub(1);
+    while s > 0 {
+        let rx = (x & s != 0) as u64;
+        let ry = (y & s != 0) as u64;
+        d += s * s * ((3 * rx) ^ ry);
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - (x & (s - 1));
+                y = s - 1 - (y & (s - 1));
+            }
+            core::mem::swap(&mut x, &mut y);
+        }
+        x &= s - 1;
+        y &= s - 1;
+        s >>= 1;
+    }
+    d
+}
+
+/// Runs Mo's algorithm over the half-open ranges `queries[q] = (l, r)` of an array of length `n`.
+///
+/// The current range starts empty. `add(state, i)` and `remove(state, i)` extend and shrink it by
+/// the element `i`, and `answer(state, q)` is called once for each query `q` when the current range
+/// equals `queries[q]`. Elements may be added and removed at either end, so the state should not
+/// depend on their order. `state` is passed to every callback so that they can all mutate it.
+pub fn mo<S, A, R, F>(
+    n: usize,
+    queries: &[(usize, usize)],
+    order: MoOrder,
+    state: &mut S,
+    mut add: A,
+    mut remove: R,
+    mut answer: F,
+) where
+    A: FnMut(&mut S, usize),
+    R: FnMut(&mut S, usize),
+    F: FnMut(&mut S, usize),
+{
+    let mut idx: Vec<u32> = (0..queries.len() as u32).collect();
+    match order {
+        MoOrder::Block => {
+            let block = (n / queries.len().isqrt().max(1)).max(1);
+            idx.sort_unstable_by_key(|&q| {
+                let (l, r) = queries[q as usize];
+                let b = l / block;
+                (b, if b & 1 == 0 { r } else { usize::MAX - r })
+            });
+        }
+        MoOrder::Hilbert => {
+            let bits = usize::BITS - n.leading_zeros();
+            let keys: Vec<u64> = queries
+                .iter()
+                .map(|&(l, r)| hilbert(l as u64, r as u64, bits))
+                .collect();
+            idx.sort_unstable_by_key(|&q| keys[q as usize]);
+        }
+    }
+    let (mut cl, mut cr) = (0, 0);
+    for q in idx {
+        let (l, r) = queries[q as usize];
+        assert!(l <= r && r <= n);
+        while cl > l {
+            cl -= 1;
+            add(state, cl);
+        }
+        while cr < r {
+            add(state, cr);
+            cr += 1;
+        }
+        while cl < l {
+            remove(state, cl);
+            cl += 1;
+        }
+        while cr > r {
+            cr -= 1;
+            remove(state, cr);
+        }
+        answer(state, q as usize);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distinct_counts_match_naive() {
+        let a: Vec<usize> = (0..200).map(|i| (i * i * 7 + 3) % 23).collect();
+        let queries: Vec<(usize, usize)> = (0..300)
+            .map(|q| {
+                let (x, y) = ((q * 37) % 201, (q * 91 + 5) % 201);
+                (x.min(y), x.max(y))
+            })
+            .collect();
+        for order in [MoOrder::Block, MoOrder::Hilbert] {
+            struct State {
+                cnt: [u32; 23],
+                distinct: u32,
+                ans: Vec<u32>,
+            }
+            let mut st = State {
+                cnt: [0; 23],
+                distinct: 0,
+                ans: alloc::vec![0; queries.len()],
+            };
+            mo(
+                a.len(),
+                &queries,
+                order,
+                &mut st,
+                |s, i| {
+                    s.cnt[a[i]] += 1;
+                    s.distinct += (s.cnt[a[i]] == 1) as u32;
+                },
+                |s, i| {
+                    s.cnt[a[i]] -= 1;
+                    s.distinct -= (s.cnt[a[i]] == 0) as u32;
+                },
+                |s, q| s.ans[q] = s.distinct,
+            );
+            for (q, &(l, r)) in queries.iter().enumerate() {
+                let mut seen = [false; 23];
+                a[l..r].iter().for_each(|&x| seen[x] = true);
+                assert_eq!(seen.iter().filter(|&&b| b).count() as u32, st.ans[q]);
+            }
+        }
+    }
+}
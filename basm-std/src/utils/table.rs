@@ -0,0 +1,142 @@
+use core::cell::{Cell, UnsafeCell};
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+
+/// Integer types that can be stored in a [`Table`].
+pub trait TableElement: Copy + 'static {
+    /// Converts the value decoded modulo 2^64 into `Self` by truncation.
+    fn from_u64(x: u64) -> Self;
+}
+
+macro_rules! impl_table_element {
+    ($($t:ty),*) => {
+        $(impl TableElement for $t {
+            fn from_u64(x: u64) -> Self {
+                x as $t
+            }
+        })*
+    };
+}
+impl_table_element!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// A constant table of `N` integers embedded in a compact form by `basm_macro::include_table!`.
+///
+/// The entries are stored as the zigzag-encoded differences between consecutive entries in LEB128,
+/// and are decoded into a [`TableBuffer`] on first access. Afterwards, the table derefs to `[T; N]`.
+pub struct Table<T: TableElement, const N: usize> {
+    encoded: &'static [u8],
+    buffer: &'static TableBuffer<T, N>,
+}
+
+/// The storage a [`Table`] is decoded into. It is kept in a static of its own, which is all zeros
+/// and thus placed in .bss rather than taking `N` entries of space in the binary.
+pub struct TableBuffer<T: TableElement, const N: usize> {
+    decoded: UnsafeCell<MaybeUninit<[T; N]>>,
+    ready: Cell<bool>,
+}
+
+// SAFETY: `decoded` is written and `ready` is set on first access without synchronization.
+// basm runs solutions on a single thread, so a table is never decoded concurrently.
+unsafe impl<T: TableElement, const N: usize> Sync for TableBuffer<T, N> {}
+
+impl<T: TableElement, const N: usize> TableBuffer<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            decoded: UnsafeCell::new(MaybeUninit::uninit()),
+            ready: Cell::new(false),
+        }
+    }
+}
+
+impl<T: TableElement, const N: usize> Default for TableBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: TableElement, const N: usize> Table<T, N> {
+    pub const fn new(encoded: &'static [u8], buffer: &'static TableBuffer<T, N>) -> Self {
+        Self { encoded, buffer }
+    }
+
+    /// Returns the decoded table, decoding it if this is the first access.
+    pub fn get(&self) -> &[T; N] {
+        if !self.buffer.ready.get() {
+            self.decode();
+        }
+        unsafe { (*self.buffer.decoded.get()).assume_init_ref() }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn decode(&self) {
+        let ptr = self.buffer.decoded.get() as *mut T;
+        let (mut pos, mut cur) = (0, 0u64);
+        for i in 0..N {
+            let (mut z, mut shift) = (0u64, 0);
+            loop {
+                let b = self.encoded[pos];
+                pos += 1;
+                z |= ((b & 0x7f) as u64) << shift;
+                shift += 7;
+                if b < 0x80 {
+                    break;
+                }
+            }
+            cur = cur.wrapping_add((z >> 1) ^ (z & 1).wrapping_neg());
+            unsafe { ptr.add(i).write(T::from_u64(cur)) };
+        }
+        self.buffer.ready.set(true);
+    }
+}
+
+impl<T: TableElement, const N: usize> Deref for Table<T, N> {
+    type Target = [T; N];
+    fn deref(&self) -> &[T; N] {
+        self.get()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn leaked<T: TableElement, const N: usize>(values: &[i64]) -> Table<T, N> {
+        let encoded = Box::leak(encode(values).into_boxed_slice());
+        Table::new(encoded, Box::leak(Box::new(TableBuffer::new())))
+    }
+
+    // The same encoding as in basm-macro
+    fn encode(values: &[i64]) -> Vec<u8> {
+        let mut out = vec![];
+        let mut prev = 0u64;
+        for &v in values {
+            let delta = (v as u64).wrapping_sub(prev) as i64;
+            let mut z = ((delta << 1) ^ (delta >> 63)) as u64;
+            while z >= 0x80 {
+                out.push((z as u8 & 0x7f) | 0x80);
+                z >>= 7;
+            }
+            out.push(z as u8);
+            prev = v as u64;
+        }
+        out
+    }
+
+    #[test]
+    fn decode_monotone_and_signed() {
+        let primes = [2i64, 3, 5, 7, 11, 13, 17, 19, 23, 29, 1_000_000_007];
+        let table = leaked::<u32, 11>(&primes);
+        assert_eq!(table[0], 2);
+        assert_eq!(table[10], 1_000_000_007);
+        assert_eq!(table.len(), 11);
+
+        let values = [-5i64, 100, i32::MIN as i64, i32::MAX as i64, 0];
+        let table = leaked::<i32, 5>(&values);
+        assert_eq!(*table, [-5, 100, i32::MIN, i32::MAX, 0]);
+
+        let values = [u64::MAX as i64, 0, u64::MAX as i64];
+        let table = leaked::<u64, 3>(&values);
+        assert_eq!(*table, [u64::MAX, 0, u64::MAX]);
+    }
+}
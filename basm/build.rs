@@ -1,4 +1,5 @@
 use std::env;
+use std::path::Path;
 use std::process::Command;
 
 fn main() {
@@ -7,6 +8,37 @@ fn main() {
     let mut link_args_basm_submit = vec![];
 
     println!("cargo:rerun-if-changed=build.rs");
+
+    // The solution may be either a single file (src/solution.rs) or
+    // a directory of modules (src/solution/main.rs along with its submodules).
+    println!("cargo:rerun-if-changed=src/solution");
+    println!("cargo:rerun-if-changed=src/solution.rs");
+    println!("cargo::rustc-check-cfg=cfg(basm_solution_dir)");
+    let has_solution_dir = Path::new("src/solution/main.rs").exists();
+    if has_solution_dir && Path::new("src/solution.rs").exists() {
+        panic!(
+            "Both src/solution.rs and src/solution/main.rs exist; please keep only one of them."
+        );
+    }
+    if has_solution_dir {
+        println!("cargo:rustc-cfg=basm_solution_dir");
+    }
+
+    // Values of `basm_macro::problem_consts!` are read from problem.toml, or from the file
+    // named by BASM_PROBLEM (relative to this crate) so that one can switch between problems.
+    println!("cargo:rerun-if-env-changed=BASM_PROBLEM");
+    let problem_toml = env::var("BASM_PROBLEM").unwrap_or_else(|_| "problem.toml".to_owned());
+    let problem_toml = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(problem_toml);
+    println!("cargo:rerun-if-changed={}", problem_toml.display());
+    println!(
+        "cargo:rustc-env=BASM_PROBLEM_TOML={}",
+        problem_toml.display()
+    );
+
+    // With the `std` feature, the binaries are ordinary host programs linked against std.
+    if env::var("CARGO_FEATURE_STD").is_ok() {
+        return;
+    }
     match target.as_str() {
         "x86_64-pc-windows-msvc" => {
             link_args_basm.push("/SUBSYSTEM:CONSOLE");
@@ -17,9 +49,21 @@ fn main() {
             link_args_basm.push("/STACK:268435456");
             link_args_basm.push("/EMITTOOLVERSIONINFO:NO");
             link_args_basm.push("/EMITPOGOPHASEINFO");
+            link_args_basm.push("/Brepro"); // deterministic timestamps for reproducible builds
             link_args_basm_submit.push("/ALIGN:128");
             link_args_basm_submit.push("/OPT:REF,ICF");
         }
+        "aarch64-pc-windows-msvc" => {
+            // ARM64 images are always ASLR- and DEP-compatible; /NXCOMPAT:NO is not supported.
+            link_args_basm.push("/SUBSYSTEM:CONSOLE");
+            link_args_basm.push("/NODEFAULTLIB");
+            link_args_basm.push("/DYNAMICBASE");
+            link_args_basm.push("/ENTRY:_basm_start");
+            link_args_basm.push("/STACK:268435456");
+            link_args_basm.push("/EMITTOOLVERSIONINFO:NO");
+            link_args_basm.push("/EMITPOGOPHASEINFO");
+            link_args_basm.push("/Brepro"); // deterministic timestamps for reproducible builds
+        }
         "x86_64-pc-windows-gnu" => {
             if env::consts::OS == "windows" {
                 panic!(
@@ -49,6 +93,29 @@ fn main() {
             link_args_basm.push("-nodefaultlibs");
             link_args_basm.push("-Wl,--entry=_basm_start,--dynamicbase,--high-entropy-va,--disable-nxcompat,--stack,268435456,--build-id=none,--gc-sections,--export-dynamic");
         }
+        "x86_64-unknown-linux-gnu" if !env::var("HOST").unwrap().contains("linux") => {
+            // Cross-compiling from macOS or Windows, where no GCC targeting Linux is assumed.
+            // static-pie.sh selects the rust-lld bundled with the toolchain as the linker,
+            // which is invoked directly as ld.lld; hence, the options are passed without -Wl.
+            link_args_basm.push("-static");
+            link_args_basm.push("-pie");
+            link_args_basm.push("--no-dynamic-linker");
+            link_args_basm.push("--entry=_basm_start");
+            link_args_basm.push("--build-id=none");
+            link_args_basm.push("--gc-sections");
+            link_args_basm.push("--export-dynamic");
+            link_args_basm.push("--no-eh-frame-hdr");
+            link_args_basm.push("-znorelro");
+            link_args_basm_submit.push("-zmax-page-size=128");
+            link_args_basm_submit.push("-zpack-relative-relocs");
+            println!("cargo:rerun-if-changed=link/x86_64-linux-submit.ld");
+            let script = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+                .join("link/x86_64-linux-submit.ld");
+            println!(
+                "cargo:rustc-link-arg-bin=basm-submit=--script={}",
+                script.display()
+            );
+        }
         "x86_64-unknown-linux-gnu"
         | "x86_64-unknown-linux-gnu-short"
         | "i686-unknown-linux-gnu" => {
@@ -68,6 +135,20 @@ fn main() {
                 link_args_basm.push("-Wl,--entry=_basm_start,--build-id=none,--gc-sections,--export-dynamic,--no-eh-frame-hdr,-z,norelro");
             }
             link_args_basm_submit.push("-Wl,-z,max-page-size=128");
+            if target != "i686-unknown-linux-gnu" {
+                // Emit relative relocations in the compact SHT_RELR format (requires binutils >= 2.38).
+                link_args_basm_submit.push("-Wl,-z,pack-relative-relocs");
+            }
+            if target == "x86_64-unknown-linux-gnu" {
+                // Place all sections in a single segment without padding (see link/x86_64-linux-submit.ld).
+                println!("cargo:rerun-if-changed=link/x86_64-linux-submit.ld");
+                let script = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+                    .join("link/x86_64-linux-submit.ld");
+                println!(
+                    "cargo:rustc-link-arg-bin=basm-submit=-Wl,-T,{}",
+                    script.display()
+                );
+            }
         }
         "aarch64-apple-darwin" => {
             link_args_basm.push("-nostartfiles");
@@ -1,13 +1,14 @@
-#![cfg_attr(not(test), no_builtins)]
-#![cfg_attr(not(test), no_std)]
-#![cfg_attr(not(test), no_main)]
+#![cfg_attr(not(any(test, feature = "std")), no_builtins)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_main)]
 
 extern crate alloc;
 extern crate basm_std as basm;
 mod lang_items;
 
 #[cfg_attr(test, allow(dead_code))]
-#[path = "../solution.rs"]
+#[cfg_attr(basm_solution_dir, path = "../solution/main.rs")]
+#[cfg_attr(not(basm_solution_dir), path = "../solution.rs")]
 mod solution;
 
 #[cfg(test)]
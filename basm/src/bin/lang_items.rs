@@ -3,7 +3,7 @@ extern "C" fn _basm_main() {
     crate::solution::main()
 }
 
-#[cfg(not(test))]
+#[cfg(not(any(test, feature = "std")))]
 mod runtime {
     #[global_allocator]
     static ALLOC: basm::platform::allocator::Allocator = basm::platform::allocator::Allocator;
@@ -14,7 +14,7 @@ mod runtime {
 
     #[no_mangle]
     #[cfg(target_os = "windows")]
-    extern "win64" fn __CxxFrameHandler3() -> ! {
+    extern "system" fn __CxxFrameHandler3() -> ! {
         unsafe { core::hint::unreachable_unchecked() }
     }
 
@@ -22,7 +22,7 @@ mod runtime {
     // This should be removed later.
     #[no_mangle]
     #[cfg(target_os = "windows")]
-    extern "win64" fn __unordtf2() {}
+    extern "system" fn __unordtf2() {}
 
     #[no_mangle]
     #[allow(non_snake_case)]
@@ -0,0 +1,8 @@
+@echo off
+python scripts/new-problem.py %* || goto :error
+
+:; exit 0
+exit /b 0
+
+:error
+exit /b %errorlevel%
\ No newline at end of file
@@ -0,0 +1 @@
+python3 scripts/new-problem.py "$@"
@@ -0,0 +1,6 @@
+#!/bin/bash
+set -e
+# Builds the Windows image with MinGW first, and then embeds it in the Linux C output along with the Linux image
+# so that the same submission runs on both Windows and Linux judges (see BASM_WINDOWS_BINARY in static-pie-gen.py)
+cargo +nightly build --target x86_64-pc-windows-gnu --bin basm-submit --features=submit --release "$@"
+BASM_WINDOWS_BINARY=target/x86_64-pc-windows-gnu/release/basm-submit.exe scripts/static-pie.sh x86_64-unknown-linux-gnu C Release "$@"
@@ -0,0 +1,9 @@
+@echo off
+cargo +nightly build --target x86_64-unknown-linux-gnu --config "target.x86_64-unknown-linux-gnu.linker='rust-lld'" --bin basm-submit --features=submit --release || goto :error
+python scripts/static-pie-gen.py basm/src/solution.rs x86_64-unknown-linux-gnu target/x86_64-unknown-linux-gnu/release/basm-submit static-pie-stub-amd64.bin C static-pie-template-amd64.c || goto :error
+
+:; exit 0
+exit /b 0
+
+:error
+exit /b %errorlevel%
\ No newline at end of file
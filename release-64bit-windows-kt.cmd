@@ -0,0 +1,9 @@
+@echo off
+cargo +nightly build --target x86_64-pc-windows-msvc --bin basm-submit --features=submit --release || goto :error
+python scripts/static-pie-gen.py basm/src/solution.rs x86_64-pc-windows-msvc target/x86_64-pc-windows-msvc/release/basm-submit.exe static-pie-stub-amd64.bin Kotlin static-pie-template-amd64.kt || goto :error
+
+:; exit 0
+exit /b 0
+
+:error
+exit /b %errorlevel%
\ No newline at end of file
@@ -0,0 +1 @@
+scripts/static-pie.sh x86_64-unknown-linux-gnu JavaScript Release "$@"
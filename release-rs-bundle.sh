@@ -0,0 +1,3 @@
+#!/bin/bash
+# Bundles the solution and the basm-std modules it uses into a single Rust file without machine code (see scripts/rust-bundle.py)
+python3 scripts/rust-bundle.py basm/ "$@"
@@ -0,0 +1,39 @@
+#!/bin/bash
+set -e
+# Builds one submission per variant of the solution, e.g.,
+#   ./release-variants.sh release-py.sh subtask1 subtask3
+# writes target/variants/subtask1.py and target/variants/subtask3.py, each built with the respective feature enabled.
+# Arguments after `--` are passed to the release script (and thus to cargo).
+if [[ $# -lt 2 ]]; then
+  >&2 echo "Usage: $0 release-script variant... [-- extra-args...]"
+  exit 1
+fi
+release_script="$1"
+shift
+variants=()
+while [[ $# -gt 0 && "$1" != "--" ]]; do
+  variants+=("$1")
+  shift
+done
+if [[ "$1" == "--" ]]; then
+  shift
+fi
+
+case "$release_script" in
+  *-py.sh) ext="py" ;;
+  *-rb.sh) ext="rb" ;;
+  *-rs.sh|*-rs-mingw.sh|*-rs-bundle.sh) ext="rs" ;;
+  *-java.sh) ext="java" ;;
+  *-js.sh) ext="js" ;;
+  *-cs.sh) ext="cs" ;;
+  *-html.sh) ext="html" ;;
+  *-fn-impl.sh|*-fn-impl-mingw.sh) ext="cpp" ;;
+  *) ext="c" ;;
+esac
+
+mkdir -p target/variants
+for variant in "${variants[@]}"; do
+  >&2 echo "Building variant ${variant}"
+  ./"${release_script#./}" --features "$variant" "$@" > target/variants/"$variant"."$ext"
+  >&2 echo "Wrote target/variants/${variant}.${ext}"
+done
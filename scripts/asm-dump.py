@@ -0,0 +1,106 @@
+"""
+asm-dump.py: prints the disassembly of the linked payload interleaved with the
+Rust source lines it was generated from, so that one can check what the optimizer
+did to the hot loops before submitting.
+
+Usage:
+    python3 scripts/asm-dump.py binary_path [--all | --function regex]
+
+The binary must not be stripped and must carry line tables
+(build with `--config profile.release.strip=false --config profile.release.debug="line-tables-only"`;
+see asm-dump.sh). By default, only the functions containing code from the solution
+(`basm/src/solution.rs` or `basm/solution/`) are shown, which includes the library code inlined into them.
+With `--function`, the functions whose demangled names match the regex are shown instead.
+"""
+
+import os
+import re
+import subprocess
+import sys
+
+def disassemble(binary_path):
+    # Returns the list of (function, [(source location or None, instruction)]) in Intel syntax.
+    out = subprocess.run(["objdump", "-d", "-l", "-C", "-M", "intel", "--no-show-raw-insn", binary_path],
+        capture_output=True, text=True, check=True).stdout
+    funcs, loc = [], None
+    for line in out.split("\n"):
+        m = re.match(r"^[0-9a-f]+ <(.*)>:$", line)
+        if m is not None:
+            funcs.append((m.group(1), []))
+            loc = None
+            continue
+        if len(funcs) == 0 or len(line) == 0:
+            continue
+        m = re.match(r"^\s*([0-9a-f]+):\s+(.*)$", line)
+        if m is not None:
+            funcs[-1][1].append((loc, "{0:>8}:  {1}".format(m.group(1), m.group(2).rstrip())))
+            continue
+        m = re.match(r"^(\S.*):(\d+)(?: \(discriminator \d+\))?$", line)
+        if m is not None:
+            loc = (m.group(1), int(m.group(2)))
+    return funcs
+
+source_cache = dict()
+def source_line(path, line):
+    # Paths in the line tables are either absolute or relative to the workspace or the crate (trim-paths)
+    if path not in source_cache:
+        source_cache[path] = None
+        for candidate in [path, os.path.join("basm", path)]:
+            if os.path.isfile(candidate):
+                with open(candidate, encoding="utf8", errors="replace") as f:
+                    source_cache[path] = f.read().split("\n")
+                break
+    lines = source_cache[path]
+    if lines is None or not (1 <= line <= len(lines)):
+        return None
+    return lines[line - 1].strip()
+
+def is_solution(path):
+    path = path.replace("\\", "/")
+    return path.endswith("src/solution.rs") or "/solution/" in path or path.startswith("solution/")
+
+def clean_name(name):
+    # Strip the hashes so that the names are stable across builds
+    return re.sub(r"::h[0-9a-f]{16}$", "", name)
+
+if __name__ == '__main__':
+    args = sys.argv[1:]
+    pattern = None
+    show_all = "--all" in args
+    args = [x for x in args if x != "--all"]
+    if "--function" in args:
+        idx = args.index("--function")
+        pattern = re.compile(args[idx + 1]) if idx + 1 < len(args) else None
+        del args[idx:idx+2]
+        if pattern is None:
+            args = []
+    if len(args) != 1:
+        print(f"Usage: {sys.argv[0]} binary_path [--all | --function regex]", file=sys.stderr)
+        sys.exit(1)
+
+    funcs = disassemble(args[0])
+    if not any(loc is not None for _, insns in funcs for loc, _ in insns):
+        print("No line tables found. Please build with `--config profile.release.debug=\"line-tables-only\"`.", file=sys.stderr)
+        sys.exit(1)
+
+    shown = 0
+    for name, insns in funcs:
+        name = clean_name(name)
+        if pattern is not None:
+            if pattern.search(name) is None:
+                continue
+        elif not show_all and not any(loc is not None and is_solution(loc[0]) for loc, _ in insns):
+            continue
+        shown += 1
+        print("{0}:".format(name))
+        last_loc = None
+        for loc, insn in insns:
+            if loc is not None and loc != last_loc:
+                src = source_line(*loc)
+                print("  ; {0}:{1}{2}".format(loc[0], loc[1], "" if src is None else "  " + src))
+                last_loc = loc
+            print(insn)
+        print()
+    if shown == 0:
+        print("No matching functions found.", file=sys.stderr)
+        sys.exit(1)
@@ -36,7 +36,7 @@ if __name__ == '__main__':
     sol_path = sys.argv[5]
     indata_path = sys.argv[6]
     outdata_path = sys.argv[7]
-    src_ext = {"Cargo": "cargo", "C": "c", "Rust": "rs", "JavaScript": "js"}[language]
+    src_ext = {"Cargo": "cargo", "C": "c", "Rust": "rs", "Python": "py", "Ruby": "rb", "Java": "java", "Kotlin": "kt", "CSharp": "cs", "JavaScript": "js"}[language]
     if language == "Cargo":
         build_cmd = ["cargo build --release --message-format=json"]
         if platform.system() == "Windows":
@@ -108,6 +108,22 @@ if __name__ == '__main__':
             os.system("rustc -C opt-level=3 -o {1} --crate-type=bin {0}".format(src_path, bin_path))
         else:
             os.system("rustc -C opt-level=3 -o {1} {0}".format(src_path, bin_path))
+    elif language == "Python":
+        run_cmd = [sys.executable, src_path]
+    elif language == "Ruby":
+        run_cmd = ["ruby", src_path]
+    elif language == "Java":
+        run_cmd = ["java", src_path]     # single-file source-code launch
+    elif language == "Kotlin":
+        jar_path = os.path.join(tmp_dir, "loader.jar")
+        os.system("kotlinc {0} -include-runtime -d {1}".format(src_path, jar_path))
+        run_cmd = ["java", "--enable-native-access=ALL-UNNAMED", "-jar", jar_path]
+    elif language == "CSharp":
+        with open(os.path.join(tmp_dir, "loader.csproj"), mode="w", encoding="utf8") as f:
+            f.write('<Project Sdk="Microsoft.NET.Sdk"><PropertyGroup><OutputType>Exe</OutputType>' +
+                '<TargetFramework>net8.0</TargetFramework></PropertyGroup></Project>\n')
+        os.system("dotnet build -c Release -o {0} {1}".format(os.path.join(tmp_dir, "bin"), os.path.join(tmp_dir, "loader.csproj")))
+        run_cmd = ["dotnet", os.path.join(tmp_dir, "bin", "loader.dll")]
     elif language == "JavaScript":
         run_cmd = ["node", src_path]
     else: # language == "Cargo"
@@ -0,0 +1,72 @@
+"""
+This script creates a skeleton for solving a new problem.
+Usage:
+    python3 scripts/new-problem.py [problem-id] [--samples N]
+Example:
+    python3 scripts/new-problem.py boj_1000 --samples 2
+
+The following files are created under problems/[problem-id]/:
+    solution.rs             solution skeleton
+    sample_1.in, ...        sample inputs (to be filled in)
+    sample_1.out, ...       sample outputs (to be filled in)
+    ci.json                 test configuration for scripts/ci.py
+"""
+
+import json
+import os
+import re
+import sys
+
+SOLUTION_SKELETON = """use basm::platform::io::{Print, Reader, ReaderTrait, Writer};
+pub fn main() {
+    let mut reader: Reader = Default::default();
+    let mut writer: Writer = Default::default();
+}
+"""
+
+if __name__ == '__main__':
+    args = sys.argv[1:]
+    num_samples = 1
+    if "--samples" in args:
+        idx = args.index("--samples")
+        try:
+            num_samples = int(args[idx + 1])
+        except (IndexError, ValueError):
+            num_samples = -1
+        del args[idx:idx+2]
+    if len(args) != 1 or num_samples < 0:
+        print(f"Usage: {sys.argv[0]} [problem-id] [--samples N]", file=sys.stderr)
+        sys.exit(1)
+    problem_id = args[0]
+    if re.fullmatch(r"[A-Za-z0-9_\-]+", problem_id) is None:
+        print(f"Invalid problem id {problem_id} (use letters, digits, '_' and '-' only)", file=sys.stderr)
+        sys.exit(1)
+
+    problem_dir = os.path.join("problems", problem_id)
+    if os.path.exists(problem_dir):
+        print(f"{problem_dir} already exists", file=sys.stderr)
+        sys.exit(1)
+    os.makedirs(problem_dir)
+
+    sol_path = "./" + "/".join(["problems", problem_id, "solution.rs"])
+    with open(sol_path, "w", encoding="utf8", newline="\n") as f:
+        f.write(SOLUTION_SKELETON)
+    ci_jobs = []
+    for i in range(1, num_samples + 1):
+        in_path = "./" + "/".join(["problems", problem_id, "sample_{0}.in".format(i)])
+        out_path = "./" + "/".join(["problems", problem_id, "sample_{0}.out".format(i)])
+        for path in [in_path, out_path]:
+            open(path, "w").close()
+        ci_jobs.append({"solution": sol_path, "input": in_path, "output": out_path})
+    ci_path = "./" + "/".join(["problems", problem_id, "ci.json"])
+    with open(ci_path, "w", encoding="utf8", newline="\n") as f:
+        f.write(json.dumps(ci_jobs, indent=4) + "\n")
+
+    print("Created {0}".format(problem_dir))
+    print("  1. Fill in the sample inputs and outputs (sample_*.in, sample_*.out).")
+    print("  2. Write the solution in {0}.".format(sol_path))
+    print("  3. Test the solution against the samples (natively, and as a submission):")
+    python = "python" if os.name == 'nt' else "python3"
+    release_cmd = "release-64bit-windows.cmd" if os.name == 'nt' else "./release.sh"
+    print("       {0} scripts/ci.py tmp/{1} _ Cargo 0 {2}".format(python, problem_id, ci_path))
+    print("       {0} scripts/ci.py tmp/{1} {2} C 64 {3}".format(python, problem_id, release_cmd, ci_path))
@@ -0,0 +1,387 @@
+"""
+rust-bundle.py: expands the solution and the basm-std modules it uses into a single
+self-contained Rust source file, which compiles with a plain `rustc` (std, stable toolchain).
+Unlike the other release scripts, the output contains no machine code; use it for judges
+that accept Rust but not the shellcode loaders (e.g., judges that reject `unsafe` asm).
+
+Usage:
+    python3 scripts/rust-bundle.py [crate_root] [--features f1,f2,...]
+
+The bundle is printed to stdout; with BASM_SOURCE=minify, it is minified like the embedded source. It is built as if the `std` feature were enabled:
+  - `mod` declarations are inlined (respecting `#[path]`), and the library is placed in `mod basm`
+  - `#[cfg]`, `#[cfg_attr]` and `cfg!` on features and `test` are evaluated, and
+    the disabled items are removed; other predicates (e.g., target_os) are left to the judge
+  - only the top-level modules of basm-std reachable from the solution are included
+  - `libm` and `ryu` are replaced by small shims over the standard library
+Procedural macros (basm_macro) and other external crates cannot be bundled.
+"""
+
+import os
+import re
+import sys
+
+import srcpack
+
+# cfg evaluation
+
+def split_args(s):
+    # Splits the arguments of all(...), any(...) and cfg_attr(...) at the top-level commas
+    out, depth, cur, i = [], 0, "", 0
+    while i < len(s):
+        c = s[i]
+        if c == '"':
+            j = i + 1
+            while s[j] != '"':
+                j += 2 if s[j] == "\\" else 1
+            cur += s[i:j+1]
+            i = j + 1
+            continue
+        if c in "([{":
+            depth += 1
+        elif c in ")]}":
+            depth -= 1
+        if c == "," and depth == 0:
+            out.append(cur.strip())
+            cur = ""
+        else:
+            cur += c
+        i += 1
+    if cur.strip():
+        out.append(cur.strip())
+    return out
+
+def eval_cfg(pred, features):
+    # Returns True, False, or None if the predicate depends on the target (three-valued logic)
+    pred = pred.strip()
+    m = re.fullmatch(r"(all|any|not)\s*\((.*)\)", pred, re.S)
+    if m:
+        values = [eval_cfg(x, features) for x in split_args(m.group(2))]
+        if m.group(1) == "not":
+            return None if values[0] is None else not values[0]
+        if m.group(1) == "all":
+            return False if False in values else (None if None in values else True)
+        return True if True in values else (None if None in values else False)
+    m = re.fullmatch(r'feature\s*=\s*"([^"]*)"', pred)
+    if m:
+        return m.group(1) in features
+    if pred in ["test", "basm_solution_dir"]:
+        return False
+    return None
+
+# lexing
+
+def skip_literal(src, i):
+    # If a comment, a string or a character literal starts at i, returns the index after it; otherwise, returns i
+    if src.startswith("//", i):
+        j = src.find("\n", i)
+        return len(src) if j < 0 else j
+    if src.startswith("/*", i):
+        depth, j = 1, i + 2
+        while depth > 0:
+            if src.startswith("/*", j):
+                depth, j = depth + 1, j + 2
+            elif src.startswith("*/", j):
+                depth, j = depth - 1, j + 2
+            else:
+                j += 1
+        return j
+    m = re.compile(r'b?r(#*)"').match(src, i)
+    if m and (i == 0 or not (src[i-1].isalnum() or src[i-1] == "_")):
+        return src.index('"' + m.group(1), m.end()) + 1 + len(m.group(1))
+    if src[i] == '"':
+        j = i + 1
+        while src[j] != '"':
+            j += 2 if src[j] == "\\" else 1
+        return j + 1
+    if src[i] == "'":
+        if src[i+1] == "\\":
+            return src.index("'", i + 3) + 1
+        if i + 2 < len(src) and src[i+2] == "'":
+            return i + 3
+    return i
+
+def find_closing(src, i):
+    # Returns the index after the bracket matching the one at i
+    depth = 0
+    while True:
+        j = skip_literal(src, i)
+        if j != i:
+            i = j
+            continue
+        if src[i] in "([{":
+            depth += 1
+        elif src[i] in ")]}":
+            depth -= 1
+            if depth == 0:
+                return i + 1
+        i += 1
+
+ITEM_KEYWORDS = ["fn", "impl", "mod", "struct", "enum", "trait", "union", "macro_rules", "extern", "unsafe", "async"]
+STATEMENT_KEYWORDS = ["let", "const", "static", "use", "type"]
+
+def skip_item(src, i):
+    # Returns the end of the item (or field, variant, statement, ...) starting at i
+    m = re.compile(r"\s*(?:pub\s*(?:\([^)]*\))?\s*)?(\w+)").match(src, i)
+    keyword = m.group(1) if m else ""
+    depth = 0
+    while i < len(src):
+        j = skip_literal(src, i)
+        if j != i:
+            i = j
+            continue
+        c = src[i]
+        if c in "([{":
+            depth += 1
+        elif c in ")]}":
+            if depth == 0:
+                return i
+            depth -= 1
+            if depth == 0 and c == "}" and keyword not in STATEMENT_KEYWORDS:
+                k = i + 1
+                while k < len(src) and src[k] in " \t":
+                    k += 1
+                return k + 1 if k < len(src) and src[k] in ";," else i + 1
+        elif depth == 0 and (c == ";" or (c == "," and keyword not in ITEM_KEYWORDS + STATEMENT_KEYWORDS)):
+            return i + 1
+        i += 1
+    return i
+
+def process_cfg(src, features):
+    # Evaluates #[cfg], #[cfg_attr] and cfg! wherever the result does not depend on the target
+    out, i = [], 0
+    while i < len(src):
+        j = skip_literal(src, i)
+        if j != i:
+            out.append(src[i:j])
+            i = j
+            continue
+        m = re.compile(r"cfg!\s*\(").match(src, i)
+        if m and not (src[i-1].isalnum() or src[i-1] == "_"):
+            end = find_closing(src, m.end() - 1)
+            value = eval_cfg(src[m.end():end-1], features)
+            if value is not None:
+                out.append("true" if value else "false")
+                i = end
+                continue
+        if src.startswith("#[", i) or src.startswith("#![", i):
+            # Collect the run of attributes (and doc comments) in front of an item
+            attrs, k, removed = [], i, False
+            while True:
+                while k < len(src) and src[k].isspace():
+                    k += 1
+                if src.startswith("///", k) and not src.startswith("////", k):
+                    end = src.find("\n", k)
+                    attrs.append(src[k:end])
+                    k = end
+                    continue
+                if not (src.startswith("#[", k) or src.startswith("#![", k)):
+                    break
+                inner = src.startswith("#![", k)
+                start = k + (3 if inner else 2)
+                end = find_closing(src, start - 1)
+                body = src[start:end-1].strip()
+                k = end
+                m = re.fullmatch(r"cfg\s*\((.*)\)", body, re.S)
+                if m:
+                    value = eval_cfg(m.group(1), features)
+                    if value is False:
+                        removed = True
+                    elif value is None:
+                        attrs.append(src[start-(3 if inner else 2):end])
+                    continue
+                m = re.fullmatch(r"cfg_attr\s*\((.*)\)", body, re.S)
+                if m:
+                    args = split_args(m.group(1))
+                    value = eval_cfg(args[0], features)
+                    if value is None:
+                        attrs.append(src[start-(3 if inner else 2):end])
+                    elif value:
+                        attrs.extend(("#![" if inner else "#[") + a + "]" for a in args[1:])
+                    continue
+                attrs.append(src[start-(3 if inner else 2):end])
+            if removed:
+                # Doc comments written before the attributes belong to the removed item as well
+                out = [re.sub(r"(\s*///[^\n]*)+\s*$", "\n", "".join(out))]
+                i = skip_item(src, k)
+            else:
+                out.append("\n".join(attrs) + "\n" if attrs else "")
+                i = k
+                while i < len(src) and src[i] in " \t":
+                    i += 1
+            continue
+        out.append(src[i])
+        i += 1
+    return "".join(out)
+
+# modules
+
+def read_module(path, mod_dir, features):
+    # Reads a module file and inlines its `mod` declarations recursively
+    with open(path, encoding="utf8") as f:
+        src = f.read().replace("﻿", "")
+    src = process_cfg(src, features)
+    pattern = re.compile(r"^([ \t]*)((?:#\[[^\n]*\]\s*)*)((?:pub\s*(?:\([^)]*\))?\s*)?mod\s+(\w+))\s*;", re.M)
+    def inline(m):
+        indent, attrs, decl, name = m.groups()
+        path_attr = re.search(r'#\[path\s*=\s*"([^"]*)"\]', attrs)
+        if path_attr:
+            child = os.path.join(os.path.dirname(path), path_attr.group(1))
+            attrs = attrs.replace(path_attr.group(0), "")
+        else:
+            child = os.path.join(mod_dir, name + ".rs")
+            if not os.path.exists(child):
+                child = os.path.join(mod_dir, name, "mod.rs")
+        # A file loaded with #[path] is treated like mod.rs
+        if path_attr or os.path.basename(child) == "mod.rs":
+            child_dir = os.path.dirname(child)
+        else:
+            child_dir = os.path.join(os.path.dirname(child), os.path.splitext(os.path.basename(child))[0])
+        body = read_module(child, child_dir, features)
+        return indent + attrs + decl + " {\n" + body.rstrip() + "\n" + indent + "}"
+    return pattern.sub(inline, src)
+
+def split_top_modules(lib):
+    # Finds the top-level `mod name { ... }` blocks of the (inlined) library root
+    mods, i = {}, 0
+    pattern = re.compile(r"^((?:#\[[^\n]*\]\s*)*)(pub\s+)?mod\s+(\w+)\s*\{", re.M)
+    while True:
+        m = pattern.search(lib, i)
+        if m is None:
+            return mods
+        end = find_closing(lib, m.end() - 1)
+        mods[m.group(3)] = (m.start(), end)
+        i = end
+
+def referenced_names(src, root):
+    # Names used right after `root::`, including those in `root::{a, b::c}`
+    names = set()
+    for m in re.finditer(r"(?<![\w$])" + re.escape(root) + r"::\s*(\{|\w+)", src):
+        if m.group(1) != "{":
+            names.add(m.group(1))
+        else:
+            group = src[m.end(1):find_closing(src, m.end(1) - 1) - 1]
+            names.update(re.findall(r"(?:^|,)\s*(\w+)", group))
+    return names
+
+LIBM_SHIMS = {
+    "sqrt": "x.sqrt()", "floor": "x.floor()", "ceil": "x.ceil()", "round": "x.round()", "trunc": "x.trunc()",
+    "fabs": "x.abs()", "exp": "x.exp()", "log": "x.ln()", "sin": "x.sin()", "cos": "x.cos()", "tan": "x.tan()",
+    "atan": "x.atan()", "cbrt": "x.cbrt()",
+    "rint": "{ let r = x.round(); if (r - x).abs() == 0.5 { 2.0 * (x / 2.0).round() } else { r } }",
+}
+LIBM_SHIMS_2 = {"copysign": "x.copysign(y)", "pow": "x.powf(y)", "atan2": "x.atan2(y)", "hypot": "x.hypot(y)", "fmod": "x % y"}
+
+def shims(src):
+    out = []
+    libm_names = referenced_names(src, "crate::libm")
+    if libm_names:
+        out.append("mod libm {")
+        for name in sorted(libm_names):
+            if name in LIBM_SHIMS:
+                out.append(f"    pub fn {name}(x: f64) -> f64 {{ {LIBM_SHIMS[name]} }}")
+            elif name in LIBM_SHIMS_2:
+                out.append(f"    pub fn {name}(x: f64, y: f64) -> f64 {{ {LIBM_SHIMS_2[name]} }}")
+            elif name == "fma":
+                out.append("    pub fn fma(x: f64, y: f64, z: f64) -> f64 { x.mul_add(y, z) }")
+            else:
+                print(f"libm::{name} is not supported in the bundle", file=sys.stderr)
+                sys.exit(1)
+        out.append("}")
+    if referenced_names(src, "crate::ryu"):
+        # The shortest representation that round-trips, as in ryu (the exponent thresholds may differ)
+        out.append("mod ryu {\n"
+                   "    pub struct Buffer(String);\n"
+                   "    impl Buffer {\n"
+                   "        pub fn new() -> Self { Buffer(String::new()) }\n"
+                   "        pub fn format(&mut self, f: f64) -> &str { self.0 = format!(\"{:?}\", f); &self.0 }\n"
+                   "    }\n"
+                   "}")
+    return "\n".join(out)
+
+def bundle(crate_root, features):
+    features = set(features) | {"std"}
+    src_dir = os.path.join(crate_root, "src")
+    if os.path.exists(os.path.join(src_dir, "solution/main.rs")):
+        sol = read_module(os.path.join(src_dir, "solution/main.rs"), os.path.join(src_dir, "solution"), features)
+    else:
+        sol = read_module(os.path.join(src_dir, "solution.rs"), os.path.join(src_dir, "solution"), features)
+    if "basm_macro" in sol:
+        print("The procedural macros of basm_macro cannot be bundled", file=sys.stderr)
+        sys.exit(1)
+    std_dir = os.path.join(crate_root, "../basm-std/src")
+    lib = read_module(os.path.join(std_dir, "lib.rs"), std_dir, features)
+    lib = re.sub(r"(?<![\w$])crate::", "crate::basm::", lib)
+    lib = lib.replace("$crate::", "$crate::basm::")
+
+    # Keep the top-level modules reachable from the solution
+    mods = split_top_modules(lib)
+    macros = {}
+    for name, (start, end) in mods.items():
+        for m in re.finditer(r"#\[macro_export\]\s*macro_rules!\s*(\w+)", lib[start:end]):
+            macros[m.group(1)] = name
+    used, queue = set(), list(referenced_names(sol, "basm"))
+    queue += [m for m in re.findall(r"(\w+)\s*!", sol) if m in macros]
+    while queue:
+        name = queue.pop()
+        name = macros.get(name, name)
+        if name not in mods or name in used:
+            continue
+        used.add(name)
+        start, end = mods[name]
+        queue.extend(referenced_names(lib[start:end], "crate::basm"))
+        queue.extend(m for m in re.findall(r"(\w+)\s*!", lib[start:end]) if m in macros)
+    for name, (start, end) in sorted(mods.items(), key=lambda x: -x[1][0]):
+        if name not in used:
+            lib = lib[:start] + lib[end:]
+    exported = sorted(m for m, name in macros.items() if name in used)
+    if exported:
+        lib = lib.rstrip() + "\n#[allow(unused_imports)]\npub use crate::{" + ", ".join(exported) + "};\n"
+
+    # The solution refers to the library as `basm`, which is a module of the bundle
+    sol = re.sub(r"(?m)^((?:\s*(?:#!\[[^\n]*\]|//![^\n]*))*)", lambda m: m.group(1) + "\n#[allow(unused_imports)]\nuse crate::basm;\n", sol, count=1)
+    sol = re.sub(r"(\bmod\s+\w+\s*\{)", r"\1\n#[allow(unused_imports)]\nuse crate::basm;", sol)
+    out_lib = re.sub(r"(?<![\w:$])(libm|ryu)::", r"crate::\1::", lib)
+    out_sol = re.sub(r"(?<![\w:$])(libm|ryu)::", r"crate::\1::", sol)
+    out = "\n".join([
+        "// Generated with https://github.com/kiwiyou/basm-rs (scripts/rust-bundle.py)",
+        "// Learn rust (https://doc.rust-lang.org/book/) and get high performance out of the box!",
+        "// This file requires Rust 2021 (e.g., `rustc --edition 2021 -O`).",
+        "#![allow(dead_code, unused_imports, unused_macros, unused_mut, unused_variables, unused_unsafe)]",
+        "extern crate alloc;",
+        "",
+        "// SOLUTION BEGIN",
+        "mod solution {",
+        out_sol.rstrip(),
+        "}",
+        "// SOLUTION END",
+        "",
+        "fn main() {",
+        "    solution::main()",
+        "}",
+        "",
+        "// LIBRARY BEGIN",
+        "mod basm {",
+        out_lib.rstrip(),
+        "}",
+        shims(out_lib + out_sol),
+        "// LIBRARY END",
+        "",
+    ])
+    return out
+
+if __name__ == '__main__':
+    args = sys.argv[1:]
+    features = []
+    if "--features" in args:
+        idx = args.index("--features")
+        features = [x for x in args[idx + 1].split(",") if x]
+        del args[idx:idx+2]
+    if len(args) > 1:
+        print(f"Usage: {sys.argv[0]} [crate_root] [--features f1,f2,...]", file=sys.stderr)
+        sys.exit(1)
+    crate_root = args[0] if args else "basm/"
+    out = bundle(crate_root, features)
+    # BASM_SOURCE=minify removes comments, blank lines and indentation (see srcpack.py) to fit in the code size limit
+    if os.environ.get("BASM_SOURCE", "full").lower() == "minify":
+        out = "".join(srcpack.minify_rust(out.splitlines(keepends=True))) + "\n"
+    sys.stdout.write(out)
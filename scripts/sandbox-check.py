@@ -0,0 +1,127 @@
+"""
+sandbox-check.py: scans the submission binary for system calls and instructions
+that are known to be blocked or unsupported in the sandboxes of online judges,
+so that they can be fixed before submission instead of causing mysterious runtime errors.
+
+Usage:
+    python3 scripts/sandbox-check.py binary_path [--judge generic|strict]
+
+The binary is disassembled with objdump (Binutils). For each `syscall` (amd64) or
+`int 0x80` (i686) instruction, the system call number is inferred from the last
+immediate moved into eax/rax within the same function. Numbers computed at runtime
+cannot be inferred and are reported as unknown.
+
+The judge profiles are conservative heuristics:
+    generic     process creation, signals to other processes, ptrace and networking
+    strict      additionally file system access and getrandom
+Exits with status 1 if anything is found.
+"""
+
+import re
+import subprocess
+import sys
+
+SYSCALLS_AMD64 = {
+    0: "read", 1: "write", 2: "open", 3: "close", 9: "mmap", 10: "mprotect", 11: "munmap", 12: "brk",
+    13: "rt_sigaction", 25: "mremap", 28: "madvise", 41: "socket", 42: "connect", 56: "clone", 57: "fork",
+    58: "vfork", 59: "execve", 60: "exit", 62: "kill", 101: "ptrace", 200: "tkill", 231: "exit_group",
+    234: "tgkill", 257: "openat", 302: "prlimit64", 318: "getrandom", 322: "execveat", 435: "clone3",
+}
+SYSCALLS_I686 = {
+    1: "exit", 2: "fork", 3: "read", 4: "write", 5: "open", 6: "close", 11: "execve", 26: "ptrace",
+    37: "kill", 45: "brk", 91: "munmap", 102: "socketcall", 120: "clone", 125: "mprotect", 163: "mremap",
+    174: "rt_sigaction", 190: "vfork", 192: "mmap2", 219: "madvise", 238: "tkill", 252: "exit_group",
+    270: "tgkill", 295: "openat", 340: "prlimit64", 355: "getrandom", 358: "execveat", 359: "socket",
+    362: "connect", 435: "clone3",
+}
+
+BLOCKED_GENERIC = ["fork", "vfork", "clone", "clone3", "execve", "execveat", "kill", "tkill", "tgkill",
+    "ptrace", "socket", "connect", "socketcall"]
+JUDGE_PROFILES = {
+    "generic": {
+        "syscalls": BLOCKED_GENERIC,
+        "instructions": {
+            "rdrand": "may trap or be disabled in virtualized sandboxes",
+            "rdseed": "may trap or be disabled in virtualized sandboxes",
+        },
+    },
+    "strict": {
+        "syscalls": BLOCKED_GENERIC + ["open", "openat", "getrandom"],
+        "instructions": {
+            "rdrand": "may trap or be disabled in virtualized sandboxes",
+            "rdseed": "may trap or be disabled in virtualized sandboxes",
+            "rdtscp": "may be disabled in virtualized sandboxes",
+            "cpuid": "may be intercepted in virtualized sandboxes",
+        },
+    },
+}
+
+def disassemble(binary_path):
+    # Returns the list of (function, address, instruction) in Intel syntax.
+    out = subprocess.run(["objdump", "-d", "-M", "intel", "--no-show-raw-insn", binary_path],
+        capture_output=True, text=True, check=True).stdout
+    insns, func = [], "?"
+    for line in out.split("\n"):
+        m = re.match(r"^[0-9a-f]+ <(.*)>:$", line)
+        if m is not None:
+            func = m.group(1)
+            continue
+        m = re.match(r"^\s*([0-9a-f]+):\s+(.*)$", line)
+        if m is not None:
+            insns.append((func, int(m.group(1), 16), m.group(2).split("#")[0].strip()))
+    return insns
+
+def check(binary_path, judge):
+    profile = JUDGE_PROFILES[judge]
+    with open(binary_path, "rb") as f:
+        header = f.read(5)
+    is_64 = not (header[:4] == b"\x7fELF" and header[4] == 1)
+    syscall_names = SYSCALLS_AMD64 if is_64 else SYSCALLS_I686
+
+    findings = []
+    last_eax, last_func = None, None
+    for func, addr, insn in disassemble(binary_path):
+        if func != last_func:
+            last_eax, last_func = None, func
+        op = insn.split()[0] if len(insn) > 0 else ""
+        m = re.match(r"^mov\s+[er]ax,\s*(0x[0-9a-f]+|\d+)$", insn)
+        if m is not None:
+            last_eax = int(m.group(1), 0)
+        elif re.match(r"^xor\s+eax,\s*eax$", insn):
+            last_eax = 0
+        elif op == "call" or re.match(r"^\w+\s+(rax|eax|ax|al)\b", insn):
+            last_eax = None     # clobbered
+        if op == "syscall" or insn.replace(" ", "") == "int0x80":
+            if last_eax is None:
+                findings.append((addr, func, "system call with a number computed at runtime (cannot be checked)", False))
+            else:
+                name = syscall_names.get(last_eax, "#{0}".format(last_eax))
+                if name in profile["syscalls"]:
+                    findings.append((addr, func, "system call {0} is blocked by most judges".format(name), True))
+        if op in profile["instructions"]:
+            findings.append((addr, func, "instruction {0} {1}".format(op, profile["instructions"][op]), True))
+        if "zmm" in insn:
+            findings.append((addr, func, "AVX-512 instruction ({0}) is not supported by many judge machines".format(op), True))
+    return findings
+
+if __name__ == '__main__':
+    args = sys.argv[1:]
+    judge = "generic"
+    if "--judge" in args:
+        idx = args.index("--judge")
+        judge = args[idx + 1] if idx + 1 < len(args) else ""
+        del args[idx:idx+2]
+    if len(args) != 1 or judge not in JUDGE_PROFILES:
+        print(f"Usage: {sys.argv[0]} binary_path [--judge {'|'.join(JUDGE_PROFILES.keys())}]", file=sys.stderr)
+        sys.exit(1)
+
+    findings = check(args[0], judge)
+    errors = [x for x in findings if x[3]]
+    for addr, func, msg, _ in errors:
+        print("error: {0:#x} in {1}: {2}".format(addr, func, msg), file=sys.stderr)
+    unknown = len(findings) - len(errors)
+    if unknown > 0:
+        print("note: {0} system call(s) with numbers computed at runtime could not be checked".format(unknown), file=sys.stderr)
+    if len(errors) > 0:
+        print("Sandbox check ({0}) found {1} problem(s).".format(judge, len(errors)), file=sys.stderr)
+        sys.exit(1)
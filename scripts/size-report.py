@@ -0,0 +1,180 @@
+"""
+size-report.py: prints a per-function size breakdown of the linked payload,
+similar to `cargo bloat`.
+
+Usage:
+    python3 scripts/size-report.py binary_path [--crates] [-n count]
+
+The binary must not be stripped (build with `--config profile.release.strip=false`;
+see size-report.sh). ELF (Linux) binaries and PE binaries carrying a COFF symbol
+table (MinGW) are supported. MSVC binaries keep their symbols in a separate PDB
+file, which is not supported.
+"""
+
+import re
+import struct
+import subprocess
+import sys
+
+def read_elf_symbols(elf):
+    is_64 = elf[4] == 2
+    if is_64:
+        e_shoff, = struct.unpack_from("<Q", elf, 0x28)
+        e_shentsize, e_shnum = struct.unpack_from("<HH", elf, 0x3a)
+    else:
+        e_shoff, = struct.unpack_from("<I", elf, 0x20)
+        e_shentsize, e_shnum = struct.unpack_from("<HH", elf, 0x2e)
+    sections = []
+    for i in range(e_shnum):
+        off = e_shoff + i * e_shentsize
+        if is_64:
+            sh_name, sh_type, sh_flags, sh_addr, sh_offset, sh_size, sh_link = struct.unpack_from("<IIQQQQI", elf, off)
+        else:
+            sh_name, sh_type, sh_flags, sh_addr, sh_offset, sh_size, sh_link = struct.unpack_from("<IIIIIII", elf, off)
+        sections.append((sh_type, sh_flags, sh_offset, sh_size, sh_link))
+    code_size = sum(sh_size for sh_type, sh_flags, _, sh_size, _ in sections if sh_type == 1 and (sh_flags & 4) != 0) # SHT_PROGBITS, SHF_EXECINSTR
+
+    symbols = []
+    for sh_type, _, sh_offset, sh_size, sh_link in sections:
+        if sh_type != 2: # SHT_SYMTAB
+            continue
+        strtab_off = sections[sh_link][2]
+        entsize = 24 if is_64 else 16
+        for off in range(sh_offset, sh_offset + sh_size, entsize):
+            if is_64:
+                st_name, st_info, _, _, _, st_size = struct.unpack_from("<IBBHQQ", elf, off)
+            else:
+                st_name, _, st_size, st_info, _, _ = struct.unpack_from("<IIIBBH", elf, off)
+            if (st_info & 0xf) != 2 or st_size == 0: # STT_FUNC
+                continue
+            name_end = elf.index(b"\0", strtab_off + st_name)
+            symbols.append((elf[strtab_off + st_name:name_end].decode("utf8", "replace"), st_size))
+    return symbols, code_size
+
+def read_coff_symbols(pe):
+    e_lfanew, = struct.unpack_from("<I", pe, 0x3c)
+    n_sections, = struct.unpack_from("<H", pe, e_lfanew + 6)
+    ptr_symtab, n_symbols = struct.unpack_from("<II", pe, e_lfanew + 12)
+    size_opt_header, = struct.unpack_from("<H", pe, e_lfanew + 20)
+    if ptr_symtab == 0:
+        return [], 0
+    section_off = e_lfanew + 24 + size_opt_header
+    section_ends, code_size = [], 0
+    for i in range(n_sections):
+        virtual_size, virtual_address = struct.unpack_from("<II", pe, section_off + 40 * i + 8)
+        characteristics, = struct.unpack_from("<I", pe, section_off + 40 * i + 36)
+        section_ends.append((virtual_address + virtual_size, (characteristics & 0x20) != 0)) # IMAGE_SCN_CNT_CODE
+        if (characteristics & 0x20) != 0:
+            code_size += virtual_size
+    strtab_off = ptr_symtab + 18 * n_symbols
+
+    # COFF symbols carry no size; we infer it from the address of the next symbol in the same section
+    funcs, i = [], 0
+    while i < n_symbols:
+        off = ptr_symtab + 18 * i
+        raw_name = pe[off:off+8]
+        value, section_number, sym_type, storage_class, n_aux = struct.unpack_from("<IhHBB", pe, off + 8)
+        if raw_name[:4] == b"\0\0\0\0":
+            name_off = strtab_off + struct.unpack_from("<I", raw_name, 4)[0]
+            name = pe[name_off:pe.index(b"\0", name_off)]
+        else:
+            name = raw_name.rstrip(b"\0")
+        if section_number > 0 and section_ends[section_number - 1][1] and storage_class in [2, 3]: # EXTERNAL, STATIC
+            funcs.append((section_number, value, name.decode("utf8", "replace")))
+        i += 1 + n_aux
+    funcs.sort()
+    symbols = []
+    for j, (section_number, value, name) in enumerate(funcs):
+        if j + 1 < len(funcs) and funcs[j + 1][0] == section_number:
+            end = funcs[j + 1][1]
+        else:
+            end = section_ends[section_number - 1][0]
+        if end > value and not name.startswith("."):
+            symbols.append((name, end - value))
+    return symbols, code_size
+
+# Demangles the legacy Rust mangling scheme (_ZN...17h<hash>E); other names are returned as is.
+def demangle_legacy(name):
+    m = re.fullmatch(r"_?_ZN(.*)E(\.llvm\.\d+)?", name)
+    if m is None:
+        return name
+    body, parts, i = m.group(1), [], 0
+    while i < len(body):
+        j = i
+        while j < len(body) and body[j].isdigit():
+            j += 1
+        if j == i:
+            return name
+        length = int(body[i:j])
+        parts.append(body[j:j+length])
+        i = j + length
+    escapes = {"$SP$": "@", "$BP$": "*", "$RF$": "&", "$LT$": "<", "$GT$": ">", "$LP$": "(", "$RP$": ")", "$C$": ","}
+    def unescape(part):
+        if part.startswith("_$"):
+            part = part[1:]
+        for k, v in escapes.items():
+            part = part.replace(k, v)
+        part = re.sub(r"\$u([0-9a-f]+)\$", lambda x: chr(int(x.group(1), 16)), part)
+        return part.replace("..", "::")
+    return "::".join(unescape(part) for part in parts)
+
+# Demangles the names with c++filt (Binutils), which also supports the v0 mangling scheme used by the standard library.
+# Falls back to the built-in legacy demangler if c++filt is unavailable.
+def demangle_all(names):
+    try:
+        out = subprocess.run(["c++filt"], input="\n".join(names), capture_output=True, text=True, check=True).stdout.split("\n")
+        assert len(out) >= len(names)
+        out = out[:len(names)]
+    except (OSError, subprocess.CalledProcessError, AssertionError):
+        out = [demangle_legacy(name) for name in names]
+    # Strip the hashes (e.g., `::h0123456789abcdef` and `[0123456789abcdef]`) so that the names are stable across builds
+    out = [re.sub(r"::h[0-9a-f]{16}$", "", x) for x in out]
+    out = [re.sub(r"\[[0-9a-f]+\]", "", x) for x in out]
+    return out
+
+def crate_of(demangled):
+    s = demangled.lstrip("<&*")
+    m = re.match(r"[A-Za-z_][A-Za-z0-9_]*", s)
+    return m.group(0) if m is not None and "::" in s else "[Unknown]"
+
+if __name__ == '__main__':
+    args = sys.argv[1:]
+    by_crate = "--crates" in args
+    count = 30
+    if "-n" in args:
+        count = int(args[args.index("-n") + 1])
+        del args[args.index("-n"):args.index("-n") + 2]
+    args = [x for x in args if x != "--crates"]
+    if len(args) != 1:
+        print(f"Usage: {sys.argv[0]} binary_path [--crates] [-n count]", file=sys.stderr)
+        sys.exit(1)
+
+    with open(args[0], "rb") as f:
+        binary = f.read()
+    if binary[:4] == b"\x7fELF":
+        symbols, code_size = read_elf_symbols(binary)
+    elif binary[:2] == b"MZ":
+        symbols, code_size = read_coff_symbols(binary)
+    else:
+        print("Unknown binary format (expected ELF or PE)", file=sys.stderr)
+        sys.exit(1)
+    if len(symbols) == 0:
+        print("No symbols found. Please build without stripping (e.g., `--config profile.release.strip=false`).", file=sys.stderr)
+        sys.exit(1)
+
+    entries = dict()
+    for (_, size), key in zip(symbols, demangle_all([name for name, _ in symbols])):
+        if by_crate:
+            key = crate_of(key)
+        entries[key] = entries.get(key, 0) + size
+    entries = sorted(entries.items(), key=lambda x: (-x[1], x[0]))
+    code_size = max(code_size, sum(size for _, size in entries))
+
+    print(".text%     Size {0}".format("Crate" if by_crate else "Name"))
+    for key, size in entries[:count]:
+        print("{0:5.1f}% {1:>8} {2}".format(100 * size / code_size, size, key))
+    rest = entries[count:]
+    if len(rest) > 0:
+        size = sum(x[1] for x in rest)
+        print("{0:5.1f}% {1:>8} And {2} smaller {3}".format(100 * size / code_size, size, len(rest), "crates" if by_crate else "functions"))
+    print("{0:5.1f}% {1:>8} Code size in total".format(100.0, code_size))
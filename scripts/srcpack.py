@@ -1,12 +1,27 @@
 import os
+import re
+import sys
 
 # Reads and assembles the source code in the crate at the path `crate_root`.
 # `crate_root` usually equals `basm/`.
+# The environment variable BASM_SOURCE selects how the source code is embedded:
+#   full (default)  as is
+#   minify          without comments, blank lines and indentation
+#   none            not embedded
 def read_assemble(crate_root, target_language):
+    source_mode = os.environ.get("BASM_SOURCE", "full").lower()
+    if source_mode not in ["full", "minify", "none"]:
+        print(f"Unknown BASM_SOURCE {source_mode} (expected full, minify or none)", file=sys.stderr)
+        sys.exit(1)
+    if source_mode == "none":
+        return ""
     sol_first = []
     sol_all = []
     crate_src_path = os.path.join(crate_root, "src/")
     for root, dirs, files in os.walk(crate_src_path):
+        # Visit directories and files in a fixed order so that the output does not depend on the file system
+        dirs.sort()
+        files.sort()
         if os.path.abspath(root).startswith(os.path.abspath(os.path.join(crate_src_path, "bin/"))):
             continue
         for f in files:
@@ -14,7 +29,10 @@ def read_assemble(crate_root, target_language):
             if f_path.endswith(".rs"):
                 with open(f_path, encoding='utf8') as f:
                     sol = f.readlines()
-                if os.path.abspath(f_path) == os.path.abspath(os.path.join(crate_root, "src/solution.rs")):
+                if source_mode == "minify":
+                    sol = minify_rust(sol)
+                if os.path.abspath(f_path) in [os.path.abspath(os.path.join(crate_root, "src/solution.rs")),
+                                               os.path.abspath(os.path.join(crate_root, "src/solution/main.rs"))]:
                     sol_first.append((f_path, sol))
                 else:
                     sol_all.append((f_path, sol))
@@ -30,9 +48,59 @@ def read_assemble(crate_root, target_language):
             sol_flat.extend(sol)
     if target_language in ["Rust", "HTML"]:
         return assemble_as_is(sol_flat)
+    elif target_language in ["Python", "Ruby"]:
+        return assemble_with_line_commenting(sol_flat)
+    elif target_language == "Java":
+        return assemble_with_commenting(escape_java_unicode(sol_flat))
+    elif target_language == "C89":
+        return assemble_with_block_commenting(sol_flat)
     else:
         return assemble_with_commenting(sol_flat)
 
+# Removes comments, blank lines and indentation from Rust source code.
+# String, raw string and character literals are kept intact (apart from indentation within multiline strings).
+def minify_rust(sol):
+    src = "".join(sol).replace("\ufeff", "")
+    out, i = [], 0
+    while i < len(src):
+        if src.startswith("//", i):
+            while i < len(src) and src[i] != "\n":
+                i += 1
+        elif src.startswith("/*", i):
+            depth, i = 1, i + 2
+            while i < len(src) and depth > 0:
+                if src.startswith("/*", i):
+                    depth, i = depth + 1, i + 2
+                elif src.startswith("*/", i):
+                    depth, i = depth - 1, i + 2
+                else:
+                    i += 1
+            out.append(" ")
+        elif src[i] == '"':
+            j = i + 1
+            while j < len(src) and src[j] != '"':
+                j += 2 if src[j] == "\\" else 1
+            out.append(src[i:j+1])
+            i = j + 1
+        elif src[i] == "'":
+            m = re.compile(r"'(?:\\(?:x[0-9a-fA-F]{2}|u\{[0-9a-fA-F]+\}|.)|[^\\'])'").match(src, i)
+            j = i + 1 if m is None else m.end()     # a lifetime or a character literal
+            out.append(src[i:j])
+            i = j
+        else:
+            m = re.compile(r"\b(?:b|c)?r(#*)\"").match(src, i)
+            if m is not None and (i == 0 or not (src[i-1].isalnum() or src[i-1] == "_")):
+                j = src.find('"' + m.group(1), m.end())
+                j = len(src) if j < 0 else j + 1 + len(m.group(1))
+                out.append(src[i:j])
+                i = j
+            else:
+                m = re.compile(r"[A-Za-z0-9_]+|.", re.DOTALL).match(src, i)
+                out.append(m.group(0))
+                i = m.end()
+    lines = [line.strip() for line in "".join(out).split("\n")]
+    return [line + "\n" for line in lines if len(line) > 0]
+
 def assemble_as_is(sol):
     sol = [line.replace("\ufeff", "") for line in sol]
     sol = [line.rstrip() + "\n" for line in sol]
@@ -55,4 +123,26 @@ def assemble_with_commenting(sol):
     if len(sol) > 0:
         sol[-1] = sol[-1].rstrip()
     sol = "".join(sol)
-    return sol
\ No newline at end of file
+    return sol
+
+# C89 has no `//` comments, so the source is always put in a single block comment.
+# Comment delimiters inside the source are broken up with a backslash, which is harmless inside a comment.
+def assemble_with_block_commenting(sol):
+    sol = [line.replace("\ufeff", "").rstrip() for line in sol]
+    sol = [line.replace("/*", "/\\*").replace("*/", "*\\/") + "\n" for line in sol]
+    sol = ["/*\n"] + sol + ["*/"]
+    return "".join(sol)
+
+# Java translates unicode escapes (e.g., `\u0041`) even inside comments, so a Rust
+# escape such as `\u{1F600}` would break the compilation. Adding a backslash to an
+# odd-length run of backslashes followed by `u` prevents the translation.
+def escape_java_unicode(sol):
+    return [re.sub(r"(?<!\\)((?:\\\\)*\\)u", r"\1\\u", line) for line in sol]
+
+def assemble_with_line_commenting(sol):
+    sol = [line.replace("\ufeff", "") for line in sol]
+    sol = ["# " + line.rstrip() + "\n" for line in sol]
+    if len(sol) > 0:
+        sol[-1] = sol[-1].rstrip()
+    sol = "".join(sol)
+    return sol
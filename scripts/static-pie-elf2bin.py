@@ -131,6 +131,184 @@ def check_header(elf):
         (elf[EI_DATA] == ELFDATA2LSB) and \
         (b2i(elf[16:18]) == ET_DYN)
 
+# Dynamic section entry types used for prebinding
+DT_NULL         = 0
+DT_PLTRELSZ     = 2
+DT_RELA         = 7
+DT_RELASZ       = 8
+DT_RELAENT      = 9
+DT_REL          = 17
+DT_RELSZ        = 18
+DT_PLTREL       = 20
+DT_JMPREL       = 23
+DT_RELRSZ       = 35
+DT_RELR         = 36
+DT_RELRENT      = 37
+
+# Relocation types
+R_X86_64_NONE       = 0
+R_X86_64_64         = 1
+R_X86_64_GLOB_DAT   = 6
+R_X86_64_JUMP_SLOT  = 7
+R_X86_64_RELATIVE   = 8
+R_386_RELATIVE      = 8
+
+# Symbol binding
+STB_WEAK            = 2
+
+def encode_relr(offsets):
+    # Encodes the sorted word offsets into the SHT_RELR format
+    # (an address entry followed by bitmap entries each covering 63 words).
+    out = []
+    i = 0
+    while i < len(offsets):
+        out.append(offsets[i])
+        base = offsets[i] + 8
+        i += 1
+        while True:
+            bitmap = 0
+            while i < len(offsets) and offsets[i] - base < 63 * 8:
+                bitmap |= 1 << ((offsets[i] - base) // 8)
+                i += 1
+            if bitmap == 0:
+                break
+            out.append((bitmap << 1) | 1)
+            base += 63 * 8
+    return out
+
+def decode_relr(entries):
+    out = []
+    base = 0
+    for entry in entries:
+        if entry & 1 == 0:
+            out.append(entry)
+            base = entry + 8
+        else:
+            for i in range(63):
+                if (entry >> (i + 1)) & 1:
+                    out.append(base + i * 8)
+            base += 63 * 8
+    return out
+
+def prebind_elf64(memory_bin, dyn_off, dynsym):
+    # Applies the relative relocations assuming the canonical image base 0,
+    # and replaces the relocation tables by a patch list (in the SHT_RELR format)
+    # of the words to which the actual image base must be added at runtime.
+    # Since the patch list is much smaller than the Elf64_Rela table, both the runtime
+    # relocation work and the size of the embedded tables are reduced.
+    # Symbolic relocations (R_X86_64_64, R_X86_64_GLOB_DAT and R_X86_64_JUMP_SLOT), which may be
+    # emitted for code pulled in from external crates, are resolved here against the symbols
+    # defined in the binary itself, since the payload is never linked against shared libraries.
+    # Returns the number of entries in the patch list, or None if not applicable.
+    dyn = []
+    pos = dyn_off
+    while True:
+        d_tag, d_val = b2i(memory_bin[pos:pos+8]), b2i(memory_bin[pos+8:pos+16])
+        if d_tag == DT_NULL:
+            break
+        dyn.append((d_tag, d_val))
+        pos += 16
+    dyn_end = pos + 16
+    d = dict(dyn)
+
+    offsets = []
+    regions = []
+    if DT_RELR in d:
+        relr_off, relr_sz = d[DT_RELR], d[DT_RELRSZ]
+        relr = [b2i(memory_bin[relr_off+i:relr_off+i+8]) for i in range(0, relr_sz, 8)]
+        offsets.extend(decode_relr(relr))
+        regions.append((relr_off, relr_sz))
+    absolutes = []
+    rela_tables = []
+    if DT_RELA in d:
+        rela_tables.append((d[DT_RELA], d[DT_RELASZ]))
+    if DT_JMPREL in d:
+        rela_tables.append((d[DT_JMPREL], d[DT_PLTRELSZ]))
+    for rela_off, rela_sz in rela_tables:
+        for i in range(rela_off, rela_off + rela_sz, 24):
+            r_offset = b2i(memory_bin[i:i+8])
+            r_info = b2i(memory_bin[i+8:i+16])
+            r_addend = b2i(memory_bin[i+16:i+24])
+            r_type, r_sym = r_info & 0xFFFFFFFF, r_info >> 32
+            if r_type == R_X86_64_RELATIVE:
+                offsets.append((r_offset, r_addend))
+            elif r_type in [R_X86_64_64, R_X86_64_GLOB_DAT, R_X86_64_JUMP_SLOT]:
+                st_value, st_shndx, st_info, st_name = dynsym[r_sym]
+                if r_type != R_X86_64_64:
+                    r_addend = 0
+                if st_shndx != SHN_UNDEF:
+                    offsets.append((r_offset, (st_value + r_addend) & 0xFFFFFFFFFFFFFFFF))
+                elif (st_info >> 4) == STB_WEAK:
+                    absolutes.append((r_offset, r_addend & 0xFFFFFFFFFFFFFFFF))
+                else:
+                    print(f"Unresolved symbol {st_name} (relocation type {r_type} at offset {r_offset:#x}). " +
+                        "Make sure that all dependencies are no_std and do not link against system libraries.", file=sys.stderr)
+                    sys.exit(1)
+            elif r_type != R_X86_64_NONE:
+                return None         # leave the tables as-is; the runtime loader will reject them
+        regions.append((rela_off, rela_sz))
+    if len(regions) == 0:
+        return None
+
+    for r_offset, value in absolutes:
+        memory_bin[r_offset:r_offset+8] = value.to_bytes(8, byteorder='little')
+    words = set()
+    for x in offsets:
+        if type(x) is tuple:
+            r_offset, r_addend = x
+            memory_bin[r_offset:r_offset+8] = r_addend.to_bytes(8, byteorder='little')
+            x = r_offset
+        if x % 8 != 0:
+            return None             # cannot be represented in SHT_RELR
+        words.add(x)
+    relr = encode_relr(sorted(words))
+
+    # Reuse the largest of the existing tables for the patch list
+    table_off, table_sz = max(regions, key=lambda r: r[1])
+    if len(relr) * 8 > table_sz:
+        return None
+    for off, sz in regions:
+        memory_bin[off:off+sz] = bytearray(sz)
+    for i, entry in enumerate(relr):
+        memory_bin[table_off+i*8:table_off+i*8+8] = entry.to_bytes(8, byteorder='little')
+
+    new_dyn = [(t, v) for t, v in dyn if t not in [DT_RELA, DT_RELASZ, DT_RELAENT, DT_RELR, DT_RELRSZ, DT_RELRENT, DT_JMPREL, DT_PLTRELSZ, DT_PLTREL]]
+    new_dyn += [(DT_RELR, table_off), (DT_RELRSZ, len(relr) * 8), (DT_RELRENT, 8)]
+    memory_bin[dyn_off:dyn_end] = bytearray(dyn_end - dyn_off)
+    for i, (t, v) in enumerate(new_dyn):
+        memory_bin[dyn_off+i*16:dyn_off+i*16+16] = t.to_bytes(8, byteorder='little') + v.to_bytes(8, byteorder='little')
+    return len(relr)
+
+def relocation_offsets(memory_bin, dyn_off, is_64):
+    # Returns the sorted offsets of the words to which the image base is added at runtime,
+    # as described by the (possibly prebound) relocation tables in the dynamic section.
+    if dyn_off is None:
+        return []
+    word = 8 if is_64 else 4
+    dyn = []
+    pos = dyn_off
+    while True:
+        d_tag, d_val = b2i(memory_bin[pos:pos+word]), b2i(memory_bin[pos+word:pos+2*word])
+        if d_tag == DT_NULL:
+            break
+        dyn.append((d_tag, d_val))
+        pos += 2 * word
+    d = dict(dyn)
+
+    offsets = []
+    if is_64 and DT_RELR in d:
+        relr = [b2i(memory_bin[d[DT_RELR]+i:d[DT_RELR]+i+8]) for i in range(0, d[DT_RELRSZ], 8)]
+        offsets.extend(decode_relr(relr))
+    if is_64 and DT_RELA in d:
+        for i in range(d[DT_RELA], d[DT_RELA] + d[DT_RELASZ], 24):
+            if b2i(memory_bin[i+8:i+12]) == R_X86_64_RELATIVE:
+                offsets.append(b2i(memory_bin[i:i+8]))
+    if not is_64 and DT_REL in d:
+        for i in range(d[DT_REL], d[DT_REL] + d[DT_RELSZ], 8):
+            if memory_bin[i+4] == R_386_RELATIVE:
+                offsets.append(b2i(memory_bin[i:i+4]))
+    return sorted(offsets)
+
 def load_elf64(elf):
     sh = []
 
@@ -173,6 +351,7 @@ def load_elf64(elf):
     memory_bin = bytearray(pos_end)
     dynsym = []
     dynstr = b''
+    dyn_off = None
     for sh_dict in sh:
         dst_off, src_off, cnt = sh_dict['sh_addr'], sh_dict['sh_offset'], sh_dict['sh_size']
         blob = elf[src_off:src_off+cnt]
@@ -185,16 +364,18 @@ def load_elf64(elf):
                 # Included entries:
                 #   DT_PLTRELSZ = 2, DT_RELA = 7, DT_RELASZ = 8, DT_RELAENT = 9,
                 #   DT_REL = 17, DT_RELSZ = 18, DT_RELENT = 19, DT_PLTREL = 20,
-                #   DT_TEXT_REL = 22, DT_JMPREL = 23.
+                #   DT_TEXT_REL = 22, DT_JMPREL = 23,
+                #   DT_RELRSZ = 35, DT_RELR = 36, DT_RELRENT = 37.
                 #
                 # Note: DT_RELACOUNT = 0x6fff_fff9 and DT_RELCOUNT = 0x6fff_fffa
                 #   are not included since they are redundant since
                 #   DT_RELACOUNT = DT_RELASZ/DT_RELAENT and
                 #   DT_RELCOUNT = DT_RELSZ/DT_RELENT.
-                if b2i(blob[src:src+8]) in [2, 7, 8, 9, 17, 18, 19, 20, 22, 23]:
+                if b2i(blob[src:src+8]) in [2, 7, 8, 9, 17, 18, 19, 20, 22, 23, 35, 36, 37]:
                     blob[dst:dst+16] = blob[src:src+16]
                     dst += 16
             blob[dst:] = bytearray(len(blob[dst:])) # fill remaining part with zeros
+            dyn_off = dst_off
         elif sh_dict['sh_type'] == SHT_DYNSYM:
             for i in range(0, sh_dict['sh_size'], 24):
                 st_entry = blob[i:][:24]
@@ -224,6 +405,10 @@ def load_elf64(elf):
             i += 1
         return dynstr[st_name:i]
 
+    if dyn_off is not None:
+        prebind_elf64(memory_bin, dyn_off, [(st_dict['st_value'], st_dict['st_shndx'], st_dict['st_info'],
+            resolve_st_name(st_dict['st_name']).decode('utf8', 'replace')) for st_dict in dynsym])
+
     exports = dict()
     for st_dict in dynsym:
         st_name_str = resolve_st_name(st_dict['st_name']).decode('utf8')
@@ -231,7 +416,7 @@ def load_elf64(elf):
             exports[st_name_str] = st_dict['st_value']
 
     entrypoint_offset = b2i(elf[24:32])
-    return memory_bin, pos_begin, entrypoint_offset, exports
+    return memory_bin, pos_begin, entrypoint_offset, exports, dyn_off
 
 def load_elf32(elf):
     sh = []
@@ -258,7 +443,10 @@ def load_elf32(elf):
             pos_end = max(pos_end, sh_dict['sh_addr'] + sh_dict['sh_size'])
 
     memory_bin = bytearray(pos_end)
+    dyn_off = None
     for sh_dict in sh:
+        if sh_dict['sh_type'] == SHT_DYNAMIC:
+            dyn_off = sh_dict['sh_addr']
         if (sh_dict['sh_flags'] & SHF_ALLOC) == 0 or sh_dict['sh_size'] == 0:
             continue
         if sh_dict['sh_type'] == SHT_NOBITS:
@@ -269,7 +457,7 @@ def load_elf32(elf):
 
     entrypoint_offset = b2i(elf[24:28])
     exports = dict()        # TBD
-    return memory_bin, pos_begin, entrypoint_offset, exports
+    return memory_bin, pos_begin, entrypoint_offset, exports, dyn_off
 
 
 if __name__ == '__main__':
@@ -287,9 +475,9 @@ if __name__ == '__main__':
         sys.exit(1)
 
     if elf[EI_CLASS] == ELFCLASS64:
-        memory_bin, pos_begin, entrypoint_offset, exports = load_elf64(elf)
+        memory_bin, pos_begin, entrypoint_offset, exports, dyn_off = load_elf64(elf)
     elif elf[EI_CLASS] == ELFCLASS32:
-        memory_bin, pos_begin, entrypoint_offset, exports = load_elf32(elf)
+        memory_bin, pos_begin, entrypoint_offset, exports, dyn_off = load_elf32(elf)
     else:
         print(f"Unsupported EI_CLASS value: {elf[EI_CLASS]}", file=sys.stderr)
         sys.exit(1)
@@ -298,6 +486,7 @@ if __name__ == '__main__':
         pos_begin = 0
     pos_begin -= pos_begin % 128
     assert entrypoint_offset >= pos_begin
+    relocations = [x - pos_begin for x in relocation_offsets(memory_bin, dyn_off, elf[EI_CLASS] == ELFCLASS64)]
     memory_bin = memory_bin[pos_begin:]
     entrypoint_offset -= pos_begin
 
@@ -321,4 +510,8 @@ if __name__ == '__main__':
     fdict = {}
     fdict['entrypoint_offset'] = entrypoint_offset
     fdict['exports'] = exports
+    fdict['vaddr_base'] = pos_begin
+    fdict['memory_size'] = len(memory_bin)
+    fdict['dynamic_offset'] = None if dyn_off is None else dyn_off - pos_begin
+    fdict['relocations'] = relocations
     print(json.dumps(fdict))    # callers of this script can capture stdout to get this value
\ No newline at end of file
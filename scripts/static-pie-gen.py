@@ -11,6 +11,7 @@ import srcpack
 import subprocess
 import sys
 import utils
+import zlib
 
 try:
     solution_src_path, target_name, elf_path, stub_path, lang_name, template_path = sys.argv[1:]
@@ -19,7 +20,7 @@ try:
 except ValueError:
     print(f"Usage: {sys.argv[0]} solution_src_path target_name elf_path stub_path lang_name template_path", file=sys.stderr)
     sys.exit(1)
-if lang_name not in ["C", "Rust"]:
+if lang_name not in ["C", "C89", "Rust", "Python", "Ruby", "Java", "JavaScript", "CSharp", "Kotlin", "Raw"]:
     print(f"Unsupported language {lang_name}", file=sys.stderr)
     sys.exit(1)
 
@@ -34,6 +35,33 @@ else:
 loader_fdict = json.loads(elf2bin)
 assert 'entrypoint_offset' in loader_fdict
 
+# Raw: emit the flat binary as-is along with its metadata, for building custom wrappers
+if lang_name == "Raw":
+    loader_fdict['binary_path'] = binary_path
+    loader_fdict['target_name'] = target_name
+    print(json.dumps(loader_fdict, indent=4))
+    sys.exit(0)
+
+# size budget of the generated source code in bytes (e.g., BASM_MAX_SIZE=65536)
+max_size = os.environ.get("BASM_MAX_SIZE")
+if max_size is not None:
+    try:
+        max_size = int(max_size)
+    except ValueError:
+        print(f"Invalid BASM_MAX_SIZE {max_size} (expected the number of bytes)", file=sys.stderr)
+        sys.exit(1)
+
+# maximum length of the lines holding the payload (e.g., BASM_LINE_LENGTH=1000), for judges that reject long lines or literals;
+#   the payload is then split into many literals that are concatenated back by the wrapper
+line_length = os.environ.get("BASM_LINE_LENGTH")
+if line_length is not None:
+    try:
+        line_length = int(line_length)
+        assert line_length >= 32
+    except (ValueError, AssertionError):
+        print(f"Invalid BASM_LINE_LENGTH {line_length} (expected the number of characters, at least 32)", file=sys.stderr)
+        sys.exit(1)
+
 # Please refer to the following link for the lzma file format:
 #   https://svn.python.org/projects/external/xz-5.0.3/doc/lzma-file-format.txt
 # However, we use a different format:
@@ -43,27 +71,65 @@ assert 'entrypoint_offset' in loader_fdict
 #   [ 3,  4) = lp + lc + 8
 #   [ 4,  8) = Uncompressed size
 #   [ 8, ..) = Compressed data without the leading byte
-with open(binary_path, "rb") as f:
-    memory_bin = f.read()
+def read_image(binary_path, entrypoint_offset):
+    with open(binary_path, "rb") as f:
+        memory_bin = f.read()
     # Embed these information into the LZMA file to reduce the generated code length
-    x = loader_fdict['entrypoint_offset'].to_bytes(8, byteorder='little')
-    memory_bin += x
-lzma_filter = {'id': lzma.FILTER_LZMA1, 'preset': lzma.PRESET_EXTREME, 'lp': 0, 'lc': 0, 'pb': 0, 'dict_size': 1 << 22, 'depth': 200}
-compressed_memory_bin = bytearray(lzma.compress(memory_bin, format=lzma.FORMAT_RAW, filters=[lzma_filter]))
-while len(compressed_memory_bin) < 4:
-    compressed_memory_bin += b'\x00'                # append zeros for byte order swap (this won't happen in almost all cases, though)
-compressed_memory_bin = compressed_memory_bin[1:]   # strip the (redundant) leading zero byte of the LZMA stream
-compressed_memory_bin[:4] = reversed(compressed_memory_bin[:4]) # perform byte order swap in advance
-
-pb, lp, lc = lzma_filter['pb'], lzma_filter['lp'], lzma_filter['lc']
-lzma_header_properties = ((((1 << pb) - 1) + ((1 << lp) - 1) << 8) + (lc << 16) + ((lp + lc + 8) << 24)).to_bytes(4, byteorder='little')
-lzma_header_uncompressed_size = len(memory_bin).to_bytes(4, byteorder='little')
-compressed_memory_bin = lzma_header_properties + lzma_header_uncompressed_size + bytes(compressed_memory_bin)
+    return memory_bin + entrypoint_offset.to_bytes(8, byteorder='little')
+memory_bin = read_image(binary_path, loader_fdict['entrypoint_offset'])
+def lzma_compress(data, lc, lp, pb):
+    lzma_filter = {'id': lzma.FILTER_LZMA1, 'preset': lzma.PRESET_EXTREME, 'lp': lp, 'lc': lc, 'pb': pb, 'dict_size': 1 << 22, 'depth': 200}
+    compressed = bytearray(lzma.compress(data, format=lzma.FORMAT_RAW, filters=[lzma_filter]))
+    while len(compressed) < 4:
+        compressed += b'\x00'                      # append zeros for byte order swap (this won't happen in almost all cases, though)
+    compressed = compressed[1:]                     # strip the (redundant) leading zero byte of the LZMA stream
+    compressed[:4] = reversed(compressed[:4])       # perform byte order swap in advance
+
+    lzma_header_properties = (((1 << pb) - 1) + (((1 << lp) - 1) << 8) + (lc << 16) + ((lp + lc + 8) << 24)).to_bytes(4, byteorder='little')
+    lzma_header_uncompressed_size = len(data).to_bytes(4, byteorder='little')
+    return lzma_header_properties + lzma_header_uncompressed_size + bytes(compressed)
+
+# The stub reads the literal context/position bits (lc, lp, pb) from the header,
+#   so we try a few combinations and keep the smallest result.
+# Machine code usually favors pb=0 (no alignment), but data-heavy binaries may benefit from lc > 0.
+# When a size budget is given, we search all the combinations allowed by LZMA1 (lc + lp <= 4).
+if max_size is None:
+    lzma_params = [(lc, 0, pb) for pb in [0, 2] for lc in range(4)]
+else:
+    lzma_params = [(lc, lp, pb) for pb in range(5) for lp in range(5) for lc in range(5 - lp)]
+def lzma_compress_best(data):
+    best = None
+    for lc, lp, pb in lzma_params:
+        candidate = lzma_compress(data, lc, lp, pb)
+        if best is None or len(candidate) < len(best):
+            best = candidate
+    return best
+compressed_memory_bin = lzma_compress_best(memory_bin)
+
+# polyglot (BASM_WINDOWS_BINARY=path to basm-submit.exe built for a Windows target; see release-64bit-polyglot.sh):
+#   the compressed Windows image is appended to the payload, and the wrapper passes it to the stub
+#   instead of the Linux image when PLATFORM_DATA says it is running on Windows
+payload_windows_offset = 0
+windows_binary = os.environ.get("BASM_WINDOWS_BINARY")
+if windows_binary:
+    if target_name != "x86_64-unknown-linux-gnu" or lang_name not in ["C", "Python", "Ruby"]:
+        print("BASM_WINDOWS_BINARY is only supported for C, Python and Ruby on x86_64-unknown-linux-gnu", file=sys.stderr)
+        sys.exit(1)
+    windows_binary_path = windows_binary + ".bin"
+    pe2bin = subprocess.check_output([sys.executable, "scripts/static-pie-pe2bin.py", windows_binary, windows_binary_path]).decode("utf-8")
+    windows_memory_bin = read_image(windows_binary_path, json.loads(pe2bin)['entrypoint_offset'])
+    # Keep the Windows image 4-byte aligned so that it starts on a Base85 block boundary
+    compressed_memory_bin += b'\x00' * (-len(compressed_memory_bin) % 4)
+    payload_windows_offset = len(compressed_memory_bin)
+    compressed_memory_bin += lzma_compress_best(windows_memory_bin)
 with open(compressed_binary_path, "wb") as f:
     f.write(compressed_memory_bin)
 
 # solution_src
 sol = srcpack.read_assemble("basm/", lang_name)
+if lang_name == "C89":
+    # Only the solution comment differs from C (no `//` comments); the rest is handled by the template
+    lang_name = "C"
 
 # binary (raw)
 # Since we append a little-endian 8-byte nonnegative integer, we can practically ensure that the last byte is zero.
@@ -90,18 +156,111 @@ while len(code) % 4 != 0:
     code.append(0)
 code_b85 = base64.b85encode(code, pad=False).decode('ascii') + ']'
 
-if lang_name == "C":
+def split_escaped(x):
+    # Splits the (escaped) contents of a literal into the pieces fitting in BASM_LINE_LENGTH
+    #   without breaking escape sequences; a few characters are reserved for quotes and separators
+    if line_length is None:
+        return [x]
+    L = line_length - 8
+    pieces, i = [""], 0
+    while i < len(x):
+        n = 1 if x[i] != "\\" else 2
+        if len(pieces[-1]) + n > L:
+            pieces.append("")
+        pieces[-1] += x[i:i+n]
+        i += n
+    return pieces
+
+def c_string_array(x_all):
     L = 4095
     s = []
-    for i in range(0, len(code_b85), L):
-        x = code_b85[i:min(i+L,len(code_b85))]
+    for i in range(0, len(x_all), L):
+        x = x_all[i:min(i+L,len(x_all))]
+        x = x.replace("\\", "\\\\")
         x = x.replace("?", "\\?")
-        x = '"' + x + '",\n'
+        x = "\n".join('"' + y + '"' for y in split_escaped(x)) + ',\n'
         s.append(x)
-    r = "{\n" + "".join(s) + "}"
+    return "{\n" + "".join(s) + "}"
+
+def java_string_array(x_all):
+    # A string constant in a Java class file cannot exceed 65535 bytes
+    L = 65535
+    s = []
+    for i in range(0, len(x_all), L):
+        x = x_all[i:min(i+L,len(x_all))]
+        x = x.replace("\\", "\\\\")
+        s.extend('"' + y + '",\n' for y in split_escaped(x))
+    return "{\n" + "".join(s) + "}"
+
+def kotlin_string_array(x_all):
+    # The same limit as in Java applies; '$' starts a string template in Kotlin
+    L = 65535
+    s = []
+    for i in range(0, len(x_all), L):
+        x = x_all[i:min(i+L,len(x_all))]
+        x = x.replace("\\", "\\\\")
+        x = x.replace("$", "\\$")
+        s.extend('"' + y + '",\n' for y in split_escaped(x))
+    return "arrayOf(\n" + "".join(s) + ")"
+
+def rust_byte_string(x_all):
+    # A byte string continues on the next line after a trailing backslash
+    if line_length is None:
+        return 'br"' + x_all + '"'
+    x = x_all.replace("\\", "\\\\")
+    return 'b"\\\n' + "\\\n".join(split_escaped(x)) + '"'
+
+if lang_name == "C":
+    r = c_string_array(code_b85)
 else:
     r = '"' + code_b85 + '"'
 
+# payload encoding (selected by the BASM_ENCODING environment variable; templates without the placeholder always use their own encoding)
+# Both encodings only use printable ASCII characters. Since the number of characters that must be escaped
+#   differs by the target language (e.g., '\\' and '?' in C), `auto` picks the one whose literal is the shortest.
+def payload_literal(payload_encoded):
+    if lang_name == "C":
+        return c_string_array(payload_encoded)
+    elif lang_name == "Python":
+        if line_length is not None:
+            # Adjacent literals in parentheses are concatenated (raw literals are avoided since they cannot end with a backslash)
+            return "(\n" + "\n".join('"' + y + '"' for y in split_escaped(payload_encoded.replace("\\", "\\\\"))) + "\n)"
+        return 'r"' + payload_encoded + '"'
+    elif lang_name == "Ruby":
+        # Single-quoted literals only interpret `\\` and `\'`; adjacent literals are concatenated across a trailing backslash
+        x = " \\\n".join("'" + y + "'" for y in split_escaped(payload_encoded.replace("\\", "\\\\").replace("'", "\\'")))
+        return x if line_length is None else "\n" + x
+    elif lang_name == "Java":
+        return java_string_array(payload_encoded)
+    elif lang_name == "Kotlin":
+        return kotlin_string_array(payload_encoded)
+    elif lang_name == "JavaScript":
+        x = " +\n".join('"' + y + '"' for y in split_escaped(payload_encoded.replace("\\", "\\\\")))
+        return x if line_length is None else "\n" + x
+    elif lang_name == "CSharp":
+        x = " +\n".join('@"' + y + '"' for y in split_escaped(payload_encoded))
+        return x if line_length is None else "\n" + x
+    else:
+        return '"' + payload_encoded + '"'
+payload_candidates = {"base85": code_b85, "base91": code_b91[1:-1]}
+payload_encoding = os.environ.get("BASM_ENCODING", "base85" if max_size is None else "auto").lower()
+if payload_encoding == "auto":
+    payload_encoding = min(payload_candidates, key=lambda x: len(payload_literal(payload_candidates[x])))
+if payload_encoding not in payload_candidates:
+    print(f"Unsupported payload encoding {payload_encoding} (expected base85, base91 or auto)", file=sys.stderr)
+    sys.exit(1)
+payload_encoded = payload_candidates[payload_encoding]
+
+# payload checksum (enabled by BASM_CHECKSUM=1): the wrappers verify the CRC-32 of the decoded payload
+#   before running it, so that a submission corrupted by copy-and-paste fails with a clear message.
+# The value 0 disables the check.
+if os.environ.get("BASM_CHECKSUM", "0") not in ["", "0"]:
+    payload_crc32 = zlib.crc32(compressed_memory_bin)
+else:
+    payload_crc32 = 0
+payload = payload_literal(payload_encoded)
+assert all(0x20 <= ord(c) < 0x7f or c == "\n" for c in payload)
+
 # stub
 with open(stub_path, "rb") as f:
     stub = f.read()
@@ -122,8 +281,17 @@ stub_b85 = base64.b85encode(stub, pad=False).decode('ascii') + ']'
 stub_b85_len = len(stub_b85)
 if lang_name == "C":
     stub_b85 = stub_b85.replace("?", "\\?")
+elif lang_name == "Kotlin":
+    stub_b85 = stub_b85.replace("$", "\\$")
 stub_b85 = '"' + stub_b85 + '"'
 
+# reflective prestub (first stage for running the stub from non-executable memory)
+prestub_reflective = b''
+if "x86_64" in target_name:
+    with open(locator.template_path("static-pie-prestub-amd64-reflective.bin"), "rb") as f:
+        prestub_reflective = f.read()
+prestub_reflective_raw = '"' + "".join("\\x{:02x}".format(x) for x in prestub_reflective) + '"'
+
 # template
 template_candidates = [template_path]
 if lang_name in ["C", "Rust"] and "x86_64" in target_name and "short" in template_path and len(code_raw) <= 4096 - 256:
@@ -142,6 +310,9 @@ for each_template_path in template_candidates:
     with open(each_template_path, encoding='utf8') as f:
         template = f.read()
     template = template.replace("\ufeff", "")
+    if payload_windows_offset != 0 and "$$$$payload_windows_offset$$$$" not in template:
+        print(f"The template {os.path.basename(each_template_path)} does not support BASM_WINDOWS_BINARY", file=sys.stderr)
+        sys.exit(1)
 
     out_candidate = utils.multiple_replace(template, {
         "$$$$solution_src$$$$": sol,
@@ -151,16 +322,41 @@ for each_template_path in template_candidates:
         "$$$$stub_base85_len$$$$": str(stub_b85_len),
         "$$$$stub_base91$$$$": stub_b91,
         "$$$$stub_base91_len$$$$": str(stub_b91_len),
+        "$$$$prestub_reflective_raw$$$$": prestub_reflective_raw,
+        "$$$$prestub_reflective_len$$$$": str(len(prestub_reflective)),
         "$$$$binary_base85$$$$": r,
+        "$$$$binary_encoding$$$$": payload_encoding[4:],
+        "$$$$binary_payload$$$$": payload,
         "$$$$binary_base85_len$$$$": str(len(code_b85)),
         "$$$$binary_base91$$$$": code_b91,
         "$$$$binary_base91_len$$$$": str(code_b91_len),
+        "$$$$binary_base91_bytes$$$$": rust_byte_string(code_b91[1:-1]),
         "$$$$binary_raw_base91$$$$": code_raw_b91,
         "$$$$binary_raw_base91_len$$$$": str(code_raw_b91_len),
-        "$$$$min_len_4096$$$$": str(min(len(code_b85)+1, 4096)),
+        "$$$$payload_crc32$$$$": str(payload_crc32),
+        "$$$$payload_len$$$$": str(len(compressed_memory_bin)),
+        "$$$$payload_windows_offset$$$$": str(payload_windows_offset),
+        "$$$$min_len_4096$$$$": str(min(len(payload_encoded)+1, 4096)),
         "$$$$entrypoint_offset$$$$": str(loader_fdict['entrypoint_offset']),
         "$$$$exports_cpp$$$$": exports_cpp
     })
     if out is None or len(out_candidate) < len(out):
         out = out_candidate
+
+if max_size is not None and len(out.encode('utf8')) > max_size:
+    out_size = len(out.encode('utf8'))
+    sol_size = len(sol.encode('utf8'))
+    print(f"The generated code ({out_size} bytes) exceeds the size budget BASM_MAX_SIZE={max_size} by {out_size - max_size} bytes.", file=sys.stderr)
+    print(f"  Solution source code (embedded as comments): {sol_size} bytes", file=sys.stderr)
+    print(f"  Compressed binary: {len(compressed_memory_bin)} bytes ({len(memory_bin)} bytes uncompressed)", file=sys.stderr)
+    print(f"  Encoded binary and loader: {out_size - sol_size} bytes", file=sys.stderr)
+    # Show the largest functions if the unstripped binary is available
+    report = subprocess.run([sys.executable, "scripts/size-report.py", elf_path.removesuffix("-stripped"), "-n", "10"],
+        capture_output=True, text=True)
+    if report.returncode == 0:
+        print("The largest functions:", file=sys.stderr)
+        print(report.stdout.rstrip(), file=sys.stderr)
+    else:
+        print("Run size-report.sh to find out the functions taking up the most space.", file=sys.stderr)
+    sys.exit(1)
 print(out)
\ No newline at end of file
@@ -8,6 +8,60 @@ except ModuleNotFoundError as e:
         "  \033[93mpip install pefile\n\033[0m", file=sys.stderr)
     raise e
 
+IMAGE_FILE_MACHINE_ARM64 = 0xaa64
+
+def patch_entrypoint_x86(memory_bin, entrypoint_offset, reloc_off, reloc_sz):
+    # Patch the entrypoint
+    # We look for:
+    #   0:  f8                      clc
+    # and replace it with:
+    #   0:  f9                      stc
+    # This works for both i686 and amd64.
+    assert memory_bin[entrypoint_offset:entrypoint_offset+1] == b"\xf8"
+    memory_bin[entrypoint_offset:entrypoint_offset+1] = b"\xf9"
+
+    # Patch the relocation offset and size (which is in _start)
+    # We look for:
+    #   0:  be 78 56 34 12          mov    esi,0x12345678  <- replaced with reloc_off
+    #   5:  ba 78 56 34 12          mov    edx,0x12345678  <- replaced with reloc_sz
+    template = b"\xbe\x78\x56\x34\x12\xba\x78\x56\x34\x12"
+    reloc_patched = False
+    for i in range(entrypoint_offset, len(memory_bin) - len(template)):
+        if memory_bin[i:i+len(template)] == template:
+            memory_bin[i+1:i+5] = reloc_off.to_bytes(4, byteorder='little')
+            memory_bin[i+6:i+10] = reloc_sz.to_bytes(4, byteorder='little')
+            reloc_patched = True
+            break
+    assert reloc_patched, "Failed to incorporate the relocation information into the binary. Please report this error."
+
+def patch_entrypoint_arm64(memory_bin, entrypoint_offset, reloc_off, reloc_sz):
+    # Patch the entrypoint
+    # We look for:
+    #   0:  d2800009                mov    x9, #0x0
+    # and replace it with:
+    #   0:  d2800029                mov    x9, #0x1
+    assert memory_bin[entrypoint_offset:entrypoint_offset+4] == b"\x09\x00\x80\xd2"
+    memory_bin[entrypoint_offset:entrypoint_offset+4] = b"\x29\x00\x80\xd2"
+
+    # Patch the relocation offset and size (which is in _start)
+    # We look for:
+    #   0:  528acf01                mov    w1, #0x5678
+    #   4:  72a24681                movk   w1, #0x1234, lsl #16  <- replaced with reloc_off
+    #   8:  528acf02                mov    w2, #0x5678
+    #   c:  72a24682                movk   w2, #0x1234, lsl #16  <- replaced with reloc_sz
+    def movz_movk(rd, imm32):
+        movz = 0x52800000 | ((imm32 & 0xffff) << 5) | rd
+        movk = 0x72a00000 | ((imm32 >> 16) << 5) | rd
+        return movz.to_bytes(4, byteorder='little') + movk.to_bytes(4, byteorder='little')
+    template = movz_movk(1, 0x12345678) + movz_movk(2, 0x12345678)
+    reloc_patched = False
+    for i in range(entrypoint_offset, len(memory_bin) - len(template), 4):
+        if memory_bin[i:i+len(template)] == template:
+            memory_bin[i:i+len(template)] = movz_movk(1, reloc_off) + movz_movk(2, reloc_sz)
+            reloc_patched = True
+            break
+    assert reloc_patched, "Failed to incorporate the relocation information into the binary. Please report this error."
+
 if __name__ == '__main__':
     try:
         pe_path, binary_path = sys.argv[1:]
@@ -51,28 +105,10 @@ if __name__ == '__main__':
         memory_bin += reloc_bin
     reloc_off = 0 if reloc_sz == 0 else pos_begin + reloc_off
 
-    # Patch the entrypoint
-    # We look for:
-    #   0:  f8                      clc
-    # and replace it with:
-    #   0:  f9                      stc
-    # This works for both i686 and amd64.
-    assert memory_bin[entrypoint_offset:entrypoint_offset+1] == b"\xf8"
-    memory_bin[entrypoint_offset:entrypoint_offset+1] = b"\xf9"
-
-    # Patch the relocation offset and size (which is in _start)
-    # We look for:
-    #   0:  be 78 56 34 12          mov    esi,0x12345678  <- replaced with reloc_off
-    #   5:  ba 78 56 34 12          mov    edx,0x12345678  <- replaced with reloc_sz
-    template = b"\xbe\x78\x56\x34\x12\xba\x78\x56\x34\x12"
-    reloc_patched = False
-    for i in range(entrypoint_offset, len(memory_bin) - len(template)):
-        if memory_bin[i:i+len(template)] == template:
-            memory_bin[i+1:i+5] = reloc_off.to_bytes(4, byteorder='little')
-            memory_bin[i+6:i+10] = reloc_sz.to_bytes(4, byteorder='little')
-            reloc_patched = True
-            break
-    assert reloc_patched, "Failed to incorporate the relocation information into the binary. Please report this error."
+    if pe.FILE_HEADER.Machine == IMAGE_FILE_MACHINE_ARM64:
+        patch_entrypoint_arm64(memory_bin, entrypoint_offset, reloc_off, reloc_sz)
+    else:
+        patch_entrypoint_x86(memory_bin, entrypoint_offset, reloc_off, reloc_sz)
 
     # Write to file
     with open(binary_path, "wb") as f:
@@ -87,7 +123,17 @@ if __name__ == '__main__':
                 assert e.address >= pos_begin
                 exports[e_name] = e.address
 
+    # Process base relocations (IMAGE_REL_BASED_HIGHLOW = 3, IMAGE_REL_BASED_DIR64 = 10)
+    relocations = []
+    if hasattr(pe, "DIRECTORY_ENTRY_BASERELOC"):
+        for block in pe.DIRECTORY_ENTRY_BASERELOC:
+            relocations += [e.rva - pos_begin for e in block.entries if e.type in [3, 10]]
+
     fdict = {}
     fdict['entrypoint_offset'] = entrypoint_offset
     fdict['exports'] = exports
+    fdict['vaddr_base'] = pos_begin
+    fdict['memory_size'] = len(memory_bin)
+    fdict['dynamic_offset'] = None
+    fdict['relocations'] = sorted(relocations)
     print(json.dumps(fdict))    # callers of this script can capture stdout to get this value
\ No newline at end of file
@@ -8,7 +8,28 @@ shift
 build_mode="$1"
 shift
 
-if [[ "$target_name" == "x86_64-unknown-linux-gnu" ]]; then
+# BASM_BITS=32 retargets 64-bit Linux builds to i686 for judges that only run 32-bit binaries.
+if [[ "$BASM_BITS" == "32" ]]; then
+  if [[ "$target_name" == "x86_64-unknown-linux-gnu" ]]; then
+    target_name="i686-unknown-linux-gnu"
+  elif [[ "$target_name" != "i686-unknown-linux-gnu" ]]; then
+    >&2 echo "BASM_BITS=32 is not supported for target ${target_name}"
+    exit
+  fi
+  if [[ "$*" == *"short"* ]]; then
+    >&2 echo "The --short option is not supported for 32-bit builds"
+    exit
+  fi
+elif [[ -n "$BASM_BITS" && "$BASM_BITS" != "64" ]]; then
+  >&2 echo "Unknown BASM_BITS ${BASM_BITS} (expected 32 or 64)"
+  exit
+fi
+
+if [[ "$lang_name" == "Raw" ]]; then
+  # The flat binary and its metadata only; no stub or template is involved
+  stub="static-pie-stub-amd64.bin"
+  template=""
+elif [[ "$target_name" == "x86_64-unknown-linux-gnu" ]]; then
   stub="static-pie-stub-amd64.bin"
   if [[ "$lang_name" == "C" ]]; then
     if [[ "$*" == *"short"* ]]; then
@@ -18,12 +39,26 @@ if [[ "$target_name" == "x86_64-unknown-linux-gnu" ]]; then
     fi
   elif [[ "$lang_name" == "CFnImpl" ]]; then
     template="static-pie-template-amd64-fn-impl.c"
+  elif [[ "$lang_name" == "C89" ]]; then
+    template="static-pie-template-amd64-c89.c"
   elif [[ "$lang_name" == "Rust" ]]; then
     if [[ "$*" == *"short"* ]]; then
       template="static-pie-template-amd64-short.rs"
     else
       template="static-pie-template-amd64.rs"
     fi
+  elif [[ "$lang_name" == "Python" ]]; then
+    template="static-pie-template-amd64.py"
+  elif [[ "$lang_name" == "Ruby" ]]; then
+    template="static-pie-template-amd64.rb"
+  elif [[ "$lang_name" == "Java" ]]; then
+    template="static-pie-template-amd64.java"
+  elif [[ "$lang_name" == "Kotlin" ]]; then
+    template="static-pie-template-amd64.kt"
+  elif [[ "$lang_name" == "CSharp" ]]; then
+    template="static-pie-template-amd64.cs"
+  elif [[ "$lang_name" == "JavaScript" ]]; then
+    template="static-pie-template-amd64.js"
   else
     >&2 echo "Language ${lang_name} is not supported for target ${target_name}"
     exit
@@ -42,8 +77,20 @@ elif [[ "$target_name" == "x86_64-pc-windows-msvc" ]] || [[ "$target_name" == "x
     template="static-pie-template-amd64.c"
   elif [[ "$lang_name" == "CFnImpl" ]]; then
     template="static-pie-template-amd64-fn-impl.c"
+  elif [[ "$lang_name" == "C89" ]]; then
+    template="static-pie-template-amd64-c89.c"
   elif [[ "$lang_name" == "Rust" ]]; then
     template="static-pie-template-amd64.rs"
+  elif [[ "$lang_name" == "Python" ]]; then
+    template="static-pie-template-amd64.py"
+  elif [[ "$lang_name" == "Ruby" ]]; then
+    template="static-pie-template-amd64.rb"
+  elif [[ "$lang_name" == "Java" ]]; then
+    template="static-pie-template-amd64.java"
+  elif [[ "$lang_name" == "Kotlin" ]]; then
+    template="static-pie-template-amd64.kt"
+  elif [[ "$lang_name" == "CSharp" ]]; then
+    template="static-pie-template-amd64.cs"
   else
     >&2 echo "Language ${lang_name} is not supported for target ${target_name}"
     exit
@@ -70,6 +117,36 @@ else
   extra_config=""
 fi
 
+# BASM_TOOLCHAIN=stable builds with the stable toolchain for environments where nightly is unavailable.
+# The `nightly` feature is turned off (see basm-std/Cargo.toml), and the -Z flags in .cargo/config.toml are
+# replaced. The output may be slightly larger (e.g., generic functions are shared between crates).
+toolchain="+nightly"
+if [[ "$BASM_TOOLCHAIN" == "stable" ]]; then
+  if [[ "$target_name" != "x86_64-unknown-linux-gnu" ]]; then
+    >&2 echo "BASM_TOOLCHAIN=stable is only supported for target x86_64-unknown-linux-gnu (without --short)"
+    exit
+  fi
+  toolchain="+stable"
+  extra_config="--no-default-features"
+  export RUSTFLAGS="-C target-feature=+avx,+avx2,+sse,+sse2,+sse3,+ssse3,+sse4.1,+sse4.2,+fma,+f16c,+aes -C relocation-model=pie -C target-cpu=haswell"
+elif [[ -n "$BASM_TOOLCHAIN" && "$BASM_TOOLCHAIN" != "nightly" ]]; then
+  >&2 echo "Unknown BASM_TOOLCHAIN ${BASM_TOOLCHAIN} (expected nightly or stable)"
+  exit
+fi
+
+# Cross-compiling the Linux payload from macOS or Windows (Git Bash): link with the rust-lld bundled with
+# the toolchain instead of a GCC targeting Linux (see basm/build.rs), and strip with its objcopy.
+objcopy="objcopy"
+if [[ "$target_name" == x86_64-unknown-linux-gnu* && "$(uname -s)" != "Linux" ]]; then
+  if [[ "$target_name" != "x86_64-unknown-linux-gnu" ]]; then
+    >&2 echo "The --short option is not supported when cross-compiling from $(uname -s)"
+    exit
+  fi
+  extra_config="$extra_config --config target.x86_64-unknown-linux-gnu.linker='rust-lld'"
+  host_name="$(rustc $toolchain -vV | sed -n 's/^host: //p')"
+  objcopy="$(rustc $toolchain --print sysroot)/lib/rustlib/${host_name}/bin/rust-objcopy"
+fi
+
 if [[ "$lang_name" == "CFnImpl" ]]; then
   lang_name="C"
 fi
@@ -77,16 +154,17 @@ fi
 >&2 echo "Building project for target ${target_name}, language ${lang_name}, build mode ${build_mode}"
 
 if [[ "$build_mode" == "Debug" ]]; then
-  cargo +nightly build $extra_config --target "$target_name_cargo" --bin basm-submit --features=submit "$@"
+  cargo $toolchain build $extra_config --target "$target_name_cargo" --bin basm-submit --features=submit "$@"
 else
-  cargo +nightly build $extra_config --target "$target_name_cargo" --bin basm-submit --features=submit --release "$@"
+  cargo $toolchain build $extra_config --target "$target_name_cargo" --bin basm-submit --features=submit --release "$@"
 fi
 
 if [[ "$target_name" == "x86_64-pc-windows-msvc" ]] || [[ "$target_name" == "x86_64-pc-windows-gnu" ]]; then
   python3 scripts/static-pie-gen.py basm/ "$target_name" target/"$target_name"/"$build_mode_dir"/basm-submit.exe "$stub" "$lang_name" "$template"
 else
   cp target/"$target_name"/"$build_mode_dir"/basm-submit target/"$target_name"/"$build_mode_dir"/basm-submit-stripped
-  objcopy --strip-all target/"$target_name"/"$build_mode_dir"/basm-submit-stripped
-  objcopy --remove-section .eh_frame --remove-section .gcc_except_table --remove-section .gnu.hash target/"$target_name"/"$build_mode_dir"/basm-submit-stripped
+  "$objcopy" --strip-all target/"$target_name"/"$build_mode_dir"/basm-submit-stripped
+  "$objcopy" --remove-section .eh_frame --remove-section .gcc_except_table --remove-section .gnu.hash target/"$target_name"/"$build_mode_dir"/basm-submit-stripped
+  python3 scripts/sandbox-check.py target/"$target_name"/"$build_mode_dir"/basm-submit --judge "${BASM_JUDGE:-generic}" || >&2 echo "Warning: the binary may not run in the sandbox of the judge (see above)"
   python3 scripts/static-pie-gen.py basm/ "$target_name" target/"$target_name"/"$build_mode_dir"/basm-submit-stripped "$stub" "$lang_name" "$template"
 fi
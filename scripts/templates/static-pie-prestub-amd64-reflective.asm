@@ -0,0 +1,51 @@
+; -*- tab-width: 4 -*-
+;
+; The reflective prestub for amd64 targets
+; (reflective prestub: a first stage that runs the stub from non-executable memory)
+;
+; When the stub and the payload are embedded in a byte array of the host
+; language (e.g., Python, Java), the host buffer can never be made executable
+; in place. Only this first stage, which is a few dozen bytes long, needs to be
+; placed in executable memory by the host. It allocates an RWX region through
+; PLATFORM_DATA.ptr_alloc_rwx, copies the stub (the second stage) there,
+; and jumps to it as if the stub were called directly by the host.
+;
+; Calling convention: Microsoft x64 (same as the stub), with two extra arguments
+;   rcx = PLATFORM_DATA table
+;   rdx = LZMA-compressed binary (passed to the stub as-is)
+;   r8  = stub (may reside in non-executable memory)
+;   r9  = size of the stub in bytes
+;
+; build: nasm -f bin -O9 static-pie-prestub-amd64-reflective.asm -o static-pie-prestub-amd64-reflective.bin
+
+BITS 64
+ORG 0
+section .text
+
+_start:
+    push    rbx                     ; Save non-volatile registers per win64 calling convention
+    push    rsi
+    push    rdi
+    push    r12
+    push    rbp
+    mov     rbp, rsp
+    and     rsp, -16                ; Align stack on 16-byte boundary
+    sub     rsp, 32                 ; shadow space
+    mov     rbx, rcx                ; rbx = PLATFORM_DATA table
+    mov     rdi, rdx                ; rdi = LZMA-compressed binary
+    mov     rsi, r8                 ; rsi = stub
+    mov     r12, r9                 ; r12 = size of the stub
+    mov     rcx, r9
+    call    qword [rbx + 32]        ; svc_alloc_rwx: allocate the RWX memory for the stub
+    mov     rdx, rdi                ; rdx = LZMA-compressed binary
+    mov     rcx, r12
+    mov     rdi, rax
+    rep     movsb                   ; Copy the stub
+    mov     rcx, rbx                ; rcx = PLATFORM_DATA table
+    mov     rsp, rbp
+    pop     rbp                     ; Restore non-volatile registers
+    pop     r12
+    pop     rdi
+    pop     rsi
+    pop     rbx
+    jmp     rax                     ; Tail-call the stub (it returns directly to the host)
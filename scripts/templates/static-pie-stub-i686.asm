@@ -5,6 +5,11 @@
 ;
 ; Micro LZMA decoder for x86 (static)
 ;
+; This is the first code to run in a submission: a position-independent LZMA
+; decoder that unpacks the payload into memory from svc_alloc_rwx and calls its
+; entrypoint. It uses no absolute addresses, so it needs no relocation; the
+; decompressed binary relocates and initializes itself afterwards.
+;
 ; This software is distributed under the terms of the
 ; Creative Commons Attribution 3.0 License (CC-BY 3.0)
 ; http://creativecommons.org/licenses/by/3.0/
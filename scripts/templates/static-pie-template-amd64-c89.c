@@ -0,0 +1,239 @@
+/* Generated with https://github.com/kiwiyou/basm-rs */
+/* Learn rust (https://doc.rust-lang.org/book/) and get high performance out of the box! */
+
+/* SOLUTION BEGIN */
+$$$$solution_src$$$$
+/* SOLUTION END */
+
+/* LOADER BEGIN */
+/* This loader only uses ANSI C89 (ISO C90) so that it compiles on judges running old compilers in strict mode */
+/* (e.g., `gcc -std=c89 -pedantic`): no declarations after statements, no `//` comments, no <stdint.h> */
+/* and no `long long` literals. Unlike static-pie-template-amd64.c, the stub is always copied to RWX memory. */
+#include <stddef.h>
+#ifdef _WIN32
+#define WIN32_LEAN_AND_MEAN
+#include <Windows.h>
+#include <io.h>
+#elif defined(__linux__)
+#include <sys/mman.h>
+#include <unistd.h>
+#else
+#include <stdio.h>
+#include <stdlib.h>
+#endif
+
+typedef unsigned char basm_u8;
+typedef unsigned int basm_u32;
+#if defined(_MSC_VER)
+typedef unsigned __int64 basm_u64;
+#elif defined(__LP64__)
+typedef unsigned long basm_u64;
+#else
+__extension__ typedef unsigned long long basm_u64;
+#endif
+
+/* Use the Microsoft x64 calling convention on amd64 (64bit) */
+#if defined(__LP64__)
+#define BASMCALL __attribute__((ms_abi))
+#elif defined(_WIN64)
+#if defined(_MSC_VER)
+#define BASMCALL
+#else
+#define BASMCALL __attribute__((ms_abi))
+#endif
+#else
+#error "The current file can only be compiled for amd64."
+#define BASMCALL
+#endif
+
+/* Base85 decoder. Code adapted from: */
+/*     https://github.com/rafagafe/base85/blob/master/base85.c */
+const char *b85 = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>\?@^_`{|}~";
+void b85tobin(void *dest, char const *src) {
+    basm_u32 *p = (basm_u32 *)dest;
+    basm_u8 digittobin[256];
+    basm_u32 value, i;
+    for (i = 0; i < 85; i++) digittobin[(basm_u8)b85[i]] = (basm_u8)i;
+    while (1) {
+        while (*src == '\0') src++;
+        if (*src == ']') break;
+        value = 0;
+        for (i = 0; i < 5; i++) {
+            value *= 85;
+            value += digittobin[(basm_u8)*src++];
+        }
+        *p++ = (value >> 24) | ((value >> 8) & 0xff00) | ((value << 8) & 0xff0000) | (value << 24);
+    }
+}
+
+#define BASM_PAYLOAD_ENCODING $$$$binary_encoding$$$$
+#if BASM_PAYLOAD_ENCODING == 91
+/* Base91 decoder (the same scheme as the one in static-pie-prestub-amd64.asm) */
+/* Each pair of characters encodes 13 bits; the stream is terminated by '!'. */
+void b91tobin(void *dest, char const *src) {
+    basm_u8 *p = (basm_u8 *)dest;
+    basm_u32 state = 0x1f;
+    while (1) {
+        while (*src == '\0') src++;
+        if ((basm_u8)*src < 0x24) break;
+        state = (state << 13) + ((basm_u8)*src++ - 0x24);
+        while (*src == '\0') src++;
+        state += ((basm_u8)*src++ - 0x24) * 91;
+        do {
+            *p++ = (basm_u8)state;
+            state >>= 8;
+        } while (state & 0x1000);
+    }
+}
+#define PAYLOAD_DECODE b91tobin
+#else
+#define PAYLOAD_DECODE b85tobin
+#endif
+
+/* Offset of the Windows image in the decoded payload (0 if there is none; see BASM_WINDOWS_BINARY) */
+#define BASM_PAYLOAD_WINDOWS_OFFSET $$$$payload_windows_offset$$$$
+
+/* CRC-32 of the decoded payload (0 if the check is disabled; see BASM_CHECKSUM) */
+#define BASM_PAYLOAD_CRC32 $$$$payload_crc32$$$$U
+#if BASM_PAYLOAD_CRC32 != 0
+int payload_intact(const basm_u8 *p) {
+    basm_u32 crc = 0xFFFFFFFFU;
+    size_t i;
+    int k;
+    for (i = 0; i < $$$$payload_len$$$$; i++) {
+        crc ^= p[i];
+        for (k = 0; k < 8; k++) crc = (crc >> 1) ^ (0xEDB88320U & (0U - (crc & 1)));
+    }
+    return (~crc & 0xFFFFFFFFU) == BASM_PAYLOAD_CRC32;
+}
+#endif
+
+/* Function pointers are kept as function pointer types, since ISO C does not allow */
+/* converting them to `void *` */
+typedef void (*basm_fn)(void);
+typedef void *(BASMCALL *alloc_rwx_ptr)(size_t);
+
+#pragma pack(push, 1)
+typedef struct {
+    basm_u64        env_id;
+    basm_u64        env_flags;
+    basm_u64        win_kernel32;       /* handle of kernel32.dll */
+    basm_u64        win_GetProcAddress; /* pointer to kernel32!GetProcAddress */
+    alloc_rwx_ptr   ptr_alloc_rwx;      /* pointer to function */
+    basm_fn         ptr_alloc;          /* pointer to function */
+    basm_fn         ptr_alloc_zeroed;   /* pointer to function */
+    basm_fn         ptr_dealloc;        /* pointer to function */
+    basm_fn         ptr_realloc;        /* pointer to function */
+    basm_fn         ptr_read_stdio;     /* pointer to function */
+    basm_fn         ptr_write_stdio;    /* pointer to function */
+} PLATFORM_DATA;
+#pragma pack(pop)
+
+#define ENV_ID_UNKNOWN              0
+#define ENV_ID_WINDOWS              1
+#define ENV_ID_LINUX                2
+#define ENV_ID_WASM                 3
+#define ENV_ID_MACOS                4
+#define ENV_FLAGS_LINUX_STYLE_CHKSTK    0x0001  /* disables __chkstk in binaries compiled with Windows target */
+#define ENV_FLAGS_NATIVE                0x0002  /* indicates the binary is running without the loader */
+#define ENV_FLAGS_NO_EXIT               0x0004  /* do not call SYS_exitgroup on Linux (support fn-impl scenarios) */
+
+#if !defined(_WIN32) && !defined(__linux__)
+BASMCALL void *svc_alloc(size_t size, size_t align) {
+    return malloc(size);
+}
+BASMCALL void *svc_alloc_zeroed(size_t size, size_t align) {
+    return calloc(1, size);
+}
+BASMCALL void svc_free(void *ptr, size_t size, size_t align) {
+    free(ptr);
+}
+BASMCALL void *svc_realloc(void* memblock, size_t old_size, size_t old_align, size_t new_size) {
+    /* This won't be called in loader stub. */
+    /* Also, the main executable will directly call OS APIs/syscalls */
+    return realloc(memblock, new_size);
+}
+BASMCALL size_t svc_read_stdio(size_t fd, void *buf, size_t count) {
+    if (fd != 0) return 0;
+    return fread(buf, 1, count, stdin);
+}
+BASMCALL size_t svc_write_stdio(size_t fd, void *buf, size_t count) {
+    if (fd != 1 && fd != 2) return 0;
+    return fwrite(buf, 1, count, (fd == 1) ? stdout : stderr);
+}
+#endif
+
+BASMCALL void *svc_alloc_rwx(size_t size) {
+#ifdef _WIN32
+    size_t ret = (size_t) VirtualAlloc(NULL, size, MEM_COMMIT | MEM_RESERVE, PAGE_EXECUTE_READWRITE);
+#elif defined(__linux__)
+    size_t ret = (size_t) mmap(NULL, size, 0x7, 0x22, -1, 0);
+    if (ret == (size_t)-1) ret = 0;
+#else
+    size_t ret = (size_t) malloc(size);
+#endif
+    return (void *) ret;
+}
+
+typedef int (BASMCALL *stub_ptr)(void *, void *);
+
+const char stub_raw[] = $$$$stub_raw$$$$;
+char payload[][$$$$min_len_4096$$$$] = $$$$binary_payload$$$$;
+
+int main(void) {
+    PLATFORM_DATA pd;
+    union { void *mem; stub_ptr fn; } stub;
+    char *stub_mem;
+    size_t i;
+    if (sizeof(size_t) != 8 || sizeof(basm_u64) != 8) {
+        /* Cannot run amd64 binaries on non-64bit environment */
+        return 1;
+    }
+    pd.env_flags            = 0; /* necessary since pd is on stack */
+    pd.win_kernel32         = 0;
+    pd.win_GetProcAddress   = 0;
+#if defined(_WIN32)
+    pd.env_id               = ENV_ID_WINDOWS;
+#elif defined(__linux__)
+    pd.env_id               = ENV_ID_LINUX;
+    /* Linux's stack growth works differently than Windows. */
+    /* Hence, we disable the __chkstk mechanism on Linux. */
+    pd.env_flags            |= ENV_FLAGS_LINUX_STYLE_CHKSTK;
+#else
+    pd.env_id               = ENV_ID_UNKNOWN;
+#endif
+#if defined(_WIN32)
+    pd.win_kernel32         = (basm_u64) (size_t) GetModuleHandleW(L"kernel32");
+    pd.win_GetProcAddress   = (basm_u64) (size_t) GetProcAddress;
+#endif
+    pd.ptr_alloc_rwx        = svc_alloc_rwx;
+#if !defined(_WIN32) && !defined(__linux__)
+    pd.ptr_alloc            = (basm_fn) svc_alloc;
+    pd.ptr_alloc_zeroed     = (basm_fn) svc_alloc_zeroed;
+    pd.ptr_dealloc          = (basm_fn) svc_free;
+    pd.ptr_realloc          = (basm_fn) svc_realloc;
+    pd.ptr_read_stdio       = (basm_fn) svc_read_stdio;
+    pd.ptr_write_stdio      = (basm_fn) svc_write_stdio;
+#endif
+
+    stub_mem = (char *) svc_alloc_rwx(4096);
+    if (stub_mem == NULL) return 1;
+    for (i = 0; i < sizeof(stub_raw); i++) stub_mem[i] = stub_raw[i];
+    stub.mem = stub_mem;
+    PAYLOAD_DECODE(payload, (char const *)payload);
+#if BASM_PAYLOAD_CRC32 != 0
+    if (!payload_intact((const basm_u8 *)payload)) {
+        static const char msg[] = "The payload is corrupted (checksum mismatch). Please copy the whole code again.\n";
+#if defined(_WIN32)
+        _write(2, msg, sizeof(msg) - 1);
+#elif defined(__linux__)
+        write(2, msg, sizeof(msg) - 1);
+#else
+        fputs(msg, stderr);
+#endif
+        return 1;
+    }
+#endif
+    return stub.fn(&pd, (char *)payload + (pd.env_id == ENV_ID_WINDOWS ? BASM_PAYLOAD_WINDOWS_OFFSET : 0));
+}
+/* LOADER END */
@@ -63,6 +63,46 @@ void b85tobin(void *dest, char const *src) {
     }
 }
 
+#define BASM_PAYLOAD_ENCODING $$$$binary_encoding$$$$
+#if BASM_PAYLOAD_ENCODING == 91
+// Base91 decoder (the same scheme as the one in static-pie-prestub-amd64.asm)
+// Each pair of characters encodes 13 bits; the stream is terminated by '!'.
+void b91tobin(void *dest, char const *src) {
+    uint8_t *p = (uint8_t *)dest;
+    uint32_t state = 0x1f;
+    while (1) {
+        while (*src == '\0') src++;
+        if ((uint8_t)*src < 0x24) break;
+        state = (state << 13) + ((uint8_t)*src++ - 0x24);
+        while (*src == '\0') src++;
+        state += ((uint8_t)*src++ - 0x24) * 91;
+        do {
+            *p++ = (uint8_t)state;
+            state >>= 8;
+        } while (state & 0x1000);
+    }
+}
+#define PAYLOAD_DECODE b91tobin
+#else
+#define PAYLOAD_DECODE b85tobin
+#endif
+
+// Offset of the Windows image in the decoded payload (0 if there is none; see BASM_WINDOWS_BINARY)
+#define BASM_PAYLOAD_WINDOWS_OFFSET $$$$payload_windows_offset$$$$
+
+// CRC-32 of the decoded payload (0 if the check is disabled; see BASM_CHECKSUM)
+#define BASM_PAYLOAD_CRC32 $$$$payload_crc32$$$$U
+#if BASM_PAYLOAD_CRC32 != 0
+int payload_intact(const uint8_t *p) {
+    uint32_t crc = 0xFFFFFFFFU;
+    for (size_t i = 0; i < $$$$payload_len$$$$; i++) {
+        crc ^= p[i];
+        for (int k = 0; k < 8; k++) crc = (crc >> 1) ^ (0xEDB88320U & (0U - (crc & 1)));
+    }
+    return ~crc == BASM_PAYLOAD_CRC32;
+}
+#endif
+
 #pragma pack(push, 1)
 typedef struct {
     uint64_t    env_id;
@@ -139,7 +179,7 @@ stub_ptr get_stub() {
     return (stub_ptr) stub;
 }
 #endif
-char payload[][$$$$min_len_4096$$$$] = $$$$binary_base85$$$$;
+char payload[][$$$$min_len_4096$$$$] = $$$$binary_payload$$$$;
 
 #if defined(__linux__) && (defined(BOJ) || defined(BASM_CI))
 int main() {}
@@ -205,7 +245,20 @@ int main(int argc, char *argv[]) {
     pd.ptr_alloc_rwx = (void *) (stubbuf + 0x1c); // thunk implements its own svc_alloc_rwx
     stub = (stub_ptr) stubbuf;
 #endif
-    b85tobin(payload, (char const *)payload);
-    return stub(&pd, payload);
+    PAYLOAD_DECODE(payload, (char const *)payload);
+#if BASM_PAYLOAD_CRC32 != 0
+    if (!payload_intact((const uint8_t *)payload)) {
+        static const char msg[] = "The payload is corrupted (checksum mismatch). Please copy the whole code again.\n";
+#if defined(_WIN32)
+        _write(2, msg, sizeof(msg) - 1);
+#elif defined(__linux__)
+        write(2, msg, sizeof(msg) - 1);
+#else
+        fputs(msg, stderr);
+#endif
+        return 1;
+    }
+#endif
+    return stub(&pd, (char *)payload + (pd.env_id == ENV_ID_WINDOWS ? BASM_PAYLOAD_WINDOWS_OFFSET : 0));
 }
 // LOADER END
\ No newline at end of file
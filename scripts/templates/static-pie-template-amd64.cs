@@ -0,0 +1,147 @@
+// Generated with https://github.com/kiwiyou/basm-rs
+// Learn rust (https://doc.rust-lang.org/book/) and get high performance out of the box!
+
+// SOLUTION BEGIN
+$$$$solution_src$$$$
+// SOLUTION END
+
+// LOADER BEGIN
+using System;
+using System.Collections.Generic;
+using System.Runtime.InteropServices;
+using System.Threading;
+
+public static class Program {
+    const ulong ENV_ID_WINDOWS = 1;
+    const ulong ENV_ID_LINUX = 2;
+    const ulong ENV_FLAGS_LINUX_STYLE_CHKSTK = 0x0001;  // disables __chkstk in binaries compiled with Windows target
+
+    [StructLayout(LayoutKind.Sequential, Pack = 1)]
+    struct PLATFORM_DATA {
+        public ulong env_id;
+        public ulong env_flags;
+        public IntPtr win_kernel32;         // handle of kernel32.dll
+        public IntPtr win_GetProcAddress;   // pointer to kernel32!GetProcAddress
+        public IntPtr ptr_alloc_rwx;        // pointer to function
+        public IntPtr ptr_alloc;            // pointer to function
+        public IntPtr ptr_alloc_zeroed;     // pointer to function
+        public IntPtr ptr_dealloc;          // pointer to function
+        public IntPtr ptr_realloc;          // pointer to function
+        public IntPtr ptr_read_stdio;       // pointer to function
+        public IntPtr ptr_write_stdio;      // pointer to function
+    }
+
+    [DllImport("kernel32")]
+    static extern IntPtr VirtualAlloc(IntPtr lpAddress, UIntPtr dwSize, uint flAllocationType, uint flProtect);
+    [DllImport("kernel32", CharSet = CharSet.Unicode)]
+    static extern IntPtr GetModuleHandleW(string lpModuleName);
+    [DllImport("kernel32", CharSet = CharSet.Ansi)]
+    static extern IntPtr GetProcAddress(IntPtr hModule, string lpProcName);
+    [DllImport("libc")]
+    static extern IntPtr mmap(IntPtr addr, UIntPtr length, int prot, int flags, int fd, IntPtr offset);
+
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    delegate IntPtr AllocRwxFn(UIntPtr size);
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    delegate int StubFn(IntPtr pd, IntPtr payload);
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    delegate int CallThunkFn(IntPtr stub, IntPtr pd, IntPtr payload);
+
+    static byte[] B85Decode(string s) {
+        const string b85 = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+        int n = s.IndexOf(']') / 5;
+        byte[] output = new byte[n * 4];
+        for (int i = 0; i < n; i++) {
+            uint value = 0;
+            for (int j = 0; j < 5; j++) value = value * 85 + (uint)b85.IndexOf(s[i * 5 + j]);
+            for (int j = 0; j < 4; j++) output[i * 4 + j] = (byte)(value >> (24 - 8 * j));
+        }
+        return output;
+    }
+
+    static byte[] B91Decode(string s) {
+        // Each pair of characters encodes 13 bits; the stream is terminated by '!'.
+        List<byte> output = new List<byte>();
+        ulong state = 0x1f;
+        for (int i = 0; i + 1 < s.Length; i += 2) {
+            state = (state << 13) + (ulong)(s[i] - 0x24) + (ulong)(s[i + 1] - 0x24) * 91;
+            do {
+                output.Add((byte)state);
+                state >>= 8;
+            } while ((state & 0x1000) != 0);
+        }
+        return output.ToArray();
+    }
+
+    static uint Crc32(byte[] data, int length) {
+        uint crc = 0xFFFFFFFF;
+        for (int i = 0; i < length; i++) {
+            crc ^= data[i];
+            for (int k = 0; k < 8; k++) crc = (crc >> 1) ^ (0xEDB88320 & (0 - (crc & 1)));
+        }
+        return ~crc;
+    }
+
+    static IntPtr Copy(IntPtr dest, byte[] src) {
+        Marshal.Copy(src, 0, dest, src.Length);
+        return dest;
+    }
+
+    const int BASM_PAYLOAD_ENCODING = $$$$binary_encoding$$$$;
+    const string STUB = $$$$stub_base85$$$$;
+    const string PAYLOAD = $$$$binary_payload$$$$;
+    const uint BASM_PAYLOAD_CRC32 = $$$$payload_crc32$$$$;     // CRC-32 of the decoded payload (0 if the check is disabled; see BASM_CHECKSUM)
+    static AllocRwxFn svcAllocRwx;      // kept alive while the payload is running
+
+    static int Run() {
+        byte[] stub = B85Decode(STUB);
+        byte[] payload = BASM_PAYLOAD_ENCODING == 91 ? B91Decode(PAYLOAD) : B85Decode(PAYLOAD);
+        if (BASM_PAYLOAD_CRC32 != 0 && Crc32(payload, $$$$payload_len$$$$) != BASM_PAYLOAD_CRC32) {
+            Console.Error.WriteLine("The payload is corrupted (checksum mismatch). Please copy the whole code again.");
+            return 1;
+        }
+        IntPtr payloadPtr = Copy(Marshal.AllocHGlobal(payload.Length), payload);
+        PLATFORM_DATA pd = new PLATFORM_DATA();
+        IntPtr pdPtr = Marshal.AllocHGlobal(Marshal.SizeOf(typeof(PLATFORM_DATA)));
+        if (RuntimeInformation.IsOSPlatform(OSPlatform.Windows)) {
+            // On Windows, delegates are called with the Microsoft x64 calling convention as the stub expects
+            svcAllocRwx = size => VirtualAlloc(IntPtr.Zero, size, 0x3000, 0x40);   // MEM_COMMIT | MEM_RESERVE, PAGE_EXECUTE_READWRITE
+            pd.env_id = ENV_ID_WINDOWS;
+            pd.win_kernel32 = GetModuleHandleW("kernel32");
+            pd.win_GetProcAddress = GetProcAddress(pd.win_kernel32, "GetProcAddress");
+            pd.ptr_alloc_rwx = Marshal.GetFunctionPointerForDelegate(svcAllocRwx);
+            Marshal.StructureToPtr(pd, pdPtr, false);
+            IntPtr stubPtr = Copy(svcAllocRwx((UIntPtr)stub.Length), stub);
+            StubFn stubFn = Marshal.GetDelegateForFunctionPointer<StubFn>(stubPtr);
+            return stubFn(pdPtr, payloadPtr);
+        } else {
+            // .NET calls native code with the System V ABI, whereas the stub uses the Microsoft x64 ABI.
+            // Hence, we place two thunks in front of the stub:
+            //   [0x00, 0x16) call_thunk(stub, pd, payload): aligns the stack, provides the shadow space and calls stub(pd, payload)
+            //   [0x16, 0x39) svc_alloc_rwx(size) with the Microsoft x64 ABI: mmap(NULL, size, RWX, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0)
+            byte[] thunk = Convert.FromHexString("554889e54883e4f04883ec204889f84889f1ffd0c9c3"
+                + "57564889ce31ffba0700000041ba220000004983c8ff4531c9b8090000000f055e5fc3");
+            IntPtr buf = mmap(IntPtr.Zero, (UIntPtr)(thunk.Length + stub.Length), 7, 0x22, -1, IntPtr.Zero);
+            Copy(buf, thunk);
+            Copy(buf + thunk.Length, stub);
+            pd.env_id = ENV_ID_LINUX;
+            // Linux's stack growth works differently than Windows.
+            // Hence, we disable the __chkstk mechanism on Linux.
+            pd.env_flags = ENV_FLAGS_LINUX_STYLE_CHKSTK;
+            pd.ptr_alloc_rwx = buf + 0x16;
+            Marshal.StructureToPtr(pd, pdPtr, false);
+            CallThunkFn callThunk = Marshal.GetDelegateForFunctionPointer<CallThunkFn>(buf);
+            return callThunk(buf + thunk.Length, pdPtr, payloadPtr);
+        }
+    }
+
+    public static void Main() {
+        // The payload runs on the stack of the calling thread, so we provide a large one
+        int ret = 0;
+        Thread t = new Thread(() => { ret = Run(); }, 1 << 28);
+        t.Start();
+        t.Join();
+        Environment.Exit(ret);
+    }
+}
+// LOADER END
@@ -0,0 +1,131 @@
+// Generated with https://github.com/kiwiyou/basm-rs
+// Learn rust (https://doc.rust-lang.org/book/) and get high performance out of the box!
+
+// SOLUTION BEGIN
+$$$$solution_src$$$$
+// SOLUTION END
+
+// LOADER BEGIN
+// Requires Java 22 or later (Foreign Function & Memory API)
+import java.lang.foreign.*;
+import java.lang.invoke.*;
+import java.nio.charset.StandardCharsets;
+import static java.lang.foreign.ValueLayout.*;
+
+public class Main {
+    static final long ENV_ID_WINDOWS = 1;
+    static final long ENV_ID_LINUX = 2;
+    static final long ENV_FLAGS_LINUX_STYLE_CHKSTK = 0x0001;   // disables __chkstk in binaries compiled with Windows target
+
+    static final int BASM_PAYLOAD_ENCODING = $$$$binary_encoding$$$$;
+    static final String STUB = $$$$stub_base85$$$$;
+    // Split into multiple literals since a string constant in a class file is limited to 65535 bytes
+    static final String[] PAYLOAD = $$$$binary_payload$$$$;
+    static final long BASM_PAYLOAD_CRC32 = $$$$payload_crc32$$$$L;  // CRC-32 of the decoded payload (0 if the check is disabled; see BASM_CHECKSUM)
+
+    static byte[] b85decode(String s) {
+        String b85 = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+        int n = s.indexOf(']') / 5;
+        byte[] out = new byte[n * 4];
+        for (int i = 0; i < n; i++) {
+            long value = 0;
+            for (int j = 0; j < 5; j++) value = value * 85 + b85.indexOf(s.charAt(i * 5 + j));
+            for (int j = 0; j < 4; j++) out[i * 4 + j] = (byte) (value >> (24 - 8 * j));
+        }
+        return out;
+    }
+
+    static byte[] b91decode(String s) {
+        // Each pair of characters encodes 13 bits; the stream is terminated by '!'.
+        java.io.ByteArrayOutputStream out = new java.io.ByteArrayOutputStream();
+        long state = 0x1f;
+        for (int i = 0; i + 1 < s.length(); i += 2) {
+            state = (state << 13) + (s.charAt(i) - 0x24) + (s.charAt(i + 1) - 0x24) * 91;
+            do {
+                out.write((int) (state & 0xff));
+                state >>= 8;
+            } while ((state & 0x1000) != 0);
+        }
+        return out.toByteArray();
+    }
+
+    static MethodHandle virtualAlloc;
+    static MemorySegment svcAllocRwx(long size) throws Throwable {
+        return (MemorySegment) virtualAlloc.invokeExact(MemorySegment.NULL, size, 0x3000, 0x40);    // MEM_COMMIT | MEM_RESERVE, PAGE_EXECUTE_READWRITE
+    }
+
+    static int run() throws Throwable {
+        String payloadEncoded = String.join("", PAYLOAD);
+        byte[] stub = b85decode(STUB);
+        byte[] payload = BASM_PAYLOAD_ENCODING == 91 ? b91decode(payloadEncoded) : b85decode(payloadEncoded);
+        if (BASM_PAYLOAD_CRC32 != 0) {
+            java.util.zip.CRC32 crc = new java.util.zip.CRC32();
+            crc.update(payload, 0, $$$$payload_len$$$$);
+            if (crc.getValue() != BASM_PAYLOAD_CRC32) {
+                System.err.println("The payload is corrupted (checksum mismatch). Please copy the whole code again.");
+                return 1;
+            }
+        }
+        Linker linker = Linker.nativeLinker();
+        Arena arena = Arena.global();
+        MemorySegment pd = arena.allocate(88, 8);      // PLATFORM_DATA (zero-initialized)
+        MemorySegment payloadSegment = arena.allocate(payload.length);
+        MemorySegment.copy(payload, 0, payloadSegment, JAVA_BYTE, 0, payload.length);
+        if (System.getProperty("os.name").startsWith("Windows")) {
+            SymbolLookup kernel32 = SymbolLookup.libraryLookup("kernel32", arena);
+            virtualAlloc = linker.downcallHandle(kernel32.find("VirtualAlloc").orElseThrow(),
+                FunctionDescriptor.of(ADDRESS, ADDRESS, JAVA_LONG, JAVA_INT, JAVA_INT));
+            MethodHandle getModuleHandleW = linker.downcallHandle(kernel32.find("GetModuleHandleW").orElseThrow(),
+                FunctionDescriptor.of(ADDRESS, ADDRESS));
+            // On Windows, upcall stubs follow the Microsoft x64 calling convention as the stub expects
+            MemorySegment allocRwx = linker.upcallStub(
+                MethodHandles.lookup().findStatic(Main.class, "svcAllocRwx", MethodType.methodType(MemorySegment.class, long.class)),
+                FunctionDescriptor.of(ADDRESS, JAVA_LONG), arena);
+            MemorySegment kernel32Handle = (MemorySegment) getModuleHandleW.invokeExact(arena.allocateFrom("kernel32", StandardCharsets.UTF_16LE));
+            pd.set(JAVA_LONG, 0, ENV_ID_WINDOWS);
+            pd.set(JAVA_LONG, 16, kernel32Handle.address());
+            pd.set(JAVA_LONG, 24, kernel32.find("GetProcAddress").orElseThrow().address());
+            pd.set(ADDRESS, 32, allocRwx);
+            MemorySegment stubSegment = svcAllocRwx(stub.length).reinterpret(stub.length);
+            MemorySegment.copy(stub, 0, stubSegment, JAVA_BYTE, 0, stub.length);
+            MethodHandle stubFn = linker.downcallHandle(stubSegment, FunctionDescriptor.of(JAVA_INT, ADDRESS, ADDRESS));
+            return (int) stubFn.invokeExact(pd, payloadSegment);
+        } else {
+            // Java calls native code with the System V ABI, whereas the stub uses the Microsoft x64 ABI.
+            // Hence, we place two thunks in front of the stub:
+            //   [0x00, 0x16) call_thunk(stub, pd, payload): aligns the stack, provides the shadow space and calls stub(pd, payload)
+            //   [0x16, 0x39) svc_alloc_rwx(size) with the Microsoft x64 ABI: mmap(NULL, size, RWX, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0)
+            byte[] thunk = java.util.HexFormat.of().parseHex("554889e54883e4f04883ec204889f84889f1ffd0c9c3"
+                + "57564889ce31ffba0700000041ba220000004983c8ff4531c9b8090000000f055e5fc3");
+            MethodHandle mmap = linker.downcallHandle(linker.defaultLookup().find("mmap").orElseThrow(),
+                FunctionDescriptor.of(ADDRESS, ADDRESS, JAVA_LONG, JAVA_INT, JAVA_INT, JAVA_INT, JAVA_LONG));
+            long size = thunk.length + stub.length;
+            MemorySegment buf = ((MemorySegment) mmap.invokeExact(MemorySegment.NULL, size, 7, 0x22, -1, 0L)).reinterpret(size);
+            MemorySegment.copy(thunk, 0, buf, JAVA_BYTE, 0, thunk.length);
+            MemorySegment.copy(stub, 0, buf, JAVA_BYTE, thunk.length, stub.length);
+            pd.set(JAVA_LONG, 0, ENV_ID_LINUX);
+            // Linux's stack growth works differently than Windows.
+            // Hence, we disable the __chkstk mechanism on Linux.
+            pd.set(JAVA_LONG, 8, ENV_FLAGS_LINUX_STYLE_CHKSTK);
+            pd.set(JAVA_LONG, 32, buf.address() + 0x16);
+            MethodHandle callThunk = linker.downcallHandle(buf, FunctionDescriptor.of(JAVA_INT, ADDRESS, ADDRESS, ADDRESS));
+            return (int) callThunk.invokeExact(buf.asSlice(thunk.length), pd, payloadSegment);
+        }
+    }
+
+    static int ret = 0;
+    public static void main(String[] args) throws Throwable {
+        // The payload runs on the stack of the calling thread, so we provide a large one
+        Thread t = new Thread(null, () -> {
+            try {
+                ret = run();
+            } catch (Throwable e) {
+                throw new RuntimeException(e);
+            }
+        }, "basm", 1L << 28);
+        t.start();
+        t.join();
+        System.exit(ret);
+    }
+}
+// LOADER END
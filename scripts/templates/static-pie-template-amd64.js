@@ -0,0 +1,106 @@
+// Generated with https://github.com/kiwiyou/basm-rs
+// Learn rust (https://doc.rust-lang.org/book/) and get high performance out of the box!
+
+// SOLUTION BEGIN
+$$$$solution_src$$$$
+// SOLUTION END
+
+// LOADER BEGIN
+const fs = require('fs'), os = require('os'), path = require('path')
+
+function b85decode(s) {
+  const b85 = '0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~'
+  const n = s.indexOf(']') / 5
+  const out = Buffer.alloc(n * 4)
+  for (let i = 0; i < n; i++) {
+    let value = 0
+    for (let j = 0; j < 5; j++) value = value * 85 + b85.indexOf(s[i * 5 + j])
+    out.writeUInt32BE(value, i * 4)
+  }
+  return out
+}
+
+function b91decode(s) {
+  // Each pair of characters encodes 13 bits; the stream is terminated by '!'.
+  const out = []
+  let state = 0x1f
+  for (let i = 0; i + 1 < s.length; i += 2) {
+    state = state * 8192 + (s.charCodeAt(i) - 0x24) + (s.charCodeAt(i + 1) - 0x24) * 91
+    do {
+      out.push(state & 0xff)
+      state = Math.floor(state / 256)
+    } while (state & 0x1000)
+  }
+  return Buffer.from(out)
+}
+
+const BASM_PAYLOAD_ENCODING = $$$$binary_encoding$$$$
+const stub = b85decode($$$$stub_base85$$$$)
+const payloadEncoded = $$$$binary_payload$$$$
+const payload = BASM_PAYLOAD_ENCODING == 91 ? b91decode(payloadEncoded) : b85decode(payloadEncoded)
+const BASM_PAYLOAD_CRC32 = $$$$payload_crc32$$$$    // CRC-32 of the decoded payload (0 if the check is disabled; see BASM_CHECKSUM)
+if (BASM_PAYLOAD_CRC32 != 0) {
+  let crc = 0xffffffff
+  for (let i = 0; i < $$$$payload_len$$$$; i++) {
+    crc ^= payload[i]
+    for (let k = 0; k < 8; k++) crc = (crc >>> 1) ^ (0xedb88320 & -(crc & 1))
+  }
+  if (((~crc) >>> 0) != BASM_PAYLOAD_CRC32) {
+    console.error('The payload is corrupted (checksum mismatch). Please copy the whole code again.')
+    process.exit(1)
+  }
+}
+
+// Node.js cannot call native code without an addon, but process.dlopen() runs the initializer (DT_INIT)
+// of the shared object it loads. Hence, we wrap the stub and the payload into a minimal shared object:
+//   [0x000, 0x040) ELF header
+//   [0x040, 0x0e8) program headers: PT_LOAD (RWX), PT_DYNAMIC, PT_GNU_STACK
+//   [0x0e8, 0x158) dynamic section: DT_HASH, DT_STRTAB, DT_SYMTAB, DT_STRSZ, DT_SYMENT, DT_INIT
+//   [0x158, 0x168) hash table with a single empty bucket
+//   [0x168, 0x180) symbol table with the null symbol only (doubles as the string table)
+//   [0x180, ...)   code: the initializer, svc_alloc_rwx, PLATFORM_DATA, the stub and the payload
+// The initializer calls stub(pd, payload) with the Microsoft x64 ABI and exits with its return value.
+// svc_alloc_rwx(size) follows the Microsoft x64 ABI: mmap(NULL, size, RWX, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0)
+const CODE = 0x180
+const init = Buffer.from('534883ec20488d0d44000000488d051900000048894120488d1500000000e88500000089c7b8e70000000f05' +
+  '57564889ce31ffba0700000041ba220000004983c8ff4531c9b8090000000f055e5fc390', 'hex')
+const pd = Buffer.alloc(88)     // PLATFORM_DATA (ptr_alloc_rwx is filled in by the initializer)
+pd.writeUInt32LE(2, 0)          // env_id = ENV_ID_LINUX
+pd.writeUInt32LE(1, 8)          // env_flags = ENV_FLAGS_LINUX_STYLE_CHKSTK
+init.writeUInt32LE(init.length + pd.length + stub.length - 0x1e, 0x1a)      // lea rdx, [rip + payload]
+const image = Buffer.concat([Buffer.alloc(CODE), init, pd, stub, payload])
+Buffer.from('7f454c46020101', 'hex').copy(image, 0)
+image.writeUInt16LE(3, 16)      // e_type = ET_DYN
+image.writeUInt16LE(0x3e, 18)   // e_machine = EM_X86_64
+image.writeUInt32LE(1, 20)      // e_version
+image.writeUInt32LE(0x40, 32)   // e_phoff
+image.writeUInt16LE(0x40, 52)   // e_ehsize
+image.writeUInt16LE(56, 54)     // e_phentsize
+image.writeUInt16LE(3, 56)      // e_phnum
+image.writeUInt16LE(64, 58)     // e_shentsize
+const phdr = (i, type, flags, offset, size, align) => {
+  const x = 0x40 + 56 * i
+  image.writeUInt32LE(type, x)
+  image.writeUInt32LE(flags, x + 4)
+  for (const y of [8, 16, 24]) image.writeUInt32LE(offset, x + y)
+  for (const y of [32, 40]) image.writeUInt32LE(size, x + y)
+  image.writeUInt32LE(align, x + 48)
+}
+phdr(0, 1, 7, 0, image.length, 0x1000)  // PT_LOAD
+phdr(1, 2, 6, 0xe8, 0x70, 8)            // PT_DYNAMIC
+phdr(2, 0x6474e551, 6, 0, 0, 16)        // PT_GNU_STACK (non-executable stack)
+const dynamic = [[4, 0x158], [5, 0x168], [6, 0x168], [10, 1], [11, 24], [12, CODE]]
+dynamic.forEach(([tag, value], i) => {
+  image.writeUInt32LE(tag, 0xe8 + 16 * i)
+  image.writeUInt32LE(value, 0xe8 + 16 * i + 8)
+})
+image.writeUInt32LE(1, 0x158)   // nbucket
+image.writeUInt32LE(1, 0x15c)   // nchain
+
+// The file is unlinked right away and loaded through /proc/self/fd, so that nothing is left behind
+const file = path.join(os.tmpdir(), `basm-${process.pid}.node`)
+const fd = fs.openSync(file, 'w+', 0o700)
+fs.writeSync(fd, image)
+fs.unlinkSync(file)
+process.dlopen({ exports: {} }, `/proc/self/fd/${fd}`)
+// LOADER END
@@ -0,0 +1,127 @@
+// Generated with https://github.com/kiwiyou/basm-rs
+// Learn rust (https://doc.rust-lang.org/book/) and get high performance out of the box!
+
+// SOLUTION BEGIN
+$$$$solution_src$$$$
+// SOLUTION END
+
+// LOADER BEGIN
+// Requires Kotlin/JVM running on Java 22 or later (Foreign Function & Memory API).
+// This is a line-by-line port of the Java loader (static-pie-template-amd64.java).
+import java.lang.foreign.*
+import java.lang.invoke.*
+import java.nio.charset.StandardCharsets
+import java.lang.foreign.ValueLayout.*
+
+const val ENV_ID_WINDOWS = 1L
+const val ENV_ID_LINUX = 2L
+const val ENV_FLAGS_LINUX_STYLE_CHKSTK = 0x0001L    // disables __chkstk in binaries compiled with Windows target
+
+const val BASM_PAYLOAD_ENCODING = $$$$binary_encoding$$$$
+const val STUB = $$$$stub_base85$$$$
+// Split into multiple literals since a string constant in a class file is limited to 65535 bytes
+val PAYLOAD = $$$$binary_payload$$$$
+const val BASM_PAYLOAD_CRC32 = $$$$payload_crc32$$$$L    // CRC-32 of the decoded payload (0 if the check is disabled; see BASM_CHECKSUM)
+
+fun b85decode(s: String): ByteArray {
+    val b85 = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#\$%&()*+-;<=>?@^_`{|}~"
+    val n = s.indexOf(']') / 5
+    val out = ByteArray(n * 4)
+    for (i in 0 until n) {
+        var value = 0L
+        for (j in 0 until 5) value = value * 85 + b85.indexOf(s[i * 5 + j])
+        for (j in 0 until 4) out[i * 4 + j] = (value shr (24 - 8 * j)).toByte()
+    }
+    return out
+}
+
+fun b91decode(s: String): ByteArray {
+    // Each pair of characters encodes 13 bits; the stream is terminated by '!'.
+    val out = java.io.ByteArrayOutputStream()
+    var state = 0x1fL
+    var i = 0
+    while (i + 1 < s.length) {
+        state = (state shl 13) + (s[i].code - 0x24) + (s[i + 1].code - 0x24) * 91
+        do {
+            out.write((state and 0xff).toInt())
+            state = state shr 8
+        } while ((state and 0x1000) != 0L)
+        i += 2
+    }
+    return out.toByteArray()
+}
+
+lateinit var virtualAlloc: MethodHandle
+fun svcAllocRwx(size: Long): MemorySegment {
+    return virtualAlloc.invoke(MemorySegment.NULL, size, 0x3000, 0x40) as MemorySegment    // MEM_COMMIT | MEM_RESERVE, PAGE_EXECUTE_READWRITE
+}
+
+fun run(): Int {
+    val payloadEncoded = PAYLOAD.joinToString("")
+    val stub = b85decode(STUB)
+    val payload = if (BASM_PAYLOAD_ENCODING == 91) b91decode(payloadEncoded) else b85decode(payloadEncoded)
+    if (BASM_PAYLOAD_CRC32 != 0L) {
+        val crc = java.util.zip.CRC32()
+        crc.update(payload, 0, $$$$payload_len$$$$)
+        if (crc.value != BASM_PAYLOAD_CRC32) {
+            System.err.println("The payload is corrupted (checksum mismatch). Please copy the whole code again.")
+            return 1
+        }
+    }
+    val linker = Linker.nativeLinker()
+    val arena = Arena.global()
+    val pd = arena.allocate(88, 8)      // PLATFORM_DATA (zero-initialized)
+    val payloadSegment = arena.allocate(payload.size.toLong())
+    MemorySegment.copy(payload, 0, payloadSegment, JAVA_BYTE, 0, payload.size)
+    if (System.getProperty("os.name").startsWith("Windows")) {
+        val kernel32 = SymbolLookup.libraryLookup("kernel32", arena)
+        virtualAlloc = linker.downcallHandle(kernel32.find("VirtualAlloc").orElseThrow(),
+            FunctionDescriptor.of(ADDRESS, ADDRESS, JAVA_LONG, JAVA_INT, JAVA_INT))
+        val getModuleHandleW = linker.downcallHandle(kernel32.find("GetModuleHandleW").orElseThrow(),
+            FunctionDescriptor.of(ADDRESS, ADDRESS))
+        // On Windows, upcall stubs follow the Microsoft x64 calling convention as the stub expects
+        val lookup = MethodHandles.lookup()
+        val allocRwx = linker.upcallStub(
+            lookup.findStatic(lookup.lookupClass(), "svcAllocRwx", MethodType.methodType(MemorySegment::class.java, Long::class.javaPrimitiveType)),
+            FunctionDescriptor.of(ADDRESS, JAVA_LONG), arena)
+        val kernel32Handle = getModuleHandleW.invoke(arena.allocateFrom("kernel32", StandardCharsets.UTF_16LE)) as MemorySegment
+        pd.set(JAVA_LONG, 0, ENV_ID_WINDOWS)
+        pd.set(JAVA_LONG, 16, kernel32Handle.address())
+        pd.set(JAVA_LONG, 24, kernel32.find("GetProcAddress").orElseThrow().address())
+        pd.set(ADDRESS, 32, allocRwx)
+        val stubSegment = svcAllocRwx(stub.size.toLong()).reinterpret(stub.size.toLong())
+        MemorySegment.copy(stub, 0, stubSegment, JAVA_BYTE, 0, stub.size)
+        val stubFn = linker.downcallHandle(stubSegment, FunctionDescriptor.of(JAVA_INT, ADDRESS, ADDRESS))
+        return stubFn.invoke(pd, payloadSegment) as Int
+    } else {
+        // The JVM calls native code with the System V ABI, whereas the stub uses the Microsoft x64 ABI.
+        // Hence, we place two thunks in front of the stub:
+        //   [0x00, 0x16) call_thunk(stub, pd, payload): aligns the stack, provides the shadow space and calls stub(pd, payload)
+        //   [0x16, 0x39) svc_alloc_rwx(size) with the Microsoft x64 ABI: mmap(NULL, size, RWX, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0)
+        val thunk = java.util.HexFormat.of().parseHex("554889e54883e4f04883ec204889f84889f1ffd0c9c3" +
+            "57564889ce31ffba0700000041ba220000004983c8ff4531c9b8090000000f055e5fc3")
+        val mmap = linker.downcallHandle(linker.defaultLookup().find("mmap").orElseThrow(),
+            FunctionDescriptor.of(ADDRESS, ADDRESS, JAVA_LONG, JAVA_INT, JAVA_INT, JAVA_INT, JAVA_LONG))
+        val size = (thunk.size + stub.size).toLong()
+        val buf = (mmap.invoke(MemorySegment.NULL, size, 7, 0x22, -1, 0L) as MemorySegment).reinterpret(size)
+        MemorySegment.copy(thunk, 0, buf, JAVA_BYTE, 0, thunk.size)
+        MemorySegment.copy(stub, 0, buf, JAVA_BYTE, thunk.size.toLong(), stub.size)
+        pd.set(JAVA_LONG, 0, ENV_ID_LINUX)
+        // Linux's stack growth works differently than Windows.
+        // Hence, we disable the __chkstk mechanism on Linux.
+        pd.set(JAVA_LONG, 8, ENV_FLAGS_LINUX_STYLE_CHKSTK)
+        pd.set(JAVA_LONG, 32, buf.address() + 0x16)
+        val callThunk = linker.downcallHandle(buf, FunctionDescriptor.of(JAVA_INT, ADDRESS, ADDRESS, ADDRESS))
+        return callThunk.invoke(buf.asSlice(thunk.size.toLong()), pd, payloadSegment) as Int
+    }
+}
+
+fun main() {
+    // The payload runs on the stack of the calling thread, so we provide a large one
+    var ret = 0
+    val t = Thread(null, { ret = run() }, "basm", 1L shl 28)
+    t.start()
+    t.join()
+    System.exit(ret)
+}
+// LOADER END
@@ -0,0 +1,90 @@
+# Generated with https://github.com/kiwiyou/basm-rs
+# Learn rust (https://doc.rust-lang.org/book/) and get high performance out of the box!
+
+# SOLUTION BEGIN
+$$$$solution_src$$$$
+# SOLUTION END
+
+# LOADER BEGIN
+import base64, ctypes, mmap, sys, zlib
+
+ENV_ID_WINDOWS = 1
+ENV_ID_LINUX = 2
+ENV_FLAGS_LINUX_STYLE_CHKSTK = 0x0001   # disables __chkstk in binaries compiled with Windows target
+
+class PLATFORM_DATA(ctypes.Structure):
+    _pack_ = 1
+    _fields_ = [
+        ("env_id", ctypes.c_uint64),
+        ("env_flags", ctypes.c_uint64),
+        ("win_kernel32", ctypes.c_uint64),          # handle of kernel32.dll
+        ("win_GetProcAddress", ctypes.c_uint64),    # pointer to kernel32!GetProcAddress
+        ("ptr_alloc_rwx", ctypes.c_void_p),         # pointer to function
+        ("ptr_alloc", ctypes.c_void_p),             # pointer to function
+        ("ptr_alloc_zeroed", ctypes.c_void_p),      # pointer to function
+        ("ptr_dealloc", ctypes.c_void_p),           # pointer to function
+        ("ptr_realloc", ctypes.c_void_p),           # pointer to function
+        ("ptr_read_stdio", ctypes.c_void_p),        # pointer to function
+        ("ptr_write_stdio", ctypes.c_void_p),       # pointer to function
+    ]
+
+def b91decode(s):
+    # Each pair of characters encodes 13 bits; the stream is terminated by '!'.
+    out, state = bytearray(), 0x1f
+    for i in range(0, len(s) - 1, 2):
+        state = (state << 13) + (ord(s[i]) - 0x24) + (ord(s[i+1]) - 0x24) * 91
+        while True:
+            out.append(state & 0xff)
+            state >>= 8
+            if not state & 0x1000:
+                break
+    return bytes(out)
+
+BASM_PAYLOAD_ENCODING = $$$$binary_encoding$$$$
+stub = b$$$$stub_raw$$$$
+# The stub stays in this non-executable bytes object; only the reflective prestub below is placed
+# in executable memory, and it copies the stub into memory from svc_alloc_rwx before jumping to it.
+#   prestub(pd, payload, stub, len(stub)) with the Microsoft x64 ABI
+prestub = b$$$$prestub_reflective_raw$$$$
+payload = $$$$binary_payload$$$$
+payload = b91decode(payload) if BASM_PAYLOAD_ENCODING == 91 else base64.b85decode(payload[:-1])
+BASM_PAYLOAD_WINDOWS_OFFSET = $$$$payload_windows_offset$$$$     # offset of the Windows image in the payload (0 if there is none; see BASM_WINDOWS_BINARY)
+BASM_PAYLOAD_CRC32 = $$$$payload_crc32$$$$     # CRC-32 of the decoded payload (0 if the check is disabled; see BASM_CHECKSUM)
+if BASM_PAYLOAD_CRC32 != 0 and zlib.crc32(payload[:$$$$payload_len$$$$]) != BASM_PAYLOAD_CRC32:
+    sys.exit("The payload is corrupted (checksum mismatch). Please copy the whole code again.")
+pd = PLATFORM_DATA()
+if sys.platform == "win32":
+    kernel32 = ctypes.windll.kernel32
+    kernel32.VirtualAlloc.argtypes = [ctypes.c_void_p, ctypes.c_size_t, ctypes.c_uint32, ctypes.c_uint32]
+    kernel32.VirtualAlloc.restype = ctypes.c_void_p
+    kernel32.GetModuleHandleW.restype = ctypes.c_void_p
+    # On Windows, ctypes callbacks follow the Microsoft x64 calling convention as the stub expects
+    svc_alloc_rwx = ctypes.CFUNCTYPE(ctypes.c_void_p, ctypes.c_size_t)(
+        lambda size: kernel32.VirtualAlloc(None, size, 0x3000, 0x40))   # MEM_COMMIT | MEM_RESERVE, PAGE_EXECUTE_READWRITE
+    pd.env_id = ENV_ID_WINDOWS
+    pd.win_kernel32 = kernel32.GetModuleHandleW("kernel32")
+    pd.win_GetProcAddress = ctypes.cast(kernel32.GetProcAddress, ctypes.c_void_p).value
+    pd.ptr_alloc_rwx = ctypes.cast(svc_alloc_rwx, ctypes.c_void_p).value
+    prestub_addr = kernel32.VirtualAlloc(None, len(prestub), 0x3000, 0x40)
+    ctypes.memmove(prestub_addr, prestub, len(prestub))
+    prestub_fn = ctypes.CFUNCTYPE(ctypes.c_int, ctypes.c_void_p, ctypes.c_char_p, ctypes.c_char_p, ctypes.c_size_t)(prestub_addr)
+    ret = prestub_fn(ctypes.addressof(pd), payload[BASM_PAYLOAD_WINDOWS_OFFSET:], stub, len(stub))
+else:
+    # Python calls native code with the System V ABI, whereas the prestub uses the Microsoft x64 ABI.
+    # Hence, we place two thunks in front of the prestub:
+    #   [0x00, 0x1c) call_thunk(prestub, pd, payload, stub, len): aligns the stack, provides the shadow space and calls prestub(pd, payload, stub, len)
+    #   [0x1c, 0x3f) svc_alloc_rwx(size) with the Microsoft x64 ABI: mmap(NULL, size, RWX, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0)
+    thunk = bytes.fromhex("554889e54883e4f04883ec204889f84d89c14989c84889f1ffd0c9c3"
+        "57564889ce31ffba0700000041ba220000004983c8ff4531c9b8090000000f055e5fc3")
+    buf = mmap.mmap(-1, len(thunk) + len(prestub), prot=mmap.PROT_READ | mmap.PROT_WRITE | mmap.PROT_EXEC)
+    buf.write(thunk + prestub)
+    buf_addr = ctypes.addressof(ctypes.c_char.from_buffer(buf))
+    pd.env_id = ENV_ID_LINUX
+    # Linux's stack growth works differently than Windows.
+    # Hence, we disable the __chkstk mechanism on Linux.
+    pd.env_flags = ENV_FLAGS_LINUX_STYLE_CHKSTK
+    pd.ptr_alloc_rwx = buf_addr + 0x1c
+    call_thunk = ctypes.CFUNCTYPE(ctypes.c_int, ctypes.c_void_p, ctypes.c_void_p, ctypes.c_char_p, ctypes.c_char_p, ctypes.c_size_t)(buf_addr)
+    ret = call_thunk(buf_addr + len(thunk), ctypes.addressof(pd), payload, stub, len(stub))
+sys.exit(ret)
+# LOADER END
@@ -0,0 +1,86 @@
+# Generated with https://github.com/kiwiyou/basm-rs
+# Learn rust (https://doc.rust-lang.org/book/) and get high performance out of the box!
+
+# SOLUTION BEGIN
+$$$$solution_src$$$$
+# SOLUTION END
+
+# LOADER BEGIN
+require 'fiddle'
+require 'zlib'
+
+ENV_ID_WINDOWS = 1
+ENV_ID_LINUX = 2
+ENV_FLAGS_LINUX_STYLE_CHKSTK = 0x0001   # disables __chkstk in binaries compiled with Windows target
+
+def b85decode(s)
+  # Each group of five characters encodes a big-endian 32-bit word; the stream is terminated by ']'.
+  digits = {}
+  '0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~'.each_char.with_index { |c, i| digits[c] = i }
+  s.chomp(']').each_char.each_slice(5).map { |g| [g.inject(0) { |v, c| v * 85 + digits[c] }].pack('N') }.join
+end
+
+def b91decode(s)
+  # Each pair of characters encodes 13 bits; the stream is terminated by '!'.
+  out, state = [], 0x1f
+  (0...s.length - 1).step(2) do |i|
+    state = (state << 13) + (s.getbyte(i) - 0x24) + (s.getbyte(i + 1) - 0x24) * 91
+    loop do
+      out << (state & 0xff)
+      state >>= 8
+      break if state & 0x1000 == 0
+    end
+  end
+  out.pack('C*')
+end
+
+# PLATFORM_DATA: env_id, env_flags, win_kernel32, win_GetProcAddress, ptr_alloc_rwx,
+#   ptr_alloc, ptr_alloc_zeroed, ptr_dealloc, ptr_realloc, ptr_read_stdio, ptr_write_stdio
+def platform_data(*fields)
+  pd = Fiddle::Pointer.malloc(88)
+  pd[0, 88] = (fields + [0] * (11 - fields.length)).pack('Q*')
+  pd
+end
+
+BASM_PAYLOAD_ENCODING = $$$$binary_encoding$$$$
+stub = $$$$stub_raw$$$$.b
+payload = $$$$binary_payload$$$$
+payload = BASM_PAYLOAD_ENCODING == 91 ? b91decode(payload) : b85decode(payload)
+BASM_PAYLOAD_WINDOWS_OFFSET = $$$$payload_windows_offset$$$$     # offset of the Windows image in the payload (0 if there is none; see BASM_WINDOWS_BINARY)
+BASM_PAYLOAD_CRC32 = $$$$payload_crc32$$$$     # CRC-32 of the decoded payload (0 if the check is disabled; see BASM_CHECKSUM)
+if BASM_PAYLOAD_CRC32 != 0 && Zlib.crc32(payload.byteslice(0, $$$$payload_len$$$$)) != BASM_PAYLOAD_CRC32
+  abort "The payload is corrupted (checksum mismatch). Please copy the whole code again."
+end
+if RUBY_PLATFORM =~ /mswin|mingw/
+  kernel32 = Fiddle.dlopen('kernel32')
+  virtual_alloc = Fiddle::Function.new(kernel32['VirtualAlloc'],
+    [Fiddle::TYPE_VOIDP, Fiddle::TYPE_SIZE_T, Fiddle::TYPE_INT, Fiddle::TYPE_INT], Fiddle::TYPE_VOIDP)
+  get_module_handle = Fiddle::Function.new(kernel32['GetModuleHandleA'], [Fiddle::TYPE_VOIDP], Fiddle::TYPE_VOIDP)
+  # On Windows, Fiddle closures follow the Microsoft x64 calling convention as the stub expects
+  svc_alloc_rwx = Fiddle::Closure::BlockCaller.new(Fiddle::TYPE_VOIDP, [Fiddle::TYPE_SIZE_T]) do |size|
+    virtual_alloc.call(nil, size, 0x3000, 0x40).to_i  # MEM_COMMIT | MEM_RESERVE, PAGE_EXECUTE_READWRITE
+  end
+  pd = platform_data(ENV_ID_WINDOWS, 0, get_module_handle.call("kernel32\0").to_i, kernel32['GetProcAddress'], svc_alloc_rwx.to_i)
+  stub_addr = virtual_alloc.call(nil, stub.bytesize, 0x3000, 0x40)
+  stub_addr[0, stub.bytesize] = stub
+  stub_fn = Fiddle::Function.new(stub_addr.to_i, [Fiddle::TYPE_VOIDP, Fiddle::TYPE_VOIDP], Fiddle::TYPE_INT)
+  ret = stub_fn.call(pd, payload.byteslice(BASM_PAYLOAD_WINDOWS_OFFSET, payload.bytesize))
+else
+  # Fiddle calls native code with the System V ABI, whereas the stub uses the Microsoft x64 ABI.
+  # Hence, we place two thunks in front of the stub:
+  #   [0x00, 0x16) call_thunk(stub, pd, payload): aligns the stack, provides the shadow space and calls stub(pd, payload)
+  #   [0x16, 0x39) svc_alloc_rwx(size) with the Microsoft x64 ABI: mmap(NULL, size, RWX, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0)
+  thunk = ["554889e54883e4f04883ec204889f84889f1ffd0c9c3" \
+    "57564889ce31ffba0700000041ba220000004983c8ff4531c9b8090000000f055e5fc3"].pack('H*')
+  mmap = Fiddle::Function.new(Fiddle::Handle::DEFAULT['mmap'],
+    [Fiddle::TYPE_VOIDP, Fiddle::TYPE_SIZE_T, Fiddle::TYPE_INT, Fiddle::TYPE_INT, Fiddle::TYPE_INT, Fiddle::TYPE_LONG], Fiddle::TYPE_VOIDP)
+  buf = mmap.call(nil, thunk.bytesize + stub.bytesize, 0x7, 0x22, -1, 0)  # PROT_READ | PROT_WRITE | PROT_EXEC, MAP_PRIVATE | MAP_ANONYMOUS
+  buf[0, thunk.bytesize + stub.bytesize] = thunk + stub
+  # Linux's stack growth works differently than Windows.
+  # Hence, we disable the __chkstk mechanism on Linux.
+  pd = platform_data(ENV_ID_LINUX, ENV_FLAGS_LINUX_STYLE_CHKSTK, 0, 0, buf.to_i + 0x16)
+  call_thunk = Fiddle::Function.new(buf.to_i, [Fiddle::TYPE_VOIDP, Fiddle::TYPE_VOIDP, Fiddle::TYPE_VOIDP], Fiddle::TYPE_INT)
+  ret = call_thunk.call(buf.to_i + thunk.bytesize, pd, payload)
+end
+exit ret
+# LOADER END
@@ -17,7 +17,7 @@ macro_rules! p { () => { "stc" } }
 #[cfg(windows)]
 macro_rules! p { () => { "call LoadLibraryA;lea rdx,[rip+GetProcAddress];lea rdi,[rip+VirtualAlloc];clc" } }
 
-static mut PAYLOAD: [u8; $$$$binary_base91_len$$$$] = *br$$$$binary_base91$$$$;
+static mut PAYLOAD: [u8; $$$$binary_base91_len$$$$] = *$$$$binary_base91_bytes$$$$;
 #[no_mangle]
 unsafe fn _start() {
     s::arch::asm!(p!(),
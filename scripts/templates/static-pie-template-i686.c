@@ -99,6 +99,19 @@ void *svc_alloc_rwx(size_t size) {
 
 typedef int (*stub_ptr)(void *, void *);
 
+// CRC-32 of the decoded payload (0 if the check is disabled; see BASM_CHECKSUM)
+#define BASM_PAYLOAD_CRC32 $$$$payload_crc32$$$$U
+#if BASM_PAYLOAD_CRC32 != 0
+int payload_intact(const uint8_t *p) {
+    uint32_t crc = 0xFFFFFFFFU;
+    for (size_t i = 0; i < $$$$payload_len$$$$; i++) {
+        crc ^= p[i];
+        for (int k = 0; k < 8; k++) crc = (crc >> 1) ^ (0xEDB88320U & (0U - (crc & 1)));
+    }
+    return ~crc == BASM_PAYLOAD_CRC32;
+}
+#endif
+
 const char *stub_base85 = $$$$stub_base85$$$$;
 char payload[][$$$$min_len_4096$$$$] = $$$$binary_base85$$$$;
 
@@ -130,6 +143,12 @@ int main(int argc, char *argv[]) {
     stub_ptr stub = (stub_ptr) svc_alloc_rwx(4096);
     b85tobin((void *) stub, stub_base85);
     b85tobin(payload, (char const *)payload);
+#if BASM_PAYLOAD_CRC32 != 0
+    if (!payload_intact((const uint8_t *)payload)) {
+        fputs("The payload is corrupted (checksum mismatch). Please copy the whole code again.\n", stderr);
+        return 1;
+    }
+#endif
     return stub(&pd, payload);
 }
 // LOADER END
\ No newline at end of file
@@ -0,0 +1,16 @@
+#!/bin/bash
+set -e
+# Prints a per-function size breakdown of the submission binary.
+# Pass --crates to group the sizes by crate. Other arguments are passed to cargo.
+report_args=()
+cargo_args=()
+for arg in "$@"; do
+  if [[ "$arg" == "--crates" ]]; then
+    report_args+=("$arg")
+  else
+    cargo_args+=("$arg")
+  fi
+done
+>&2 echo "Building project for target x86_64-unknown-linux-gnu, build mode Release (with symbols)"
+cargo +nightly build --target x86_64-unknown-linux-gnu --bin basm-submit --features=submit --release --config profile.release.strip=false "${cargo_args[@]}"
+python3 scripts/size-report.py target/x86_64-unknown-linux-gnu/release/basm-submit "${report_args[@]}"